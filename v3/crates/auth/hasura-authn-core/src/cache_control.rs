@@ -0,0 +1,65 @@
+//! Caching of the responses which authentication depends on, like JWK sets and webhook identities.
+
+use std::time::Duration;
+
+use http::header::{HeaderMap, CACHE_CONTROL};
+
+/// How long a response can be cached, according to its `Cache-Control` header. A `no-cache` or
+/// `no-store` directive means that it can't be cached at all. Returns `None` if there is no
+/// `Cache-Control` header, or it has none of these directives or a `max-age`.
+pub fn cache_control_max_age(headers: &HeaderMap) -> Option<Duration> {
+    let cache_control = headers.get(CACHE_CONTROL)?.to_str().ok()?;
+    let directives: Vec<&str> = cache_control.split(',').map(str::trim).collect();
+    if directives.iter().any(|directive| {
+        directive.eq_ignore_ascii_case("no-store") || directive.eq_ignore_ascii_case("no-cache")
+    }) {
+        return Some(Duration::ZERO);
+    }
+    directives.iter().find_map(|directive| {
+        let (name, seconds) = directive.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("max-age") {
+            seconds
+                .trim()
+                .trim_matches('"')
+                .parse()
+                .ok()
+                .map(Duration::from_secs)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    fn cache_control(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_cache_control_max_age() {
+        assert_eq!(
+            cache_control_max_age(&cache_control("public, max-age=600")),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            cache_control_max_age(&cache_control("max-age=\"60\"")),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            cache_control_max_age(&cache_control("max-age=300, no-cache")),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            cache_control_max_age(&cache_control("no-store")),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(cache_control_max_age(&cache_control("private")), None);
+        assert_eq!(cache_control_max_age(&HeaderMap::new()), None);
+    }
+}
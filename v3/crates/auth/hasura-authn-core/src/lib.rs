@@ -10,6 +10,8 @@ use std::{
     str::FromStr,
 };
 
+pub mod cache_control;
+
 /// The architecture is as follows:
 /// 1. Every authn mechanism returns an 'Identity'.
 ///    An 'Identity' lists the allowed roles and a default role
@@ -23,14 +25,36 @@ pub use open_dds::{
 };
 
 // Value of a session variable
-// TODO: This need to change to serde_json::Value
-// TODO: Get rid of the pub access to inner value
 #[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize, JsonSchema)]
-pub struct SessionVariableValue(pub String);
+#[serde(untagged)]
+pub enum SessionVariableValue {
+    // A string, like the value of a header, which is parsed according to the type it's used
+    // at, e.g. "1" can be used as an integer
+    Unparsed(String),
+    // Any other JSON value, like a number, boolean or array from a JWT claim or a webhook
+    // response, which is used as it is
+    Parsed(serde_json::Value),
+}
 
 impl SessionVariableValue {
     pub fn new(value: &str) -> Self {
-        SessionVariableValue(value.to_string())
+        SessionVariableValue::Unparsed(value.to_string())
+    }
+
+    // A JSON string is kept unparsed, so that it's handled like any other string value
+    pub fn from_json(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(string) => SessionVariableValue::Unparsed(string),
+            value => SessionVariableValue::Parsed(value),
+        }
+    }
+
+    // The value if it's a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SessionVariableValue::Unparsed(string) => Some(string.as_str()),
+            SessionVariableValue::Parsed(_) => None,
+        }
     }
 }
 
@@ -166,33 +190,43 @@ pub async fn resolve_session<'a, B>(
     mut request: Request<B>,
     next: Next<B>,
 ) -> axum::response::Result<axum::response::Response> {
+    let session = authorize_identity(&identity, request.headers())?;
+    request.extensions_mut().insert(session);
+    let response = next.run(request).await;
+    Ok(response)
+}
+
+/// Resolves the 'session' of an identity using the role and session variables found in the
+/// x-hasura-* headers.
+pub fn authorize_identity(
+    identity: &Identity,
+    headers: &axum::http::HeaderMap,
+) -> Result<Session, SessionError> {
     let mut session_variables = HashMap::new();
     let mut role = None;
     // traverse through the headers and collect role and session variables
-    for (header_name, header_value) in request.headers() {
+    for (header_name, header_value) in headers {
         if let Ok(session_variable) = SessionVariable::from_str(header_name.as_str()) {
             let variable_value = match header_value.to_str() {
                 Err(e) => Err(SessionError::InvalidHeaderValue {
                     header_name: header_name.to_string(),
                     error: e.to_string(),
                 })?,
-                Ok(h) => SessionVariableValue::new(h),
+                Ok(h) => h,
             };
 
             if session_variable == SESSION_VARIABLE_ROLE.to_owned() {
-                role = Some(Role::new(&variable_value.0));
+                role = Some(Role::new(variable_value));
             } else {
                 // TODO: Handle the duplicate case?
-                session_variables.insert(session_variable, variable_value);
+                session_variables
+                    .insert(session_variable, SessionVariableValue::new(variable_value));
             }
         }
     }
-    let session = identity
+    Ok(identity
         .get_role_authorization(role.as_ref())?
-        .build_session(session_variables);
-    request.extensions_mut().insert(session);
-    let response = next.run(request).await;
-    Ok(response)
+        .build_session(session_variables))
 }
 
 #[cfg(test)]
@@ -75,7 +75,12 @@ pub async fn authenticate_request(
                                 let role = hasura_claims
                                     .custom_claims
                                     .get(&SESSION_VARIABLE_ROLE)
-                                    .map(|v| Role::new(v.0.as_str()));
+                                    .map(|role| {
+                                        role.as_str()
+                                            .map(Role::new)
+                                            .ok_or(Error::RoleClaimShouldBeString)
+                                    })
+                                    .transpose()?;
                                 match role {
                                     // `x-hasura-role` is found, check if it's the
                                     // role that can emulate by comparing it to
@@ -106,6 +111,58 @@ pub async fn authenticate_request(
         .await
 }
 
+/// Authenticates the request with the first of the `jwt_configs` that accepts it.
+///
+/// A config is only tried if its token location is present in the request and, when it
+/// specifies an `issuer`, the token was issued by that issuer. The configs are tried in
+/// order, and if none of them authenticates the request, the errors of all of them are
+/// returned.
+pub async fn authenticate_request_with_jwt_configs(
+    http_client: &reqwest::Client,
+    jwt_configs: &[JWTConfig],
+    allow_role_emulation_for: Option<&Role>,
+    headers: &HeaderMap,
+) -> Result<Identity, Error> {
+    let mut errors = Vec::new();
+    for (index, jwt_config) in jwt_configs.iter().enumerate() {
+        let authorization_token = match get_authorization_token(&jwt_config.token_location, headers)
+        {
+            Ok(authorization_token) => authorization_token,
+            Err(error) => {
+                errors.push(JWTConfigError { index, error });
+                continue;
+            }
+        };
+        if let (Some(expected), Some(found)) = (
+            &jwt_config.issuer,
+            get_unverified_issuer(&authorization_token),
+        ) {
+            if *expected != found {
+                errors.push(JWTConfigError {
+                    index,
+                    error: Error::IssuerMismatch {
+                        expected: expected.clone(),
+                        found,
+                    },
+                });
+                continue;
+            }
+        }
+        match authenticate_request(
+            http_client,
+            jwt_config.clone(),
+            allow_role_emulation_for,
+            headers,
+        )
+        .await
+        {
+            Ok(identity) => return Ok(identity),
+            Err(error) => errors.push(JWTConfigError { index, error }),
+        }
+    }
+    Err(Error::NoMatchingJWTConfig(errors))
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -167,7 +224,7 @@ mod tests {
         let mut hasura_custom_claims = HashMap::new();
         hasura_custom_claims.insert(
             SessionVariable::from_str("x-hasura-user-id").unwrap(),
-            SessionVariableValue("1".to_string()),
+            SessionVariableValue::new("1"),
         );
         HasuraClaims {
             default_role: Role::new("user"),
@@ -325,4 +382,135 @@ mod tests {
         );
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_role_emulation_with_non_string_role_claim() -> anyhow::Result<()> {
+        let mut hasura_claims = get_default_hasura_claims();
+        hasura_claims.custom_claims.insert(
+            SessionVariable::from_str("x-hasura-role").unwrap(),
+            SessionVariableValue::from_json(json!(["admin"])),
+        );
+        let encoded_claims = get_encoded_claims(Algorithm::HS256, &hasura_claims)?;
+
+        let jwt_secret_config_json = json!(
+            {
+               "key": {
+                 "fixed": {
+                    "algorithm": "HS256",
+                    "key": {
+                       "value": "token"
+                    }
+                 }
+               },
+               "tokenLocation": {
+                  "type": "BearerAuthorization"
+               },
+               "claimsConfig": {
+                  "namespace": {
+                     "claimsFormat": "Json",
+                     "location": "/https:~1~1hasura.io~1jwt~1claims"
+                  }
+               }
+            }
+        );
+
+        let jwt_config: JWTConfig = serde_json::from_value(jwt_secret_config_json)?;
+
+        let http_client = reqwest::Client::new();
+
+        let mut header_map = HeaderMap::new();
+        header_map.insert(
+            AUTHORIZATION,
+            ("Bearer ".to_owned() + &encoded_claims).parse()?,
+        );
+
+        let error = authenticate_request(
+            &http_client,
+            jwt_config,
+            Some(&Role::new("admin")),
+            &header_map,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, Error::RoleClaimShouldBeString));
+        Ok(())
+    }
+
+    fn get_jwt_config(issuer: &str, key: &str) -> anyhow::Result<JWTConfig> {
+        Ok(serde_json::from_value(json!(
+            {
+               "issuer": issuer,
+               "key": {
+                 "fixed": {
+                    "algorithm": "HS256",
+                    "key": {
+                       "value": key
+                    }
+                 }
+               },
+               "tokenLocation": {
+                  "type": "BearerAuthorization"
+               },
+               "claimsConfig": {
+                  "namespace": {
+                     "claimsFormat": "Json",
+                     "location": "/https:~1~1hasura.io~1jwt~1claims"
+                  }
+               }
+            }
+        ))?)
+    }
+
+    fn get_issued_token(issuer: &str) -> anyhow::Result<String> {
+        let mut claims = get_claims(
+            &serde_json::to_value(get_default_hasura_claims())?,
+            &DEFAULT_HASURA_CLAIMS_NAMESPACE_POINTER,
+        )?;
+        claims.iss = Some(issuer.to_string());
+        Ok(encode(
+            &jwt::Header::default(),
+            &claims,
+            &EncodingKey::from_secret("token".as_ref()),
+        )?)
+    }
+
+    #[tokio::test]
+    async fn test_jwt_config_is_picked_by_issuer() -> anyhow::Result<()> {
+        let jwt_configs = vec![
+            get_jwt_config("https://internal.example.com", "internal-token")?,
+            get_jwt_config("https://customer.example.com", "token")?,
+        ];
+        let http_client = reqwest::Client::new();
+
+        let mut header_map = HeaderMap::new();
+        header_map.insert(
+            AUTHORIZATION,
+            ("Bearer ".to_owned() + &get_issued_token("https://customer.example.com")?).parse()?,
+        );
+        let authenticated_identity =
+            authenticate_request_with_jwt_configs(&http_client, &jwt_configs, None, &header_map)
+                .await?;
+        assert!(matches!(
+            authenticated_identity,
+            Identity::Specific { default_role, .. } if default_role == Role::new("user")
+        ));
+
+        header_map.insert(
+            AUTHORIZATION,
+            ("Bearer ".to_owned() + &get_issued_token("https://unknown.example.com")?).parse()?,
+        );
+        let error =
+            authenticate_request_with_jwt_configs(&http_client, &jwt_configs, None, &header_map)
+                .await
+                .unwrap_err();
+        assert_eq!(error.to_status_code(), reqwest::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            error.to_string(),
+            "None of the JWT configs could authenticate the request: \
+             [0] The token was issued by https://unknown.example.com, but the JWT config only accepts tokens issued by https://internal.example.com; \
+             [1] The token was issued by https://unknown.example.com, but the JWT config only accepts tokens issued by https://customer.example.com"
+        );
+        Ok(())
+    }
 }
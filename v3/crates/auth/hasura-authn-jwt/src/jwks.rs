@@ -0,0 +1,456 @@
+//! A cache of the JWK sets fetched from `jwkFromUrl`, shared by all requests.
+//!
+//! A JWK set is kept for as long as its response allows, according to the `Cache-Control:
+//! max-age` or `Expires` headers, and refreshed in the background shortly before it expires.
+//! If the identity provider can't be reached, the last key set that was fetched successfully
+//! keeps being used, until it has been expired for longer than `MAX_JWK_SET_STALENESS`. Past
+//! that, keys which may have been rotated out are no longer accepted.
+//!
+//! A token signed with a key which isn't in the cached set causes the set to be fetched again,
+//! in case the keys were rotated. Fetches are rate limited, so that tokens with unknown key ids
+//! can't be used to flood the identity provider with requests.
+//!
+//! While no key set has been fetched yet, requests wait for a single fetch rather than each
+//! fetching the set, and a failed fetch is only retried once the rate limit allows it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use hasura_authn_core::cache_control::cache_control_max_age;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use lazy_static::lazy_static;
+use reqwest::header::{HeaderMap, EXPIRES};
+use tracing_util::{add_event_on_active_span, SpanVisibility};
+use url::Url;
+
+use crate::jwt::{Error, InternalError};
+
+/// How long a JWK set is cached when its response has no caching headers.
+const DEFAULT_JWK_SET_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// How long before its expiry a JWK set is refreshed in the background.
+const REFRESH_BEFORE_EXPIRY: Duration = Duration::from_secs(30);
+
+/// The minimum time between two fetches of the same JWK set, once it has been fetched.
+const MIN_FETCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long an expired JWK set keeps being used while it can't be fetched again.
+const MAX_JWK_SET_STALENESS: Duration = Duration::from_secs(60 * 60);
+
+/// The timeout for fetching a JWK set.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    /// The JWK sets of all the `jwkFromUrl` keys, which outlives reloads of the auth config.
+    pub(crate) static ref JWK_SET_CACHE: JwkSetCache = JwkSetCache::default();
+}
+
+/// A JWK set along with when it should be fetched again.
+struct CachedJwkSet {
+    jwk_set: Arc<JwkSet>,
+    expires_at: Instant,
+}
+
+impl CachedJwkSet {
+    /// Whether the key set has been expired for too long to be used at all.
+    fn is_too_stale(&self, now: Instant) -> bool {
+        now >= self.expires_at + MAX_JWK_SET_STALENESS
+    }
+}
+
+#[derive(Default)]
+struct CacheEntry {
+    /// The last key set fetched successfully.
+    cached: Option<CachedJwkSet>,
+    /// When the key set was last fetched, successfully or not.
+    last_fetched_at: Option<Instant>,
+    /// Held while fetching a key set which isn't cached yet, so that only one request fetches it.
+    cold_fetch: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl CacheEntry {
+    /// Records a fetch as started, unless the key set was fetched too recently.
+    fn start_fetch(&mut self, now: Instant) -> bool {
+        let allowed = self.last_fetched_at.map_or(true, |last_fetched_at| {
+            now >= last_fetched_at + MIN_FETCH_INTERVAL
+        });
+        if allowed {
+            self.last_fetched_at = Some(now);
+        }
+        allowed
+    }
+}
+
+/// The JWK sets fetched so far, keyed by their URL.
+#[derive(Default)]
+pub(crate) struct JwkSetCache {
+    entries: Mutex<HashMap<Url, CacheEntry>>,
+}
+
+impl JwkSetCache {
+    /// Finds the key with the given id in the JWK set served at `url`, fetching the set if it
+    /// isn't cached yet or doesn't contain the key.
+    pub(crate) async fn get_jwk(
+        &'static self,
+        http_client: &reqwest::Client,
+        url: &Url,
+        kid: &str,
+    ) -> Result<Jwk, Error> {
+        let now = Instant::now();
+        let (cached_jwk_set, refresh) = {
+            let mut entries = sync_ext::lock(&self.entries);
+            let entry = entries.entry(url.clone()).or_default();
+            if entry
+                .cached
+                .as_ref()
+                .is_some_and(|cached| cached.is_too_stale(now))
+            {
+                entry.cached = None;
+            }
+            match &entry.cached {
+                Some(cached) => {
+                    let jwk_set = cached.jwk_set.clone();
+                    let refresh =
+                        now + REFRESH_BEFORE_EXPIRY >= cached.expires_at && entry.start_fetch(now);
+                    (Some(jwk_set), refresh)
+                }
+                None => (None, false),
+            }
+        };
+
+        let Some(jwk_set) = cached_jwk_set else {
+            // nothing to fall back on, so the key set has to be fetched now
+            let jwk_set = self.fetch_uncached(http_client, url).await?;
+            return find_jwk(&jwk_set, kid);
+        };
+
+        if refresh {
+            let http_client = http_client.clone();
+            let url = url.clone();
+            tokio::spawn(async move {
+                // the cached key set keeps being used if the refresh fails
+                let _ = self.fetch(&http_client, &url).await;
+            });
+        }
+
+        // the keys may have been rotated since they were fetched
+        if jwk_set.find(kid).is_none()
+            && sync_ext::lock(&self.entries)
+                .entry(url.clone())
+                .or_default()
+                .start_fetch(now)
+        {
+            let jwk_set = self.fetch(http_client, url).await?;
+            return find_jwk(&jwk_set, kid);
+        }
+        find_jwk(&jwk_set, kid)
+    }
+
+    /// Fetches the JWK set served at `url` when it isn't cached yet. Concurrent requests wait for
+    /// the request which is fetching it, and use the key set it fetched.
+    async fn fetch_uncached(
+        &self,
+        http_client: &reqwest::Client,
+        url: &Url,
+    ) -> Result<Arc<JwkSet>, Error> {
+        let cold_fetch = sync_ext::lock(&self.entries)
+            .entry(url.clone())
+            .or_default()
+            .cold_fetch
+            .clone();
+        let _fetching = cold_fetch.lock().await;
+
+        // the key set may have been fetched while waiting
+        let fetch_allowed = {
+            let mut entries = sync_ext::lock(&self.entries);
+            let entry = entries.entry(url.clone()).or_default();
+            if let Some(cached) = &entry.cached {
+                return Ok(cached.jwk_set.clone());
+            }
+            entry.start_fetch(Instant::now())
+        };
+        if fetch_allowed {
+            self.fetch(http_client, url).await
+        } else {
+            Err(InternalError::JWKSetFetchRateLimited)?
+        }
+    }
+
+    /// Fetches the JWK set served at `url` and caches it.
+    async fn fetch(&self, http_client: &reqwest::Client, url: &Url) -> Result<Arc<JwkSet>, Error> {
+        let fetched = fetch_jwk_set(http_client, url).await;
+        match fetched {
+            Ok(cached) => {
+                let jwk_set = cached.jwk_set.clone();
+                sync_ext::lock(&self.entries)
+                    .entry(url.clone())
+                    .or_default()
+                    .cached = Some(cached);
+                Ok(jwk_set)
+            }
+            Err(error) => {
+                add_event_on_active_span(format!("could not fetch the JWK set: {error}"));
+                Err(error)
+            }
+        }
+    }
+}
+
+fn find_jwk(jwk_set: &JwkSet, kid: &str) -> Result<Jwk, Error> {
+    jwk_set.find(kid).cloned().ok_or_else(|| {
+        InternalError::NoMatchingJWKFound {
+            kid: kid.to_string(),
+        }
+        .into()
+    })
+}
+
+async fn fetch_jwk_set(http_client: &reqwest::Client, url: &Url) -> Result<CachedJwkSet, Error> {
+    let tracer = tracing_util::global_tracer();
+    tracer
+        .in_span_async("fetch_jwk", "Fetch JWK", SpanVisibility::Internal, || {
+            Box::pin(async {
+                let jwk_request = http_client
+                    .get(url.clone())
+                    .headers(tracing_util::get_trace_headers())
+                    .timeout(FETCH_TIMEOUT)
+                    .build()
+                    .map_err(InternalError::ReqwestError)?;
+
+                let jwk_response = http_client
+                    .execute(jwk_request)
+                    .await
+                    .map_err(InternalError::ErrorFetchingJWKSet)?;
+                if jwk_response.status().is_success() {
+                    let expires_at = Instant::now() + max_age(jwk_response.headers());
+                    let jwk_set: JwkSet = jwk_response
+                        .json()
+                        .await
+                        .map_err(InternalError::ReqwestError)?;
+                    Ok(CachedJwkSet {
+                        jwk_set: Arc::new(jwk_set),
+                        expires_at,
+                    })
+                } else {
+                    Err(InternalError::UnsuccessfulJWKFetch(jwk_response.status()))?
+                }
+            })
+        })
+        .await
+}
+
+/// How long a response can be cached, according to its `Cache-Control` header, or its
+/// `Expires` header if the `Cache-Control` header doesn't say.
+fn max_age(headers: &HeaderMap) -> Duration {
+    cache_control_max_age(headers).unwrap_or_else(|| {
+        headers
+            .get(EXPIRES)
+            .and_then(|value| value.to_str().ok())
+            .map_or(DEFAULT_JWK_SET_MAX_AGE, |expires| {
+                // an invalid date means that the response has already expired
+                httpdate::parse_http_date(expires)
+                    .ok()
+                    .and_then(|expires| expires.duration_since(SystemTime::now()).ok())
+                    .unwrap_or(Duration::ZERO)
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, CACHE_CONTROL};
+
+    fn headers(name: reqwest::header::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    fn jwk_set(kids: &[&str]) -> serde_json::Value {
+        let keys: Vec<serde_json::Value> = kids
+            .iter()
+            .map(|kid| {
+                serde_json::json!({
+                    "kty": "oct",
+                    "kid": kid,
+                    "alg": "HS256",
+                    "k": "c2VjcmV0"
+                })
+            })
+            .collect();
+        serde_json::json!({ "keys": keys })
+    }
+
+    #[test]
+    fn test_max_age() {
+        assert_eq!(
+            max_age(&headers(CACHE_CONTROL, "public, max-age=600")),
+            Duration::from_secs(600)
+        );
+        assert_eq!(max_age(&headers(CACHE_CONTROL, "no-store")), Duration::ZERO);
+        assert_eq!(
+            max_age(&headers(EXPIRES, "Thu, 01 Jan 1970 00:00:00 GMT")),
+            Duration::ZERO
+        );
+        let expires = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(3600));
+        let from_expires = max_age(&headers(EXPIRES, &expires));
+        assert!(
+            from_expires > Duration::from_secs(3500) && from_expires <= Duration::from_secs(3600)
+        );
+        assert_eq!(max_age(&HeaderMap::new()), DEFAULT_JWK_SET_MAX_AGE);
+    }
+
+    #[tokio::test]
+    async fn test_jwk_set_is_cached() -> anyhow::Result<()> {
+        let cache: &'static JwkSetCache = Box::leak(Box::default());
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/jwk")
+            .with_status(200)
+            .with_header("cache-control", "max-age=600")
+            .with_body(jwk_set(&["kid_1"]).to_string())
+            .expect(1)
+            .create();
+        let url = Url::parse(&(server.url() + "/jwk"))?;
+        let http_client = reqwest::Client::new();
+
+        cache.get_jwk(&http_client, &url, "kid_1").await?;
+        cache.get_jwk(&http_client, &url, "kid_1").await?;
+        // the key set was fetched too recently to be fetched again for an unknown key
+        assert!(cache.get_jwk(&http_client, &url, "kid_2").await.is_err());
+
+        mock.assert();
+        Ok(())
+    }
+
+    /// Requests `kid_1` from the cache by `requests` concurrent requests
+    async fn get_jwk_concurrently(
+        cache: &'static JwkSetCache,
+        url: &Url,
+        requests: usize,
+    ) -> Vec<Result<Jwk, Error>> {
+        let http_client = reqwest::Client::new();
+        let handles: Vec<_> = (0..requests)
+            .map(|_| {
+                let http_client = http_client.clone();
+                let url = url.clone();
+                tokio::spawn(async move { cache.get_jwk(&http_client, &url, "kid_1").await })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+        results
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_first_fetch() -> anyhow::Result<()> {
+        let cache: &'static JwkSetCache = Box::leak(Box::default());
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/jwk")
+            .with_status(200)
+            .with_body(jwk_set(&["kid_1"]).to_string())
+            .expect(1)
+            .create();
+        let url = Url::parse(&(server.url() + "/jwk"))?;
+
+        for result in get_jwk_concurrently(cache, &url, 10).await {
+            result?;
+        }
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_first_fetch_is_rate_limited() -> anyhow::Result<()> {
+        let cache: &'static JwkSetCache = Box::leak(Box::default());
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/jwk")
+            .with_status(503)
+            .expect(1)
+            .create();
+        let url = Url::parse(&(server.url() + "/jwk"))?;
+
+        let results = get_jwk_concurrently(cache, &url, 10).await;
+        assert!(results.iter().all(Result::is_err));
+        // the identity provider is not asked again until the rate limit allows it
+        assert!(get_jwk_concurrently(cache, &url, 1).await[0].is_err());
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refetches_jwk_set() -> anyhow::Result<()> {
+        let cache: &'static JwkSetCache = Box::leak(Box::default());
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&(server.url() + "/jwk"))?;
+        let http_client = reqwest::Client::new();
+
+        // cache a key set which was fetched long enough ago to be fetched again
+        sync_ext::lock(&cache.entries).insert(
+            url.clone(),
+            CacheEntry {
+                cached: Some(CachedJwkSet {
+                    jwk_set: Arc::new(serde_json::from_value(jwk_set(&["kid_1"]))?),
+                    expires_at: Instant::now() + Duration::from_secs(600),
+                }),
+                last_fetched_at: None,
+                cold_fetch: Arc::default(),
+            },
+        );
+        let mock = server
+            .mock("GET", "/jwk")
+            .with_status(200)
+            .with_body(jwk_set(&["kid_1", "kid_2"]).to_string())
+            .expect(1)
+            .create();
+
+        cache.get_jwk(&http_client, &url, "kid_2").await?;
+
+        mock.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_last_jwk_set_is_used_when_fetch_fails() -> anyhow::Result<()> {
+        let cache: &'static JwkSetCache = Box::leak(Box::default());
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&(server.url() + "/jwk"))?;
+        let http_client = reqwest::Client::new();
+
+        // cache a key set which has already expired
+        sync_ext::lock(&cache.entries).insert(
+            url.clone(),
+            CacheEntry {
+                cached: Some(CachedJwkSet {
+                    jwk_set: Arc::new(serde_json::from_value(jwk_set(&["kid_1"]))?),
+                    expires_at: Instant::now(),
+                }),
+                last_fetched_at: None,
+                cold_fetch: Arc::default(),
+            },
+        );
+        let _mock = server.mock("GET", "/jwk").with_status(503).create();
+
+        cache.get_jwk(&http_client, &url, "kid_1").await?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_expired_jwk_set_is_used_for_a_limited_time() -> anyhow::Result<()> {
+        let cached = CachedJwkSet {
+            jwk_set: Arc::new(serde_json::from_value(jwk_set(&["kid_1"]))?),
+            expires_at: Instant::now(),
+        };
+        assert!(!cached.is_too_stale(cached.expires_at + MAX_JWK_SET_STALENESS / 2));
+        assert!(cached.is_too_stale(cached.expires_at + MAX_JWK_SET_STALENESS));
+        Ok(())
+    }
+}
@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::time::Duration;
 
 use axum::http::{HeaderMap, HeaderValue};
 use axum::response::IntoResponse;
@@ -22,6 +21,8 @@ use thiserror::Error;
 use tracing_util::{ErrorVisibility, SpanVisibility, TraceableError};
 use url::Url;
 
+use crate::jwks::JWK_SET_CACHE;
+
 /// Name of the key, which is by default used to lookup the Hasura claims
 /// in the claims obtained after decoding the JWT.
 const DEFAULT_HASURA_CLAIMS_NAMESPACE: &str = "https://hasura.io/jwt/claims";
@@ -64,10 +65,34 @@ pub enum Error {
     CookieParseError { err: cookie::ParseError },
     #[error("Missing corresponding value for the cookie with cookie name: {cookie_name}")]
     MissingCookieValue { cookie_name: String },
+    #[error("The token was issued by {found}, but the JWT config only accepts tokens issued by {expected}")]
+    IssuerMismatch { expected: String, found: String },
+    #[error("The x-hasura-role claim is expected to be a string")]
+    RoleClaimShouldBeString,
+    #[error(
+        "None of the JWT configs could authenticate the request: {}",
+        display_jwt_config_errors(.0)
+    )]
+    NoMatchingJWTConfig(Vec<JWTConfigError>),
     #[error("Internal Error - {0}")]
     Internal(#[from] InternalError),
 }
 
+/// The error with which the JWT config at `index` failed to authenticate a request.
+#[derive(Debug)]
+pub struct JWTConfigError {
+    pub index: usize,
+    pub error: Error,
+}
+
+fn display_jwt_config_errors(errors: &[JWTConfigError]) -> String {
+    errors
+        .iter()
+        .map(|JWTConfigError { index, error }| format!("[{index}] {error}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 impl TraceableError for Error {
     fn visibility(&self) -> ErrorVisibility {
         // For the purpose of traces, all JWT errors should be developer facing.
@@ -97,6 +122,8 @@ pub enum InternalError {
     NoMatchingJWKFound { kid: String },
     #[error("Received unsuccessful response {0} status while fetching JWK ")]
     UnsuccessfulJWKFetch(StatusCode),
+    #[error("The JWKSet could not be fetched, and will not be fetched again for a few seconds")]
+    JWKSetFetchRateLimited,
     #[error("Algorithm not found in the JWK")]
     AlgorithmNotFoundInJWK,
 }
@@ -105,6 +132,13 @@ impl Error {
     pub fn to_status_code(&self) -> StatusCode {
         match self {
             Error::Internal(_e) => StatusCode::INTERNAL_SERVER_ERROR,
+            // If any of the configs failed for reasons other than the request, the
+            // request may well have been valid.
+            Error::NoMatchingJWTConfig(errors) => errors
+                .iter()
+                .map(|JWTConfigError { index: _, error }| error.to_status_code())
+                .find(|status_code| *status_code != StatusCode::BAD_REQUEST)
+                .unwrap_or(StatusCode::BAD_REQUEST),
             Error::ErrorDecodingAuthorizationHeader(_)
             | Error::KidHeaderNotFound
             | Error::ExpectedStringifiedJson
@@ -122,7 +156,12 @@ impl Error {
                 header_name: _,
             }
             | Error::CookieParseError { err: _ }
-            | Error::MissingCookieValue { cookie_name: _ } => StatusCode::BAD_REQUEST,
+            | Error::MissingCookieValue { cookie_name: _ }
+            | Error::IssuerMismatch {
+                expected: _,
+                found: _,
+            }
+            | Error::RoleClaimShouldBeString => StatusCode::BAD_REQUEST,
         }
     }
 }
@@ -444,45 +483,19 @@ async fn get_decoding_key_from_jwk_url(
     jwk_url: Url,
     jwt_authorization_header: &str,
 ) -> Result<(jwt::Algorithm, jwt::DecodingKey), Error> {
-    let tracer = tracing_util::global_tracer();
-    tracer
-        .in_span_async("fetch_jwk", "Fetch JWK", SpanVisibility::Internal, || {
-            Box::pin(async {
-                let jwk_request = http_client
-                    .get(jwk_url)
-                    .headers(tracing_util::get_trace_headers())
-                    .timeout(Duration::from_secs(60))
-                    .build()
-                    .map_err(InternalError::ReqwestError)?;
-
-                let jwk_response = http_client
-                    .execute(jwk_request)
-                    .await
-                    .map_err(InternalError::ErrorFetchingJWKSet)?;
-                if jwk_response.status().is_success() {
-                    let jwk_set: jwt::jwk::JwkSet = jwk_response
-                        .json()
-                        .await
-                        .map_err(InternalError::ReqwestError)?;
-                    let decoded_header = decode_header(jwt_authorization_header)
-                        .map_err(Error::ErrorDecodingAuthorizationHeader)?;
-                    let kid = decoded_header.kid.ok_or(Error::KidHeaderNotFound)?;
-                    let jwk = jwk_set
-                        .find(kid.as_str())
-                        .ok_or(InternalError::NoMatchingJWKFound { kid })?;
-                    let decoding_key = jwt::DecodingKey::from_jwk(jwk)
-                        .map_err(InternalError::JWTDecodingKeyError)?;
-                    let algorithm = jwk
-                        .common
-                        .algorithm
-                        .ok_or(InternalError::AlgorithmNotFoundInJWK)?;
-                    Ok((algorithm, decoding_key))
-                } else {
-                    Err(InternalError::UnsuccessfulJWKFetch(jwk_response.status()))?
-                }
-            })
-        })
-        .await
+    let decoded_header =
+        decode_header(jwt_authorization_header).map_err(Error::ErrorDecodingAuthorizationHeader)?;
+    let kid = decoded_header.kid.ok_or(Error::KidHeaderNotFound)?;
+    let jwk = JWK_SET_CACHE
+        .get_jwk(http_client, &jwk_url, kid.as_str())
+        .await?;
+    let decoding_key =
+        jwt::DecodingKey::from_jwk(&jwk).map_err(InternalError::JWTDecodingKeyError)?;
+    let algorithm = jwk
+        .common
+        .algorithm
+        .ok_or(InternalError::AlgorithmNotFoundInJWK)?;
+    Ok((algorithm, decoding_key))
 }
 
 fn get_claims_mapping_entry_value<T: for<'de> serde::Deserialize<'de>>(
@@ -504,6 +517,20 @@ fn get_claims_mapping_entry_value<T: for<'de> serde::Deserialize<'de>>(
     }
 }
 
+/// Reads the `iss` claim of a JWT without verifying it, so that a JWT config can be picked
+/// for the token before it's decoded. Returns `None` if the token can't be decoded or has
+/// no issuer.
+pub(crate) fn get_unverified_issuer(jwt: &str) -> Option<String> {
+    let mut validation = Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.validate_exp = false;
+    validation.required_spec_claims = HashSet::new();
+    let claims: serde_json::Value = decode(jwt, &DecodingKey::from_secret(&[]), &validation)
+        .ok()?
+        .claims;
+    claims.get("iss")?.as_str().map(ToString::to_string)
+}
+
 pub(crate) async fn decode_and_parse_hasura_claims(
     http_client: &reqwest::Client,
     jwt_config: JWTConfig,
@@ -607,6 +634,16 @@ pub(crate) async fn decode_and_parse_hasura_claims(
     Ok(hasura_claims)
 }
 
+/// Whether the request has a token at the given location, whether or not it's a valid one.
+pub fn has_authorization_token(token_location: &JWTTokenLocation, headers: &HeaderMap) -> bool {
+    !matches!(
+        get_authorization_token(token_location, headers),
+        Err(Error::AuthorizationHeaderSourceNotFound { .. }
+            | Error::CookieNotFound
+            | Error::CookieNameNotFound { .. })
+    )
+}
+
 pub(crate) fn get_authorization_token(
     token_location: &JWTTokenLocation,
     headers: &HeaderMap,
@@ -742,7 +779,7 @@ mod tests {
         let mut hasura_custom_claims = HashMap::new();
         hasura_custom_claims.insert(
             SessionVariable::from_str("x-hasura-user-id").unwrap(),
-            SessionVariableValue("1".to_string()),
+            SessionVariableValue::new("1"),
         );
         HasuraClaims {
             default_role: Role::new("user"),
@@ -1,2 +1,3 @@
 pub mod auth;
+mod jwks;
 pub mod jwt;
@@ -0,0 +1,108 @@
+//! A cache of the identities returned by the authentication webhook, shared by all requests.
+//!
+//! An identity is cached against everything which is sent to the webhook, i.e. the forwarded
+//! client headers and the GraphQL operation, if it's included, so a request is only served a
+//! cached identity if the webhook would have been called with exactly the same credentials.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use auth_base::cache_control::cache_control_max_age;
+use auth_base::{Identity, Role};
+use hasura_authn_core as auth_base;
+use lazy_static::lazy_static;
+use reqwest::header::{HeaderMap, CACHE_CONTROL};
+use reqwest::Url;
+
+use crate::webhook::{AuthHookMethod, AuthHookOperation};
+
+/// The maximum number of identities which are cached.
+const MAX_CACHED_IDENTITIES: usize = 10_000;
+
+lazy_static! {
+    pub(crate) static ref IDENTITY_CACHE: IdentityCache = IdentityCache::default();
+}
+
+/// Everything that is sent to the webhook to authenticate a request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct IdentityCacheKey {
+    pub url: Url,
+    pub method: AuthHookMethod,
+    /// The forwarded client headers, sorted by name.
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub operation: Option<AuthHookOperation>,
+    pub allow_role_emulation_for: Option<Role>,
+}
+
+#[derive(Default)]
+pub(crate) struct IdentityCache {
+    entries: Mutex<HashMap<IdentityCacheKey, (Identity, Instant)>>,
+}
+
+impl IdentityCache {
+    /// The cached identity for the key, unless it has expired.
+    pub(crate) fn get(&self, key: &IdentityCacheKey) -> Option<Identity> {
+        let mut entries = sync_ext::lock(&self.entries);
+        let (identity, expires_at) = entries.get(key)?;
+        if Instant::now() < *expires_at {
+            return Some(identity.clone());
+        }
+        entries.remove(key);
+        None
+    }
+
+    /// Caches the identity for the key, for the given duration.
+    pub(crate) fn insert(&self, key: IdentityCacheKey, identity: Identity, ttl: Duration) {
+        let now = Instant::now();
+        let mut entries = sync_ext::lock(&self.entries);
+        if entries.len() >= MAX_CACHED_IDENTITIES {
+            entries.retain(|_, (_, expires_at)| now < *expires_at);
+        }
+        // rather than evicting identities which are still valid, stop caching until some expire
+        if entries.len() < MAX_CACHED_IDENTITIES {
+            entries.insert(key, (identity, now + ttl));
+        }
+    }
+}
+
+/// How long the identity in a webhook response can be cached, according to the `max-age` of
+/// its `Cache-Control` header, or the configured default if there is no `Cache-Control` header.
+pub(crate) fn cache_ttl(headers: &HeaderMap, default_ttl: Option<Duration>) -> Duration {
+    if headers.contains_key(CACHE_CONTROL) {
+        // a `Cache-Control` header without a `max-age` means that the response isn't cached
+        cache_control_max_age(headers).unwrap_or(Duration::ZERO)
+    } else {
+        default_ttl.unwrap_or(Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn cache_control(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        let default_ttl = Some(Duration::from_secs(30));
+        assert_eq!(
+            cache_ttl(&cache_control("private, max-age=300"), default_ttl),
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            cache_ttl(&cache_control("no-cache, max-age=300"), default_ttl),
+            Duration::ZERO
+        );
+        assert_eq!(
+            cache_ttl(&HeaderMap::new(), default_ttl),
+            Duration::from_secs(30)
+        );
+        assert_eq!(cache_ttl(&HeaderMap::new(), None), Duration::ZERO);
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 
 use auth_base::{Identity, Role, RoleAuthorization, SessionVariable, SessionVariableValue};
 use axum::{
-    http::{HeaderMap, HeaderName, StatusCode},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
     response::IntoResponse,
 };
 use lazy_static::lazy_static;
@@ -19,6 +19,11 @@ use schemars::JsonSchema;
 use thiserror::Error;
 use tracing_util::{ErrorVisibility, SpanVisibility, TraceableError};
 
+use crate::cache::{cache_ttl, IdentityCacheKey, IDENTITY_CACHE};
+
+/// The timeout of requests to the auth hook, unless it is configured.
+const DEFAULT_AUTH_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Error in converting the header value corresponding to the {header_name} to a String - {error}")]
@@ -49,6 +54,10 @@ pub enum InternalError {
     ReqwestError(reqwest::Error),
     #[error("'x-hasura-role' session variable not found in the webhook response.")]
     RoleSessionVariableNotFound,
+    #[error(
+        "'x-hasura-role' session variable in the webhook response is expected to be a string."
+    )]
+    RoleSessionVariableShouldBeString,
 }
 
 impl TraceableError for InternalError {
@@ -117,7 +126,7 @@ where
     Url::parse(&buf).map_err(SerdeDeError::custom)
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq, Eq, Hash)]
 #[schemars(title = "AuthHookMethod")]
 pub enum AuthHookMethod {
     Get,
@@ -136,6 +145,42 @@ pub struct AuthHookConfig {
     pub url: Url,
     /// The HTTP method to be used to make the request to the auth hook.
     pub method: AuthHookMethod,
+    /// The timeout, in seconds, of the requests to the auth hook. Defaults to 60 seconds.
+    pub timeout: Option<u64>,
+    /// The client headers to forward to the auth hook. All of them are forwarded by default.
+    pub headers: Option<AuthHookHeadersConfig>,
+    /// Whether to send the GraphQL query and operation name of the request to the auth hook,
+    /// in the `request` field of the body. Only applies to the `Post` method.
+    #[serde(default)]
+    pub include_operation: bool,
+    /// How long, in seconds, to cache the identity returned by the auth hook for a set of
+    /// credentials when its response has no `Cache-Control` header. Identities are only cached
+    /// according to the `Cache-Control` header by default.
+    pub cache_ttl: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[schemars(title = "AuthHookHeadersConfig")]
+/// Which of the client headers are forwarded to the auth hook. Header names are matched
+/// case-insensitively.
+pub struct AuthHookHeadersConfig {
+    /// If set, only these headers are forwarded.
+    pub allow: Option<Vec<String>>,
+    /// These headers are never forwarded.
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl AuthHookHeadersConfig {
+    fn forwards(&self, header_name: &HeaderName) -> bool {
+        let matches = |name: &String| name.eq_ignore_ascii_case(header_name.as_str());
+        self.allow
+            .as_ref()
+            .map_or(true, |allowed| allowed.iter().any(matches))
+            && !self.deny.iter().any(matches)
+    }
 }
 
 impl AuthHookConfig {
@@ -152,10 +197,21 @@ impl AuthHookConfig {
     }
 }
 
+/// The GraphQL operation of the request being authenticated, which is sent to the auth hook
+/// when `includeOperation` is set.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthHookOperation {
+    pub query: String,
+    pub operation_name: Option<String>,
+}
+
 /// The body of the POST request to be made to the Auth hook.
 #[derive(Serialize, Debug)]
-struct AuthHookPostRequestBody {
+struct AuthHookPostRequestBody<'a> {
     headers: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request: Option<&'a AuthHookOperation>,
 }
 
 async fn make_auth_hook_request(
@@ -165,24 +221,62 @@ async fn make_auth_hook_request(
     auth_hook_config: &AuthHookConfig,
     client_headers: &HeaderMap,
     allow_role_emulation_for: Option<&Role>,
+    operation: Option<&AuthHookOperation>,
 ) -> Result<auth_base::Identity, Error> {
     let tracer = tracing_util::global_tracer();
+    let forwarded_headers: Vec<(&HeaderName, &HeaderValue)> = client_headers
+        .iter()
+        .filter(|(header_name, _)| {
+            auth_hook_config
+                .headers
+                .as_ref()
+                .map_or(true, |headers_config| headers_config.forwards(header_name))
+                && (auth_hook_config.method == AuthHookMethod::Post
+                    || !COMMON_CLIENT_HEADERS_TO_IGNORE.contains(header_name.as_str()))
+        })
+        .collect();
+    let operation = match auth_hook_config.method {
+        AuthHookMethod::Post if auth_hook_config.include_operation => operation,
+        AuthHookMethod::Get | AuthHookMethod::Post => None,
+    };
+
+    let cache_key = IdentityCacheKey {
+        url: auth_hook_config.url.clone(),
+        method: auth_hook_config.method.clone(),
+        headers: {
+            let mut headers: Vec<(String, Vec<u8>)> = forwarded_headers
+                .iter()
+                .map(|(header_name, header_value)| {
+                    (header_name.to_string(), header_value.as_bytes().to_vec())
+                })
+                .collect();
+            headers.sort();
+            headers
+        },
+        operation: operation.cloned(),
+        allow_role_emulation_for: allow_role_emulation_for.cloned(),
+    };
+    if let Some(identity) = IDENTITY_CACHE.get(&cache_key) {
+        return Ok(identity);
+    }
+
+    let timeout = auth_hook_config
+        .timeout
+        .map_or(DEFAULT_AUTH_HOOK_TIMEOUT, Duration::from_secs);
     let http_request_builder = match auth_hook_config.method {
         AuthHookMethod::Get => {
             let mut auth_hook_headers = tracing_util::get_trace_headers();
-            for (header_name, header_value) in client_headers {
-                if !COMMON_CLIENT_HEADERS_TO_IGNORE.contains(header_name.as_str()) {
-                    auth_hook_headers.insert(header_name, header_value.clone());
-                }
+            for (header_name, header_value) in forwarded_headers {
+                auth_hook_headers.insert(header_name, header_value.clone());
             }
             http_client
                 .get(auth_hook_config.url.clone())
                 .headers(auth_hook_headers)
-                .timeout(Duration::from_secs(60))
+                .timeout(timeout)
         }
         AuthHookMethod::Post => {
             let mut auth_hook_headers = HashMap::new();
-            for (header_name, header_value) in client_headers {
+            for (header_name, header_value) in forwarded_headers {
                 auth_hook_headers.insert(
                     header_name.to_string(),
                     header_value
@@ -196,12 +290,13 @@ async fn make_auth_hook_request(
             }
             let request_body = AuthHookPostRequestBody {
                 headers: auth_hook_headers,
+                request: operation,
             };
             http_client
                 .post(auth_hook_config.url.clone())
                 .headers(tracing_util::get_trace_headers())
                 .json(&request_body)
-                .timeout(Duration::from_secs(60))
+                .timeout(timeout)
         }
     };
 
@@ -228,14 +323,18 @@ async fn make_auth_hook_request(
     match response.status() {
         reqwest::StatusCode::UNAUTHORIZED => Err(Error::AuthenticationFailed),
         reqwest::StatusCode::OK => {
-            let auth_hook_response: HashMap<String, String> =
+            let ttl = cache_ttl(
+                response.headers(),
+                auth_hook_config.cache_ttl.map(Duration::from_secs),
+            );
+            let auth_hook_response: HashMap<String, serde_json::Value> =
                 response.json().await.map_err(InternalError::ReqwestError)?;
             let mut session_variables = HashMap::new();
-            for (k, v) in &auth_hook_response {
-                match SessionVariable::from_str(k) {
+            for (k, v) in auth_hook_response {
+                match SessionVariable::from_str(&k) {
                     Ok(session_variable) => {
                         session_variables
-                            .insert(session_variable, SessionVariableValue(v.to_string()));
+                            .insert(session_variable, SessionVariableValue::from_json(v));
                     }
                     Err(_e) => {}
                 }
@@ -244,8 +343,8 @@ async fn make_auth_hook_request(
                 session_variables
                     .get(&session_variables::SESSION_VARIABLE_ROLE)
                     .ok_or(InternalError::RoleSessionVariableNotFound)?
-                    .0
-                    .as_str(),
+                    .as_str()
+                    .ok_or(InternalError::RoleSessionVariableShouldBeString)?,
             );
             let role_authorization = RoleAuthorization {
                 role: role.clone(),
@@ -257,7 +356,7 @@ async fn make_auth_hook_request(
             let mut allowed_roles = HashMap::new();
             allowed_roles.insert(role.clone(), role_authorization);
 
-            Ok(match allow_role_emulation_for {
+            let identity = match allow_role_emulation_for {
                 Some(emulation_role) => {
                     if role == *emulation_role {
                         Identity::RoleEmulationEnabled(role)
@@ -272,14 +371,19 @@ async fn make_auth_hook_request(
                     default_role: role,
                     allowed_roles,
                 },
-            })
+            };
+            if !ttl.is_zero() {
+                IDENTITY_CACHE.insert(cache_key, identity.clone(), ttl);
+            }
+            Ok(identity)
         }
         status_code => Err(InternalError::AuthHookUnexpectedStatus(status_code))?,
     }
 }
 
 /// Makes the HTTP request to the auth hook. The webhook
-/// is authenticates the request based on the client headers.
+/// is authenticates the request based on the client headers,
+/// and the GraphQL operation if `includeOperation` is set.
 pub async fn authenticate_request(
     // HTTP client that needs to be passed to make the
     // HTTP request to the auth hook.
@@ -287,6 +391,7 @@ pub async fn authenticate_request(
     auth_hook_config: &AuthHookConfig,
     client_headers: &HeaderMap,
     allow_role_emulation_for: Option<&Role>,
+    operation: Option<&AuthHookOperation>,
 ) -> Result<auth_base::Identity, Error> {
     let tracer = tracing_util::global_tracer();
     tracer
@@ -300,6 +405,7 @@ pub async fn authenticate_request(
                     auth_hook_config,
                     client_headers,
                     allow_role_emulation_for,
+                    operation,
                 ))
             },
         )
@@ -347,7 +453,7 @@ mod tests {
         client_headers.insert("foo", "baz".parse().unwrap());
 
         let auth_response =
-            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None)
+            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None, None)
                 .await
                 .unwrap();
 
@@ -418,7 +524,7 @@ mod tests {
         client_headers.insert("foo", "baz".parse().unwrap());
 
         let auth_response =
-            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None)
+            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None, None)
                 .await
                 .unwrap();
 
@@ -490,7 +596,7 @@ mod tests {
         client_headers.insert("foo", "baz".parse().unwrap());
 
         let auth_response =
-            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None)
+            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None, None)
                 .await
                 .unwrap();
 
@@ -569,6 +675,7 @@ mod tests {
             &auth_hook_config,
             &client_headers,
             Some(&Role::new("test-admin-role")),
+            None,
         )
         .await
         .unwrap();
@@ -618,6 +725,7 @@ mod tests {
             &auth_hook_config,
             &client_headers,
             Some(&Role::new("test-admin-role")),
+            None,
         )
         .await
         .unwrap();
@@ -684,7 +792,8 @@ mod tests {
         client_headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
 
         let auth_response =
-            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None).await;
+            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None, None)
+                .await;
 
         mock.assert(); // Make sure the webhook has been called.
 
@@ -694,6 +803,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    /// A role which isn't a string is rejected rather than ignored
+    async fn test_webhook_returning_non_string_role() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        // Create a mock
+        let mock = server
+            .mock("POST", "/validate-request")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"x-hasura-role": ["user"], "x-hasura-user-id": "1"}"#)
+            .create();
+
+        let http_client = reqwest::Client::new();
+
+        let webhook_url = url + "/validate-request";
+
+        let auth_hook_config_str =
+            format!("{{ \"url\": \"{webhook_url}\", \"method\": \"Post\"  }}");
+
+        let auth_hook_config: AuthHookConfig = serde_json::from_str(&auth_hook_config_str).unwrap();
+
+        let mut client_headers = HeaderMap::new();
+        client_headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        let auth_response =
+            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None, None)
+                .await;
+
+        mock.assert(); // Make sure the webhook has been called.
+
+        assert_eq!(
+            auth_response.unwrap_err().to_string(),
+            "Internal Error - 'x-hasura-role' session variable in the webhook response is expected to be a string."
+        );
+    }
+
     #[tokio::test]
     /// Test HTTP status codes returned by the webhook,
     /// other than 200 and 401 are not recognized.
@@ -734,7 +883,8 @@ mod tests {
         client_headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
 
         let auth_response =
-            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None).await;
+            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None, None)
+                .await;
 
         mock.assert(); // Make sure the webhook has been called.
 
@@ -748,4 +898,98 @@ mod tests {
             "Only 200 and 401 response status are recognized"
         );
     }
+
+    #[tokio::test]
+    // This test checks that the identity returned by the webhook is cached according to its
+    // `Cache-Control` header
+    async fn test_webhook_identity_is_cached() {
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        let mock = server
+            .mock("POST", "/validate-cached-request")
+            .match_body(r#"{"headers":{"foo":"cached"}}"#)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("cache-control", "max-age=60")
+            .with_body(r#"{"x-hasura-role": "test-role"}"#)
+            .expect(1)
+            .create();
+
+        let http_client = reqwest::Client::new();
+
+        let webhook_url = url + "/validate-cached-request";
+
+        let auth_hook_config_str =
+            format!("{{ \"url\": \"{webhook_url}\", \"method\": \"Post\"  }}");
+
+        let auth_hook_config: AuthHookConfig = serde_json::from_str(&auth_hook_config_str).unwrap();
+
+        let mut client_headers = HeaderMap::new();
+        client_headers.insert("foo", "cached".parse().unwrap());
+
+        let auth_response =
+            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None, None)
+                .await
+                .unwrap();
+        let cached_auth_response =
+            make_auth_hook_request(&http_client, &auth_hook_config, &client_headers, None, None)
+                .await
+                .unwrap();
+
+        mock.assert(); // Make sure the webhook has been called only once.
+        assert_eq!(auth_response, cached_auth_response);
+    }
+
+    #[tokio::test]
+    // This test checks that only the configured headers, and the operation, are sent to the
+    // webhook
+    async fn test_webhook_request_shaping() {
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        let mock = server
+            .mock("POST", "/validate-request")
+            .match_body(
+                r#"{"headers":{"foo":"baz"},"request":{"query":"query A { a }","operationName":"A"}}"#,
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"x-hasura-role": "test-role"}"#)
+            .create();
+
+        let http_client = reqwest::Client::new();
+
+        let auth_hook_config: AuthHookConfig = serde_json::from_value(serde_json::json!({
+            "url": url + "/validate-request",
+            "method": "Post",
+            "timeout": 5,
+            "headers": { "allow": ["Foo", "Bar"], "deny": ["bar"] },
+            "includeOperation": true
+        }))
+        .unwrap();
+
+        let mut client_headers = HeaderMap::new();
+        client_headers.insert("foo", "baz".parse().unwrap());
+        client_headers.insert("bar", "denied".parse().unwrap());
+        client_headers.insert("baz", "not-allowed".parse().unwrap());
+
+        let operation = AuthHookOperation {
+            query: "query A { a }".to_string(),
+            operation_name: Some("A".to_string()),
+        };
+        let auth_response = make_auth_hook_request(
+            &http_client,
+            &auth_hook_config,
+            &client_headers,
+            None,
+            Some(&operation),
+        )
+        .await;
+
+        mock.assert(); // Make sure the webhook has been called.
+        assert!(auth_response.is_ok());
+    }
 }
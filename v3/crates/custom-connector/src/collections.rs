@@ -9,6 +9,7 @@ use crate::{
 
 pub mod actors;
 pub mod actors_by_movie;
+pub mod countries;
 pub mod institutions;
 pub mod movies;
 pub mod movies_by_actor_name;
@@ -20,6 +21,7 @@ pub(crate) fn get_collections() -> Vec<ndc_models::CollectionInfo> {
         institutions::collection_info(),
         actors_by_movie::collection_info(),
         movies_by_actor_name::collection_info(),
+        countries::collection_info(),
     ]
 }
 
@@ -34,6 +36,7 @@ pub(crate) fn get_collection_by_name(
         "institutions" => institutions::rows(state),
         "actors_by_movie" => actors_by_movie::rows(arguments, state),
         "movies_by_actor_name" => movies_by_actor_name::rows(arguments, state),
+        "countries" => countries::rows(state),
         _ => super::functions::get_function_by_name(collection_name, arguments, state),
     }
 }
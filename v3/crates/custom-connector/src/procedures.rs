@@ -3,12 +3,18 @@ use std::collections::BTreeMap;
 use axum::{http::StatusCode, Json};
 use ndc_models;
 
-use crate::{query::Result, state::AppState};
+use crate::{
+    query::{eval_expression, eval_nested_field, Result},
+    state::{AppState, Row},
+};
 
 pub mod add_movie_with_genres;
+pub mod delete_actors_by_id;
+pub mod insert_actors;
 pub mod login;
 pub mod noop_procedure;
 pub mod update_actor_name_by_id;
+pub mod update_actors_by_id;
 pub mod uppercase_actor_name_by_id;
 pub mod uppercase_all_actor_names;
 pub mod uppercase_all_actor_names_return_names_list;
@@ -21,6 +27,9 @@ pub(crate) fn get_procedures() -> Vec<ndc_models::ProcedureInfo> {
         login::procedure_info(),
         noop_procedure::procedure_info(),
         add_movie_with_genres::procedure_info(),
+        insert_actors::procedure_info(),
+        update_actors_by_id::procedure_info(),
+        delete_actors_by_id::procedure_info(),
         // TODO: Looks like the other procedures where never added to the schema?
     ]
 }
@@ -51,6 +60,15 @@ pub(crate) fn execute_procedure(
         "add_movie_with_genres" => {
             add_movie_with_genres::execute(arguments, fields, collection_relationships, state)
         }
+        "insert_actors" => {
+            insert_actors::execute(arguments, fields, collection_relationships, state)
+        }
+        "update_actors_by_id" => {
+            update_actors_by_id::execute(arguments, fields, collection_relationships, state)
+        }
+        "delete_actors_by_id" => {
+            delete_actors_by_id::execute(arguments, fields, collection_relationships, state)
+        }
         _ => Err((
             StatusCode::BAD_REQUEST,
             Json(ndc_models::ErrorResponse {
@@ -60,3 +78,89 @@ pub(crate) fn execute_procedure(
         )),
     }
 }
+
+/// Parse the `id` of an actor from an argument value
+pub(crate) fn parse_actor_id(argument_name: &str, id: &serde_json::Value) -> Result<i32> {
+    id.as_i64()
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            Json(ndc_models::ErrorResponse {
+                message: format!("argument '{argument_name}' is not an integer"),
+                details: serde_json::Value::Null,
+            }),
+        ))?
+        .try_into()
+        .map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ndc_models::ErrorResponse {
+                    message: format!("argument '{argument_name}' is out of range"),
+                    details: serde_json::Value::Null,
+                }),
+            )
+        })
+}
+
+/// Whether a row satisfies the predicate given in an argument
+pub(crate) fn eval_predicate_argument(
+    arguments: &BTreeMap<String, serde_json::Value>,
+    argument_name: &str,
+    collection_relationships: &BTreeMap<String, ndc_models::Relationship>,
+    state: &AppState,
+    row: &Row,
+) -> Result<bool> {
+    let predicate = arguments.get(argument_name).ok_or((
+        StatusCode::BAD_REQUEST,
+        Json(ndc_models::ErrorResponse {
+            message: format!("required argument '{argument_name}' is missing"),
+            details: serde_json::Value::Null,
+        }),
+    ))?;
+    let expression: ndc_models::Expression =
+        serde_json::from_value(predicate.clone()).map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ndc_models::ErrorResponse {
+                    message: format!("argument '{argument_name}' is not a valid predicate"),
+                    details: serde_json::Value::Null,
+                }),
+            )
+        })?;
+    eval_expression(
+        collection_relationships,
+        &BTreeMap::new(),
+        state,
+        &expression,
+        row,
+        row,
+    )
+}
+
+/// Select the requested fields of a row returned by a procedure
+pub(crate) fn project_row(
+    row: &Row,
+    fields: &Option<ndc_models::NestedField>,
+    collection_relationships: &BTreeMap<String, ndc_models::Relationship>,
+    state: &AppState,
+) -> Result<serde_json::Value> {
+    let row_value = serde_json::to_value(row).map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ndc_models::ErrorResponse {
+                message: "cannot encode response".into(),
+                details: serde_json::Value::Null,
+            }),
+        )
+    })?;
+    let row_fields = match fields {
+        None => Ok(ndc_models::RowFieldValue(row_value)),
+        Some(nested_field) => eval_nested_field(
+            collection_relationships,
+            &BTreeMap::new(),
+            state,
+            row_value,
+            nested_field,
+        ),
+    }?;
+    Ok(row_fields.0)
+}
@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use axum::{http::StatusCode, Json};
+use ndc_models;
+
+use super::{eval_predicate_argument, parse_actor_id, project_row};
+use crate::{query::Result, state::AppState};
+
+pub(crate) fn procedure_info() -> ndc_models::ProcedureInfo {
+    ndc_models::ProcedureInfo {
+        name: "delete_actors_by_id".into(),
+        description: Some("Delete an actor given its ID".into()),
+        arguments: BTreeMap::from_iter([
+            (
+                "key_id".into(),
+                ndc_models::ArgumentInfo {
+                    description: Some("the id of the actor to delete".into()),
+                    argument_type: ndc_models::Type::Named { name: "Int".into() },
+                },
+            ),
+            (
+                "pre_check".into(),
+                ndc_models::ArgumentInfo {
+                    description: Some("the predicate the actor must satisfy to be deleted".into()),
+                    argument_type: ndc_models::Type::Predicate {
+                        object_type_name: "actor".into(),
+                    },
+                },
+            ),
+        ]),
+        result_type: ndc_models::Type::Nullable {
+            underlying_type: Box::new(ndc_models::Type::Named {
+                name: "actor".into(),
+            }),
+        },
+    }
+}
+
+pub(crate) fn execute(
+    arguments: &BTreeMap<String, serde_json::Value>,
+    fields: &Option<ndc_models::NestedField>,
+    collection_relationships: &BTreeMap<String, ndc_models::Relationship>,
+    state: &mut AppState,
+) -> Result<serde_json::Value> {
+    let id = arguments.get("key_id").ok_or((
+        StatusCode::BAD_REQUEST,
+        Json(ndc_models::ErrorResponse {
+            message: "required argument 'key_id' is missing".into(),
+            details: serde_json::Value::Null,
+        }),
+    ))?;
+    let id_int = parse_actor_id("key_id", id)?;
+
+    // an actor which does not satisfy the pre check can't be deleted, as if it didn't exist
+    let Some(old_row) = state.actors.get(&id_int) else {
+        return Ok(serde_json::Value::Null);
+    };
+    if !eval_predicate_argument(
+        arguments,
+        "pre_check",
+        collection_relationships,
+        state,
+        old_row,
+    )? {
+        return Ok(serde_json::Value::Null);
+    }
+
+    let old_row = state.actors.remove(&id_int).unwrap_or_default();
+    project_row(&old_row, fields, collection_relationships, state)
+}
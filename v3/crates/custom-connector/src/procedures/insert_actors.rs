@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use axum::{http::StatusCode, Json};
+use ndc_models;
+
+use super::{eval_predicate_argument, parse_actor_id, project_row};
+use crate::{query::Result, state::AppState};
+
+pub(crate) fn procedure_info() -> ndc_models::ProcedureInfo {
+    ndc_models::ProcedureInfo {
+        name: "insert_actors".into(),
+        description: Some("Insert an actor".into()),
+        arguments: BTreeMap::from_iter([
+            (
+                "object".into(),
+                ndc_models::ArgumentInfo {
+                    description: Some("The actor to insert".into()),
+                    argument_type: ndc_models::Type::Named {
+                        name: "actor".into(),
+                    },
+                },
+            ),
+            (
+                "post_check".into(),
+                ndc_models::ArgumentInfo {
+                    description: Some("The predicate the inserted actor must satisfy".into()),
+                    argument_type: ndc_models::Type::Predicate {
+                        object_type_name: "actor".into(),
+                    },
+                },
+            ),
+        ]),
+        result_type: ndc_models::Type::Nullable {
+            underlying_type: Box::new(ndc_models::Type::Named {
+                name: "actor".into(),
+            }),
+        },
+    }
+}
+
+pub(crate) fn execute(
+    arguments: &BTreeMap<String, serde_json::Value>,
+    fields: &Option<ndc_models::NestedField>,
+    collection_relationships: &BTreeMap<String, ndc_models::Relationship>,
+    state: &mut AppState,
+) -> Result<serde_json::Value> {
+    let actor_obj = arguments
+        .get("object")
+        .and_then(serde_json::Value::as_object)
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            Json(ndc_models::ErrorResponse {
+                message: "required argument 'object' is missing or not an object".into(),
+                details: serde_json::Value::Null,
+            }),
+        ))?;
+    let id = actor_obj.get("id").ok_or((
+        StatusCode::BAD_REQUEST,
+        Json(ndc_models::ErrorResponse {
+            message: "required argument field 'id' is missing".into(),
+            details: serde_json::Value::Null,
+        }),
+    ))?;
+    let id_int = parse_actor_id("id", id)?;
+    if state.actors.contains_key(&id_int) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ndc_models::ErrorResponse {
+                message: format!("an actor with id {id_int} already exists"),
+                details: serde_json::Value::Null,
+            }),
+        ));
+    }
+
+    let new_row = actor_obj
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect::<BTreeMap<_, _>>();
+    if !eval_predicate_argument(
+        arguments,
+        "post_check",
+        collection_relationships,
+        state,
+        &new_row,
+    )? {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ndc_models::ErrorResponse {
+                message: "the inserted actor does not satisfy the post check".into(),
+                details: serde_json::Value::Null,
+            }),
+        ));
+    }
+    state.actors.insert(id_int, new_row.clone());
+    project_row(&new_row, fields, collection_relationships, state)
+}
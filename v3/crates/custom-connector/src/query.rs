@@ -820,7 +820,7 @@ fn eval_relationship_argument(
     }
 }
 
-fn eval_expression(
+pub(crate) fn eval_expression(
     collection_relationships: &BTreeMap<String, ndc_models::Relationship>,
     variables: &BTreeMap<String, serde_json::Value>,
     state: &AppState,
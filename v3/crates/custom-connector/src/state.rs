@@ -5,11 +5,13 @@ pub type Row = BTreeMap<String, serde_json::Value>;
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub actors: BTreeMap<i32, Row>,
+    pub countries: BTreeMap<i32, Row>,
     pub institutions: BTreeMap<i32, Row>,
     pub movies: BTreeMap<i32, Row>,
 }
 
 const ACTORS_JSON: &str = include_str!("../data/actors.json");
+const COUNTRIES_JSON: &str = include_str!("../data/countries.json");
 const INSTITUTIONS_JSON: &str = include_str!("../data/institutions.json");
 const MOVIES_JSON: &str = include_str!("../data/movies.json");
 
@@ -33,10 +35,12 @@ fn read_json_lines(json: &str) -> anyhow::Result<BTreeMap<i32, Row>> {
 
 pub fn init_app_state() -> anyhow::Result<AppState> {
     let actors = read_json_lines(ACTORS_JSON)?;
+    let countries = read_json_lines(COUNTRIES_JSON)?;
     let institutions = read_json_lines(INSTITUTIONS_JSON)?;
     let movies = read_json_lines(MOVIES_JSON)?;
     Ok(AppState {
         actors,
+        countries,
         institutions,
         movies,
     })
@@ -2,6 +2,7 @@ use ndc_models;
 use std::collections::BTreeMap;
 
 pub mod actor;
+pub mod country;
 pub mod genre;
 pub mod institution;
 pub mod location;
@@ -121,5 +122,6 @@ pub(crate) fn object_types() -> BTreeMap<String, ndc_models::ObjectType> {
         ("location".into(), location::definition()),
         ("staff_member".into(), staff_member::definition()),
         ("login_response".into(), login::definition()),
+        ("country".into(), country::definition()),
     ])
 }
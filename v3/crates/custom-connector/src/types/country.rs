@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+
+use ndc_models;
+
+pub(crate) fn definition() -> ndc_models::ObjectType {
+    ndc_models::ObjectType {
+        description: Some("A country".into()),
+        fields: BTreeMap::from_iter([
+            (
+                "id".into(),
+                ndc_models::ObjectField {
+                    description: Some("The country's primary key".into()),
+                    r#type: ndc_models::Type::Named { name: "Int".into() },
+                    arguments: BTreeMap::new(),
+                },
+            ),
+            (
+                "name".into(),
+                ndc_models::ObjectField {
+                    description: Some("The country's name".into()),
+                    r#type: ndc_models::Type::Named {
+                        name: "String".into(),
+                    },
+                    arguments: BTreeMap::new(),
+                },
+            ),
+            (
+                "capital".into(),
+                ndc_models::ObjectField {
+                    description: Some("The country's capital".into()),
+                    r#type: ndc_models::Type::Named {
+                        name: "String".into(),
+                    },
+                    arguments: BTreeMap::new(),
+                },
+            ),
+        ]),
+    }
+}
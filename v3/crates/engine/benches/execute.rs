@@ -1,7 +1,10 @@
 use core::time::Duration;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
 use execute::{execute_mutation_plan, execute_query_plan, generate_request_plan};
-use execute::{execute_query_internal, generate_ir, HttpContext};
+use execute::{
+    execute_query_internal, generate_ir, ConnectTimeoutClients, HttpContext, RemoteJoinOptions,
+    RetryPolicy,
+};
 use hasura_authn_core::Identity;
 use lang_graphql::http::RawRequest;
 use open_dds::permissions::Role;
@@ -52,6 +55,10 @@ pub fn bench_execute(
     let http_context = HttpContext {
         client: reqwest::Client::new(),
         ndc_response_size_limit: None,
+        ndc_request_timeout: None,
+        ndc_retry_policy: RetryPolicy::default(),
+        ndc_connect_timeout_clients: ConnectTimeoutClients::default(),
+        remote_join_options: RemoteJoinOptions::default(),
     };
     let runtime = Runtime::new().unwrap();
 
@@ -60,6 +67,7 @@ pub fn bench_execute(
         operation_name: None,
         query,
         variables: None,
+        extensions: None,
     };
 
     let request_headers = reqwest::header::HeaderMap::new();
@@ -179,6 +187,7 @@ pub fn bench_execute(
                     &request_headers,
                     request.clone(),
                     None,
+                    None,
                 )
                 .await
                 .unwrap()
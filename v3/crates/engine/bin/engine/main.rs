@@ -1,10 +1,12 @@
 use std::fmt::Display;
 use std::net;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
-    body::HttpBody,
+    body::{Bytes, HttpBody},
     extract::{DefaultBodyLimit, State},
     http::{HeaderMap, Request},
     middleware::Next,
@@ -23,9 +25,10 @@ use tracing_util::{
 use base64::engine::Engine;
 use engine::authentication::{AuthConfig, AuthConfig::V1 as V1AuthConfig, AuthModeConfig};
 use engine::internal_flags::{resolve_unstable_features, UnstableFeature};
+use engine::persisted_queries::{self, Allowlist, AllowlistConfig, PersistedQueryStore};
 use engine::VERSION;
-use execute::HttpContext;
-use hasura_authn_core::Session;
+use execute::{HttpContext, QueryCache};
+use hasura_authn_core::{Identity, Role, Session};
 use hasura_authn_jwt::auth as jwt_auth;
 use hasura_authn_jwt::jwt;
 use hasura_authn_webhook::webhook;
@@ -35,11 +38,26 @@ use std::hash;
 use std::hash::{Hash, Hasher};
 
 mod cors;
+mod reload;
+mod subscriptions;
+
+use reload::ReloadableEngineState;
 
 const DEFAULT_PORT: u16 = 3000;
 
+const DEFAULT_WATCH_INTERVAL_SECONDS: u64 = 5;
+
+const DEFAULT_SUBSCRIPTION_POLL_INTERVAL_MILLISECONDS: u64 = 1000;
+
+const DEFAULT_NDC_MAX_RETRIES: u32 = 0;
+
+const DEFAULT_NDC_RETRY_BACKOFF_MILLISECONDS: u64 = 100;
+
 const MB: usize = 1_048_576;
 
+/// The maximum size of a request body.
+const MAX_REQUEST_BODY_SIZE: usize = 10 * MB;
+
 #[derive(Parser)]
 #[command(version = VERSION)]
 struct ServerOptions {
@@ -82,12 +100,164 @@ struct ServerOptions {
         value_delimiter = ','
     )]
     unstable_features: Vec<UnstableFeature>,
+    /// How often, in seconds, to check the metadata and auth config files for changes. Set to 0
+    /// to disable file watching; the engine still reloads both files on SIGHUP.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        env = "METADATA_WATCH_INTERVAL",
+        default_value_t = DEFAULT_WATCH_INTERVAL_SECONDS
+    )]
+    metadata_watch_interval: u64,
+    /// How often, in milliseconds, subscriptions re-execute their query to check for changes.
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        env = "SUBSCRIPTION_POLL_INTERVAL",
+        default_value_t = DEFAULT_SUBSCRIPTION_POLL_INTERVAL_MILLISECONDS,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    subscription_poll_interval: u64,
+    /// The path to a file listing the operations allowed to be executed, optionally scoped to
+    /// roles. If provided, all other operations are rejected.
+    #[arg(long, value_name = "PATH", env = "ALLOWLIST_PATH")]
+    allowlist_path: Option<PathBuf>,
+    /// The maximum number of automatic persisted queries held in memory, beyond which the least
+    /// recently used queries are evicted. Set to 0 to disable persisting queries.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "MAX_PERSISTED_QUERIES",
+        default_value_t = persisted_queries::DEFAULT_MAX_PERSISTED_QUERIES
+    )]
+    max_persisted_queries: usize,
+    /// The maximum number of parsed queries kept in memory, so that queries sent repeatedly
+    /// are not parsed again. Set to 0 to disable the cache.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "QUERY_CACHE_SIZE",
+        default_value_t = execute::DEFAULT_QUERY_CACHE_SIZE
+    )]
+    query_cache_size: usize,
+    /// Timeout, in milliseconds, for establishing a connection to a data connector. Data
+    /// connectors may override it in their `requestOptions`.
+    #[arg(long, value_name = "MILLISECONDS", env = "NDC_CONNECT_TIMEOUT")]
+    ndc_connect_timeout: Option<u64>,
+    /// Timeout, in milliseconds, for a complete request to a data connector, including reading
+    /// the response. Data connectors may override it in their `requestOptions`.
+    #[arg(long, value_name = "MILLISECONDS", env = "NDC_REQUEST_TIMEOUT")]
+    ndc_request_timeout: Option<u64>,
+    /// The maximum size, in bytes, of a response from a data connector. Data connectors may
+    /// override it in their `requestOptions`.
+    #[arg(long, value_name = "BYTES", env = "NDC_RESPONSE_SIZE_LIMIT")]
+    ndc_response_size_limit: Option<usize>,
+    /// The maximum number of times a query to a data connector is retried after a connection
+    /// error or a server error. Mutations are never retried. Requests are not retried by default.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "NDC_MAX_RETRIES",
+        default_value_t = DEFAULT_NDC_MAX_RETRIES
+    )]
+    ndc_max_retries: u32,
+    /// The delay, in milliseconds, before retrying a request to a data connector, which doubles
+    /// for every subsequent retry.
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        env = "NDC_RETRY_BACKOFF",
+        default_value_t = DEFAULT_NDC_RETRY_BACKOFF_MILLISECONDS
+    )]
+    ndc_retry_backoff: u64,
+    /// The maximum number of rows whose remote relationship is fetched in a single request to a
+    /// data connector. Larger remote joins are split into several requests.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "REMOTE_JOIN_CHUNK_SIZE",
+        default_value_t = execute::RemoteJoinOptions::default().chunk_size
+    )]
+    remote_join_chunk_size: NonZeroUsize,
+    /// The maximum number of requests made to a data connector at once for a single remote join.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "REMOTE_JOIN_MAX_CONCURRENT_CHUNKS",
+        default_value_t = execute::RemoteJoinOptions::default().max_concurrent_chunks
+    )]
+    remote_join_max_concurrent_chunks: NonZeroUsize,
+    /// The maximum number of rows the engine sorts when ordering by fields of remote
+    /// relationships. Queries which would sort more rows fail.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "REMOTE_ORDER_BY_MAX_ROWS",
+        default_value_t = execute::RemoteJoinOptions::default().max_sorted_rows
+    )]
+    remote_order_by_max_rows: NonZeroUsize,
+    /// The maximum number of rows a predicate across a remote relationship may match in the
+    /// target model. Queries whose predicates match more rows fail.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        env = "REMOTE_PREDICATE_MAX_ROWS",
+        default_value_t = execute::RemoteJoinOptions::default().max_remote_predicate_rows
+    )]
+    remote_predicate_max_rows: NonZeroUsize,
+}
+
+/// Options for the requests made to data connectors. The timeouts, size limit and retries are
+/// defaults, which data connectors may override in metadata.
+#[derive(Clone, Copy)]
+struct HttpContextOptions {
+    connect_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    response_size_limit: Option<usize>,
+    retry_policy: execute::RetryPolicy,
+    remote_join_options: execute::RemoteJoinOptions,
+}
+
+impl HttpContextOptions {
+    fn new(server: &ServerOptions) -> Self {
+        Self {
+            connect_timeout: server.ndc_connect_timeout.map(Duration::from_millis),
+            request_timeout: server.ndc_request_timeout.map(Duration::from_millis),
+            response_size_limit: server.ndc_response_size_limit,
+            retry_policy: execute::RetryPolicy {
+                max_retries: server.ndc_max_retries,
+                initial_backoff: Duration::from_millis(server.ndc_retry_backoff),
+            },
+            remote_join_options: execute::RemoteJoinOptions {
+                chunk_size: server.remote_join_chunk_size,
+                max_concurrent_chunks: server.remote_join_max_concurrent_chunks,
+                max_sorted_rows: server.remote_order_by_max_rows,
+                max_remote_predicate_rows: server.remote_predicate_max_rows,
+            },
+        }
+    }
+
+    fn build_http_context(&self) -> Result<HttpContext, reqwest::Error> {
+        let mut client = reqwest::Client::builder();
+        if let Some(connect_timeout) = self.connect_timeout {
+            client = client.connect_timeout(connect_timeout);
+        }
+        Ok(HttpContext {
+            client: client.build()?,
+            ndc_response_size_limit: self.response_size_limit,
+            ndc_request_timeout: self.request_timeout,
+            ndc_retry_policy: self.retry_policy,
+            ndc_connect_timeout_clients: execute::ConnectTimeoutClients::default(),
+            remote_join_options: self.remote_join_options,
+        })
+    }
 }
 
 struct EngineState {
     http_context: HttpContext,
     schema: gql::schema::Schema<GDS>,
     auth_config: AuthConfig,
+    allowlist: Option<Allowlist>,
 }
 
 #[tokio::main]
@@ -157,6 +327,12 @@ enum StartupError {
     ReadAuth(anyhow::Error),
     #[error("could not read the schema - {0}")]
     ReadSchema(anyhow::Error),
+    #[error("could not read the allowlist - {0}")]
+    ReadAllowlist(anyhow::Error),
+    #[error("could not build the HTTP client - {0}")]
+    BuildHttpClient(reqwest::Error),
+    #[error("the reload task failed - {0}")]
+    ReloadTask(tokio::task::JoinError),
 }
 
 impl TraceableError for StartupError {
@@ -168,7 +344,7 @@ impl TraceableError for StartupError {
 /// The main router for the engine.
 struct EngineRouter {
     /// The base router for the engine.
-    /// Contains /, /graphql, /v1/explain and /health routes.
+    /// Contains /, /graphql, /graphql/ws, /v1/explain, /health and /metrics routes.
     base_router: Router,
     /// The metadata routes for the introspection metadata file.
     /// Contains /metadata and /metadata-hash routes.
@@ -178,9 +354,16 @@ struct EngineRouter {
 }
 
 impl EngineRouter {
-    fn new(state: Arc<EngineState>) -> Self {
+    fn new(
+        state: Arc<ReloadableEngineState>,
+        persisted_queries: Arc<PersistedQueryStore>,
+        query_cache: Arc<QueryCache>,
+        subscription_poll_interval: Duration,
+    ) -> Self {
         let graphql_route = Router::new()
             .route("/graphql", post(handle_request))
+            .layer(Extension(persisted_queries.clone()))
+            .layer(Extension(query_cache))
             .layer(axum::middleware::from_fn(
                 hasura_authn_core::resolve_session,
             ))
@@ -199,6 +382,7 @@ impl EngineRouter {
 
         let explain_route = Router::new()
             .route("/v1/explain", post(handle_explain_request))
+            .layer(Extension(persisted_queries.clone()))
             .layer(axum::middleware::from_fn(
                 hasura_authn_core::resolve_session,
             ))
@@ -213,21 +397,38 @@ impl EngineRouter {
             // BEFORE THE `explain_request_tracing_middleware`*
             // Refer to it for more details.
             .layer(TraceLayer::new_for_http())
-            .with_state(state);
+            .with_state(state.clone());
+
+        // Subscriptions authenticate once per connection, when the client initialises it, so the
+        // authentication middleware is not used here.
+        let subscriptions_route = Router::new()
+            .route("/graphql/ws", get(subscriptions::handle_websocket))
+            .layer(TraceLayer::new_for_http())
+            .with_state(Arc::new(subscriptions::SubscriptionState {
+                engine_state: state,
+                persisted_queries,
+                poll_interval: subscription_poll_interval,
+            }));
 
         let health_route = Router::new().route("/health", get(handle_health));
 
+        let metrics_route = Router::new().route("/metrics", get(handle_metrics));
+
         let base_routes = Router::new()
             // serve graphiql at root
             .route("/", get(graphiql))
             // The '/graphql' route
             .merge(graphql_route)
+            // The '/graphql/ws' route
+            .merge(subscriptions_route)
             // The '/v1/explain' route
             .merge(explain_route)
             // The '/health' route
             .merge(health_route)
+            // The '/metrics' route
+            .merge(metrics_route)
             // Set request payload limit to 10 MB
-            .layer(DefaultBodyLimit::max(10 * MB));
+            .layer(DefaultBodyLimit::max(MAX_REQUEST_BODY_SIZE));
 
         Self {
             base_router: base_routes,
@@ -276,26 +477,68 @@ impl EngineRouter {
     }
 }
 
-async fn start_engine(server: &ServerOptions) -> Result<(), StartupError> {
-    let auth_config =
-        read_auth_config(&server.authn_config_path).map_err(StartupError::ReadAuth)?;
-
-    let metadata_resolve_flags = resolve_unstable_features(&server.unstable_features);
-
-    let schema = read_schema(&server.metadata_path, &metadata_resolve_flags)
-        .map_err(StartupError::ReadSchema)?;
-
-    let http_context = HttpContext {
-        client: reqwest::Client::new(),
-        ndc_response_size_limit: None,
-    };
-    let state = Arc::new(EngineState {
+/// Reads the auth config and metadata from disk and builds the state used to serve requests.
+fn build_engine_state(
+    metadata_path: &PathBuf,
+    authn_config_path: &PathBuf,
+    allowlist_path: Option<&PathBuf>,
+    metadata_resolve_flags: &metadata_resolve::MetadataResolveFlagsInternal,
+    http_context_options: &HttpContextOptions,
+) -> Result<EngineState, StartupError> {
+    let auth_config = read_auth_config(authn_config_path).map_err(StartupError::ReadAuth)?;
+
+    let allowlist = allowlist_path
+        .map(read_allowlist)
+        .transpose()
+        .map_err(StartupError::ReadAllowlist)?;
+
+    let schema =
+        read_schema(metadata_path, metadata_resolve_flags).map_err(StartupError::ReadSchema)?;
+
+    let http_context = http_context_options
+        .build_http_context()
+        .map_err(StartupError::BuildHttpClient)?;
+    Ok(EngineState {
         http_context,
         schema,
         auth_config,
-    });
+        allowlist,
+    })
+}
+
+async fn start_engine(server: &ServerOptions) -> Result<(), StartupError> {
+    let metadata_resolve_flags = resolve_unstable_features(&server.unstable_features);
+    let http_context_options = HttpContextOptions::new(server);
+
+    let state = Arc::new(ReloadableEngineState::new(build_engine_state(
+        &server.metadata_path,
+        &server.authn_config_path,
+        server.allowlist_path.as_ref(),
+        &metadata_resolve_flags,
+        &http_context_options,
+    )?));
+
+    // Reload the metadata and auth config when either file changes, or on SIGHUP.
+    let reload_config = reload::ReloadConfig {
+        metadata_path: server.metadata_path.clone(),
+        authn_config_path: server.authn_config_path.clone(),
+        allowlist_path: server.allowlist_path.clone(),
+        metadata_resolve_flags,
+        http_context_options,
+        watch_interval: (server.metadata_watch_interval > 0)
+            .then_some(Duration::from_secs(server.metadata_watch_interval)),
+    };
+    tokio::spawn(reload::watch_for_changes(reload_config, state.clone()));
 
-    let mut engine_router = EngineRouter::new(state);
+    let persisted_queries = Arc::new(PersistedQueryStore::new(server.max_persisted_queries));
+    let query_cache = Arc::new(QueryCache::new(server.query_cache_size));
+
+    let mut engine_router = EngineRouter::new(
+        state,
+        persisted_queries,
+        query_cache,
+        Duration::from_millis(server.subscription_poll_interval),
+    );
 
     // If `--introspection-metadata` is specified we also serve the file indicated on `/metadata`
     // and its hash on `/metadata-hash`.
@@ -328,6 +571,25 @@ async fn handle_health() -> reqwest::StatusCode {
     reqwest::StatusCode::OK
 }
 
+/// Prometheus metrics endpoint
+async fn handle_metrics() -> axum::response::Response {
+    match metrics_util::global_metrics().encode() {
+        Ok(metrics) => (
+            [(axum::http::header::CONTENT_TYPE, metrics_util::CONTENT_TYPE)],
+            metrics,
+        )
+            .into_response(),
+        Err(e) => (reqwest::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Records the size of a response body, when it is known up front.
+fn observe_response_size(path: &str, response: &axum::response::Response) {
+    if let Some(size) = response.body().size_hint().exact() {
+        metrics_util::global_metrics().observe_response_size(path, size);
+    }
+}
+
 /// Middleware to start tracing of the `/graphql` request.
 /// This middleware must be active for the entire duration
 /// of the request i.e. this middleware should be the
@@ -349,6 +611,7 @@ async fn graphql_request_tracing_middleware<B: Send>(
                 set_attribute_on_active_span(AttributeVisibility::Internal, "version", VERSION);
                 Box::pin(async move {
                     let mut response = next.run(request).await;
+                    observe_response_size(path, &response);
                     TraceContextResponsePropagator::new().inject_context(
                         &Context::current(),
                         &mut HeaderInjector(response.headers_mut()),
@@ -380,6 +643,7 @@ async fn explain_request_tracing_middleware<B: Send>(
             || {
                 Box::pin(async move {
                     let response = next.run(request).await;
+                    observe_response_size(path, &response);
                     TraceableHttpResponse::new(response, path)
                 })
             },
@@ -394,6 +658,19 @@ enum AuthError {
     Jwt(#[from] jwt::Error),
     #[error("Webhook auth error: {0}")]
     Webhook(#[from] webhook::Error),
+    #[error("None of the authentication modes found credentials in the request")]
+    CredentialsNotFound,
+}
+
+impl AuthError {
+    /// The label used for this error in the auth failure metrics.
+    fn metric_label(&self) -> &'static str {
+        match self {
+            AuthError::Jwt(_) => "jwt",
+            AuthError::Webhook(_) => "webhook",
+            AuthError::CredentialsNotFound => "chain",
+        }
+    }
 }
 
 impl TraceableError for AuthError {
@@ -401,6 +678,7 @@ impl TraceableError for AuthError {
         match self {
             AuthError::Jwt(e) => e.visibility(),
             AuthError::Webhook(e) => e.visibility(),
+            AuthError::CredentialsNotFound => tracing_util::ErrorVisibility::User,
         }
     }
 }
@@ -410,6 +688,11 @@ impl IntoResponse for AuthError {
         match self {
             AuthError::Jwt(e) => e.into_response(),
             AuthError::Webhook(e) => e.into_response(),
+            AuthError::CredentialsNotFound => gql::http::Response::error_message_with_status(
+                axum::http::StatusCode::BAD_REQUEST,
+                self.to_string(),
+            )
+            .into_response(),
         }
     }
 }
@@ -417,18 +700,28 @@ impl IntoResponse for AuthError {
 /// This middleware authenticates the incoming GraphQL request according to the
 /// authentication configuration present in the `auth_config` of `EngineState`. The
 /// result of the authentication is `hasura-authn-core::Identity`, which is then
-/// made available to the GraphQL request handler.
+/// made available to the GraphQL request handler, along with the snapshot of the
+/// `EngineState` it was authenticated with, so that the request is executed against
+/// the same state even if it is reloaded in the meantime.
 async fn authentication_middleware<'a, B>(
-    State(engine_state): State<Arc<EngineState>>,
+    State(engine_state): State<Arc<ReloadableEngineState>>,
     headers_map: HeaderMap,
     mut request: Request<B>,
     next: Next<B>,
 ) -> axum::response::Result<axum::response::Response>
 where
-    B: HttpBody,
+    B: HttpBody<Data = Bytes> + Default + From<Bytes> + Unpin,
     B::Error: Display,
 {
     let tracer = tracing_util::global_tracer();
+    let engine_state = engine_state.current();
+
+    // Only read the body when the webhook authorizes requests by their operation.
+    let operation = if webhook_needs_operation(&engine_state, &headers_map) {
+        read_operation(&mut request).await?
+    } else {
+        None
+    };
 
     let resolved_identity = tracer
         .in_span_async(
@@ -436,48 +729,156 @@ where
             "Authentication middleware",
             SpanVisibility::Internal,
             || {
-                Box::pin(async {
-                    match &engine_state.auth_config {
-                        V1AuthConfig(auth_config) => match &auth_config.mode {
-                            AuthModeConfig::Webhook(webhook_config) => {
-                                webhook::authenticate_request(
-                                    &engine_state.http_context.client,
-                                    webhook_config,
-                                    &headers_map,
-                                    auth_config.allow_role_emulation_by.as_ref(),
-                                )
-                                .await
-                                .map_err(AuthError::from)
-                            }
-                            AuthModeConfig::Jwt(jwt_secret_config) => {
-                                jwt_auth::authenticate_request(
-                                    &engine_state.http_context.client,
-                                    *jwt_secret_config.clone(),
-                                    auth_config.allow_role_emulation_by.as_ref(),
-                                    &headers_map,
-                                )
-                                .await
-                                .map_err(AuthError::from)
-                            }
-                        },
-                    }
-                })
+                Box::pin(authenticate_request(
+                    &engine_state,
+                    &headers_map,
+                    operation.as_ref(),
+                ))
             },
         )
-        .await?;
+        .await
+        .inspect_err(|e| metrics_util::global_metrics().inc_auth_failure(e.metric_label()))?;
 
     request.extensions_mut().insert(resolved_identity);
+    request.extensions_mut().insert(engine_state);
     Ok(next.run(request).await)
 }
 
+/// Whether the request will be authenticated by a webhook which is sent the GraphQL operation.
+fn webhook_needs_operation(engine_state: &EngineState, headers_map: &HeaderMap) -> bool {
+    match &engine_state.auth_config {
+        V1AuthConfig(auth_config) => matches!(
+            auth_config.mode.select_mode(headers_map),
+            Some(AuthModeConfig::Webhook(webhook_config)) if webhook_config.include_operation
+        ),
+    }
+}
+
+/// Reads the GraphQL operation from the body of the request, and puts the body back for the
+/// request handler. A body which isn't a GraphQL request is left for the handler to reject.
+async fn read_operation<B>(
+    request: &mut Request<B>,
+) -> Result<Option<webhook::AuthHookOperation>, gql::http::Response>
+where
+    B: HttpBody<Data = Bytes> + Default + From<Bytes> + Unpin,
+    B::Error: Display,
+{
+    let mut body = std::mem::take(request.body_mut());
+    let mut buffer = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| {
+            gql::http::Response::error_message_with_status(
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("Error reading the request body: {e}"),
+            )
+        })?;
+        if buffer.len() + chunk.len() > MAX_REQUEST_BODY_SIZE {
+            return Err(gql::http::Response::error_message_with_status(
+                axum::http::StatusCode::PAYLOAD_TOO_LARGE,
+                "length limit exceeded".to_string(),
+            ));
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    let operation = serde_json::from_slice::<gql::http::RawRequest>(&buffer)
+        .ok()
+        .map(|raw_request| webhook::AuthHookOperation {
+            query: raw_request.query,
+            operation_name: raw_request.operation_name.map(|name| name.to_string()),
+        });
+    *request.body_mut() = B::from(Bytes::from(buffer));
+    Ok(operation)
+}
+
+/// Authenticates a request with the given headers according to the authentication
+/// configuration present in the `auth_config` of `EngineState`.
+async fn authenticate_request(
+    engine_state: &EngineState,
+    headers_map: &HeaderMap,
+    operation: Option<&webhook::AuthHookOperation>,
+) -> Result<Identity, AuthError> {
+    match &engine_state.auth_config {
+        V1AuthConfig(auth_config) => match (
+            auth_config.mode.select_mode(headers_map),
+            &auth_config.unauthenticated_role,
+        ) {
+            (Some(mode), _) => {
+                authenticate_request_with_mode(
+                    engine_state,
+                    mode,
+                    auth_config.allow_role_emulation_by.as_ref(),
+                    headers_map,
+                    operation,
+                )
+                .await
+            }
+            (None, Some(unauthenticated_role)) => Ok(unauthenticated_role.identity()),
+            // Let the mode report which credentials are missing.
+            (None, None) => {
+                authenticate_request_with_mode(
+                    engine_state,
+                    &auth_config.mode,
+                    auth_config.allow_role_emulation_by.as_ref(),
+                    headers_map,
+                    operation,
+                )
+                .await
+            }
+        },
+    }
+}
+
+async fn authenticate_request_with_mode(
+    engine_state: &EngineState,
+    mode: &AuthModeConfig,
+    allow_role_emulation_by: Option<&Role>,
+    headers_map: &HeaderMap,
+    operation: Option<&webhook::AuthHookOperation>,
+) -> Result<Identity, AuthError> {
+    match mode {
+        AuthModeConfig::Webhook(webhook_config) => webhook::authenticate_request(
+            &engine_state.http_context.client,
+            webhook_config,
+            headers_map,
+            allow_role_emulation_by,
+            operation,
+        )
+        .await
+        .map_err(AuthError::from),
+        AuthModeConfig::Jwt(jwt_secret_config) => jwt_auth::authenticate_request(
+            &engine_state.http_context.client,
+            *jwt_secret_config.clone(),
+            allow_role_emulation_by,
+            headers_map,
+        )
+        .await
+        .map_err(AuthError::from),
+        AuthModeConfig::MultipleJwt(jwt_configs) => {
+            jwt_auth::authenticate_request_with_jwt_configs(
+                &engine_state.http_context.client,
+                jwt_configs,
+                allow_role_emulation_by,
+                headers_map,
+            )
+            .await
+            .map_err(AuthError::from)
+        }
+        // A chain is only authenticated with directly when none of its modes found
+        // credentials in the request.
+        AuthModeConfig::Chain(_) => Err(AuthError::CredentialsNotFound),
+    }
+}
+
 async fn graphiql() -> Html<&'static str> {
     Html(include_str!("index.html"))
 }
 
 async fn handle_request(
     headers: axum::http::header::HeaderMap,
-    State(state): State<Arc<EngineState>>,
+    Extension(state): Extension<Arc<EngineState>>,
     Extension(session): Extension<Session>,
+    Extension(persisted_queries): Extension<Arc<PersistedQueryStore>>,
+    Extension(query_cache): Extension<Arc<QueryCache>>,
     Json(request): Json<gql::http::RawRequest>,
 ) -> gql::http::Response {
     let tracer = tracing_util::global_tracer();
@@ -487,14 +888,35 @@ async fn handle_request(
             "Handle request",
             SpanVisibility::User,
             || {
-                Box::pin(execute::execute_query(
-                    &state.http_context,
-                    &state.schema,
-                    &session,
-                    &headers,
-                    request,
-                    None,
-                ))
+                Box::pin(async {
+                    // Persisted queries are resolved, and the allowlist enforced, before the
+                    // query is parsed.
+                    match persisted_queries::resolve_request(
+                        &persisted_queries,
+                        state.allowlist.as_ref(),
+                        &session.role,
+                        request,
+                    ) {
+                        Ok(request) => {
+                            execute::execute_query(
+                                &state.http_context,
+                                &state.schema,
+                                &session,
+                                &headers,
+                                request,
+                                None,
+                                Some(&query_cache),
+                            )
+                            .await
+                        }
+                        Err(e) => execute::GraphQLResponse(
+                            gql::http::Response::error_message_with_status(
+                                axum::http::StatusCode::OK,
+                                e.to_string(),
+                            ),
+                        ),
+                    }
+                })
             },
         )
         .await;
@@ -511,8 +933,9 @@ async fn handle_request(
 
 async fn handle_explain_request(
     headers: axum::http::header::HeaderMap,
-    State(state): State<Arc<EngineState>>,
+    Extension(state): Extension<Arc<EngineState>>,
     Extension(session): Extension<Session>,
+    Extension(persisted_queries): Extension<Arc<PersistedQueryStore>>,
     Json(request): Json<gql::http::RawRequest>,
 ) -> execute::ExplainResponse {
     let tracer = tracing_util::global_tracer();
@@ -522,13 +945,30 @@ async fn handle_explain_request(
             "Handle explain request",
             SpanVisibility::User,
             || {
-                Box::pin(execute::execute_explain(
-                    &state.http_context,
-                    &state.schema,
-                    &session,
-                    &headers,
-                    request,
-                ))
+                Box::pin(async {
+                    match persisted_queries::resolve_request(
+                        &persisted_queries,
+                        state.allowlist.as_ref(),
+                        &session.role,
+                        request,
+                    ) {
+                        Ok(request) => {
+                            execute::execute_explain(
+                                &state.http_context,
+                                &state.schema,
+                                &session,
+                                &headers,
+                                request,
+                            )
+                            .await
+                        }
+                        Err(e) => execute::ExplainResponse::error(gql::http::GraphQLError {
+                            message: e.to_string(),
+                            path: None,
+                            extensions: None,
+                        }),
+                    }
+                })
             },
         )
         .await;
@@ -552,7 +992,14 @@ fn read_schema(
 
 fn read_auth_config(path: &PathBuf) -> Result<AuthConfig, anyhow::Error> {
     let raw_auth_config = std::fs::read_to_string(path)?;
-    Ok(open_dds::traits::OpenDd::deserialize(
-        serde_json::from_str(&raw_auth_config)?,
-    )?)
+    let auth_config: AuthConfig =
+        open_dds::traits::OpenDd::deserialize(serde_json::from_str(&raw_auth_config)?)?;
+    auth_config.validate()?;
+    Ok(auth_config)
+}
+
+fn read_allowlist(path: &PathBuf) -> Result<Allowlist, anyhow::Error> {
+    let raw_allowlist = std::fs::read_to_string(path)?;
+    let config: AllowlistConfig = serde_json::from_str(&raw_allowlist)?;
+    Ok(Allowlist::new(config))
 }
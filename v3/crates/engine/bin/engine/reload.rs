@@ -0,0 +1,320 @@
+//! Hot-reloading of the metadata and auth config.
+//!
+//! The engine state is kept behind a lock and swapped out wholesale when the metadata, auth
+//! config or allowlist files change on disk, or when the process receives a SIGHUP. The
+//! authentication middleware takes a snapshot (an `Arc`) of the state when a request starts and
+//! hands it on to the request handler, so a request is authenticated and executed against the
+//! same state, and in-flight requests keep running against the schema they started with while
+//! new requests see the new one.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use tracing_util::{add_event_on_active_span, SpanVisibility};
+
+use crate::{build_engine_state, EngineState, HttpContextOptions, StartupError};
+use metadata_resolve::MetadataResolveFlagsInternal;
+
+/// The engine state currently being served, which can be atomically replaced.
+pub struct ReloadableEngineState {
+    current: RwLock<Arc<EngineState>>,
+}
+
+impl ReloadableEngineState {
+    pub fn new(state: EngineState) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(state)),
+        }
+    }
+
+    /// A snapshot of the engine state currently being served. The snapshot stays valid for as
+    /// long as it is held, even if the state is replaced in the meantime.
+    pub fn current(&self) -> Arc<EngineState> {
+        sync_ext::read(&self.current).clone()
+    }
+
+    fn replace(&self, state: EngineState) {
+        *sync_ext::write(&self.current) = Arc::new(state);
+    }
+}
+
+/// Everything needed to rebuild the engine state from disk.
+pub struct ReloadConfig {
+    pub metadata_path: PathBuf,
+    pub authn_config_path: PathBuf,
+    pub allowlist_path: Option<PathBuf>,
+    pub metadata_resolve_flags: MetadataResolveFlagsInternal,
+    pub http_context_options: HttpContextOptions,
+    /// How often to check the files for changes. `None` disables file watching.
+    pub watch_interval: Option<Duration>,
+}
+
+/// Rebuilds the engine state from disk and swaps it in. If the new metadata or auth config
+/// cannot be read or resolved, the error is logged and the previous state is kept.
+///
+/// Reading the files and resolving the metadata is blocking work, so it runs on the blocking
+/// thread pool rather than holding up requests on the async workers.
+async fn reload_engine_state(config: &Arc<ReloadConfig>, state: &ReloadableEngineState) {
+    let tracer = tracing_util::global_tracer();
+    let result = tracer
+        .in_span_async(
+            "reload_engine_state",
+            "Reload metadata and auth config",
+            SpanVisibility::Internal,
+            || {
+                Box::pin(async {
+                    let config = config.clone();
+                    let new_state = tokio::task::spawn_blocking(move || {
+                        build_engine_state(
+                            &config.metadata_path,
+                            &config.authn_config_path,
+                            config.allowlist_path.as_ref(),
+                            &config.metadata_resolve_flags,
+                            &config.http_context_options,
+                        )
+                    })
+                    .await
+                    .map_err(StartupError::ReloadTask)??;
+                    state.replace(new_state);
+                    let log = "reloaded metadata and auth config".to_string();
+                    println!("{log}");
+                    add_event_on_active_span(log);
+                    Ok::<(), StartupError>(())
+                })
+            },
+        )
+        .await;
+    if let Err(e) = result {
+        println!("Error while reloading the engine, continuing with the previous metadata: {e}");
+    }
+}
+
+/// The last modification times of the watched files, used to detect changes.
+#[derive(PartialEq, Eq)]
+struct Fingerprint {
+    metadata: Option<SystemTime>,
+    auth_config: Option<SystemTime>,
+    allowlist: Option<SystemTime>,
+}
+
+impl Fingerprint {
+    fn read(config: &ReloadConfig) -> Self {
+        Self {
+            metadata: modified_time(&config.metadata_path),
+            auth_config: modified_time(&config.authn_config_path),
+            allowlist: config.allowlist_path.as_deref().and_then(modified_time),
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Reloads the engine state whenever the watched files change or a SIGHUP is received.
+/// This future never completes, and is intended to be spawned as a background task.
+pub async fn watch_for_changes(config: ReloadConfig, state: Arc<ReloadableEngineState>) {
+    let config = Arc::new(config);
+    let mut last_seen = Fingerprint::read(&config);
+
+    let mut interval = config.watch_interval.map(|period| {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        interval
+    });
+
+    #[cfg(unix)]
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .expect("failed to install SIGHUP handler");
+
+    loop {
+        let file_tick = async {
+            match interval.as_mut() {
+                Some(interval) => {
+                    interval.tick().await;
+                }
+                None => std::future::pending::<()>().await,
+            }
+        };
+
+        #[cfg(unix)]
+        let signal = async {
+            hangup.recv().await;
+        };
+        #[cfg(not(unix))]
+        let signal = std::future::pending::<()>();
+
+        tokio::select! {
+            () = file_tick => {
+                let fingerprint = Fingerprint::read(&config);
+                if fingerprint == last_seen {
+                    continue;
+                }
+                last_seen = fingerprint;
+            }
+            () = signal => {
+                last_seen = Fingerprint::read(&config);
+            }
+        }
+
+        reload_engine_state(&config, &state).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use engine::persisted_queries::PersistedQueryStore;
+    use execute::QueryCache;
+    use json_value_merge::Merge;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::EngineRouter;
+
+    /// An auth webhook, which authenticates every request as `admin`, and a data connector,
+    /// which always returns the same author.
+    fn serve_mock_services() -> SocketAddr {
+        let router = Router::new()
+            .route(
+                "/auth",
+                get(|| async { Json(json!({"x-hasura-role": "admin"})) }),
+            )
+            .route(
+                "/query",
+                post(|| async {
+                    Json(json!([{
+                        "rows": [{"author_id": 1, "first_name": "Peter", "last_name": "Landin"}]
+                    }]))
+                }),
+            );
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(router.into_make_service());
+        let address = server.local_addr();
+        tokio::spawn(server);
+        address
+    }
+
+    fn read_json(path: &Path) -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// Metadata which serves the author with id 1 on the given root field
+    fn metadata(mock_services: SocketAddr, root_field: &str) -> String {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/execute");
+        let mut metadata =
+            read_json(&tests_dir.join("models/select_one/simple_select/metadata.json"));
+        metadata.merge(&read_json(
+            &tests_dir.join("common_metadata/postgres_connector_schema.json"),
+        ));
+        for subgraph in metadata["subgraphs"].as_array_mut().unwrap() {
+            for object in subgraph["objects"].as_array_mut().unwrap() {
+                if object["kind"] == "DataConnectorLink" {
+                    object["definition"]["url"] =
+                        json!({"singleUrl": {"value": format!("http://{mock_services}")}});
+                }
+            }
+        }
+        metadata
+            .to_string()
+            .replace("\"AuthorByID\"", &format!("\"{root_field}\""))
+    }
+
+    async fn query_author(engine: SocketAddr, root_field: &str) -> serde_json::Value {
+        reqwest::Client::new()
+            .post(format!("http://{engine}/graphql"))
+            .json(&json!({
+                "query": format!("query {{ {root_field}(author_id: 1) {{ author_id first_name }} }}")
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reload_serves_new_metadata_to_new_requests() {
+        let mock_services = serve_mock_services();
+        let dir = std::env::temp_dir().join(format!("engine-reload-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = Arc::new(ReloadConfig {
+            metadata_path: dir.join("metadata.json"),
+            authn_config_path: dir.join("auth_config.json"),
+            allowlist_path: None,
+            metadata_resolve_flags: MetadataResolveFlagsInternal {
+                enable_boolean_expression_types: true,
+            },
+            http_context_options: HttpContextOptions {
+                connect_timeout: None,
+                request_timeout: None,
+                response_size_limit: None,
+                retry_policy: execute::RetryPolicy::default(),
+                remote_join_options: execute::RemoteJoinOptions::default(),
+            },
+            watch_interval: None,
+        });
+        std::fs::write(
+            &config.authn_config_path,
+            json!({
+                "version": "v1",
+                "definition": {
+                    "mode": {
+                        "webhook": {"url": format!("http://{mock_services}/auth"), "method": "Get"}
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        std::fs::write(&config.metadata_path, metadata(mock_services, "AuthorByID")).unwrap();
+
+        let state = Arc::new(ReloadableEngineState::new(
+            build_engine_state(
+                &config.metadata_path,
+                &config.authn_config_path,
+                None,
+                &config.metadata_resolve_flags,
+                &config.http_context_options,
+            )
+            .unwrap(),
+        ));
+        let router = EngineRouter::new(
+            state.clone(),
+            Arc::new(PersistedQueryStore::new(0)),
+            Arc::new(QueryCache::new(0)),
+            Duration::from_secs(1),
+        );
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(router.into_make_service());
+        let engine = server.local_addr();
+        tokio::spawn(server);
+
+        let author = json!({"author_id": 1, "first_name": "Peter"});
+        assert_eq!(
+            query_author(engine, "AuthorByID").await,
+            json!({"data": {"AuthorByID": author}})
+        );
+
+        std::fs::write(
+            &config.metadata_path,
+            metadata(mock_services, "AuthorByKey"),
+        )
+        .unwrap();
+        reload_engine_state(&config, &state).await;
+
+        assert_eq!(
+            query_author(engine, "AuthorByKey").await,
+            json!({"data": {"AuthorByKey": author}})
+        );
+        assert!(query_author(engine, "AuthorByID").await["errors"].is_array());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
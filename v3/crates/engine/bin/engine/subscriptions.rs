@@ -0,0 +1,699 @@
+//! GraphQL subscriptions over WebSocket, using the `graphql-transport-ws` protocol.
+//!
+//! <https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md>
+//!
+//! The connection is authenticated once, when the client sends `connection_init`, using the
+//! headers of the upgrade request together with any `headers` sent in the `connection_init`
+//! payload. Every `subscribe` message then starts a live query which is re-executed on an
+//! interval, pushing a `next` message whenever its result changes.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{HeaderMap, HeaderName, HeaderValue},
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::reload::ReloadableEngineState;
+use crate::{authenticate_request, EngineState};
+use engine::persisted_queries::{self, PersistedQueryStore};
+use lang_graphql as gql;
+
+/// The WebSocket sub-protocol implemented by this module.
+const GRAPHQL_TRANSPORT_WS_PROTOCOL: &str = "graphql-transport-ws";
+
+/// How long a client has to send `connection_init` after opening the connection.
+const CONNECTION_INIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Close codes defined by the `graphql-transport-ws` protocol.
+mod close_code {
+    pub const BAD_REQUEST: u16 = 4400;
+    pub const UNAUTHORIZED: u16 = 4401;
+    pub const FORBIDDEN: u16 = 4403;
+    pub const CONNECTION_INIT_TIMEOUT: u16 = 4408;
+    pub const SUBSCRIBER_ALREADY_EXISTS: u16 = 4409;
+    pub const TOO_MANY_INITIALISATION_REQUESTS: u16 = 4429;
+}
+
+/// State shared by all subscription connections.
+pub struct SubscriptionState {
+    pub engine_state: Arc<ReloadableEngineState>,
+    pub persisted_queries: Arc<PersistedQueryStore>,
+    /// How often live queries are re-executed.
+    pub poll_interval: Duration,
+}
+
+/// Messages sent by the client.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    ConnectionInit {
+        payload: Option<ConnectionInitPayload>,
+    },
+    Ping {
+        payload: Option<serde_json::Value>,
+    },
+    Pong {},
+    Subscribe {
+        id: String,
+        payload: gql::http::RawRequest,
+    },
+    Complete {
+        id: String,
+    },
+}
+
+#[derive(Deserialize, Default)]
+struct ConnectionInitPayload {
+    /// Headers to authenticate the connection with, in addition to those of the upgrade request.
+    /// Browsers cannot set headers on WebSocket requests, so clients send them here instead.
+    #[serde(default)]
+    headers: HashMap<String, String>,
+}
+
+/// Messages sent by the server.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    ConnectionAck,
+    Pong {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        payload: Option<serde_json::Value>,
+    },
+    Next {
+        id: String,
+        payload: gql::http::Response,
+    },
+    Error {
+        id: String,
+        payload: Vec<gql::http::GraphQLError>,
+    },
+    Complete {
+        id: String,
+    },
+}
+
+impl ServerMessage {
+    fn into_ws_message(self) -> Message {
+        // serializing these messages cannot fail: all keys are strings
+        Message::Text(serde_json::to_string(&self).unwrap_or_default())
+    }
+}
+
+/// Handles the `/graphql/ws` route, upgrading the connection to a WebSocket.
+pub async fn handle_websocket(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    State(state): State<Arc<SubscriptionState>>,
+) -> axum::response::Response {
+    ws.protocols([GRAPHQL_TRANSPORT_WS_PROTOCOL])
+        .on_upgrade(move |socket| serve_connection(socket, headers, state))
+}
+
+fn close_message(code: u16, reason: impl Into<String>) -> Message {
+    Message::Close(Some(CloseFrame {
+        code,
+        reason: reason.into().into(),
+    }))
+}
+
+/// Waits for `connection_init` and authenticates the connection, returning the session and
+/// headers that all subscriptions on this connection run with. On failure, the close message
+/// to send to the client is returned instead.
+async fn initialise_connection(
+    socket: &mut WebSocket,
+    mut headers: HeaderMap,
+    engine_state: &EngineState,
+) -> Result<(hasura_authn_core::Session, HeaderMap), Message> {
+    let payload = loop {
+        let message = match tokio::time::timeout(CONNECTION_INIT_TIMEOUT, socket.recv()).await {
+            Err(_) => {
+                return Err(close_message(
+                    close_code::CONNECTION_INIT_TIMEOUT,
+                    "Connection initialisation timeout",
+                ))
+            }
+            Ok(None | Some(Err(_) | Ok(Message::Close(_)))) => {
+                return Err(close_message(1000, "Connection closed"))
+            }
+            Ok(Some(Ok(message))) => message,
+        };
+        match parse_client_message(message)? {
+            None | Some(ClientMessage::Pong {}) => {}
+            Some(ClientMessage::Ping { payload }) => {
+                let _ = socket
+                    .send(ServerMessage::Pong { payload }.into_ws_message())
+                    .await;
+            }
+            Some(ClientMessage::ConnectionInit { payload }) => break payload.unwrap_or_default(),
+            Some(ClientMessage::Subscribe { .. } | ClientMessage::Complete { .. }) => {
+                return Err(close_message(close_code::UNAUTHORIZED, "Unauthorized"))
+            }
+        }
+    };
+
+    for (name, value) in payload.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::from_str(&name), HeaderValue::from_str(&value))
+        {
+            headers.insert(name, value);
+        }
+    }
+
+    let identity = authenticate_request(engine_state, &headers, None)
+        .await
+        .map_err(|e| close_message(close_code::FORBIDDEN, format!("Forbidden: {e}")))?;
+    let session = hasura_authn_core::authorize_identity(&identity, &headers)
+        .map_err(|e| close_message(close_code::FORBIDDEN, format!("Forbidden: {e}")))?;
+    Ok((session, headers))
+}
+
+/// Parses a client message. Binary frames and control frames are ignored, and malformed
+/// messages are answered with the close message to send to the client.
+fn parse_client_message(message: Message) -> Result<Option<ClientMessage>, Message> {
+    match message {
+        Message::Text(text) => serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| close_message(close_code::BAD_REQUEST, format!("Invalid message: {e}"))),
+        Message::Binary(_) | Message::Ping(_) | Message::Pong(_) | Message::Close(_) => Ok(None),
+    }
+}
+
+async fn serve_connection(
+    mut socket: WebSocket,
+    headers: HeaderMap,
+    state: Arc<SubscriptionState>,
+) {
+    let (session, headers) =
+        match initialise_connection(&mut socket, headers, &state.engine_state.current()).await {
+            Ok(initialised) => initialised,
+            Err(close) => {
+                let _ = socket.send(close).await;
+                return;
+            }
+        };
+    let session = Arc::new(session);
+    let headers = Arc::new(headers);
+
+    if socket
+        .send(ServerMessage::ConnectionAck.into_ws_message())
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    // Subscription tasks send their messages here, to be forwarded to the socket.
+    let (outgoing_sender, mut outgoing_receiver) = mpsc::channel::<ServerMessage>(32);
+    let mut subscriptions: HashMap<String, JoinHandle<()>> = HashMap::new();
+
+    let close = loop {
+        tokio::select! {
+            Some(message) = outgoing_receiver.recv() => {
+                if socket.send(message.into_ws_message()).await.is_err() {
+                    break None;
+                }
+            }
+            message = socket.recv() => {
+                let message = match message {
+                    None | Some(Err(_) | Ok(Message::Close(_))) => break None,
+                    Some(Ok(message)) => message,
+                };
+                match parse_client_message(message) {
+                    Err(close) => break Some(close),
+                    Ok(None | Some(ClientMessage::Pong {})) => {}
+                    Ok(Some(ClientMessage::Ping { payload })) => {
+                        if socket.send(ServerMessage::Pong { payload }.into_ws_message()).await.is_err() {
+                            break None;
+                        }
+                    }
+                    Ok(Some(ClientMessage::ConnectionInit { .. })) => {
+                        break Some(close_message(
+                            close_code::TOO_MANY_INITIALISATION_REQUESTS,
+                            "Too many initialisation requests",
+                        ));
+                    }
+                    Ok(Some(ClientMessage::Subscribe { id, payload })) => {
+                        subscriptions.retain(|_, task| !task.is_finished());
+                        if subscriptions.contains_key(&id) {
+                            break Some(close_message(
+                                close_code::SUBSCRIBER_ALREADY_EXISTS,
+                                format!("Subscriber for {id} already exists"),
+                            ));
+                        }
+                        let engine_state = state.engine_state.current();
+                        // the allowlist applies to subscriptions just as it does to queries
+                        let payload = match persisted_queries::resolve_request(
+                            &state.persisted_queries,
+                            engine_state.allowlist.as_ref(),
+                            &session.role,
+                            payload,
+                        ) {
+                            Ok(payload) => payload,
+                            Err(e) => {
+                                let error = ServerMessage::Error {
+                                    id,
+                                    payload: vec![gql::http::GraphQLError {
+                                        message: e.to_string(),
+                                        path: None,
+                                        extensions: None,
+                                    }],
+                                };
+                                if socket.send(error.into_ws_message()).await.is_err() {
+                                    break None;
+                                }
+                                continue;
+                            }
+                        };
+                        let task = tokio::spawn(run_subscription(
+                            id.clone(),
+                            payload,
+                            engine_state,
+                            state.poll_interval,
+                            session.clone(),
+                            headers.clone(),
+                            outgoing_sender.clone(),
+                        ));
+                        subscriptions.insert(id, task);
+                    }
+                    Ok(Some(ClientMessage::Complete { id })) => {
+                        if let Some(task) = subscriptions.remove(&id) {
+                            task.abort();
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    for task in subscriptions.into_values() {
+        task.abort();
+    }
+    if let Some(close) = close {
+        let _ = socket.send(close).await;
+    }
+}
+
+/// Runs a single subscription until it completes, errors, or the client goes away.
+async fn run_subscription(
+    id: String,
+    request: gql::http::RawRequest,
+    engine_state: Arc<EngineState>,
+    poll_interval: Duration,
+    session: Arc<hasura_authn_core::Session>,
+    headers: Arc<HeaderMap>,
+    outgoing: mpsc::Sender<ServerMessage>,
+) {
+    let (response_sender, mut response_receiver) = mpsc::channel(1);
+
+    let execute = execute::execute_subscription(
+        &engine_state.http_context,
+        &engine_state.schema,
+        &session,
+        &headers,
+        request,
+        poll_interval,
+        response_sender,
+    );
+
+    let forward = async {
+        while let Some(response) = response_receiver.recv().await {
+            let message = to_server_message(id.clone(), response);
+            let is_error = matches!(message, ServerMessage::Error { .. });
+            if outgoing.send(message).await.is_err() {
+                return false;
+            }
+            // an `error` message terminates the operation, so no `complete` should follow it
+            if is_error {
+                return false;
+            }
+        }
+        true
+    };
+
+    let ((), send_complete) = tokio::join!(execute, forward);
+    if send_complete {
+        let _ = outgoing.send(ServerMessage::Complete { id }).await;
+    }
+}
+
+/// Responses without any data failed before execution started, and are reported with an `error`
+/// message. Everything else is a (possibly partial) result.
+fn to_server_message(id: String, response: gql::http::Response) -> ServerMessage {
+    match response {
+        gql::http::Response {
+            data: None,
+            errors: Some(errors),
+            ..
+        } => ServerMessage::Error {
+            id,
+            payload: errors.into(),
+        },
+        response => ServerMessage::Next {
+            id,
+            payload: response,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::path::{Path, PathBuf};
+
+    use axum::response::IntoResponse;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use futures_util::{SinkExt, StreamExt};
+    use json_value_merge::Merge;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest};
+
+    use super::*;
+
+    type Client = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    const AUTHOR_QUERY: &str = "AuthorByID(author_id: 1) { author_id first_name last_name }";
+
+    fn author_response() -> serde_json::Value {
+        json!({
+            "data": {
+                "AuthorByID": {"author_id": 1, "first_name": "Peter", "last_name": "Landin"}
+            }
+        })
+    }
+
+    fn serve(router: Router) -> SocketAddr {
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(router.into_make_service());
+        let address = server.local_addr();
+        tokio::spawn(server);
+        address
+    }
+
+    /// An auth webhook, which authenticates requests with `Authorization: Bearer valid` as
+    /// `admin`, and a data connector, which always returns the same author.
+    fn serve_mock_services() -> SocketAddr {
+        let router = Router::new()
+            .route(
+                "/auth",
+                get(|headers: HeaderMap| async move {
+                    if headers
+                        .get("authorization")
+                        .is_some_and(|value| value == "Bearer valid")
+                    {
+                        Json(json!({"x-hasura-role": "admin"})).into_response()
+                    } else {
+                        axum::http::StatusCode::UNAUTHORIZED.into_response()
+                    }
+                }),
+            )
+            .route(
+                "/query",
+                post(|| async {
+                    Json(json!([{
+                        "rows": [{"author_id": 1, "first_name": "Peter", "last_name": "Landin"}]
+                    }]))
+                }),
+            );
+        serve(router)
+    }
+
+    fn read_json(path: &Path) -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn engine_state(mock_services: SocketAddr) -> EngineState {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/execute");
+        let mut metadata =
+            read_json(&tests_dir.join("models/select_one/simple_select/metadata.json"));
+        metadata.merge(&read_json(
+            &tests_dir.join("common_metadata/postgres_connector_schema.json"),
+        ));
+        for subgraph in metadata["subgraphs"].as_array_mut().unwrap() {
+            for object in subgraph["objects"].as_array_mut().unwrap() {
+                if object["kind"] == "DataConnectorLink" {
+                    object["definition"]["url"] =
+                        json!({"singleUrl": {"value": format!("http://{mock_services}")}});
+                }
+            }
+        }
+        let schema = engine::build::build_schema(
+            open_dds::traits::OpenDd::deserialize(metadata).unwrap(),
+            &metadata_resolve::MetadataResolveFlagsInternal {
+                enable_boolean_expression_types: true,
+            },
+        )
+        .unwrap();
+
+        let auth_config = open_dds::traits::OpenDd::deserialize(json!({
+            "version": "v1",
+            "definition": {
+                "mode": {
+                    "webhook": {"url": format!("http://{mock_services}/auth"), "method": "Get"}
+                }
+            }
+        }))
+        .unwrap();
+
+        EngineState {
+            http_context: execute::HttpContext {
+                client: reqwest::Client::new(),
+                ndc_response_size_limit: None,
+                ndc_request_timeout: None,
+                ndc_retry_policy: execute::RetryPolicy::default(),
+                ndc_connect_timeout_clients: execute::ConnectTimeoutClients::default(),
+                remote_join_options: execute::RemoteJoinOptions::default(),
+            },
+            schema,
+            auth_config,
+            allowlist: None,
+        }
+    }
+
+    /// Serves subscriptions, backed by the mock services, returning the address of the engine.
+    fn serve_engine() -> SocketAddr {
+        let state = SubscriptionState {
+            engine_state: Arc::new(ReloadableEngineState::new(engine_state(
+                serve_mock_services(),
+            ))),
+            persisted_queries: Arc::new(PersistedQueryStore::new(
+                persisted_queries::DEFAULT_MAX_PERSISTED_QUERIES,
+            )),
+            poll_interval: Duration::from_millis(10),
+        };
+        serve(
+            Router::new()
+                .route("/graphql/ws", get(handle_websocket))
+                .with_state(Arc::new(state)),
+        )
+    }
+
+    async fn connect(engine: SocketAddr) -> Client {
+        let mut request = format!("ws://{engine}/graphql/ws")
+            .into_client_request()
+            .unwrap();
+        request.headers_mut().insert(
+            "sec-websocket-protocol",
+            HeaderValue::from_static(GRAPHQL_TRANSPORT_WS_PROTOCOL),
+        );
+        let (client, _response) = tokio_tungstenite::connect_async(request).await.unwrap();
+        client
+    }
+
+    async fn send(client: &mut Client, message: serde_json::Value) {
+        client
+            .send(tungstenite::Message::Text(message.to_string()))
+            .await
+            .unwrap();
+    }
+
+    /// The next message from the server, which must not close the connection.
+    async fn receive(client: &mut Client) -> serde_json::Value {
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(10), client.next())
+                .await
+                .expect("timed out waiting for a message");
+            match message {
+                Some(Ok(tungstenite::Message::Text(text))) => {
+                    return serde_json::from_str(&text).unwrap()
+                }
+                Some(Ok(tungstenite::Message::Close(frame))) => {
+                    panic!("connection closed: {frame:?}")
+                }
+                Some(Ok(_)) => {}
+                other => panic!("connection closed: {other:?}"),
+            }
+        }
+    }
+
+    /// The code with which the server closes the connection, which must be the next message.
+    async fn receive_close_code(client: &mut Client) -> u16 {
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(10), client.next())
+                .await
+                .expect("timed out waiting for the connection to close");
+            match message {
+                Some(Ok(tungstenite::Message::Close(Some(frame)))) => return frame.code.into(),
+                Some(Ok(tungstenite::Message::Text(text))) => panic!("unexpected message: {text}"),
+                Some(Ok(_)) => {}
+                other => panic!("connection closed without a close frame: {other:?}"),
+            }
+        }
+    }
+
+    async fn connect_and_initialise(engine: SocketAddr) -> Client {
+        let mut client = connect(engine).await;
+        send(
+            &mut client,
+            json!({
+                "type": "connection_init",
+                "payload": {"headers": {"Authorization": "Bearer valid"}}
+            }),
+        )
+        .await;
+        assert_eq!(
+            receive(&mut client).await,
+            json!({"type": "connection_ack"})
+        );
+        client
+    }
+
+    fn subscribe(id: &str, query: &str) -> serde_json::Value {
+        json!({"type": "subscribe", "id": id, "payload": {"query": query}})
+    }
+
+    #[tokio::test]
+    async fn test_connection_init_is_authenticated_with_payload_headers() {
+        let engine = serve_engine();
+        connect_and_initialise(engine).await;
+
+        let mut client = connect(engine).await;
+        send(
+            &mut client,
+            json!({
+                "type": "connection_init",
+                "payload": {"headers": {"Authorization": "Bearer invalid"}}
+            }),
+        )
+        .await;
+        assert_eq!(receive_close_code(&mut client).await, close_code::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_next_complete() {
+        let mut client = connect_and_initialise(serve_engine()).await;
+
+        send(
+            &mut client,
+            subscribe("1", &format!("subscription {{ {AUTHOR_QUERY} }}")),
+        )
+        .await;
+        assert_eq!(
+            receive(&mut client).await,
+            json!({"type": "next", "id": "1", "payload": author_response()})
+        );
+
+        // the result doesn't change between polls, so it isn't sent again, and nothing is sent
+        // once the client completes the subscription
+        send(&mut client, json!({"type": "complete", "id": "1"})).await;
+        send(&mut client, json!({"type": "ping"})).await;
+        assert_eq!(receive(&mut client).await, json!({"type": "pong"}));
+    }
+
+    #[tokio::test]
+    async fn test_query_is_completed_by_the_server() {
+        let mut client = connect_and_initialise(serve_engine()).await;
+
+        send(
+            &mut client,
+            subscribe("1", &format!("query {{ {AUTHOR_QUERY} }}")),
+        )
+        .await;
+        assert_eq!(
+            receive(&mut client).await,
+            json!({"type": "next", "id": "1", "payload": author_response()})
+        );
+        assert_eq!(
+            receive(&mut client).await,
+            json!({"type": "complete", "id": "1"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_operation_is_an_error() {
+        let mut client = connect_and_initialise(serve_engine()).await;
+
+        send(&mut client, subscribe("1", "subscription { UnknownField }")).await;
+        let message = receive(&mut client).await;
+        assert_eq!(message["type"], "error");
+        assert_eq!(message["id"], "1");
+        assert!(message["payload"][0]["message"].is_string());
+
+        // the error terminates the operation, but not the connection
+        send(&mut client, json!({"type": "ping"})).await;
+        assert_eq!(receive(&mut client).await, json!({"type": "pong"}));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_before_connection_init_is_unauthorized() {
+        let mut client = connect(serve_engine()).await;
+        send(
+            &mut client,
+            subscribe("1", &format!("subscription {{ {AUTHOR_QUERY} }}")),
+        )
+        .await;
+        assert_eq!(
+            receive_close_code(&mut client).await,
+            close_code::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_message_is_a_bad_request() {
+        let mut client = connect_and_initialise(serve_engine()).await;
+        client
+            .send(tungstenite::Message::Text("not a message".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            receive_close_code(&mut client).await,
+            close_code::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_second_connection_init_is_rejected() {
+        let mut client = connect_and_initialise(serve_engine()).await;
+        send(&mut client, json!({"type": "connection_init"})).await;
+        assert_eq!(
+            receive_close_code(&mut client).await,
+            close_code::TOO_MANY_INITIALISATION_REQUESTS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_subscription_id_is_rejected() {
+        let mut client = connect_and_initialise(serve_engine()).await;
+        let subscription = subscribe("1", &format!("subscription {{ {AUTHOR_QUERY} }}"));
+
+        send(&mut client, subscription.clone()).await;
+        assert_eq!(receive(&mut client).await["type"], "next");
+        send(&mut client, subscription).await;
+        assert_eq!(
+            receive_close_code(&mut client).await,
+            close_code::SUBSCRIBER_ALREADY_EXISTS
+        );
+    }
+}
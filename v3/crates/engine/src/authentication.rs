@@ -1,4 +1,9 @@
-use hasura_authn_core::Role;
+use std::collections::{HashMap, HashSet};
+
+use axum::http::HeaderMap;
+use hasura_authn_core::{
+    Identity, Role, RoleAuthorization, SessionVariable, SessionVariableList, SessionVariableValue,
+};
 use hasura_authn_jwt::jwt;
 use hasura_authn_webhook::webhook;
 use schemars::JsonSchema;
@@ -11,6 +16,93 @@ use serde::{Deserialize, Serialize};
 pub enum AuthModeConfig {
     Webhook(webhook::AuthHookConfig),
     Jwt(Box<jwt::JWTConfig>),
+    /// Accept JWTs from several issuers. The configs are tried in order, skipping those whose
+    /// token location is missing from the request or whose issuer doesn't match the token's.
+    MultipleJwt(Vec<jwt::JWTConfig>),
+    /// An ordered list of modes. The request is authenticated by the first mode whose
+    /// credentials are present in the request, for example JWT if there is a bearer token,
+    /// else the webhook.
+    Chain(Vec<AuthModeConfig>),
+}
+
+impl AuthModeConfig {
+    /// The mode which should authenticate a request with the given headers, which is the
+    /// first mode of a chain that finds its credentials in the request. Returns `None` if
+    /// none of the modes find their credentials.
+    ///
+    /// A webhook is always considered to have credentials, as only the webhook can tell.
+    pub fn select_mode(&self, headers: &HeaderMap) -> Option<&AuthModeConfig> {
+        match self {
+            AuthModeConfig::Webhook(_) => Some(self),
+            AuthModeConfig::Jwt(jwt_config) => {
+                jwt::has_authorization_token(&jwt_config.token_location, headers).then_some(self)
+            }
+            AuthModeConfig::MultipleJwt(jwt_configs) => jwt_configs
+                .iter()
+                .any(|jwt_config| jwt::has_authorization_token(&jwt_config.token_location, headers))
+                .then_some(self),
+            AuthModeConfig::Chain(modes) => modes.iter().find_map(|mode| mode.select_mode(headers)),
+        }
+    }
+}
+
+/// An auth config which can be parsed, but which can't authenticate requests.
+#[derive(Debug, thiserror::Error)]
+pub enum AuthConfigError {
+    #[error("multipleJwt needs at least one JWT config")]
+    EmptyMultipleJwt,
+}
+
+impl AuthModeConfig {
+    /// Checks that the mode, and any modes chained with it, can authenticate requests.
+    pub fn validate(&self) -> Result<(), AuthConfigError> {
+        match self {
+            AuthModeConfig::Webhook(_) | AuthModeConfig::Jwt(_) => Ok(()),
+            AuthModeConfig::MultipleJwt(jwt_configs) => {
+                if jwt_configs.is_empty() {
+                    Err(AuthConfigError::EmptyMultipleJwt)
+                } else {
+                    Ok(())
+                }
+            }
+            AuthModeConfig::Chain(modes) => modes.iter().try_for_each(AuthModeConfig::validate),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[schemars(title = "UnauthenticatedRoleConfig")]
+/// The role, and its session variables, used for requests without any credentials.
+pub struct UnauthenticatedRoleConfig {
+    /// The role of requests without credentials.
+    pub role: Role,
+    /// The session variables of requests without credentials.
+    #[serde(default)]
+    pub session_variables: HashMap<SessionVariable, SessionVariableValue>,
+}
+
+open_dds::impl_OpenDd_default_for!(UnauthenticatedRoleConfig);
+
+impl UnauthenticatedRoleConfig {
+    /// The identity of an unauthenticated request, which can only use the unauthenticated
+    /// role, and can't set any session variables of its own.
+    pub fn identity(&self) -> Identity {
+        Identity::Specific {
+            default_role: self.role.clone(),
+            allowed_roles: HashMap::from([(
+                self.role.clone(),
+                RoleAuthorization {
+                    role: self.role.clone(),
+                    session_variables: self.session_variables.clone(),
+                    allowed_session_variables_from_request: SessionVariableList::Some(
+                        HashSet::new(),
+                    ),
+                },
+            )]),
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Clone, JsonSchema, PartialEq, opendds_derive::OpenDd, Deserialize)]
@@ -33,6 +125,18 @@ pub enum AuthConfig {
 pub struct AuthConfigV1 {
     pub allow_role_emulation_by: Option<Role>,
     pub mode: AuthModeConfig,
+    /// The role used for requests which have no credentials for any of the authentication
+    /// modes. Requests without credentials are rejected if this isn't set.
+    pub unauthenticated_role: Option<UnauthenticatedRoleConfig>,
+}
+
+impl AuthConfig {
+    /// Checks that the config can authenticate requests, beyond what parsing it checks.
+    pub fn validate(&self) -> Result<(), AuthConfigError> {
+        match self {
+            AuthConfig::V1(auth_config) => auth_config.mode.validate(),
+        }
+    }
 }
 
 impl AuthConfigV1 {
@@ -99,6 +203,72 @@ mod tests {
         assert_eq!(auth_config_from_json, auth_config_from_serialized);
     }
 
+    #[test]
+    fn test_chain_selects_first_mode_with_credentials() {
+        let mode: super::AuthModeConfig = serde_json::from_value(serde_json::json!({
+            "chain": [
+                {
+                    "jwt": {
+                        "key": {
+                            "fixed": { "algorithm": "HS256", "key": { "value": "token" } }
+                        },
+                        "tokenLocation": { "type": "BearerAuthorization" },
+                        "claimsConfig": {
+                            "namespace": {
+                                "claimsFormat": "Json",
+                                "location": "/https:~1~1hasura.io~1jwt~1claims"
+                            }
+                        }
+                    }
+                },
+                {
+                    "webhook": {
+                        "url": "http://auth_hook:3050/validate-request",
+                        "method": "Post"
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+        let super::AuthModeConfig::Chain(modes) = &mode else {
+            panic!("expected a chain of modes");
+        };
+
+        let mut headers = axum::http::HeaderMap::new();
+        assert_eq!(mode.select_mode(&headers), Some(&modes[1]));
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            "Bearer token".parse().unwrap(),
+        );
+        assert_eq!(mode.select_mode(&headers), Some(&modes[0]));
+    }
+
+    #[test]
+    fn test_empty_multiple_jwt_is_rejected() {
+        let auth_config: super::AuthConfig =
+            open_dds::traits::OpenDd::deserialize(serde_json::json!({
+                "version": "v1",
+                "definition": {
+                    "mode": {
+                        "chain": [
+                            { "multipleJwt": [] },
+                            {
+                                "webhook": {
+                                    "url": "http://auth_hook:3050/validate-request",
+                                    "method": "Post"
+                                }
+                            }
+                        ]
+                    }
+                }
+            }))
+            .unwrap();
+        assert!(matches!(
+            auth_config.validate(),
+            Err(super::AuthConfigError::EmptyMultipleJwt)
+        ));
+    }
+
     #[test]
     /// Runs various checks on the generated JSONSchema to ensure it follows certain conventions.
     fn test_validate_auth_config_json_schema() {
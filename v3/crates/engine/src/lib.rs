@@ -1,6 +1,7 @@
 pub mod authentication;
 pub mod build;
 pub mod internal_flags;
+pub mod persisted_queries;
 
 // This is set by the build.rs script.
 /// The version of the v3-engine release.
@@ -0,0 +1,335 @@
+//! Automatic persisted queries and operation allowlists.
+//!
+//! Automatic persisted queries follow Apollo's protocol: a client may send only the SHA-256 hash
+//! of its query in `extensions.persistedQuery.sha256Hash`. If the engine hasn't seen the hash
+//! yet it responds with a `PersistedQueryNotFound` error, and the client retries with both the
+//! hash and the full query, which is remembered for subsequent requests.
+//! <https://github.com/apollographql/apollo-link-persisted-queries#protocol>
+//!
+//! An allowlist restricts the engine to a fixed set of named operations, each of which may be
+//! limited to some roles. Operations are matched on the hash of their exact query text, so
+//! allowlisted operations can also be sent by hash without being persisted first.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use hasura_authn_core::Role;
+use lang_graphql::ast::common as ast;
+use lang_graphql::http::RawRequest;
+use lru_cache::LruCache;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tracing_util::{ErrorVisibility, TraceableError};
+
+/// The only version of the persisted query protocol.
+const PERSISTED_QUERY_VERSION: u32 = 1;
+
+/// The default number of queries a `PersistedQueryStore` holds.
+pub const DEFAULT_MAX_PERSISTED_QUERIES: usize = 10_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PersistedQueryError {
+    /// Apollo clients recognise this exact message, and retry with the full query.
+    #[error("PersistedQueryNotFound")]
+    NotFound,
+    #[error("unsupported persisted query version: {version}")]
+    UnsupportedVersion { version: u32 },
+    #[error("the provided sha256Hash does not match the query")]
+    HashMismatch,
+    #[error("the request contains neither a query nor a persisted query hash")]
+    MissingQuery,
+    #[error("the operation is not in the allowlist for role {role}")]
+    NotAllowed { role: Role },
+}
+
+impl TraceableError for PersistedQueryError {
+    fn visibility(&self) -> ErrorVisibility {
+        ErrorVisibility::User
+    }
+}
+
+/// The hex-encoded SHA-256 hash of a query, which identifies it in the persisted query protocol.
+pub fn query_hash(query: &str) -> String {
+    hex::encode(Sha256::digest(query.as_bytes()))
+}
+
+/// An in-memory store of the queries sent by clients, keyed by their hash. Once it is full, the
+/// least recently used query is evicted to make room for a new one.
+pub struct PersistedQueryStore {
+    queries: Mutex<LruCache<String, Arc<str>>>,
+}
+
+impl PersistedQueryStore {
+    /// A store holding up to `max_queries` queries. A maximum of 0 disables persisting queries,
+    /// so clients always have to send them in full.
+    pub fn new(max_queries: usize) -> Self {
+        Self {
+            queries: Mutex::new(LruCache::new(max_queries)),
+        }
+    }
+
+    fn get(&self, hash: &str) -> Option<Arc<str>> {
+        sync_ext::lock(&self.queries).get(hash)
+    }
+
+    fn insert(&self, hash: String, query: &str) {
+        let mut queries = sync_ext::lock(&self.queries);
+        if queries.get(&hash).is_none() {
+            queries.insert(hash, query.into());
+        }
+    }
+}
+
+/// The operations allowed to be executed, read from the allowlist file.
+///
+/// ```json
+/// {
+///   "operations": [
+///     {
+///       "name": "GetAuthors",
+///       "query": "query GetAuthors { AuthorMany { author_id } }",
+///       "roles": ["user"]
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct AllowlistConfig {
+    pub operations: Vec<AllowlistOperation>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct AllowlistOperation {
+    /// The name of the operation in the query document.
+    pub name: ast::Name,
+    /// The exact query text that clients send.
+    pub query: String,
+    /// The roles allowed to execute the operation. All roles are allowed if omitted.
+    pub roles: Option<HashSet<Role>>,
+}
+
+/// An allowlist indexed by query hash.
+#[derive(Debug)]
+pub struct Allowlist {
+    operations: HashMap<String, AllowlistOperation>,
+}
+
+impl Allowlist {
+    pub fn new(config: AllowlistConfig) -> Self {
+        Self {
+            operations: config
+                .operations
+                .into_iter()
+                .map(|operation| (query_hash(&operation.query), operation))
+                .collect(),
+        }
+    }
+
+    fn check(
+        &self,
+        hash: &str,
+        operation_name: Option<&ast::Name>,
+        role: &Role,
+    ) -> Result<(), PersistedQueryError> {
+        let allowed = self.operations.get(hash).is_some_and(|operation| {
+            operation_name.map_or(true, |name| *name == operation.name)
+                && operation
+                    .roles
+                    .as_ref()
+                    .map_or(true, |roles| roles.contains(role))
+        });
+        if allowed {
+            Ok(())
+        } else {
+            Err(PersistedQueryError::NotAllowed { role: role.clone() })
+        }
+    }
+}
+
+/// Fills in the query of a request that only refers to a persisted query by its hash, and
+/// persists queries sent along with their hash. If an allowlist is given, the request is
+/// rejected unless its operation is allowed for the role.
+///
+/// This happens before the query is parsed, so that disallowed operations cost nothing.
+pub fn resolve_request(
+    store: &PersistedQueryStore,
+    allowlist: Option<&Allowlist>,
+    role: &Role,
+    mut request: RawRequest,
+) -> Result<RawRequest, PersistedQueryError> {
+    let persisted_query = request
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.persisted_query.as_ref());
+
+    let hash = match persisted_query {
+        Some(persisted_query) => {
+            if persisted_query.version != PERSISTED_QUERY_VERSION {
+                return Err(PersistedQueryError::UnsupportedVersion {
+                    version: persisted_query.version,
+                });
+            }
+            let hash = persisted_query.sha256_hash.to_ascii_lowercase();
+            if request.query.is_empty() {
+                request.query = store
+                    .get(&hash)
+                    .map(|query| query.to_string())
+                    .or_else(|| {
+                        allowlist
+                            .and_then(|allowlist| allowlist.operations.get(&hash))
+                            .map(|operation| operation.query.clone())
+                    })
+                    .ok_or(PersistedQueryError::NotFound)?;
+            } else if query_hash(&request.query) != hash {
+                return Err(PersistedQueryError::HashMismatch);
+            }
+            Some(hash)
+        }
+        None if request.query.is_empty() => return Err(PersistedQueryError::MissingQuery),
+        None => None,
+    };
+
+    if let Some(allowlist) = allowlist {
+        let hash = hash.clone().unwrap_or_else(|| query_hash(&request.query));
+        allowlist.check(&hash, request.operation_name.as_ref(), role)?;
+    }
+
+    // Only persist queries once they are known to be allowed, so that the store cannot be
+    // filled with operations that would be rejected anyway.
+    if let Some(hash) = hash {
+        store.insert(hash, &request.query);
+    }
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lang_graphql::http::{PersistedQuery, RequestExtensions};
+
+    const QUERY: &str = "query GetAuthors { AuthorMany { author_id } }";
+
+    fn request(query: &str, sha256_hash: Option<String>) -> RawRequest {
+        RawRequest {
+            operation_name: None,
+            query: query.to_string(),
+            variables: None,
+            extensions: sha256_hash.map(|sha256_hash| RequestExtensions {
+                persisted_query: Some(PersistedQuery {
+                    version: 1,
+                    sha256_hash,
+                }),
+            }),
+        }
+    }
+
+    fn allowlist(roles: Option<&[&str]>) -> Allowlist {
+        Allowlist::new(AllowlistConfig {
+            operations: vec![AllowlistOperation {
+                name: ast::Name::new("GetAuthors").unwrap(),
+                query: QUERY.to_string(),
+                roles: roles.map(|roles| roles.iter().copied().map(Role::new).collect()),
+            }],
+        })
+    }
+
+    #[test]
+    fn test_persisted_query_is_stored_on_miss() {
+        let store = PersistedQueryStore::new(DEFAULT_MAX_PERSISTED_QUERIES);
+        let role = Role::new("user");
+
+        let by_hash = request("", Some(query_hash(QUERY)));
+        assert!(matches!(
+            resolve_request(&store, None, &role, by_hash.clone()),
+            Err(PersistedQueryError::NotFound)
+        ));
+
+        resolve_request(&store, None, &role, request(QUERY, Some(query_hash(QUERY)))).unwrap();
+        let resolved = resolve_request(&store, None, &role, by_hash).unwrap();
+        assert_eq!(resolved.query, QUERY);
+    }
+
+    #[test]
+    fn test_least_recently_used_query_is_evicted() {
+        let store = PersistedQueryStore::new(2);
+        let role = Role::new("user");
+        let persist = |query: &str| {
+            resolve_request(&store, None, &role, request(query, Some(query_hash(query)))).unwrap();
+        };
+        let is_persisted = |query: &str| {
+            resolve_request(&store, None, &role, request("", Some(query_hash(query)))).is_ok()
+        };
+
+        persist("query { a }");
+        persist("query { b }");
+        // using `query { a }` again makes `query { b }` the least recently used query
+        assert!(is_persisted("query { a }"));
+        persist("query { c }");
+
+        assert!(is_persisted("query { a }"));
+        assert!(!is_persisted("query { b }"));
+        assert!(is_persisted("query { c }"));
+    }
+
+    #[test]
+    fn test_persisted_query_hash_must_match() {
+        let store = PersistedQueryStore::new(DEFAULT_MAX_PERSISTED_QUERIES);
+        let result = resolve_request(
+            &store,
+            None,
+            &Role::new("user"),
+            request(QUERY, Some(query_hash("query { other }"))),
+        );
+        assert!(matches!(result, Err(PersistedQueryError::HashMismatch)));
+    }
+
+    #[test]
+    fn test_allowlist_is_scoped_by_role() {
+        let store = PersistedQueryStore::new(DEFAULT_MAX_PERSISTED_QUERIES);
+        let allowlist = allowlist(Some(&["user"]));
+
+        resolve_request(
+            &store,
+            Some(&allowlist),
+            &Role::new("user"),
+            request(QUERY, None),
+        )
+        .unwrap();
+        assert!(matches!(
+            resolve_request(
+                &store,
+                Some(&allowlist),
+                &Role::new("guest"),
+                request(QUERY, None)
+            ),
+            Err(PersistedQueryError::NotAllowed { .. })
+        ));
+        assert!(matches!(
+            resolve_request(
+                &store,
+                Some(&allowlist),
+                &Role::new("user"),
+                request("query { AuthorMany { first_name } }", None)
+            ),
+            Err(PersistedQueryError::NotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_allowlisted_query_can_be_sent_by_hash() {
+        let store = PersistedQueryStore::new(DEFAULT_MAX_PERSISTED_QUERIES);
+        let allowlist = allowlist(None);
+        let resolved = resolve_request(
+            &store,
+            Some(&allowlist),
+            &Role::new("user"),
+            request("", Some(query_hash(QUERY))),
+        )
+        .unwrap();
+        assert_eq!(resolved.query, QUERY);
+    }
+}
@@ -12,7 +12,7 @@ use std::{
     path::PathBuf,
 };
 
-use execute::{execute_query, HttpContext};
+use execute::{execute_query, ConnectTimeoutClients, HttpContext, RemoteJoinOptions, RetryPolicy};
 use schema::GDS;
 
 extern crate json_value_merge;
@@ -28,6 +28,10 @@ pub fn setup(test_dir: &Path) -> GoldenTestContext {
     let http_context = HttpContext {
         client: reqwest::Client::new(),
         ndc_response_size_limit: None,
+        ndc_request_timeout: None,
+        ndc_retry_policy: RetryPolicy::default(),
+        ndc_connect_timeout_clients: ConnectTimeoutClients::default(),
+        remote_join_options: RemoteJoinOptions::default(),
     };
     let mint = Mint::new(test_dir);
     GoldenTestContext { http_context, mint }
@@ -40,7 +44,8 @@ fn resolve_session(
     let authorization = Identity::admin(Role::new("admin"));
     let role = session_variables
         .get(&SESSION_VARIABLE_ROLE)
-        .map(|v| Role::new(&v.0));
+        .and_then(SessionVariableValue::as_str)
+        .map(Role::new);
     let role_authorization = authorization.get_role_authorization(role.as_ref())?;
     let session = role_authorization.build_session(session_variables);
     Ok(session)
@@ -101,6 +106,7 @@ pub fn test_execution_expectation_legacy(
             operation_name: None,
             query,
             variables: None,
+            extensions: None,
         };
 
         // Execute the test
@@ -112,6 +118,7 @@ pub fn test_execution_expectation_legacy(
             &request_headers,
             raw_request,
             None,
+            None,
         )
         .await;
 
@@ -200,6 +207,7 @@ pub(crate) fn test_introspection_expectation(
             operation_name: None,
             query,
             variables: None,
+            extensions: None,
         };
 
         // Execute the test
@@ -212,6 +220,7 @@ pub(crate) fn test_introspection_expectation(
                 &request_headers,
                 raw_request.clone(),
                 None,
+                None,
             )
             .await;
             responses.push(response.0);
@@ -237,11 +246,25 @@ pub(crate) fn test_introspection_expectation(
 pub fn test_execution_expectation(
     test_path_string: &str,
     common_metadata_paths: &[&str],
+) -> anyhow::Result<()> {
+    test_execution_expectation_with_remote_join_options(
+        test_path_string,
+        common_metadata_paths,
+        RemoteJoinOptions::default(),
+    )
+}
+
+/// Like `test_execution_expectation`, with the given limits on remote joins
+pub fn test_execution_expectation_with_remote_join_options(
+    test_path_string: &str,
+    common_metadata_paths: &[&str],
+    remote_join_options: RemoteJoinOptions,
 ) -> anyhow::Result<()> {
     tokio_test::block_on(async {
         // Setup test context
         let root_test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
         let mut test_ctx = setup(&root_test_dir);
+        test_ctx.http_context.remote_join_options = remote_join_options;
         let test_path = root_test_dir.join(test_path_string);
 
         let request_path = test_path.join("request.gql");
@@ -322,6 +345,7 @@ pub fn test_execution_expectation(
                     operation_name: None,
                     query,
                     variables: None,
+                    extensions: None,
                 };
                 for session in &sessions {
                     let response = execute_query(
@@ -331,6 +355,7 @@ pub fn test_execution_expectation(
                         &request_headers,
                         raw_request.clone(),
                         None,
+                        None,
                     )
                     .await;
                     responses.push(response.0);
@@ -342,6 +367,7 @@ pub fn test_execution_expectation(
                         operation_name: None,
                         query: query.clone(),
                         variables: Some(variables),
+                        extensions: None,
                     };
                     let response = execute_query(
                         &test_ctx.http_context,
@@ -350,6 +376,7 @@ pub fn test_execution_expectation(
                         &request_headers,
                         raw_request.clone(),
                         None,
+                        None,
                     )
                     .await;
                     responses.push(response.0);
@@ -430,6 +457,7 @@ pub fn test_execute_explain(
             operation_name: None,
             query,
             variables: None,
+            extensions: None,
         };
         let raw_response = execute::execute_explain(
             &test_ctx.http_context,
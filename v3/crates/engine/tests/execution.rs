@@ -35,6 +35,41 @@ fn test_model_select_one_custom_scalar() -> anyhow::Result<()> {
     common::test_execution_expectation_legacy(test_path_string, &[common_metadata_path_string])
 }
 
+// Point mutations: inserts, updates and then deletes an actor, so the state of
+// the connector is unchanged afterwards. The user role has a field preset on
+// insert, so it can't set that field, and can only update the actors of another
+// movie.
+#[test]
+fn test_model_point_mutations() -> anyhow::Result<()> {
+    let test_path_string = "execute/models/point_mutations";
+    let common_metadata_path_string = "execute/common_metadata/custom_connector_schema.json";
+    let common_command_metadata_path_string = "execute/common_metadata/command_metadata.json";
+    common::test_execution_expectation(
+        test_path_string,
+        &[
+            common_metadata_path_string,
+            common_command_metadata_path_string,
+        ],
+    )
+}
+
+// Point mutations for a role whose insert preset depends on a session variable:
+// the second session presets a value that fails the insert check. The affected
+// rows are returned with a relationship.
+#[test]
+fn test_model_point_mutations_presets_and_checks() -> anyhow::Result<()> {
+    let test_path_string = "execute/models/point_mutations/presets_and_checks";
+    let common_metadata_path_string = "execute/common_metadata/custom_connector_schema.json";
+    let common_command_metadata_path_string = "execute/common_metadata/command_metadata.json";
+    common::test_execution_expectation(
+        test_path_string,
+        &[
+            common_metadata_path_string,
+            common_command_metadata_path_string,
+        ],
+    )
+}
+
 // Select Many Tests
 #[test]
 fn test_model_select_many_simple_select() -> anyhow::Result<()> {
@@ -301,6 +336,22 @@ fn test_model_select_many_where_ndc_operators() -> anyhow::Result<()> {
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+// Permission filters comparing a field with `_in` to an array-valued session variable, given
+// as a JSON array or as a stringified one. Session variables which aren't an array of the
+// field's type are rejected.
+#[test]
+fn test_model_select_many_where_array_session_variable() -> anyhow::Result<()> {
+    let test_path_string = "execute/models/select_many/where/array_session_variable";
+    common::test_execution_expectation(
+        test_path_string,
+        &[
+            "execute/aggregates/common_metadata/postgres_connector_schema.json",
+            "execute/aggregates/common_metadata/pg_types.json",
+            "execute/aggregates/common_metadata/supergraph.json",
+        ],
+    )
+}
+
 // Relationships in boolean expressions
 // What is being tested:
 // 1. Array relationships in boolean expressions (Simple, Nested array relationships). We also test multi column boolean expressions
@@ -387,6 +438,21 @@ fn test_model_select_many_limit() -> anyhow::Result<()> {
     )
 }
 
+// Limit in model select permissions: the smaller of the requested limit and
+// the limit of the role is used
+#[test]
+fn test_model_select_many_permission_limit() -> anyhow::Result<()> {
+    let test_path_string = "execute/models/select_many/limit_offset/permission_limit";
+    let ndc_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    let common_metadata_path_string =
+        "execute/models/select_many/limit_offset/common_metadata/metadata.json";
+
+    common::test_execution_expectation(
+        test_path_string,
+        &[ndc_metadata_path_string, common_metadata_path_string],
+    )
+}
+
 // Test is_null in model select permissions
 #[test]
 fn test_model_select_many_predicate_is_null() -> anyhow::Result<()> {
@@ -518,6 +584,75 @@ fn test_relay_node_model_select_permissions() -> anyhow::Result<()> {
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+// Directives
+#[test]
+fn test_include_and_skip_directives() -> anyhow::Result<()> {
+    let test_path_string = "execute/directives/include_skip";
+    let common_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_include_and_skip_directives_with_variables() -> anyhow::Result<()> {
+    let test_path_string = "execute/directives/include_skip_variables";
+    let common_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_unknown_directive() -> anyhow::Result<()> {
+    let test_path_string = "execute/directives/unknown_directive";
+    let common_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_duplicate_directive() -> anyhow::Result<()> {
+    let test_path_string = "execute/directives/duplicate_directive";
+    let common_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_directive_not_allowed_on_operation() -> anyhow::Result<()> {
+    let test_path_string = "execute/directives/directive_not_allowed";
+    let common_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_operation_limits_max_depth() -> anyhow::Result<()> {
+    let test_path_string = "execute/operation_limits/max_depth";
+    let common_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    let graphql_config_path_string = "execute/operation_limits/common_metadata/graphql_config.json";
+    common::test_execution_expectation(
+        test_path_string,
+        &[common_metadata_path_string, graphql_config_path_string],
+    )
+}
+
+#[test]
+fn test_operation_limits_max_fields() -> anyhow::Result<()> {
+    let test_path_string = "execute/operation_limits/max_fields";
+    let common_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    let graphql_config_path_string = "execute/operation_limits/common_metadata/graphql_config.json";
+    common::test_execution_expectation(
+        test_path_string,
+        &[common_metadata_path_string, graphql_config_path_string],
+    )
+}
+
+#[test]
+fn test_operation_limits_max_cost() -> anyhow::Result<()> {
+    let test_path_string = "execute/operation_limits/max_cost";
+    let common_metadata_path_string = "execute/common_metadata/postgres_connector_schema.json";
+    let graphql_config_path_string = "execute/operation_limits/common_metadata/graphql_config.json";
+    common::test_execution_expectation(
+        test_path_string,
+        &[common_metadata_path_string, graphql_config_path_string],
+    )
+}
+
 #[test]
 fn test_typename() -> anyhow::Result<()> {
     let test_path_string = "execute/typename";
@@ -810,6 +945,40 @@ fn test_command_procedures_multiple_arguments() -> anyhow::Result<()> {
     )
 }
 
+// Tests adjacent mutation commands on a data connector which supports transactional mutations:
+// both root fields are sent to the data connector in a single mutation request
+#[test]
+fn test_command_procedures_transactional_batched() -> anyhow::Result<()> {
+    let test_path_string = "execute/commands/procedures/transactional/batched";
+    let common_metadata_path_string =
+        "execute/commands/procedures/transactional/common_metadata/custom_connector_schema.json";
+    let common_command_metadata_path_string = "execute/common_metadata/command_metadata.json";
+    common::test_execution_expectation(
+        test_path_string,
+        &[
+            common_metadata_path_string,
+            common_command_metadata_path_string,
+        ],
+    )
+}
+
+// Tests that when one operation in a transactional mutation request fails, every root field in
+// the request fails, as none of the operations were applied
+#[test]
+fn test_command_procedures_transactional_rolled_back() -> anyhow::Result<()> {
+    let test_path_string = "execute/commands/procedures/transactional/rolled_back";
+    let common_metadata_path_string =
+        "execute/commands/procedures/transactional/common_metadata/custom_connector_schema.json";
+    let common_command_metadata_path_string = "execute/common_metadata/command_metadata.json";
+    common::test_execution_expectation(
+        test_path_string,
+        &[
+            common_metadata_path_string,
+            common_command_metadata_path_string,
+        ],
+    )
+}
+
 // Tests a mutation command with preset arguments:
 // arguments: 2 arguments (lower_bound, upper_bound) - one provided by presets in permissions
 // output: object (commandActor) output type
@@ -1142,3 +1311,18 @@ fn test_aggregates_root_field_nested_object() -> anyhow::Result<()> {
         ],
     )
 }
+
+// Roles with `allowAggregations: false` in their select permission can't access
+// the aggregate root field of the model, while roles that don't set it can
+#[test]
+fn test_aggregates_root_field_disallowed_aggregations() -> anyhow::Result<()> {
+    let test_path_string = "execute/aggregates/root_field/disallowed_aggregations";
+    common::test_execution_expectation(
+        test_path_string,
+        &[
+            "execute/aggregates/common_metadata/postgres_connector_schema.json",
+            "execute/aggregates/common_metadata/pg_types.json",
+            "execute/aggregates/common_metadata/supergraph.json",
+        ],
+    )
+}
@@ -63,3 +63,15 @@ fn test_field_with_nested_remote_relationship_2() -> anyhow::Result<()> {
         &["execute/common_metadata/two_postgres_connector_schema.json"],
     )
 }
+
+#[test]
+fn test_transactional_mutations() -> anyhow::Result<()> {
+    common::test_execute_explain(
+        "explain/transactional_mutations",
+        "execute/commands/procedures/transactional/batched/metadata.json",
+        &[
+            "execute/commands/procedures/transactional/common_metadata/custom_connector_schema.json",
+            "execute/common_metadata/command_metadata.json",
+        ],
+    )
+}
@@ -0,0 +1,142 @@
+#[allow(dead_code)]
+mod common;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use execute::{
+    execute_query, ConnectTimeoutClients, HttpContext, QueryCache, RemoteJoinOptions, RetryPolicy,
+};
+use hasura_authn_core::{Identity, Role, Session};
+use lang_graphql::{ast::common as ast, http::RawRequest, schema::Schema};
+use schema::GDS;
+
+fn build_schema(test_path_string: &str, common_metadata_paths: &[&str]) -> Schema<GDS> {
+    let root_test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let metadata_json_value = common::merge_with_common_metadata(
+        &root_test_dir.join(test_path_string).join("metadata.json"),
+        common_metadata_paths
+            .iter()
+            .map(|path| root_test_dir.join(path)),
+    )
+    .unwrap();
+    let metadata = open_dds::traits::OpenDd::deserialize(metadata_json_value).unwrap();
+    let metadata_resolve_flags = metadata_resolve::MetadataResolveFlagsInternal {
+        enable_boolean_expression_types: true,
+    };
+    let gds = GDS::new(metadata, &metadata_resolve_flags).unwrap();
+    GDS::build_schema(&gds).unwrap()
+}
+
+fn http_context() -> HttpContext {
+    HttpContext {
+        client: reqwest::Client::new(),
+        ndc_response_size_limit: None,
+        ndc_request_timeout: None,
+        ndc_retry_policy: RetryPolicy::default(),
+        ndc_connect_timeout_clients: ConnectTimeoutClients::default(),
+        remote_join_options: RemoteJoinOptions::default(),
+    }
+}
+
+fn session(role: &str) -> Session {
+    Identity::admin(Role::new("admin"))
+        .get_role_authorization(Some(&Role::new(role)))
+        .unwrap()
+        .build_session(HashMap::new())
+}
+
+/// Executes a request with and without the cache, and checks that both give the same response
+async fn execute_with_cache(
+    schema: &Schema<GDS>,
+    query_cache: &QueryCache,
+    session: &Session,
+    raw_request: &RawRequest,
+) -> serde_json::Value {
+    let http_context = http_context();
+    let request_headers = reqwest::header::HeaderMap::new();
+    let cached = execute_query(
+        &http_context,
+        schema,
+        session,
+        &request_headers,
+        raw_request.clone(),
+        None,
+        Some(query_cache),
+    )
+    .await;
+    let uncached = execute_query(
+        &http_context,
+        schema,
+        session,
+        &request_headers,
+        raw_request.clone(),
+        None,
+        None,
+    )
+    .await;
+    let cached = serde_json::to_value(cached.0).unwrap();
+    assert_eq!(cached, serde_json::to_value(uncached.0).unwrap());
+    cached
+}
+
+// The same query is allowed for `admin` but not for `user`, so sharing the parsed document
+// between roles and operation names must not change the response for either.
+#[test]
+fn test_query_cache_is_shared_by_roles_and_operations() {
+    tokio_test::block_on(async {
+        let schema = build_schema(
+            "execute/models/select_one/type_permission",
+            &["execute/common_metadata/postgres_connector_schema.json"],
+        );
+        let query_cache = QueryCache::new(10);
+        let raw_request = RawRequest {
+            operation_name: None,
+            query: "query MyQuery { AuthorByID(author_id: 1) { author_id first_name } }"
+                .to_string(),
+            variables: None,
+            extensions: None,
+        };
+
+        for _ in 0..2 {
+            let admin_response =
+                execute_with_cache(&schema, &query_cache, &session("admin"), &raw_request).await;
+            assert!(admin_response.get("errors").is_none());
+            let user_response =
+                execute_with_cache(&schema, &query_cache, &session("user"), &raw_request).await;
+            assert!(user_response.get("errors").is_some());
+        }
+
+        let named_request = RawRequest {
+            operation_name: Some(ast::Name::new("MyQuery").unwrap()),
+            ..raw_request
+        };
+        let named_response =
+            execute_with_cache(&schema, &query_cache, &session("admin"), &named_request).await;
+        assert!(named_response.get("errors").is_none());
+        assert_eq!(query_cache.len(), 1);
+    });
+}
+
+#[test]
+fn test_query_cache_evicts_least_recently_used_query() {
+    tokio_test::block_on(async {
+        let schema = build_schema(
+            "execute/models/select_one/type_permission",
+            &["execute/common_metadata/postgres_connector_schema.json"],
+        );
+        let query_cache = QueryCache::new(1);
+        for query in [
+            "query { AuthorByID(author_id: 1) { author_id } }",
+            "query { AuthorByID(author_id: 2) { author_id } }",
+        ] {
+            let raw_request = RawRequest {
+                operation_name: None,
+                query: query.to_string(),
+                variables: None,
+                extensions: None,
+            };
+            execute_with_cache(&schema, &query_cache, &session("admin"), &raw_request).await;
+            assert_eq!(query_cache.len(), 1);
+        }
+    });
+}
@@ -14,6 +14,8 @@
 //        - <some-field> <<Some-operator>> <some-literal-value>
 //
 
+use std::num::NonZeroUsize;
+
 mod common;
 
 // Non-remote relationships (relationship within the same connector)
@@ -131,6 +133,27 @@ fn test_local_relationships_permissions_target_model_filter_predicate() -> anyho
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+#[test]
+fn test_local_relationships_mapping_from_value() -> anyhow::Result<()> {
+    let test_path_string = "execute/relationships/mapping_from_value";
+    let common_metadata_path_string = "execute/common_metadata/custom_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_local_relationships_mapping_to_model_argument() -> anyhow::Result<()> {
+    let test_path_string = "execute/relationships/mapping_to_argument";
+    let common_metadata_path_string = "execute/common_metadata/custom_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_local_relationships_nested_field_path() -> anyhow::Result<()> {
+    let test_path_string = "execute/relationships/nested_field_path";
+    let common_metadata_path_string = "execute/common_metadata/custom_connector_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
 // Remote Relationships tests
 
 #[test]
@@ -189,6 +212,19 @@ fn test_remote_relationships_model_to_model_array() -> anyhow::Result<()> {
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+#[test]
+fn test_remote_relationships_with_unavailable_connector() -> anyhow::Result<()> {
+    let test_path_string = "execute/remote_relationships/unavailable_connector";
+    common::test_execution_expectation(test_path_string, &[])
+}
+
+#[test]
+fn test_remote_relationships_from_mutation_result() -> anyhow::Result<()> {
+    let test_path_string = "execute/remote_relationships/command/from_mutation";
+    let common_metadata_path_string = "execute/common_metadata/two_connectors_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
 #[test]
 fn test_remote_relationships_model_to_command_array() -> anyhow::Result<()> {
     let test_path_string = "execute/remote_relationships/command/model_to_command";
@@ -288,6 +324,43 @@ fn test_remote_relationships_mutually_recursive_with_where() -> anyhow::Result<(
     common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
 }
 
+#[test]
+fn test_remote_relationships_predicate_in_where() -> anyhow::Result<()> {
+    let test_path_string = "execute/remote_relationships/predicates/where";
+    let common_metadata_path_string = "execute/common_metadata/two_connectors_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_remote_relationships_predicate_in_permission() -> anyhow::Result<()> {
+    let test_path_string = "execute/remote_relationships/predicates/permission";
+    let common_metadata_path_string = "execute/common_metadata/two_connectors_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+// Tests ordering by the fields of a remote relationship, which only root fields support
+#[test]
+fn test_remote_relationships_order_by() -> anyhow::Result<()> {
+    let test_path_string = "execute/remote_relationships/order_by";
+    let common_metadata_path_string = "execute/common_metadata/two_connectors_schema.json";
+    common::test_execution_expectation(test_path_string, &[common_metadata_path_string])
+}
+
+#[test]
+fn test_remote_relationships_order_by_too_many_rows() -> anyhow::Result<()> {
+    let test_path_string = "execute/remote_relationships/order_by/too_many_rows";
+    let common_metadata_path_string = "execute/common_metadata/two_connectors_schema.json";
+    let order_by_metadata = "execute/remote_relationships/order_by/metadata.json";
+    common::test_execution_expectation_with_remote_join_options(
+        test_path_string,
+        &[order_by_metadata, common_metadata_path_string],
+        execute::RemoteJoinOptions {
+            max_sorted_rows: NonZeroUsize::new(2).unwrap(),
+            ..execute::RemoteJoinOptions::default()
+        },
+    )
+}
+
 #[test]
 fn test_remote_relationships_multi_field_mapping() -> anyhow::Result<()> {
     let test_path_string = "execute/remote_relationships/multi_field_mapping";
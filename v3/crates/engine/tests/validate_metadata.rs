@@ -42,7 +42,7 @@ fn test_field_path_to_argument_relationship_mapping() -> anyhow::Result<()> {
 
     assert_eq!(
         gds.unwrap_err().to_string(),
-        "metadata is not consistent: Relationship mappings to model arguments expressions are not supported yet."
+        "metadata is not consistent: target argument dummy_argument in argument mapping for relationship Movies on type actor (in subgraph default) to model Movies (in subgraph default) is unknown."
     );
     Ok(())
 }
@@ -68,7 +68,82 @@ fn test_relationship_mapping_unknown_target_field() -> anyhow::Result<()> {
 
     assert_eq!(
         gds.unwrap_err().to_string(),
-        "metadata is not consistent: target field author_id in field mapping for relationship author on type Article (in subgraph default) to model Authors (in subgraph default) is unknown."
+        "metadata is not consistent: target field id_unknown_field in field mapping for relationship author on type Article (in subgraph default) to model Authors (in subgraph default) is unknown."
+    );
+    Ok(())
+}
+
+#[test]
+fn test_relationship_mapping_unknown_target_model_argument() -> anyhow::Result<()> {
+    let metadata = read_metadata(
+        "validate_metadata_artifacts/relationships/unknown_target_model_argument.json",
+    )?;
+
+    let gds = GDS::new_with_default_flags(metadata);
+
+    assert_eq!(
+        gds.unwrap_err().to_string(),
+        "metadata is not consistent: target argument unknown_argument in argument mapping for relationship foosByName on type Foo (in subgraph default) to model FoosByName (in subgraph default) is unknown."
+    );
+    Ok(())
+}
+
+#[test]
+fn test_relationship_mapping_non_object_field_in_source_field_path() -> anyhow::Result<()> {
+    let metadata = read_metadata(
+        "validate_metadata_artifacts/relationships/non_object_field_in_source_field_path.json",
+    )?;
+
+    let gds = GDS::new_with_default_flags(metadata);
+
+    assert_eq!(
+        gds.unwrap_err().to_string(),
+        "metadata is not consistent: field foo of type Foo (in subgraph default) in the source field path of relationship foos is not an object, so no fields nested in it can be mapped."
+    );
+    Ok(())
+}
+
+#[test]
+fn test_relationship_mapping_unknown_nested_target_field() -> anyhow::Result<()> {
+    let metadata = read_metadata(
+        "validate_metadata_artifacts/relationships/unknown_nested_target_field.json",
+    )?;
+
+    let gds = GDS::new_with_default_flags(metadata);
+
+    assert_eq!(
+        gds.unwrap_err().to_string(),
+        "metadata is not consistent: target field unknown of type Bar (in subgraph default) in field mapping for relationship foosByBaz on type Foo (in subgraph default) to model Foos (in subgraph default) is unknown."
+    );
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_target_model_argument_relationship_mappings() -> anyhow::Result<()> {
+    let metadata = read_metadata(
+        "validate_metadata_artifacts/relationships/duplicate_target_model_argument_mappings.json",
+    )?;
+
+    let gds = GDS::new_with_default_flags(metadata);
+
+    assert_eq!(
+        gds.unwrap_err().to_string(),
+        "metadata is not consistent: Mapping for target argument name of model FoosByName (in subgraph default) already exists in the relationship foosByName on type Foo (in subgraph default)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_boolean_expression_in_relationship_mapping() -> anyhow::Result<()> {
+    let metadata = read_metadata(
+        "validate_metadata_artifacts/relationships/boolean_expression_in_relationship_mapping.json",
+    )?;
+
+    let gds = GDS::new_with_default_flags(metadata);
+
+    assert_eq!(
+        gds.unwrap_err().to_string(),
+        "metadata is not consistent: Boolean expressions are not supported as the source of a mapping in the relationship foosByName on type Foo (in subgraph default)"
     );
     Ok(())
 }
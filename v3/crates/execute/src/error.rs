@@ -28,12 +28,18 @@ pub enum RequestError {
     #[error("error while generating plan: {0}")]
     PlanError(#[from] plan::error::Error),
 
+    #[error("error while evaluating a predicate across a remote relationship: {0}")]
+    RemotePredicateError(#[from] FieldError),
+
     #[error("explain error: {0}")]
     ExplainError(String),
 }
 
 impl RequestError {
     pub fn to_graphql_error(&self) -> GraphQLError {
+        if let Self::RemotePredicateError(field_error) = self {
+            return field_error.to_graphql_error(None);
+        }
         let message = match self {
             // Error messages for internal errors from IR conversion and Plan generations are masked.
             Self::IRConversionError(ir::error::Error::Internal(_))
@@ -53,6 +59,7 @@ impl TraceableError for RequestError {
         match self {
             Self::IRConversionError(ir_error) => ir_error.visibility(),
             Self::PlanError(plan_error) => plan_error.visibility(),
+            Self::RemotePredicateError(field_error) => field_error.visibility(),
             // Rest all errors are visible to users via traces
             Self::ParseFailure(_) | Self::ValidationFailed(_) | Self::ExplainError(_) => {
                 ErrorVisibility::User
@@ -77,6 +84,23 @@ pub enum FieldError {
     #[error("field '{field_name:} not found in _Service")]
     FieldNotFoundInService { field_name: String },
 
+    #[error("the batch of mutations on data connector {data_connector} was rolled back: {reason}")]
+    MutationBatchRolledBack {
+        data_connector: metadata_resolve::Qualified<open_dds::data_connector::DataConnectorName>,
+        reason: String,
+    },
+
+    #[error("ordering by fields across remote relationships is limited to {max_sorted_rows} rows")]
+    TooManyRowsToSort { max_sorted_rows: usize },
+
+    #[error(
+        "the predicate across remote relationship {relationship_name} matches more than {max_rows} rows"
+    )]
+    TooManyRemotePredicateRows {
+        relationship_name: open_dds::relationships::RelationshipName,
+        max_rows: usize,
+    },
+
     #[error("internal error: {0}")]
     InternalError(#[from] FieldInternalError),
 }
@@ -88,7 +112,10 @@ impl FieldError {
                 Some(connector_error.error_response.details.clone())
             }
             Self::InternalError(internal) => internal.get_details(),
-            Self::FieldNotFoundInService { .. } => None,
+            Self::FieldNotFoundInService { .. }
+            | Self::MutationBatchRolledBack { .. }
+            | Self::TooManyRowsToSort { .. }
+            | Self::TooManyRemotePredicateRows { .. } => None,
         }
     }
 
@@ -111,10 +138,23 @@ impl FieldError {
     }
 }
 
+/// An error in a field nested within a root field. The field was replaced by `null` wherever it
+/// occurs, and the rest of the root field is still returned.
+#[derive(Debug)]
+pub struct NestedFieldError {
+    /// The paths of the `null` fields, relative to the root field.
+    pub paths: Vec<gql::http::Path>,
+    pub error: FieldError,
+}
+
 impl TraceableError for FieldError {
     fn visibility(&self) -> ErrorVisibility {
         match self {
-            Self::NDCExpected { .. } | Self::FieldNotFoundInService { .. } => ErrorVisibility::User,
+            Self::NDCExpected { .. }
+            | Self::FieldNotFoundInService { .. }
+            | Self::MutationBatchRolledBack { .. }
+            | Self::TooManyRowsToSort { .. }
+            | Self::TooManyRemotePredicateRows { .. } => ErrorVisibility::User,
             Self::InternalError(internal_error) => internal_error.visibility(),
         }
     }
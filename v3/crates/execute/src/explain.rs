@@ -2,14 +2,16 @@ pub mod types;
 
 use std::borrow::Cow;
 
-use super::remote_joins::types::{JoinNode, RemoteJoinType};
+use super::remote_joins::types::{JoinNode, RemoteJoinStrategy, RemoteJoinType};
 use super::HttpContext;
-use crate::ndc::client as ndc_client;
+use crate::ndc::{self, client as ndc_client};
 use crate::plan::{ApolloFederationSelect, NodeQueryPlan, ProcessResponseAs};
 use crate::remote_joins::types::{JoinId, JoinLocations, RemoteJoin};
+use crate::remote_predicates::RemotePredicatePlan;
 use crate::{error, plan};
 use async_recursion::async_recursion;
 use hasura_authn_core::Session;
+use indexmap::IndexMap;
 use lang_graphql as gql;
 use lang_graphql::ast::common as ast;
 use lang_graphql::{http::RawRequest, schema::Schema};
@@ -40,15 +42,8 @@ pub(crate) async fn explain_query_plan(
         match node {
             NodeQueryPlan::NDCQueryExecution(ndc_query_execution)
             | NodeQueryPlan::RelayNodeSelect(Some(ndc_query_execution)) => {
-                let sequence_steps = get_execution_steps(
-                    http_context,
-                    alias,
-                    &ndc_query_execution.process_response_as,
-                    ndc_query_execution.execution_tree.remote_executions,
-                    types::NDCRequest::Query(ndc_query_execution.execution_tree.root_node.query),
-                    ndc_query_execution.execution_tree.root_node.data_connector,
-                )
-                .await;
+                let sequence_steps =
+                    get_query_execution_steps(http_context, alias, ndc_query_execution).await;
                 parallel_root_steps.push(Box::new(types::Step::Sequence(sequence_steps)));
             }
             NodeQueryPlan::ApolloFederationSelect(ApolloFederationSelect::EntitiesSelect(
@@ -56,17 +51,9 @@ pub(crate) async fn explain_query_plan(
             )) => {
                 let mut parallel_steps = Vec::new();
                 for ndc_query_execution in parallel_ndc_query_executions {
-                    let sequence_steps = get_execution_steps(
-                        http_context,
-                        alias.clone(),
-                        &ndc_query_execution.process_response_as,
-                        ndc_query_execution.execution_tree.remote_executions,
-                        types::NDCRequest::Query(
-                            ndc_query_execution.execution_tree.root_node.query,
-                        ),
-                        ndc_query_execution.execution_tree.root_node.data_connector,
-                    )
-                    .await;
+                    let sequence_steps =
+                        get_query_execution_steps(http_context, alias.clone(), ndc_query_execution)
+                            .await;
                     parallel_steps.push(Box::new(types::Step::Sequence(sequence_steps)));
                 }
                 match NonEmpty::from_vec(parallel_steps) {
@@ -119,7 +106,14 @@ pub(crate) async fn explain_mutation_plan(
         ));
     }
 
-    for (_, mutation_group) in mutation_plan.nodes {
+    for mutation_group in mutation_plan.nodes {
+        if let Some(data_connector) = plan::transactional_data_connector(&mutation_group) {
+            // The fields of a transactional group are executed as a single request
+            root_steps.push(Box::new(types::Step::Sequence(
+                get_mutation_group_steps(http_context, mutation_group, data_connector).await,
+            )));
+            continue;
+        }
         for (alias, ndc_mutation_execution) in mutation_group {
             let sequence_steps = get_execution_steps(
                 http_context,
@@ -146,6 +140,65 @@ pub(crate) async fn explain_mutation_plan(
     }
 }
 
+/// Prefix the steps of a request with the queries for the join keys of the predicates across
+/// remote relationships it contains, which are made before the request.
+pub(crate) async fn explain_remote_predicates(
+    http_context: &HttpContext,
+    remote_predicate_plans: Vec<RemotePredicatePlan<'_>>,
+    step: types::Step,
+) -> types::Step {
+    let mut sequence_steps = vec![];
+    for remote_predicate_plan in remote_predicate_plans {
+        let ndc_request = types::NDCRequest::Query(remote_predicate_plan.query_request);
+        let data_connector_explain = fetch_explain_from_data_connector(
+            http_context,
+            &ndc_request,
+            remote_predicate_plan.data_connector,
+        )
+        .await;
+        sequence_steps.push(Box::new(types::Step::ModelSelect(types::ModelSelectIR {
+            model_name: remote_predicate_plan.relationship_name.to_string(),
+            ndc_request,
+            ndc_explain: data_connector_explain,
+        })));
+    }
+    match NonEmpty::from_vec(sequence_steps) {
+        Some(mut sequence_steps) => {
+            sequence_steps.push(Box::new(step));
+            *simplify_step(Box::new(types::Step::Sequence(sequence_steps)))
+        }
+        None => step,
+    }
+}
+
+/// The steps of a query execution. The remote joins for the values the engine sorts the rows by
+/// come before the other remote joins, which are executed for the sorted rows.
+async fn get_query_execution_steps<'s, 'ir>(
+    http_context: &HttpContext,
+    alias: gql::ast::common::Alias,
+    ndc_query_execution: plan::NDCQueryExecution<'s, 'ir>,
+) -> NonEmpty<Box<types::Step>> {
+    let plan::ExecutionTree {
+        root_node,
+        sort_executions,
+        remote_executions,
+    } = ndc_query_execution.execution_tree;
+    let mut sequence_steps = get_execution_steps(
+        http_context,
+        alias,
+        &ndc_query_execution.process_response_as,
+        sort_executions,
+        types::NDCRequest::Query(root_node.query),
+        root_node.data_connector,
+    )
+    .await;
+    if let Some(join_steps) = get_join_steps(remote_executions, http_context).await {
+        sequence_steps.push(Box::new(types::Step::Sequence(join_steps)));
+        sequence_steps.push(Box::new(types::Step::HashJoin));
+    };
+    sequence_steps
+}
+
 async fn get_execution_steps<'s>(
     http_context: &HttpContext,
     alias: gql::ast::common::Alias,
@@ -155,8 +208,9 @@ async fn get_execution_steps<'s>(
     data_connector: &metadata_resolve::DataConnectorLink,
 ) -> NonEmpty<Box<types::Step>> {
     let mut sequence_steps = match process_response_as {
-        ProcessResponseAs::CommandResponse { .. } => {
-            // A command execution node
+        ProcessResponseAs::CommandResponse { .. }
+        | ProcessResponseAs::ModelMutationResponse { .. } => {
+            // A command execution node, or a procedure executing a model mutation
             let data_connector_explain =
                 fetch_explain_from_data_connector(http_context, &ndc_request, data_connector).await;
             NonEmpty::new(Box::new(types::Step::CommandSelect(
@@ -187,6 +241,39 @@ async fn get_execution_steps<'s>(
     sequence_steps
 }
 
+/// Get the steps for a group of root fields which are sent to a data connector as a single
+/// transactional mutation request, followed by the remote joins of each field.
+async fn get_mutation_group_steps<'s>(
+    http_context: &HttpContext,
+    mutation_group: IndexMap<ast::Alias, plan::NDCMutationExecution<'_, 's, '_>>,
+    data_connector: &metadata_resolve::DataConnectorLink,
+) -> NonEmpty<Box<types::Step>> {
+    let ndc_request = types::NDCRequest::Mutation(plan::batch_mutation_requests(&mutation_group));
+    let data_connector_explain =
+        fetch_explain_from_data_connector(http_context, &ndc_request, data_connector).await;
+    let command_name = mutation_group
+        .keys()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sequence_steps = NonEmpty::new(Box::new(types::Step::CommandSelect(
+        types::CommandSelectIR {
+            command_name,
+            ndc_request,
+            ndc_explain: data_connector_explain,
+        },
+    )));
+    for (_, ndc_mutation_execution) in mutation_group {
+        if let Some(join_steps) =
+            get_join_steps(ndc_mutation_execution.join_locations, http_context).await
+        {
+            sequence_steps.push(Box::new(types::Step::Sequence(join_steps)));
+            sequence_steps.push(Box::new(types::Step::HashJoin));
+        };
+    }
+    sequence_steps
+}
+
 /// Get the join steps for a given join location. This should be used to get the join steps for a remote relationship.
 /// It also supports nested remote relationships.
 ///
@@ -201,7 +288,10 @@ async fn get_join_steps(
         let mut sequence_steps = vec![];
         if let JoinNode::Remote((remote_join, _join_id)) = location.join_node {
             let mut query_request = remote_join.target_ndc_ir;
-            query_request.variables = Some(vec![]);
+            // the variables of a naive join are only substituted when it is executed
+            if remote_join.strategy == RemoteJoinStrategy::ForEach {
+                query_request.variables = Some(vec![]);
+            }
             let ndc_request = types::NDCRequest::Query(query_request);
             let data_connector_explain = fetch_explain_from_data_connector(
                 http_context,
@@ -282,13 +372,12 @@ async fn fetch_explain_from_data_connector(
             SpanVisibility::Internal,
             || {
                 Box::pin(async {
-                    let ndc_config = ndc_client::Configuration {
-                        base_path: data_connector.url.get_url(ast::OperationType::Query),
-                        // This is isn't expensive, reqwest::Client is behind an Arc
-                        client: http_context.client.clone(),
-                        headers: Cow::Borrowed(&data_connector.headers.0),
-                        response_size_limit: http_context.ndc_response_size_limit,
-                    };
+                    let ndc_config = ndc::client_configuration(
+                        http_context,
+                        data_connector,
+                        ast::OperationType::Query,
+                        Cow::Borrowed(&data_connector.headers.0),
+                    )?;
                     match ndc_request {
                         types::NDCRequest::Query(query_request) => {
                             if data_connector.capabilities.supports_explaining_queries {
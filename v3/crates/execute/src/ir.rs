@@ -7,6 +7,7 @@ pub mod arguments;
 pub mod commands;
 pub mod error;
 pub mod filter;
+pub mod model_mutation;
 pub mod model_selection;
 pub mod mutation_root;
 pub mod order_by;
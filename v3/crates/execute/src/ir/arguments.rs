@@ -46,23 +46,16 @@ pub(crate) fn follow_field_path_and_insert_value(
         // if rest is *not* empty, pick the field from the current object, and
         // recursively process with the rest
         Some(tail) => {
-            match object_slice.get_mut(&field_name.0) {
-                None => {
-                    // object should have this field; if it doesn't then all the fields are preset
-                    object_slice.insert(
-                        field_name.to_string(),
-                        serde_json::Value::Object(serde_json::Map::new()),
-                    );
+            // object should have this field; if it doesn't then all the fields are preset
+            let json_value = object_slice
+                .entry(field_name.to_string())
+                .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+            let inner_object = json_value.as_object_mut().ok_or_else(|| {
+                error::InternalEngineError::ArgumentPresetExecution {
+                    description: "input value is not a valid JSON object".to_string(),
                 }
-                Some(json_value) => {
-                    let inner_object = json_value.as_object_mut().ok_or_else(|| {
-                        error::InternalEngineError::ArgumentPresetExecution {
-                            description: "input value is not a valid JSON object".to_string(),
-                        }
-                    })?;
-                    follow_field_path_and_insert_value(&tail, inner_object, value)?;
-                }
-            }
+            })?;
+            follow_field_path_and_insert_value(&tail, inner_object, value)?;
         }
     }
     Ok(())
@@ -202,7 +195,7 @@ pub fn build_ndc_model_arguments<'a, TInputFieldIter: Iterator<Item = &'a InputF
     Ok(ndc_arguments)
 }
 
-fn map_argument_value_to_ndc_type(
+pub(crate) fn map_argument_value_to_ndc_type(
     value_type: &QualifiedTypeReference,
     value: &Value<GDS>,
     type_mappings: &BTreeMap<Qualified<CustomTypeName>, TypeMapping>,
@@ -4,6 +4,7 @@ use open_dds::{
     aggregates::AggregationFunctionName,
     arguments::ArgumentName,
     data_connector::{DataConnectorColumnName, DataConnectorName},
+    models::ModelName,
     relationships::RelationshipName,
     session_variables::SessionVariable,
     types::{CustomTypeName, FieldName},
@@ -43,6 +44,25 @@ pub enum Error {
     #[error("order_by expects a list of input objects with exactly one key-value pair per input object. Please split the input object with multiple key-value pairs into a list of single key-value pair objects.")]
     OrderByObjectShouldExactlyHaveOneKeyValuePair,
 
+    #[error("ordering by fields across the remote relationship {relationship_name:} is not supported {reason:}")]
+    RemoteOrderByNotSupported {
+        relationship_name: RelationshipName,
+        reason: &'static str,
+    },
+
+    #[error("predicates across the remote relationship {relationship_name:} are not supported, as it maps fields to arguments of its target model")]
+    RemotePredicateWithArgumentMappingNotSupported { relationship_name: RelationshipName },
+
+    #[error("the mutation permissions of model {model_name:} have predicates across remote relationships, which are not supported")]
+    RemotePredicateInMutationPermissionNotSupported { model_name: Qualified<ModelName> },
+
+    #[error("the session variable {session_variable:} has the wrong type: expected {expected:}, but found: {found:}")]
+    SessionVariableTypeMismatch {
+        session_variable: SessionVariable,
+        expected: String,
+        found: String,
+    },
+
     #[error("internal: {0}")]
     Internal(#[from] InternalError),
 }
@@ -109,12 +129,6 @@ pub enum InternalDeveloperError {
     #[error("Required session variable not found in the request: {session_variable}")]
     MissingSessionVariable { session_variable: SessionVariable },
 
-    #[error("Unable to typecast session variable. Expected: {expected:}, but found: {found:}")]
-    VariableTypeCast { expected: String, found: String },
-
-    #[error("Typecasting to array is not supported.")]
-    VariableArrayTypeCast,
-
     #[error("Mapping for the {mapping_kind} typename {type_name:} not found")]
     TypenameMappingNotFound {
         type_name: ast::TypeName,
@@ -173,6 +187,16 @@ pub enum InternalDeveloperError {
         aggregation_function: AggregationFunctionName,
     },
 
+    #[error("The field {field_name:} of type {type_name:} needs exactly one equality operator to be compared across the remote relationship {relationship_name:}")]
+    NoEqualOperatorForRemotePredicate {
+        type_name: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+        field_name: FieldName,
+    },
+
+    #[error("Predicates across remote relationships are not supported in argument presets")]
+    RemotePredicateInArgumentPreset,
+
     // we'll be adding them shortly, and not advertising the feature until they are complete
     // however temporarily emitting the error allows merging the work in chunks
     #[error("boolean expressions not implemented")]
@@ -199,9 +223,6 @@ pub enum InternalEngineError {
         expected_type: String,
     },
 
-    #[error("subscription shouldn't have been validated")]
-    SubscriptionsNotSupported,
-
     #[error("expected namespace annotation type {namespace_annotation_type} but not found")]
     // Running into this error means that the GDS field was not annotated with the correct
     // namespace annotation while building the metadata.
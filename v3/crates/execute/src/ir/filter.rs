@@ -1,9 +1,10 @@
 use std::collections::BTreeMap;
 
+use hasura_authn_core::SessionVariables;
 use indexmap::IndexMap;
 use lang_graphql::ast::common as ast;
 use lang_graphql::normalized_ast;
-use metadata_resolve::{DataConnectorLink, FieldMapping, Qualified};
+use metadata_resolve::{ConnectorArgumentName, DataConnectorLink, FieldMapping, Qualified};
 use ndc_models;
 use serde::Serialize;
 
@@ -11,6 +12,8 @@ use crate::ir::error;
 use crate::model_tracking::{count_model, UsagesCounts};
 use open_dds::{
     data_connector::{DataConnectorColumnName, DataConnectorOperatorName},
+    models::ModelName,
+    relationships::RelationshipName,
     types::{CustomTypeName, FieldName},
 };
 use schema::FilterRelationshipAnnotation;
@@ -18,22 +21,301 @@ use schema::GDS;
 use schema::{self};
 use schema::{BooleanExpressionAnnotation, InputAnnotation, ModelInputAnnotation};
 
-use super::relationship::LocalModelRelationshipInfo;
+use super::relationship::{self, LocalModelRelationshipInfo};
 use crate::ir::selection_set::NDCRelationshipName;
 
 #[derive(Debug, Serialize)]
 pub(crate) struct ResolvedFilterExpression<'s> {
-    pub expression: Option<ndc_models::Expression>,
+    pub expression: Option<FilterExpression<'s>>,
     // relationships that were used in the filter expression. This is helpful
     // for collecting relatinships and sending collection_relationships
     pub relationships: BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
 }
 
+impl<'s> ResolvedFilterExpression<'s> {
+    /// Restrict the rows further to those matching `expression`
+    pub(crate) fn and(&mut self, expression: FilterExpression<'s>) {
+        self.expression = Some(match self.expression.take() {
+            Some(existing) => FilterExpression::and(vec![existing, expression]),
+            None => expression,
+        });
+    }
+}
+
+/// A filter expression over the rows of a model.
+///
+/// Predicates across remote relationships can't be evaluated by the data connector of the model,
+/// so the parts of the expression containing them are kept apart from the NDC expression until
+/// the remote predicates have been resolved (see `crate::remote_predicates`).
+#[derive(Debug, Serialize)]
+pub(crate) enum FilterExpression<'s> {
+    And {
+        expressions: Vec<FilterExpression<'s>>,
+    },
+    Or {
+        expressions: Vec<FilterExpression<'s>>,
+    },
+    Not {
+        expression: Box<FilterExpression<'s>>,
+    },
+    /// An `exists` over a local relationship, whose predicate contains remote predicates
+    LocalRelationship {
+        relationship: NDCRelationshipName,
+        predicate: Box<FilterExpression<'s>>,
+    },
+    RemoteRelationship(Box<RemoteRelationshipPredicate<'s>>),
+    /// An expression which the data connector can evaluate as it is
+    #[serde(untagged)]
+    Local(ndc_models::Expression),
+}
+
+impl<'s> FilterExpression<'s> {
+    pub(crate) fn and(expressions: Vec<FilterExpression<'s>>) -> Self {
+        match into_local_expressions(expressions) {
+            Ok(expressions) => Self::Local(ndc_models::Expression::And { expressions }),
+            Err(expressions) => Self::And { expressions },
+        }
+    }
+
+    pub(crate) fn or(expressions: Vec<FilterExpression<'s>>) -> Self {
+        match into_local_expressions(expressions) {
+            Ok(expressions) => Self::Local(ndc_models::Expression::Or { expressions }),
+            Err(expressions) => Self::Or { expressions },
+        }
+    }
+
+    pub(crate) fn not(expression: FilterExpression<'s>) -> Self {
+        match expression {
+            Self::Local(expression) => Self::Local(ndc_models::Expression::Not {
+                expression: Box::new(expression),
+            }),
+            expression => Self::Not {
+                expression: Box::new(expression),
+            },
+        }
+    }
+
+    pub(crate) fn exists_in_relationship(
+        relationship: NDCRelationshipName,
+        predicate: FilterExpression<'s>,
+    ) -> Self {
+        match predicate {
+            Self::Local(predicate) => Self::Local(ndc_models::Expression::Exists {
+                in_collection: ndc_models::ExistsInCollection::Related {
+                    relationship: relationship.0,
+                    arguments: BTreeMap::new(),
+                },
+                predicate: Some(Box::new(predicate)),
+            }),
+            predicate => Self::LocalRelationship {
+                relationship,
+                predicate: Box::new(predicate),
+            },
+        }
+    }
+
+    /// The NDC expression for this filter, or `None` if it contains remote predicates which
+    /// haven't been resolved yet.
+    pub(crate) fn to_ndc_expression(&self) -> Option<ndc_models::Expression> {
+        match self {
+            Self::Local(expression) => Some(expression.clone()),
+            Self::And { expressions } => Some(ndc_models::Expression::And {
+                expressions: expressions
+                    .iter()
+                    .map(Self::to_ndc_expression)
+                    .collect::<Option<_>>()?,
+            }),
+            Self::Or { expressions } => Some(ndc_models::Expression::Or {
+                expressions: expressions
+                    .iter()
+                    .map(Self::to_ndc_expression)
+                    .collect::<Option<_>>()?,
+            }),
+            Self::Not { expression } => Some(ndc_models::Expression::Not {
+                expression: Box::new(expression.to_ndc_expression()?),
+            }),
+            Self::LocalRelationship {
+                relationship,
+                predicate,
+            } => Some(ndc_models::Expression::Exists {
+                in_collection: ndc_models::ExistsInCollection::Related {
+                    relationship: relationship.0.clone(),
+                    arguments: BTreeMap::new(),
+                },
+                predicate: Some(Box::new(predicate.to_ndc_expression()?)),
+            }),
+            Self::RemoteRelationship(remote_predicate) => remote_predicate.resolved.clone(),
+        }
+    }
+}
+
+/// Unwraps the expressions if none of them contain remote predicates.
+fn into_local_expressions(
+    expressions: Vec<FilterExpression<'_>>,
+) -> Result<Vec<ndc_models::Expression>, Vec<FilterExpression<'_>>> {
+    if expressions
+        .iter()
+        .all(|expression| matches!(expression, FilterExpression::Local(_)))
+    {
+        Ok(expressions
+            .into_iter()
+            .filter_map(|expression| match expression {
+                FilterExpression::Local(expression) => Some(expression),
+                _ => None,
+            })
+            .collect())
+    } else {
+        Err(expressions)
+    }
+}
+
+/// A predicate across a relationship to a model in another data connector.
+///
+/// It is resolved by querying the target model for the rows matching `predicate`, and comparing
+/// the source columns with the join keys of those rows.
+#[derive(Debug, Serialize)]
+pub(crate) struct RemoteRelationshipPredicate<'s> {
+    pub relationship_name: &'s RelationshipName,
+    pub target_model_name: &'s Qualified<ModelName>,
+    pub target_source: &'s metadata_resolve::ModelTargetSource,
+    pub join_columns: Vec<RemotePredicateJoinColumn>,
+    // The arguments of the target model
+    pub arguments: BTreeMap<ConnectorArgumentName, ndc_models::Argument>,
+    // The predicate over the target model
+    pub predicate: ResolvedFilterExpression<'s>,
+    // The comparison of the source columns with the join keys, once the predicate has been
+    // evaluated
+    pub resolved: Option<ndc_models::Expression>,
+}
+
+/// A pair of columns joined by a remote relationship. Either may be joined on a field nested in
+/// it, at its field path.
+#[derive(Debug, Serialize)]
+pub(crate) struct RemotePredicateJoinColumn {
+    pub source_column: DataConnectorColumnName,
+    pub source_field_path: Vec<DataConnectorColumnName>,
+    // The operators used to compare the source column with the join keys
+    pub equal_operator: String,
+    pub in_operator: Option<String>,
+    pub target_column: DataConnectorColumnName,
+    pub target_field_path: Vec<DataConnectorColumnName>,
+}
+
+impl RemotePredicateJoinColumn {
+    /// The alias the joined target field is selected with
+    pub fn target_alias(&self) -> String {
+        std::iter::once(&self.target_column)
+            .chain(&self.target_field_path)
+            .map(|field_name| field_name.0.as_str())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// The names of the fields leading from the target column to the joined field
+    pub fn target_nested_field_path(&self) -> Vec<String> {
+        self.target_field_path
+            .iter()
+            .map(|field_name| field_name.0.clone())
+            .collect()
+    }
+}
+
+/// Builds the IR for a predicate across a remote relationship to a model.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build_remote_relationship_predicate<'s>(
+    relationship_name: &'s RelationshipName,
+    source_type: &'s Qualified<CustomTypeName>,
+    source_type_mappings: &'s BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
+    target_model_name: &'s Qualified<ModelName>,
+    target_source: &'s metadata_resolve::ModelTargetSource,
+    mappings: &'s [metadata_resolve::RelationshipModelMapping],
+    mut predicate: ResolvedFilterExpression<'s>,
+    session_variables: &SessionVariables,
+    usage_counts: &mut UsagesCounts,
+) -> Result<FilterExpression<'s>, error::Error> {
+    let target_mappings = relationship::resolve_target_mappings(
+        source_type,
+        relationship_name,
+        source_type_mappings,
+        target_source,
+        mappings,
+        session_variables,
+        usage_counts,
+    )?;
+    // the target rows are fetched once for all the source rows, so the arguments of the target
+    // model can't depend on the source fields
+    if !target_mappings.argument_join_mapping().is_empty() {
+        Err(
+            error::Error::RemotePredicateWithArgumentMappingNotSupported {
+                relationship_name: relationship_name.clone(),
+            },
+        )?;
+    }
+    if let Some(value_predicate) = &target_mappings.predicate {
+        predicate.and(FilterExpression::Local(value_predicate.clone()));
+    }
+
+    let mut join_columns = Vec::new();
+    for (source_field, target_field) in relationship::get_remote_join_mapping(
+        source_type,
+        relationship_name,
+        source_type_mappings,
+        mappings,
+    )? {
+        let comparison_operators = source_field
+            .joined_field_mapping()
+            .comparison_operators
+            .as_ref()
+            .map(|operators| {
+                (
+                    operators.equal_operators.as_slice(),
+                    &operators.in_operators,
+                )
+            });
+        let (equal_operator, in_operator) = match comparison_operators {
+            Some(([equal_operator], in_operators)) => {
+                (equal_operator.clone(), in_operators.first().cloned())
+            }
+            _ => Err(
+                error::InternalDeveloperError::NoEqualOperatorForRemotePredicate {
+                    relationship_name: relationship_name.clone(),
+                    type_name: source_type.clone(),
+                    field_name: source_field.field_name.clone(),
+                },
+            )?,
+        };
+        join_columns.push(RemotePredicateJoinColumn {
+            source_field_path: source_field
+                .nested_fields
+                .iter()
+                .map(|(_, field_mapping)| field_mapping.column.clone())
+                .collect(),
+            source_column: source_field.field_mapping.column,
+            equal_operator,
+            in_operator,
+            target_column: target_field.ndc_column.column,
+            target_field_path: target_field.ndc_field_path,
+        });
+    }
+    Ok(FilterExpression::RemoteRelationship(Box::new(
+        RemoteRelationshipPredicate {
+            relationship_name,
+            target_model_name,
+            target_source,
+            join_columns,
+            arguments: target_mappings.remote_arguments(),
+            predicate,
+            resolved: None,
+        },
+    )))
+}
+
 /// Generates the IR for GraphQL 'where' boolean expression
 pub(crate) fn resolve_filter_expression<'s>(
     fields: &IndexMap<ast::Name, normalized_ast::InputField<'s, GDS>>,
     data_connector_link: &'s DataConnectorLink,
     type_mappings: &'s BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
+    session_variables: &SessionVariables,
     usage_counts: &mut UsagesCounts,
 ) -> Result<ResolvedFilterExpression<'s>, error::Error> {
     let mut expressions = Vec::new();
@@ -44,11 +326,12 @@ pub(crate) fn resolve_filter_expression<'s>(
             &mut relationships,
             data_connector_link,
             type_mappings,
+            session_variables,
             usage_counts,
         )?;
         expressions.push(field_filter_expression);
     }
-    let expression = ndc_models::Expression::And { expressions };
+    let expression = FilterExpression::and(expressions);
     let resolved_filter_expression = ResolvedFilterExpression {
         expression: Some(expression),
         relationships,
@@ -76,8 +359,9 @@ fn build_filter_expression<'s>(
     relationships: &mut BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
     data_connector_link: &'s DataConnectorLink,
     type_mappings: &'s BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
+    session_variables: &SessionVariables,
     usage_counts: &mut UsagesCounts,
-) -> Result<ndc_models::Expression, error::Error> {
+) -> Result<FilterExpression<'s>, error::Error> {
     let boolean_expression_annotation = get_boolean_expression_annotation(field.info.generic)?;
     build_filter_expression_from_boolean_expression(
         boolean_expression_annotation,
@@ -86,11 +370,13 @@ fn build_filter_expression<'s>(
         data_connector_link,
         type_mappings,
         &mut vec![],
+        session_variables,
         usage_counts,
     )
 }
 
 // build filter expression, specifically matching on BooleanExpressionAnnotation
+#[allow(clippy::too_many_arguments)]
 fn build_filter_expression_from_boolean_expression<'s>(
     boolean_expression_annotation: &'s BooleanExpressionAnnotation,
     field: &normalized_ast::InputField<'s, GDS>,
@@ -98,8 +384,9 @@ fn build_filter_expression_from_boolean_expression<'s>(
     data_connector_link: &'s DataConnectorLink,
     type_mappings: &'s BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
     field_path: &mut Vec<DataConnectorColumnName>,
+    session_variables: &SessionVariables,
     usage_counts: &mut UsagesCounts,
-) -> Result<ndc_models::Expression, error::Error> {
+) -> Result<FilterExpression<'s>, error::Error> {
     match boolean_expression_annotation {
         // "_and"
         BooleanExpressionAnnotation::BooleanExpressionArgument {
@@ -117,12 +404,11 @@ fn build_filter_expression_from_boolean_expression<'s>(
                     relationships,
                     data_connector_link,
                     type_mappings,
+                    session_variables,
                     usage_counts,
                 )?);
             }
-            Ok(ndc_models::Expression::And {
-                expressions: and_expressions,
-            })
+            Ok(FilterExpression::and(and_expressions))
         }
         // "_or"
         BooleanExpressionAnnotation::BooleanExpressionArgument {
@@ -139,13 +425,12 @@ fn build_filter_expression_from_boolean_expression<'s>(
                     relationships,
                     data_connector_link,
                     type_mappings,
+                    session_variables,
                     usage_counts,
                 )?);
             }
 
-            Ok(ndc_models::Expression::Or {
-                expressions: or_expressions,
-            })
+            Ok(FilterExpression::or(or_expressions))
         }
         // "_not"
         BooleanExpressionAnnotation::BooleanExpressionArgument {
@@ -159,11 +444,10 @@ fn build_filter_expression_from_boolean_expression<'s>(
                 relationships,
                 data_connector_link,
                 type_mappings,
+                session_variables,
                 usage_counts,
             )?;
-            Ok(ndc_models::Expression::Not {
-                expression: Box::new(not_filter_expression),
-            })
+            Ok(FilterExpression::not(not_filter_expression))
         }
         // The column that we want to use for filtering.
         BooleanExpressionAnnotation::BooleanExpressionArgument {
@@ -176,13 +460,13 @@ fn build_filter_expression_from_boolean_expression<'s>(
             let FieldMapping { column, .. } =
                 get_field_mapping_of_field_name(type_mappings, object_type, field_name)?;
 
-            build_comparison_expression(
+            Ok(FilterExpression::Local(build_comparison_expression(
                 field,
                 field_path,
                 &column,
                 data_connector_link,
                 type_mappings,
-            )
+            )?))
         }
         // Relationship field used for filtering.
         // This relationship can either point to another relationship or a column.
@@ -201,27 +485,61 @@ fn build_filter_expression_from_boolean_expression<'s>(
             // Add the target model being used in the usage counts
             count_model(target_model_name, usage_counts);
 
-            // filter expression with relationships is currently only supported for local relationships
-            // this is the first point at which we know the source data connector, so we must
-            // ensure only a local relationship is used
-            match metadata_resolve::relationship_execution_category(
+            // This map contains the relationships or the columns of the
+            // relationship that needs to be used for filtering.
+            let filter_object = field.value.as_object()?;
+
+            // this is the first point at which we know the source data connector, so we can
+            // decide whether the relationship can be evaluated by it
+            match metadata_resolve::relationship_predicate_execution_category(
                 data_connector_link,
                 &target_source.model.data_connector,
                 &target_source.capabilities,
+                mappings,
             ) {
-                metadata_resolve::RelationshipExecutionCategory::RemoteForEach => {
-                    Err(error::Error::Internal(error::InternalError::Engine(
-                        error::InternalEngineError::InternalGeneric {
-                            description:
-                                "Remote relationships are not supported in boolean expressions"
-                                    .to_string(),
+                metadata_resolve::RelationshipExecutionCategory::RemoteForEach
+                | metadata_resolve::RelationshipExecutionCategory::RemoteNaive => {
+                    // The predicate is evaluated against the target model on its own, so the
+                    // relationships it uses are collected separately.
+                    let mut target_relationships = BTreeMap::new();
+                    let predicate = resolve_filter_object(
+                        filter_object,
+                        &mut target_relationships,
+                        &target_source.model.data_connector,
+                        &target_source.model.type_mappings,
+                        session_variables,
+                        usage_counts,
+                    )?;
+
+                    build_remote_relationship_predicate(
+                        relationship_name,
+                        source_type,
+                        type_mappings,
+                        target_model_name,
+                        target_source,
+                        mappings,
+                        ResolvedFilterExpression {
+                            expression: Some(predicate),
+                            relationships: target_relationships,
                         },
-                    )))
+                        session_variables,
+                        usage_counts,
+                    )
                 }
 
                 metadata_resolve::RelationshipExecutionCategory::Local => {
                     let ndc_relationship_name =
                         NDCRelationshipName::new(source_type, relationship_name)?;
+                    let target_mappings = relationship::resolve_target_mappings(
+                        source_type,
+                        relationship_name,
+                        type_mappings,
+                        target_source,
+                        mappings,
+                        session_variables,
+                        usage_counts,
+                    )?;
+                    let value_predicate = target_mappings.predicate.clone();
 
                     relationships.insert(
                         ndc_relationship_name.clone(),
@@ -234,37 +552,30 @@ fn build_filter_expression_from_boolean_expression<'s>(
                             target_source,
                             target_type,
                             mappings,
+                            target_mappings,
                         },
                     );
 
-                    // This map contains the relationships or the columns of the
-                    // relationship that needs to be used for ordering.
-                    let filter_object = field.value.as_object()?;
-
-                    let mut expressions = Vec::new();
-
-                    for field in filter_object.values() {
-                        let field_filter_expression = build_filter_expression(
-                            field,
-                            relationships,
-                            &target_source.model.data_connector,
-                            &target_source.model.type_mappings,
-                            usage_counts,
-                        )?;
-                        expressions.push(field_filter_expression);
+                    let mut predicate = resolve_filter_object(
+                        filter_object,
+                        relationships,
+                        &target_source.model.data_connector,
+                        &target_source.model.type_mappings,
+                        session_variables,
+                        usage_counts,
+                    )?;
+                    if let Some(value_predicate) = value_predicate {
+                        predicate = FilterExpression::and(vec![
+                            predicate,
+                            FilterExpression::Local(value_predicate),
+                        ]);
                     }
 
                     // Using exists clause to build the filter expression for relationship fields.
-                    let exists_filter_clause = ndc_models::Expression::And { expressions };
-                    let exists_in_relationship = ndc_models::ExistsInCollection::Related {
-                        relationship: ndc_relationship_name.0,
-                        arguments: BTreeMap::new(),
-                    };
-
-                    Ok(ndc_models::Expression::Exists {
-                        in_collection: exists_in_relationship,
-                        predicate: Some(Box::new(exists_filter_clause)),
-                    })
+                    Ok(FilterExpression::exists_in_relationship(
+                        ndc_relationship_name,
+                        predicate,
+                    ))
                 }
             }
         }
@@ -386,8 +697,9 @@ fn resolve_filter_object<'s>(
     relationships: &mut BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
     data_connector_link: &'s DataConnectorLink,
     type_mappings: &'s BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
+    session_variables: &SessionVariables,
     usage_counts: &mut UsagesCounts,
-) -> Result<ndc_models::Expression, error::Error> {
+) -> Result<FilterExpression<'s>, error::Error> {
     let mut expressions = Vec::new();
 
     for field in fields.values() {
@@ -396,10 +708,11 @@ fn resolve_filter_object<'s>(
             relationships,
             data_connector_link,
             type_mappings,
+            session_variables,
             usage_counts,
         )?);
     }
-    Ok(ndc_models::Expression::And { expressions })
+    Ok(FilterExpression::and(expressions))
 }
 
 /// Only pass a path if there are items in it
@@ -0,0 +1,276 @@
+//! IR for the point mutations of a model
+//!
+//! A point mutation inserts, updates or deletes a single row of a model, by
+//! executing a procedure of the model's data connector.
+
+use hasura_authn_core::SessionVariables;
+use lang_graphql::ast::common as ast;
+use lang_graphql::ast::common::TypeContainer;
+use lang_graphql::ast::common::TypeName;
+use lang_graphql::normalized_ast;
+use nonempty::NonEmpty;
+use open_dds::commands::ProcedureName;
+use open_dds::models::ModelName;
+use open_dds::types::CustomTypeName;
+use serde::Serialize;
+use serde_json as json;
+use std::collections::BTreeMap;
+
+use super::arguments;
+use super::relationship::LocalModelRelationshipInfo;
+use super::selection_set::{self, NDCRelationshipName};
+use crate::ir::error;
+use crate::ir::permissions;
+use crate::model_tracking::{count_model, UsagesCounts};
+use metadata_resolve;
+use metadata_resolve::{Qualified, QualifiedTypeReference};
+use schema::{
+    Annotation, ArgumentNameAndPath, ArgumentPresets, InputAnnotation, ModelInputAnnotation,
+    ModelMutationKind, NamespaceAnnotation, TypeKind, GDS,
+};
+
+/// IR for the 'insert', 'update' and 'delete' operations on a model
+#[derive(Serialize, Debug)]
+pub struct ModelPointMutation<'s> {
+    /// The name of the model
+    pub model_name: Qualified<ModelName>,
+
+    /// The name of the field as published in the schema
+    pub field_name: ast::Name,
+
+    /// The data connector backing this model.
+    pub data_connector: &'s metadata_resolve::DataConnectorLink,
+
+    /// The procedure executing the mutation
+    pub procedure_name: &'s ProcedureName,
+
+    /// Arguments for the NDC procedure
+    pub(crate) arguments: BTreeMap<String, json::Value>,
+
+    /// IR for the selection set of the affected row
+    pub(crate) selection: Option<selection_set::NestedSelection<'s>>,
+
+    /// Relationships used in the permission predicates
+    pub(crate) predicate_relationships:
+        BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
+
+    /// The Graphql output type of the operation
+    pub type_container: TypeContainer<TypeName>,
+
+    // All the models/commands used in this operation. This includes the models/commands
+    // used via relationships and in the permission predicates.
+    pub(crate) usage_counts: UsagesCounts,
+}
+
+/// Generates the IR for a point mutation of a model
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn generate_model_point_mutation<'n, 's>(
+    model_name: &Qualified<ModelName>,
+    kind: &ModelMutationKind,
+    procedure_name: &'s ProcedureName,
+    data_type: &Qualified<CustomTypeName>,
+    model_source: &'s metadata_resolve::ModelSource,
+    field: &'n normalized_ast::Field<'s, GDS>,
+    field_call: &'n normalized_ast::FieldCall<'s, GDS>,
+    session_variables: &SessionVariables,
+    request_headers: &reqwest::header::HeaderMap,
+) -> Result<ModelPointMutation<'s>, error::Error> {
+    let object_type = QualifiedTypeReference {
+        underlying_type: metadata_resolve::QualifiedBaseType::Named(
+            metadata_resolve::QualifiedTypeName::Custom(data_type.clone()),
+        ),
+        nullable: false,
+    };
+
+    let mut procedure_arguments = BTreeMap::new();
+    for argument in field_call.arguments.values() {
+        match argument.info.generic {
+            Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelUniqueIdentifierArgument { ndc_column },
+            )) => {
+                let ndc_column = ndc_column.as_ref().ok_or_else(|| {
+                    error::InternalEngineError::InternalGeneric {
+                        description: format!(
+                            "Missing NDC column mapping for unique identifier argument {} on field {}",
+                            argument.name, field_call.name
+                        ),
+                    }
+                })?;
+                procedure_arguments.insert(
+                    format!("key_{}", ndc_column.column),
+                    argument.value.as_json(),
+                );
+            }
+            Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelInsertObjectArgument,
+            )) => {
+                procedure_arguments.insert(
+                    "object".to_string(),
+                    arguments::map_argument_value_to_ndc_type(
+                        &object_type,
+                        &argument.value,
+                        &model_source.type_mappings,
+                    )?,
+                );
+            }
+            // the fields of the update input type are annotated with the fields of the
+            // model's object type, so they are mapped to columns just like an object
+            Annotation::Input(InputAnnotation::Model(
+                ModelInputAnnotation::ModelUpdateColumnsArgument,
+            )) => {
+                procedure_arguments.insert(
+                    "update_columns".to_string(),
+                    arguments::map_argument_value_to_ndc_type(
+                        &object_type,
+                        &argument.value,
+                        &model_source.type_mappings,
+                    )?,
+                );
+            }
+            annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
+                annotation: annotation.clone(),
+            })?,
+        }
+    }
+
+    // Add the name of the root model
+    let mut usage_counts = UsagesCounts::new();
+    count_model(model_name, &mut usage_counts);
+
+    let (pre_check, post_check, ArgumentPresets { argument_presets }) =
+        match field_call.info.namespaced {
+            Some(NamespaceAnnotation::ModelMutation {
+                pre_check,
+                post_check,
+                argument_presets,
+            }) => Ok((pre_check, post_check, argument_presets)),
+            _ => Err(
+                error::InternalEngineError::ExpectedNamespaceAnnotationNotFound {
+                    namespace_annotation_type: "ModelMutation".to_string(),
+                },
+            ),
+        }?;
+
+    // add the field presets from the mutation permissions and the input type permissions
+    for (argument_name_and_path, (field_type, argument_value)) in argument_presets {
+        let ArgumentNameAndPath {
+            field_path,
+            ndc_argument_name,
+        } = argument_name_and_path;
+
+        let argument_name = ndc_argument_name.as_ref().ok_or_else(|| {
+            error::InternalEngineError::ArgumentPresetExecution {
+                description: "unexpected; ndc argument name not preset".to_string(),
+            }
+        })?;
+
+        let actual_value = permissions::make_value_from_value_expression(
+            argument_value,
+            field_type,
+            session_variables,
+            &mut usage_counts,
+        )?;
+
+        match NonEmpty::from_slice(field_path) {
+            None => {
+                procedure_arguments.insert(argument_name.to_string(), actual_value);
+            }
+            Some(field_path) => {
+                let current_arg_object = procedure_arguments
+                    .entry(argument_name.to_string())
+                    .or_insert_with(|| json::Value::Object(json::Map::new()))
+                    .as_object_mut()
+                    .ok_or_else(|| error::InternalEngineError::ArgumentPresetExecution {
+                        description: format!("argument {argument_name} is not a JSON object"),
+                    })?;
+                arguments::follow_field_path_and_insert_value(
+                    &field_path,
+                    current_arg_object,
+                    actual_value,
+                )?;
+            }
+        }
+    }
+
+    // the predicates the affected row has to satisfy before and after the mutation
+    let mut predicate_relationships = BTreeMap::new();
+    if !matches!(kind, ModelMutationKind::Insert) {
+        procedure_arguments.insert(
+            "pre_check".to_string(),
+            permission_predicate_to_ndc(
+                model_name,
+                pre_check,
+                session_variables,
+                &mut predicate_relationships,
+                &mut usage_counts,
+            )?,
+        );
+    }
+    if !matches!(kind, ModelMutationKind::Delete) {
+        procedure_arguments.insert(
+            "post_check".to_string(),
+            permission_predicate_to_ndc(
+                model_name,
+                post_check,
+                session_variables,
+                &mut predicate_relationships,
+                &mut usage_counts,
+            )?,
+        );
+    }
+
+    let selection = selection_set::generate_nested_selection(
+        &object_type,
+        TypeKind::Object,
+        field,
+        &model_source.data_connector,
+        &model_source.type_mappings,
+        session_variables,
+        request_headers,
+        &mut usage_counts,
+    )?;
+
+    Ok(ModelPointMutation {
+        model_name: model_name.clone(),
+        field_name: field_call.name.clone(),
+        data_connector: &model_source.data_connector,
+        procedure_name,
+        arguments: procedure_arguments,
+        selection,
+        predicate_relationships,
+        type_container: field.type_container.clone(),
+        usage_counts,
+    })
+}
+
+/// The NDC expression of a permission predicate, to be passed as an argument
+/// to the procedure. Predicates across remote relationships can't be passed
+/// to a procedure, so they are rejected.
+fn permission_predicate_to_ndc<'s>(
+    model_name: &Qualified<ModelName>,
+    permission: &'s metadata_resolve::FilterPermission,
+    session_variables: &SessionVariables,
+    relationships: &mut BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
+    usage_counts: &mut UsagesCounts,
+) -> Result<json::Value, error::Error> {
+    let expression = match permission {
+        metadata_resolve::FilterPermission::AllowAll => ndc_models::Expression::And {
+            expressions: vec![],
+        },
+        metadata_resolve::FilterPermission::Filter(predicate) => {
+            permissions::process_model_predicate(
+                predicate,
+                session_variables,
+                relationships,
+                usage_counts,
+            )?
+            .to_ndc_expression()
+            .ok_or_else(|| {
+                error::Error::RemotePredicateInMutationPermissionNotSupported {
+                    model_name: model_name.clone(),
+                }
+            })?
+        }
+    };
+    Ok(json::to_value(expression)?)
+}
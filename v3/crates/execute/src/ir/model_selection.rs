@@ -9,7 +9,7 @@ use serde::Serialize;
 use std::collections::BTreeMap;
 
 use super::aggregates;
-use super::filter::ResolvedFilterExpression;
+use super::filter::{FilterExpression, ResolvedFilterExpression};
 use super::order_by::ResolvedOrderBy;
 use super::permissions;
 use super::selection_set;
@@ -114,9 +114,10 @@ fn apply_permissions_predicate<'s>(
                 usage_counts,
             )?;
             filter_clauses.expression = match filter_clauses.expression {
-                Some(existing) => Some(ndc_models::Expression::And {
-                    expressions: vec![existing, processed_model_predicate],
-                }),
+                Some(existing) => Some(FilterExpression::and(vec![
+                    existing,
+                    processed_model_predicate,
+                ])),
                 None => Some(processed_model_predicate),
             };
             for (rel_name, rel_info) in permissions_predicate_relationships {
@@ -9,7 +9,7 @@ use tracing_util::SpanVisibility;
 use schema::Annotation;
 use schema::GDS;
 
-use super::{commands, root_field};
+use super::{commands, model_mutation, root_field};
 use crate::ir::error;
 use schema::{OutputAnnotation, RootFieldAnnotation};
 
@@ -75,6 +75,37 @@ pub fn generate_ir<'n, 's>(
                                 )?,
                             })
                         }
+                        Annotation::Output(OutputAnnotation::RootField(
+                            RootFieldAnnotation::ModelMutation {
+                                data_type,
+                                source,
+                                procedure_name,
+                                kind,
+                                name,
+                            },
+                        )) => {
+                            let source = source.as_ref().ok_or_else(|| {
+                                error::InternalDeveloperError::NoSourceDataConnector {
+                                    type_name: type_name.clone(),
+                                    field_name: field_call.name.clone(),
+                                }
+                            })?;
+
+                            Ok(root_field::MutationRootField::ModelPointMutation {
+                                selection_set: &field.selection_set,
+                                ir: model_mutation::generate_model_point_mutation(
+                                    name,
+                                    kind,
+                                    procedure_name,
+                                    data_type,
+                                    source,
+                                    field,
+                                    field_call,
+                                    session_variables,
+                                    request_headers,
+                                )?,
+                            })
+                        }
                         annotation => Err(error::InternalEngineError::UnexpectedAnnotation {
                             annotation: annotation.clone(),
                         }),
@@ -1,13 +1,18 @@
 use std::collections::BTreeMap;
 
 use crate::model_tracking::{count_model, UsagesCounts};
+use hasura_authn_core::SessionVariables;
 use lang_graphql::normalized_ast::{self as normalized_ast, InputField};
+use metadata_resolve::Qualified;
 use ndc_models;
+use open_dds::{models::ModelName, relationships::RelationshipName};
 use schema::OrderByRelationshipAnnotation;
 use schema::{Annotation, InputAnnotation, ModelInputAnnotation};
 use serde::Serialize;
 
-use super::relationship::LocalModelRelationshipInfo;
+use super::relationship::{
+    self, LocalModelRelationshipInfo, RelationshipTargetMappings, SourceField, TargetField,
+};
 use super::selection_set::NDCRelationshipName;
 
 use crate::ir::error;
@@ -16,19 +21,87 @@ use schema::GDS;
 
 #[derive(Debug, Serialize)]
 pub(crate) struct ResolvedOrderBy<'s> {
-    pub(crate) order_by: ndc_models::OrderBy,
+    pub(crate) elements: Vec<OrderByElement<'s>>,
     // relationships that were used in the order_by expression. This is helpful
     // for collecting relatinships and sending collection_relationships
     pub(crate) relationships: BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
 }
 
+impl<'s> ResolvedOrderBy<'s> {
+    /// The NDC order_by, or `None` if the data connector can't order the rows because some of
+    /// the elements are across remote relationships. The engine sorts the rows then.
+    pub(crate) fn to_ndc_order_by(&self) -> Option<ndc_models::OrderBy> {
+        Some(ndc_models::OrderBy {
+            elements: self
+                .elements
+                .iter()
+                .map(|element| match element {
+                    OrderByElement::Local(element) => Some(element.clone()),
+                    OrderByElement::RemoteRelationship(_) => None,
+                })
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    /// The NDC order_by of the elements which aren't across remote relationships, if any. The
+    /// data connector orders the rows the engine sorts by them, which breaks the ties of the sort
+    /// the same way on every page.
+    pub(crate) fn to_local_ndc_order_by(&self) -> Option<ndc_models::OrderBy> {
+        let elements: Vec<_> = self
+            .elements
+            .iter()
+            .filter_map(|element| match element {
+                OrderByElement::Local(element) => Some(element.clone()),
+                OrderByElement::RemoteRelationship(_) => None,
+            })
+            .collect();
+        (!elements.is_empty()).then_some(ndc_models::OrderBy { elements })
+    }
+
+    /// The first remote relationship the rows are ordered across, if any
+    pub(crate) fn first_remote_relationship(&self) -> Option<&'s RelationshipName> {
+        self.elements.iter().find_map(|element| match element {
+            OrderByElement::Local(_) => None,
+            OrderByElement::RemoteRelationship(remote_element) => {
+                Some(remote_element.relationship_name)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) enum OrderByElement<'s> {
+    /// Fields of a model across a remote object relationship
+    RemoteRelationship(RemoteOrderByElement<'s>),
+    /// An element which the data connector of the model can order by
+    #[serde(untagged)]
+    Local(ndc_models::OrderByElement),
+}
+
+/// Ordering by fields across a remote object relationship. The values of the fields are fetched
+/// from the target model for every row, much like a remote relationship field is.
+#[derive(Debug, Serialize)]
+pub(crate) struct RemoteOrderByElement<'s> {
+    pub relationship_name: &'s RelationshipName,
+    pub target_model_name: &'s Qualified<ModelName>,
+    pub target_source: &'s metadata_resolve::ModelTargetSource,
+    pub join_mapping: Vec<(SourceField, TargetField)>,
+    // The arguments of the target model, and the comparisons of its fields with values
+    pub target_mappings: RelationshipTargetMappings,
+    // The elements of the target model to order by, in order
+    pub elements: Vec<ndc_models::OrderByElement>,
+    // local relationships of the target model used by `elements`
+    pub relationships: BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
+}
+
 pub(crate) fn build_ndc_order_by<'s>(
     args_field: &InputField<'s, GDS>,
+    session_variables: &SessionVariables,
     usage_counts: &mut UsagesCounts,
 ) -> Result<ResolvedOrderBy<'s>, error::Error> {
     match &args_field.value {
         normalized_ast::Value::List(arguments) => {
-            let mut ndc_order_elements = Vec::new();
+            let mut order_by_elements = Vec::new();
             let mut relationships = BTreeMap::new();
 
             for v in arguments {
@@ -51,9 +124,10 @@ pub(crate) fn build_ndc_order_by<'s>(
                                 argument,
                                 relationship_paths,
                                 &mut relationships,
+                                session_variables,
                                 usage_counts,
                             )?;
-                            ndc_order_elements.extend(order_by_element);
+                            order_by_elements.extend(order_by_element);
                         } else {
                             Err(error::Error::OrderByObjectShouldExactlyHaveOneKeyValuePair)?;
                         }
@@ -64,9 +138,7 @@ pub(crate) fn build_ndc_order_by<'s>(
                 }
             }
             Ok(ResolvedOrderBy {
-                order_by: ndc_models::OrderBy {
-                    elements: ndc_order_elements,
-                },
+                elements: order_by_elements,
                 relationships,
             })
         }
@@ -106,8 +178,9 @@ pub(crate) fn build_ndc_order_by_element<'s>(
     // to access the column.
     mut relationship_paths: Vec<NDCRelationshipName>,
     relationships: &mut BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
+    session_variables: &SessionVariables,
     usage_counts: &mut UsagesCounts,
-) -> Result<Vec<ndc_models::OrderByElement>, error::Error> {
+) -> Result<Vec<OrderByElement<'s>>, error::Error> {
     match argument.info.generic {
         // The column that we want to use for ordering. If the column happens to be
         // a relationship column, we'll have to join all the paths to specify NDC,
@@ -140,6 +213,11 @@ pub(crate) fn build_ndc_order_by_element<'s>(
             // called `text`, you'll have to provide the following paths to access the `text` column:
             // ["UserPosts", "PostsComments"]
             for path in &relationship_paths {
+                // the rows of the relationship are restricted to those whose fields match the
+                // values mapped to them
+                let value_predicate = relationships
+                    .get(path)
+                    .and_then(|relationship| relationship.target_mappings.predicate.clone());
                 order_by_element_path.push(ndc_models::PathElement {
                     relationship: path.0.clone(),
                     arguments: BTreeMap::new(),
@@ -160,7 +238,7 @@ pub(crate) fn build_ndc_order_by_element<'s>(
                         // result.
                         //
                         // Currently we only support the 1st type of sort. Hence we don't have any expressions/predicate.
-                        expressions: Vec::new(),
+                        expressions: value_predicate.into_iter().collect(),
                     })),
                 });
             }
@@ -175,7 +253,7 @@ pub(crate) fn build_ndc_order_by_element<'s>(
                 },
             };
 
-            Ok(vec![order_element])
+            Ok(vec![OrderByElement::Local(order_element)])
         }
         // A relationship is being used to order the results. This relationship can
         // either point to another relationship or a column.
@@ -194,22 +272,6 @@ pub(crate) fn build_ndc_order_by_element<'s>(
                 },
             ),
         )) => {
-            let ndc_relationship_name = NDCRelationshipName::new(source_type, relationship_name)?;
-
-            relationships.insert(
-                ndc_relationship_name.clone(),
-                LocalModelRelationshipInfo {
-                    relationship_name,
-                    relationship_type,
-                    source_type,
-                    source_data_connector,
-                    source_type_mappings,
-                    target_source,
-                    target_type,
-                    mappings,
-                },
-            );
-
             // Add the target model being used in the usage counts
             count_model(target_model_name, usage_counts);
 
@@ -217,17 +279,116 @@ pub(crate) fn build_ndc_order_by_element<'s>(
             let argument_value_map = argument.value.as_object()?;
             let mut order_by_elements = Vec::new();
 
-            // Add the current relationship to the relationship paths.
-            relationship_paths.push(ndc_relationship_name);
-
-            for argument in argument_value_map.values() {
-                let order_by_element = build_ndc_order_by_element(
-                    argument,
-                    relationship_paths.clone(),
-                    relationships,
-                    usage_counts,
-                )?;
-                order_by_elements.extend(order_by_element);
+            match metadata_resolve::relationship_predicate_execution_category(
+                source_data_connector,
+                &target_source.model.data_connector,
+                &target_source.capabilities,
+                mappings,
+            ) {
+                metadata_resolve::RelationshipExecutionCategory::Local => {
+                    let ndc_relationship_name =
+                        NDCRelationshipName::new(source_type, relationship_name)?;
+                    let target_mappings = relationship::resolve_target_mappings(
+                        source_type,
+                        relationship_name,
+                        source_type_mappings,
+                        target_source,
+                        mappings,
+                        session_variables,
+                        usage_counts,
+                    )?;
+
+                    relationships.insert(
+                        ndc_relationship_name.clone(),
+                        LocalModelRelationshipInfo {
+                            relationship_name,
+                            relationship_type,
+                            source_type,
+                            source_data_connector,
+                            source_type_mappings,
+                            target_source,
+                            target_type,
+                            mappings,
+                            target_mappings,
+                        },
+                    );
+
+                    // Add the current relationship to the relationship paths.
+                    relationship_paths.push(ndc_relationship_name);
+
+                    for argument in argument_value_map.values() {
+                        let order_by_element = build_ndc_order_by_element(
+                            argument,
+                            relationship_paths.clone(),
+                            relationships,
+                            session_variables,
+                            usage_counts,
+                        )?;
+                        order_by_elements.extend(order_by_element);
+                    }
+                }
+                // The data connector of the model can't order by fields of a model in another
+                // data connector, so the engine fetches them and sorts the rows itself.
+                metadata_resolve::RelationshipExecutionCategory::RemoteForEach
+                | metadata_resolve::RelationshipExecutionCategory::RemoteNaive => {
+                    // the join mapping is from the fields of the model itself
+                    if !relationship_paths.is_empty() {
+                        Err(error::Error::RemoteOrderByNotSupported {
+                            relationship_name: relationship_name.clone(),
+                            reason: "across local relationships",
+                        })?;
+                    }
+                    let join_mapping = relationship::get_remote_join_mapping(
+                        source_type,
+                        relationship_name,
+                        source_type_mappings,
+                        mappings,
+                    )?;
+                    let target_mappings = relationship::resolve_target_mappings(
+                        source_type,
+                        relationship_name,
+                        source_type_mappings,
+                        target_source,
+                        mappings,
+                        session_variables,
+                        usage_counts,
+                    )?;
+
+                    for argument in argument_value_map.values() {
+                        // the paths of the target model start afresh, and its relationships are
+                        // sent to its own data connector
+                        let mut target_relationships = BTreeMap::new();
+                        let target_elements = build_ndc_order_by_element(
+                            argument,
+                            Vec::new(),
+                            &mut target_relationships,
+                            session_variables,
+                            usage_counts,
+                        )?
+                        .into_iter()
+                        .map(|element| match element {
+                            OrderByElement::Local(element) => Ok(element),
+                            OrderByElement::RemoteRelationship(remote_element) => {
+                                Err(error::Error::RemoteOrderByNotSupported {
+                                    relationship_name: remote_element.relationship_name.clone(),
+                                    reason: "across another remote relationship",
+                                })
+                            }
+                        })
+                        .collect::<Result<_, _>>()?;
+                        order_by_elements.push(OrderByElement::RemoteRelationship(
+                            RemoteOrderByElement {
+                                relationship_name,
+                                target_model_name,
+                                target_source,
+                                join_mapping: join_mapping.clone(),
+                                target_mappings: target_mappings.clone(),
+                                elements: target_elements,
+                                relationships: target_relationships,
+                            },
+                        ));
+                    }
+                }
             }
             Ok(order_by_elements)
         }
@@ -1,6 +1,6 @@
 use std::collections::BTreeMap;
 
-use hasura_authn_core::{SessionVariableValue, SessionVariables};
+use hasura_authn_core::{SessionVariable, SessionVariableValue, SessionVariables};
 use lang_graphql::normalized_ast;
 use ndc_models;
 
@@ -17,7 +17,8 @@ use metadata_resolve::{QualifiedBaseType, QualifiedTypeName, QualifiedTypeRefere
 use schema;
 use schema::GDS;
 
-use super::relationship::LocalModelRelationshipInfo;
+use super::filter::{self, FilterExpression, ResolvedFilterExpression};
+use super::relationship::{self, LocalModelRelationshipInfo};
 use super::selection_set::NDCRelationshipName;
 
 /// Fetch filter expression from the namespace annotation
@@ -34,7 +35,8 @@ pub(crate) fn get_select_filter_predicate<'s>(
             schema::NamespaceAnnotation::Model { filter, .. } => Some(filter),
             schema::NamespaceAnnotation::NodeFieldTypeMappings(_)
             | schema::NamespaceAnnotation::EntityTypeMappings(_)
-            | schema::NamespaceAnnotation::Command(_) => None,
+            | schema::NamespaceAnnotation::Command(_)
+            | schema::NamespaceAnnotation::ModelMutation { .. } => None,
         })
         // If we're hitting this case, it means that the caller of this
         // function expects a filter predicate, but it was not annotated
@@ -46,6 +48,23 @@ pub(crate) fn get_select_filter_predicate<'s>(
         )))
 }
 
+/// Combine the limit requested in the query with the row limit of the select
+/// permission found in the namespace annotation of the field call. The smaller
+/// of the two limits is used.
+pub(crate) fn apply_select_limit(
+    limit: Option<u32>,
+    field_call: &normalized_ast::FieldCall<'_, GDS>,
+) -> Option<u32> {
+    let permission_limit = match field_call.info.namespaced.as_ref() {
+        Some(schema::NamespaceAnnotation::Model { limit, .. }) => *limit,
+        _ => None,
+    };
+    match (limit, permission_limit) {
+        (Some(limit), Some(permission_limit)) => Some(limit.min(permission_limit)),
+        (limit, permission_limit) => limit.or(permission_limit),
+    }
+}
+
 /// Fetch argument presets from the namespace annotation
 /// of the field call. If there are no annotations, this is fine,
 /// but if unexpected ones are found an error will be thrown.
@@ -58,6 +77,9 @@ pub(crate) fn get_argument_presets(
             schema::NamespaceAnnotation::Command(argument_presets)
             | schema::NamespaceAnnotation::Model {
                 argument_presets, ..
+            }
+            | schema::NamespaceAnnotation::ModelMutation {
+                argument_presets, ..
             } => Ok(Some(argument_presets)),
             other_namespace_annotation =>
             // If we're hitting this case, it means that the caller of this
@@ -80,50 +102,49 @@ pub(crate) fn process_model_predicate<'s>(
     session_variables: &SessionVariables,
     relationships: &mut BTreeMap<NDCRelationshipName, LocalModelRelationshipInfo<'s>>,
     usage_counts: &mut UsagesCounts,
-) -> Result<ndc_models::Expression, error::Error> {
+) -> Result<FilterExpression<'s>, error::Error> {
     match model_predicate {
         metadata_resolve::ModelPredicate::UnaryFieldComparison {
             field: _,
             ndc_column,
             operator,
-        } => Ok(make_permission_unary_boolean_expression(
-            ndc_column.clone(),
-            *operator,
-        )?),
+        } => Ok(FilterExpression::Local(
+            make_permission_unary_boolean_expression(ndc_column.clone(), *operator)?,
+        )),
         metadata_resolve::ModelPredicate::BinaryFieldComparison {
             field: _,
             ndc_column,
             argument_type,
             operator,
             value,
-        } => Ok(make_permission_binary_boolean_expression(
-            ndc_column.clone(),
-            argument_type,
-            operator,
-            value,
-            session_variables,
-            usage_counts,
-        )?),
+        } => Ok(FilterExpression::Local(
+            make_permission_binary_boolean_expression(
+                ndc_column.clone(),
+                argument_type,
+                operator,
+                value,
+                session_variables,
+                usage_counts,
+            )?,
+        )),
         metadata_resolve::ModelPredicate::Not(predicate) => {
             let expr =
                 process_model_predicate(predicate, session_variables, relationships, usage_counts)?;
-            Ok(ndc_models::Expression::Not {
-                expression: Box::new(expr),
-            })
+            Ok(FilterExpression::not(expr))
         }
         metadata_resolve::ModelPredicate::And(predicates) => {
             let exprs = predicates
                 .iter()
                 .map(|p| process_model_predicate(p, session_variables, relationships, usage_counts))
                 .collect::<Result<Vec<_>, error::Error>>()?;
-            Ok(ndc_models::Expression::And { expressions: exprs })
+            Ok(FilterExpression::and(exprs))
         }
         metadata_resolve::ModelPredicate::Or(predicates) => {
             let exprs = predicates
                 .iter()
                 .map(|p| process_model_predicate(p, session_variables, relationships, usage_counts))
                 .collect::<Result<Vec<_>, error::Error>>()?;
-            Ok(ndc_models::Expression::Or { expressions: exprs })
+            Ok(FilterExpression::or(exprs))
         }
         metadata_resolve::ModelPredicate::Relationship {
             relationship_info,
@@ -132,37 +153,89 @@ pub(crate) fn process_model_predicate<'s>(
             // Add the target model being used in the usage counts
             count_model(&relationship_info.target_model_name, usage_counts);
 
-            let relationship_name = (NDCRelationshipName::new(
-                &relationship_info.source_type,
-                &relationship_info.relationship_name,
-            ))?;
+            match metadata_resolve::relationship_predicate_execution_category(
+                &relationship_info.source_data_connector,
+                &relationship_info.target_source.model.data_connector,
+                &relationship_info.target_source.capabilities,
+                &relationship_info.mappings,
+            ) {
+                metadata_resolve::RelationshipExecutionCategory::RemoteForEach
+                | metadata_resolve::RelationshipExecutionCategory::RemoteNaive => {
+                    // The predicate is evaluated against the target model on its own, so the
+                    // relationships it uses are collected separately.
+                    let mut target_relationships = BTreeMap::new();
+                    let relationship_predicate = process_model_predicate(
+                        predicate,
+                        session_variables,
+                        &mut target_relationships,
+                        usage_counts,
+                    )?;
 
-            relationships.insert(
-                relationship_name.clone(),
-                LocalModelRelationshipInfo {
-                    relationship_name: &relationship_info.relationship_name,
-                    relationship_type: &relationship_info.relationship_type,
-                    source_type: &relationship_info.source_type,
-                    source_data_connector: &relationship_info.source_data_connector,
-                    source_type_mappings: &relationship_info.source_type_mappings,
-                    target_source: &relationship_info.target_source,
-                    target_type: &relationship_info.target_type,
-                    mappings: &relationship_info.mappings,
-                },
-            );
+                    filter::build_remote_relationship_predicate(
+                        &relationship_info.relationship_name,
+                        &relationship_info.source_type,
+                        &relationship_info.source_type_mappings,
+                        &relationship_info.target_model_name,
+                        &relationship_info.target_source,
+                        &relationship_info.mappings,
+                        ResolvedFilterExpression {
+                            expression: Some(relationship_predicate),
+                            relationships: target_relationships,
+                        },
+                        session_variables,
+                        usage_counts,
+                    )
+                }
+                metadata_resolve::RelationshipExecutionCategory::Local => {
+                    let relationship_name = (NDCRelationshipName::new(
+                        &relationship_info.source_type,
+                        &relationship_info.relationship_name,
+                    ))?;
+                    let target_mappings = relationship::resolve_target_mappings(
+                        &relationship_info.source_type,
+                        &relationship_info.relationship_name,
+                        &relationship_info.source_type_mappings,
+                        &relationship_info.target_source,
+                        &relationship_info.mappings,
+                        session_variables,
+                        usage_counts,
+                    )?;
+                    let value_predicate = target_mappings.predicate.clone();
 
-            let relationship_predicate =
-                process_model_predicate(predicate, session_variables, relationships, usage_counts)?;
+                    relationships.insert(
+                        relationship_name.clone(),
+                        LocalModelRelationshipInfo {
+                            relationship_name: &relationship_info.relationship_name,
+                            relationship_type: &relationship_info.relationship_type,
+                            source_type: &relationship_info.source_type,
+                            source_data_connector: &relationship_info.source_data_connector,
+                            source_type_mappings: &relationship_info.source_type_mappings,
+                            target_source: &relationship_info.target_source,
+                            target_type: &relationship_info.target_type,
+                            mappings: &relationship_info.mappings,
+                            target_mappings,
+                        },
+                    );
 
-            let exists_in_relationship = ndc_models::ExistsInCollection::Related {
-                relationship: relationship_name.0,
-                arguments: BTreeMap::new(),
-            };
+                    let mut relationship_predicate = process_model_predicate(
+                        predicate,
+                        session_variables,
+                        relationships,
+                        usage_counts,
+                    )?;
+                    if let Some(value_predicate) = value_predicate {
+                        relationship_predicate = FilterExpression::and(vec![
+                            relationship_predicate,
+                            FilterExpression::Local(value_predicate),
+                        ]);
+                    }
 
-            Ok(ndc_models::Expression::Exists {
-                in_collection: exists_in_relationship,
-                predicate: Some(Box::new(relationship_predicate)),
-            })
+                    Ok(FilterExpression::exists_in_relationship(
+                        relationship_name,
+                        relationship_predicate,
+                    ))
+                }
+            }
         }
     }
 }
@@ -223,56 +296,80 @@ pub(crate) fn make_value_from_value_expression(
                 }
             })?;
 
-            typecast_session_variable(value, value_type)
+            typecast_session_variable(session_var, value, value_type)
         }
         metadata_resolve::ValueExpression::BooleanExpression(model_predicate) => {
             let mut relationships = BTreeMap::new();
 
-            let ndc_expression = process_model_predicate(
+            let filter_expression = process_model_predicate(
                 model_predicate,
                 session_variables,
                 &mut relationships,
                 usage_counts,
             )?;
+            // argument presets are evaluated by the data connector, which can't evaluate
+            // predicates across remote relationships
+            let ndc_expression = filter_expression
+                .to_ndc_expression()
+                .ok_or(error::InternalDeveloperError::RemotePredicateInArgumentPreset)?;
             Ok(serde_json::to_value(ndc_expression)?)
         }
     }
 }
 
-/// Typecast a stringified session variable into a given type, but as a serde_json::Value
+/// Typecast a session variable into a given type, but as a serde_json::Value
 fn typecast_session_variable(
+    session_variable: &SessionVariable,
     session_var_value_wrapped: &SessionVariableValue,
     to_type: &QualifiedTypeReference,
 ) -> Result<serde_json::Value, error::Error> {
-    let session_var_value = &session_var_value_wrapped.0;
+    match session_var_value_wrapped {
+        SessionVariableValue::Unparsed(session_var_value) => {
+            typecast_unparsed_session_variable(session_variable, session_var_value, to_type)
+        }
+        SessionVariableValue::Parsed(session_var_value) => {
+            typecast_parsed_session_variable(session_variable, session_var_value, to_type)
+        }
+    }
+}
+
+/// Typecast a stringified session variable into a given type, but as a serde_json::Value
+fn typecast_unparsed_session_variable(
+    session_variable: &SessionVariable,
+    session_var_value: &str,
+    to_type: &QualifiedTypeReference,
+) -> Result<serde_json::Value, error::Error> {
     match &to_type.underlying_type {
         QualifiedBaseType::Named(type_name) => {
             match type_name {
                 QualifiedTypeName::Inbuilt(primitive) => match primitive {
                     InbuiltType::Int => {
                         let value: i32 = session_var_value.parse().map_err(|_| {
-                            error::InternalDeveloperError::VariableTypeCast {
+                            error::Error::SessionVariableTypeMismatch {
+                                session_variable: session_variable.clone(),
                                 expected: "int".into(),
-                                found: session_var_value.clone(),
+                                found: session_var_value.to_string(),
                             }
                         })?;
                         Ok(serde_json::Value::Number(value.into()))
                     }
                     InbuiltType::Float => {
                         let value: f32 = session_var_value.parse().map_err(|_| {
-                            error::InternalDeveloperError::VariableTypeCast {
+                            error::Error::SessionVariableTypeMismatch {
+                                session_variable: session_variable.clone(),
                                 expected: "float".into(),
-                                found: session_var_value.clone(),
+                                found: session_var_value.to_string(),
                             }
                         })?;
                         Ok(serde_json::to_value(value)?)
                     }
-                    InbuiltType::Boolean => match session_var_value.as_str() {
+                    InbuiltType::Boolean => match session_var_value {
                         "true" => Ok(serde_json::Value::Bool(true)),
                         "false" => Ok(serde_json::Value::Bool(false)),
-                        _ => Err(error::InternalDeveloperError::VariableTypeCast {
+                        _ => Err(error::Error::SessionVariableTypeMismatch {
+                            session_variable: session_variable.clone(),
                             expected: "true or false".into(),
-                            found: session_var_value.clone(),
+                            found: session_var_value.to_string(),
                         })?,
                     },
                     InbuiltType::String => Ok(serde_json::to_value(session_var_value)?),
@@ -292,6 +389,177 @@ fn typecast_session_variable(
                 }
             }
         }
-        QualifiedBaseType::List(_) => Err(error::InternalDeveloperError::VariableArrayTypeCast)?,
+        // A stringified array, e.g. from a header, is parsed as JSON and its elements are typecast
+        // like those of any other array
+        QualifiedBaseType::List(_) => {
+            let value: serde_json::Value = serde_json::from_str(session_var_value)
+                .ok()
+                .filter(serde_json::Value::is_array)
+                .ok_or_else(|| error::Error::SessionVariableTypeMismatch {
+                    session_variable: session_variable.clone(),
+                    expected: "an array".into(),
+                    found: session_var_value.to_string(),
+                })?;
+            typecast_parsed_session_variable(session_variable, &value, to_type)
+        }
+    }
+}
+
+/// Typecast a session variable which is already a JSON value, e.g. from a JWT claim, into a
+/// given type. The value is checked against the type rather than parsed, except that strings are
+/// typecast as if they were stringified session variables.
+fn typecast_parsed_session_variable(
+    session_variable: &SessionVariable,
+    session_var_value: &serde_json::Value,
+    to_type: &QualifiedTypeReference,
+) -> Result<serde_json::Value, error::Error> {
+    let type_cast_error = |expected: &str| error::Error::SessionVariableTypeMismatch {
+        session_variable: session_variable.clone(),
+        expected: expected.into(),
+        found: session_var_value.to_string(),
+    };
+    match (session_var_value, &to_type.underlying_type) {
+        (serde_json::Value::Null, _) if to_type.nullable => Ok(serde_json::Value::Null),
+        (serde_json::Value::String(string), _) => {
+            typecast_unparsed_session_variable(session_variable, string, to_type)
+        }
+        (serde_json::Value::Array(elements), QualifiedBaseType::List(element_type)) => {
+            let elements = elements
+                .iter()
+                .map(|element| {
+                    typecast_parsed_session_variable(session_variable, element, element_type)
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(serde_json::Value::Array(elements))
+        }
+        (_, QualifiedBaseType::List(_)) => Err(type_cast_error("an array"))?,
+        (_, QualifiedBaseType::Named(QualifiedTypeName::Inbuilt(primitive))) => match primitive {
+            InbuiltType::Int => {
+                let value = session_var_value
+                    .as_i64()
+                    .and_then(|value| i32::try_from(value).ok())
+                    .ok_or_else(|| type_cast_error("int"))?;
+                Ok(serde_json::Value::Number(value.into()))
+            }
+            InbuiltType::Float if session_var_value.is_number() => Ok(session_var_value.clone()),
+            InbuiltType::Float => Err(type_cast_error("float"))?,
+            InbuiltType::Boolean if session_var_value.is_boolean() => Ok(session_var_value.clone()),
+            InbuiltType::Boolean => Err(type_cast_error("true or false"))?,
+            InbuiltType::String => Err(type_cast_error("string"))?,
+            // IDs are serialized as strings, but may also be given as numbers
+            InbuiltType::ID => match session_var_value {
+                serde_json::Value::Number(number) => {
+                    Ok(serde_json::Value::String(number.to_string()))
+                }
+                _ => Err(type_cast_error("string or int"))?,
+            },
+        },
+        // Custom types are passed through as they are, see the TODO above about `representation`
+        (_, QualifiedBaseType::Named(QualifiedTypeName::Custom(_type_name))) => {
+            Ok(session_var_value.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn inbuilt(primitive: InbuiltType, nullable: bool) -> QualifiedTypeReference {
+        QualifiedTypeReference {
+            underlying_type: QualifiedBaseType::Named(QualifiedTypeName::Inbuilt(primitive)),
+            nullable,
+        }
+    }
+
+    fn list_of(element_type: QualifiedTypeReference) -> QualifiedTypeReference {
+        QualifiedTypeReference {
+            underlying_type: QualifiedBaseType::List(Box::new(element_type)),
+            nullable: false,
+        }
+    }
+
+    fn session_variable() -> SessionVariable {
+        "x-hasura-user-id".parse().unwrap()
+    }
+
+    #[test]
+    fn test_typecast_unparsed_session_variable() {
+        let session_variable = session_variable();
+        let int = inbuilt(InbuiltType::Int, false);
+        assert_eq!(
+            typecast_session_variable(&session_variable, &SessionVariableValue::new("1"), &int)
+                .unwrap(),
+            json!(1)
+        );
+        assert!(typecast_session_variable(
+            &session_variable,
+            &SessionVariableValue::new("one"),
+            &int
+        )
+        .is_err());
+        // stringified arrays, e.g. from headers, can be used as lists
+        assert_eq!(
+            typecast_session_variable(
+                &session_variable,
+                &SessionVariableValue::new("[1, \"2\"]"),
+                &list_of(int)
+            )
+            .unwrap(),
+            json!([1, 2])
+        );
+    }
+
+    #[test]
+    fn test_typecast_parsed_session_variable() {
+        let session_variable = session_variable();
+        let int = inbuilt(InbuiltType::Int, false);
+        let string = inbuilt(InbuiltType::String, false);
+        let parsed = SessionVariableValue::from_json;
+        assert_eq!(
+            typecast_session_variable(&session_variable, &parsed(json!(1)), &int).unwrap(),
+            json!(1)
+        );
+        assert!(typecast_session_variable(&session_variable, &parsed(json!(1.5)), &int).is_err());
+        assert!(
+            typecast_session_variable(&session_variable, &parsed(json!(true)), &string).is_err()
+        );
+        assert_eq!(
+            typecast_session_variable(
+                &session_variable,
+                &parsed(json!(["a", "b"])),
+                &list_of(string.clone())
+            )
+            .unwrap(),
+            json!(["a", "b"])
+        );
+        assert!(typecast_session_variable(
+            &session_variable,
+            &parsed(json!(["a", 1])),
+            &list_of(string)
+        )
+        .is_err());
+        assert!(
+            typecast_session_variable(&session_variable, &parsed(json!(1)), &list_of(int)).is_err()
+        );
+        assert_eq!(
+            typecast_session_variable(
+                &session_variable,
+                &parsed(json!(null)),
+                &inbuilt(InbuiltType::Boolean, true)
+            )
+            .unwrap(),
+            json!(null)
+        );
+        assert_eq!(
+            typecast_session_variable(
+                &session_variable,
+                &parsed(json!(7)),
+                &inbuilt(InbuiltType::ID, false)
+            )
+            .unwrap(),
+            json!("7")
+        );
     }
 }
@@ -7,7 +7,7 @@ use open_dds::types::CustomTypeName;
 use serde::Serialize;
 
 use crate::ir::error;
-use crate::ir::filter::ResolvedFilterExpression;
+use crate::ir::filter::{FilterExpression, ResolvedFilterExpression};
 use crate::ir::model_selection;
 use crate::model_tracking::UsagesCounts;
 use json_ext::HashMapWithJsonKey;
@@ -162,9 +162,9 @@ pub(crate) fn entities_ir<'n, 's>(
             let new_selection_set = field.selection_set.filter_field_calls_by_typename(typename);
 
             let filter_clauses = ResolvedFilterExpression {
-                expression: Some(ndc_models::Expression::And {
+                expression: Some(FilterExpression::Local(ndc_models::Expression::And {
                     expressions: filter_clause_expressions,
-                }),
+                })),
                 relationships: BTreeMap::new(),
             };
             let mut usage_counts = UsagesCounts::new();
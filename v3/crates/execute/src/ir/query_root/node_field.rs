@@ -10,7 +10,7 @@ use open_dds::types::CustomTypeName;
 use serde::Serialize;
 
 use crate::ir::error;
-use crate::ir::filter::ResolvedFilterExpression;
+use crate::ir::filter::{FilterExpression, ResolvedFilterExpression};
 use crate::ir::model_selection;
 use crate::model_tracking::UsagesCounts;
 use json_ext::HashMapWithJsonKey;
@@ -145,9 +145,9 @@ pub(crate) fn relay_node_ir<'n, 's>(
             let mut usage_counts = UsagesCounts::new();
 
             let filter_clauses = ResolvedFilterExpression {
-                expression: Some(ndc_models::Expression::And {
+                expression: Some(FilterExpression::Local(ndc_models::Expression::And {
                     expressions: filter_clause_expressions,
-                }),
+                })),
                 relationships: BTreeMap::new(),
             };
 
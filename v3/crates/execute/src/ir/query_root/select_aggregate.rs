@@ -68,8 +68,12 @@ pub(crate) fn select_aggregate_generate_ir<'n, 's>(
     let mut usage_counts = UsagesCounts::new();
     count_model(model_name, &mut usage_counts);
 
-    let mut arguments =
-        read_model_select_aggregate_arguments(field_call, model_source, &mut usage_counts)?;
+    let mut arguments = read_model_select_aggregate_arguments(
+        field_call,
+        model_source,
+        session_variables,
+        &mut usage_counts,
+    )?;
 
     // If there are model arguments presets from permissions, apply them
     if let Some(model_argument_presets) =
@@ -90,7 +94,7 @@ pub(crate) fn select_aggregate_generate_ir<'n, 's>(
         arguments.model_arguments,
         arguments.filter_input_arguments.filter_clause,
         permissions::get_select_filter_predicate(field_call)?,
-        arguments.filter_input_arguments.limit,
+        permissions::apply_select_limit(arguments.filter_input_arguments.limit, field_call),
         arguments.filter_input_arguments.offset,
         arguments.filter_input_arguments.order_by,
         session_variables,
@@ -109,6 +113,7 @@ pub(crate) fn select_aggregate_generate_ir<'n, 's>(
 fn read_model_select_aggregate_arguments<'s>(
     field_call: &normalized_ast::FieldCall<'s, GDS>,
     model_source: &'s metadata_resolve::ModelSource,
+    session_variables: &SessionVariables,
     usage_counts: &mut UsagesCounts,
 ) -> Result<ModelSelectAggregateArguments<'s>, error::Error> {
     let mut model_arguments = None;
@@ -172,8 +177,12 @@ fn read_model_select_aggregate_arguments<'s>(
         }
     }
 
-    let filter_input_arguments =
-        read_filter_input_arguments(filter_input_props, model_source, usage_counts)?;
+    let filter_input_arguments = read_filter_input_arguments(
+        filter_input_props,
+        model_source,
+        session_variables,
+        usage_counts,
+    )?;
 
     Ok(ModelSelectAggregateArguments {
         model_arguments: model_arguments.unwrap_or_else(BTreeMap::new),
@@ -184,6 +193,7 @@ fn read_model_select_aggregate_arguments<'s>(
 fn read_filter_input_arguments<'s>(
     filter_input_field_props: Option<&IndexMap<ast::Name, normalized_ast::InputField<'s, GDS>>>,
     model_source: &'s metadata_resolve::ModelSource,
+    session_variables: &SessionVariables,
     usage_counts: &mut UsagesCounts,
 ) -> Result<FilterInputArguments<'s>, error::Error> {
     let mut limit = None;
@@ -240,7 +250,19 @@ fn read_filter_input_arguments<'s>(
                         }
                         .into());
                     }
-                    order_by = Some(build_ndc_order_by(filter_input_field_arg, usage_counts)?);
+                    let resolved_order_by = build_ndc_order_by(
+                        filter_input_field_arg,
+                        session_variables,
+                        usage_counts,
+                    )?;
+                    // the engine sorts rows, but doesn't aggregate them
+                    if let Some(relationship_name) = resolved_order_by.first_remote_relationship() {
+                        return Err(error::Error::RemoteOrderByNotSupported {
+                            relationship_name: relationship_name.clone(),
+                            reason: "in aggregates",
+                        });
+                    }
+                    order_by = Some(resolved_order_by);
                 }
 
                 // Where argument
@@ -257,6 +279,7 @@ fn read_filter_input_arguments<'s>(
                         filter_input_field_arg.value.as_object()?,
                         &model_source.data_connector,
                         &model_source.type_mappings,
+                        session_variables,
                         usage_counts,
                     )?);
                 }
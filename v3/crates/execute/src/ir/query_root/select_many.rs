@@ -100,7 +100,11 @@ pub(crate) fn select_many_generate_ir<'n, 's>(
                     })?,
                 },
                 ModelInputAnnotation::ModelOrderByExpression => {
-                    order_by = Some(build_ndc_order_by(argument, &mut usage_counts)?);
+                    order_by = Some(build_ndc_order_by(
+                        argument,
+                        session_variables,
+                        &mut usage_counts,
+                    )?);
                 }
                 _ => {
                     return Err(error::InternalEngineError::UnexpectedAnnotation {
@@ -116,6 +120,7 @@ pub(crate) fn select_many_generate_ir<'n, 's>(
                     argument.value.as_object()?,
                     &model_source.data_connector,
                     &model_source.type_mappings,
+                    session_variables,
                     &mut usage_counts,
                 )?;
             }
@@ -150,7 +155,7 @@ pub(crate) fn select_many_generate_ir<'n, 's>(
         model_arguments,
         filter_clause,
         permissions::get_select_filter_predicate(field_call)?,
-        limit,
+        permissions::apply_select_limit(limit, field_call),
         offset,
         order_by,
         session_variables,
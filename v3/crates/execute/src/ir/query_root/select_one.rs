@@ -14,7 +14,7 @@ use serde::Serialize;
 
 use crate::ir::arguments;
 use crate::ir::error;
-use crate::ir::filter::ResolvedFilterExpression;
+use crate::ir::filter::{FilterExpression, ResolvedFilterExpression};
 use crate::ir::model_selection;
 use crate::ir::permissions;
 use crate::model_tracking::{count_model, UsagesCounts};
@@ -106,9 +106,9 @@ pub(crate) fn select_one_generate_ir<'n, 's>(
     }
 
     let filter_clause = ResolvedFilterExpression {
-        expression: Some(ndc_models::Expression::And {
+        expression: Some(FilterExpression::Local(ndc_models::Expression::And {
             expressions: filter_clause_expressions,
-        }),
+        })),
         relationships: BTreeMap::new(),
     };
 
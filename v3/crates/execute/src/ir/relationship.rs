@@ -4,6 +4,7 @@ use hasura_authn_core::SessionVariables;
 use lang_graphql::normalized_ast::{self, Field};
 use open_dds::{
     arguments::ArgumentName,
+    data_connector::DataConnectorColumnName,
     relationships::{RelationshipName, RelationshipType},
     types::{CustomTypeName, FieldName},
 };
@@ -14,8 +15,9 @@ use serde::Serialize;
 use super::permissions;
 use super::selection_set::FieldSelection;
 use super::{
-    commands::generate_function_based_command, filter::resolve_filter_expression,
-    filter::ResolvedFilterExpression, model_selection::model_selection_ir,
+    commands::generate_function_based_command, commands::CommandInfo,
+    filter::resolve_filter_expression, filter::FilterExpression, filter::ResolvedFilterExpression,
+    model_selection::model_selection_ir,
 };
 use super::{
     order_by::{build_ndc_order_by, ResolvedOrderBy},
@@ -23,9 +25,10 @@ use super::{
 };
 
 use crate::model_tracking::{count_model, UsagesCounts};
+use crate::remote_joins::{self, types::RemoteJoinStrategy};
 use crate::{ir::error, model_tracking::count_command};
 use metadata_resolve;
-use metadata_resolve::{serialize_qualified_btreemap, Qualified};
+use metadata_resolve::{serialize_qualified_btreemap, ConnectorArgumentName, Qualified};
 use schema::ModelRelationshipAnnotation;
 use schema::{Annotation, BooleanExpressionAnnotation, InputAnnotation, ModelInputAnnotation, GDS};
 use schema::{CommandRelationshipAnnotation, CommandTargetSource};
@@ -42,6 +45,7 @@ pub(crate) struct LocalModelRelationshipInfo<'s> {
     pub target_source: &'s metadata_resolve::ModelTargetSource,
     pub target_type: &'s Qualified<CustomTypeName>,
     pub mappings: &'s Vec<metadata_resolve::RelationshipModelMapping>,
+    pub target_mappings: RelationshipTargetMappings,
 }
 
 #[derive(Debug, Serialize)]
@@ -62,16 +66,194 @@ pub struct RemoteModelRelationshipInfo<'s> {
     /// contains mapping of field names and `metadata_resolve::FieldMapping`.
     /// Also see `build_remote_relationship`.
     pub join_mapping: Vec<(SourceField, TargetField)>,
+    /// The source fields passed to arguments of the target model
+    pub argument_join_mapping: Vec<(SourceField, ArgumentName)>,
+    pub strategy: RemoteJoinStrategy,
 }
 
 #[derive(Debug, Serialize)]
 pub(crate) struct RemoteCommandRelationshipInfo<'s> {
     pub annotation: &'s CommandRelationshipAnnotation,
     pub join_mapping: Vec<(SourceField, ArgumentName)>,
+    pub strategy: RemoteJoinStrategy,
 }
 
-pub type SourceField = (FieldName, metadata_resolve::FieldMapping);
-pub type TargetField = (FieldName, metadata_resolve::NdcColumnForComparison);
+/// A source field a relationship joins on: a field of the source type, or a field of an object
+/// nested in it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourceField {
+    pub field_name: FieldName,
+    pub field_mapping: metadata_resolve::FieldMapping,
+    /// The fields leading from the column of `field_name` to the joined field, and their
+    /// mappings
+    pub nested_fields: Vec<(FieldName, metadata_resolve::FieldMapping)>,
+}
+
+impl SourceField {
+    /// The names of the fields from the source type to the joined field
+    pub fn field_names(&self) -> Vec<FieldName> {
+        std::iter::once(&self.field_name)
+            .chain(self.nested_fields.iter().map(|(field_name, _)| field_name))
+            .cloned()
+            .collect()
+    }
+
+    /// The mapping of the joined field
+    pub fn joined_field_mapping(&self) -> &metadata_resolve::FieldMapping {
+        self.nested_fields
+            .last()
+            .map_or(&self.field_mapping, |(_, field_mapping)| field_mapping)
+    }
+
+    /// The NDC names of the fields leading from the column to the joined field
+    pub fn ndc_field_path(&self) -> Vec<String> {
+        self.nested_fields
+            .iter()
+            .map(|(_, field_mapping)| field_mapping.column.0.clone())
+            .collect()
+    }
+
+    /// The NDC names of the fields leading to the object the joined field is a field of,
+    /// starting with the column. Empty when the column is the joined field.
+    pub fn ndc_object_path(&self) -> Vec<String> {
+        let mut object_path = self.ndc_field_path();
+        if object_path.pop().is_some() {
+            object_path.insert(0, self.field_mapping.column.0.clone());
+        }
+        object_path
+    }
+}
+
+/// A target field a relationship joins on: a field of the target model, or a field of an object
+/// nested in it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TargetField {
+    pub field_name: FieldName,
+    /// The column of `field_name`, with the equality operator of the joined field
+    pub ndc_column: metadata_resolve::NdcColumnForComparison,
+    /// The NDC names of the fields leading from the column to the joined field
+    pub ndc_field_path: Vec<DataConnectorColumnName>,
+}
+
+impl TargetField {
+    /// The comparison of the joined field with the variable holding the values of the source
+    /// field, which selects the target rows of a remote join
+    pub(crate) fn join_condition(&self) -> ndc_models::Expression {
+        ndc_models::Expression::BinaryComparisonOperator {
+            column: comparison_target(&self.ndc_column.column, &self.ndc_field_path),
+            operator: self.ndc_column.equal_operator.clone(),
+            value: ndc_models::ComparisonValue::Variable {
+                name: remote_joins::types::TargetField::ModelField(self.clone())
+                    .variable_name()
+                    .0,
+            },
+        }
+    }
+}
+
+/// The comparison target of a column, or of a field nested in it
+pub(crate) fn comparison_target(
+    column: &DataConnectorColumnName,
+    field_path: &[DataConnectorColumnName],
+) -> ndc_models::ComparisonTarget {
+    ndc_models::ComparisonTarget::Column {
+        name: column.0.clone(),
+        path: vec![],
+        field_path: (!field_path.is_empty()).then(|| {
+            field_path
+                .iter()
+                .map(|field_name| field_name.0.clone())
+                .collect()
+        }),
+    }
+}
+
+/// A value passed by a relationship to an argument of its target model
+#[derive(Debug, Clone, Serialize)]
+pub(crate) enum RelationshipArgument {
+    /// The value of a field of the source type
+    Field {
+        source_field: SourceField,
+        argument_name: ArgumentName,
+    },
+    /// A literal, or the value of a session variable
+    Value(serde_json::Value),
+}
+
+/// The mappings of a model relationship other than those joining a source field with a target
+/// field, resolved for a request: the arguments the target model is queried with, and the
+/// comparisons of target fields with values.
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct RelationshipTargetMappings {
+    pub arguments: BTreeMap<ConnectorArgumentName, RelationshipArgument>,
+    pub predicate: Option<ndc_models::Expression>,
+}
+
+impl RelationshipTargetMappings {
+    /// The arguments of the NDC relationship to the target model. Source fields nested in an
+    /// object are columns of the object, which the relationship is selected from within.
+    pub(crate) fn local_arguments(&self) -> BTreeMap<String, ndc_models::RelationshipArgument> {
+        self.arguments
+            .iter()
+            .map(|(argument_name, argument)| {
+                let argument = match argument {
+                    RelationshipArgument::Field {
+                        source_field,
+                        argument_name: _,
+                    } => ndc_models::RelationshipArgument::Column {
+                        name: source_field.joined_field_mapping().column.0.clone(),
+                    },
+                    RelationshipArgument::Value(value) => {
+                        ndc_models::RelationshipArgument::Literal {
+                            value: value.clone(),
+                        }
+                    }
+                };
+                (argument_name.0.clone(), argument)
+            })
+            .collect()
+    }
+
+    /// The arguments of a query of the target model in a remote join, where the values of the
+    /// source fields are variables
+    pub(crate) fn remote_arguments(&self) -> BTreeMap<ConnectorArgumentName, ndc_models::Argument> {
+        self.arguments
+            .iter()
+            .map(|(argument_name, argument)| {
+                let argument = match argument {
+                    RelationshipArgument::Field {
+                        source_field: _,
+                        argument_name,
+                    } => ndc_models::Argument::Variable {
+                        name: remote_joins::types::TargetField::ModelArgument(
+                            argument_name.clone(),
+                        )
+                        .variable_name()
+                        .0,
+                    },
+                    RelationshipArgument::Value(value) => ndc_models::Argument::Literal {
+                        value: value.clone(),
+                    },
+                };
+                (argument_name.clone(), argument)
+            })
+            .collect()
+    }
+
+    /// The source fields passed to arguments of the target model
+    pub(crate) fn argument_join_mapping(&self) -> Vec<(SourceField, ArgumentName)> {
+        self.arguments
+            .values()
+            .filter_map(|argument| match argument {
+                RelationshipArgument::Field {
+                    source_field,
+                    argument_name,
+                } => Some((source_field.clone(), argument_name.clone())),
+                RelationshipArgument::Value(_) => None,
+            })
+            .collect()
+    }
+}
 
 pub(crate) fn generate_model_relationship_ir<'s>(
     field: &Field<'s, GDS>,
@@ -111,7 +293,11 @@ pub(crate) fn generate_model_relationship_ir<'s>(
                                 )?);
                             }
                             ModelInputAnnotation::ModelOrderByExpression => {
-                                order_by = Some(build_ndc_order_by(argument, usage_counts)?);
+                                order_by = Some(build_ndc_order_by(
+                                    argument,
+                                    session_variables,
+                                    usage_counts,
+                                )?);
                             }
                             _ => {
                                 return Err(error::InternalEngineError::UnexpectedAnnotation {
@@ -128,6 +314,7 @@ pub(crate) fn generate_model_relationship_ir<'s>(
                                 argument.value.as_object()?,
                                 &model_source.model.data_connector,
                                 &model_source.model.type_mappings,
+                                session_variables,
                                 usage_counts,
                             )?;
                         }
@@ -181,7 +368,8 @@ pub(crate) fn generate_model_relationship_ir<'s>(
             request_headers,
             usage_counts,
         ),
-        metadata_resolve::RelationshipExecutionCategory::RemoteForEach => {
+        metadata_resolve::RelationshipExecutionCategory::RemoteForEach
+        | metadata_resolve::RelationshipExecutionCategory::RemoteNaive => {
             build_remote_relationship(
                 field,
                 field_call,
@@ -242,7 +430,8 @@ pub(crate) fn generate_command_relationship_ir<'s>(
             request_headers,
             usage_counts,
         ),
-        metadata_resolve::RelationshipExecutionCategory::RemoteForEach => {
+        metadata_resolve::RelationshipExecutionCategory::RemoteForEach
+        | metadata_resolve::RelationshipExecutionCategory::RemoteNaive => {
             build_remote_command_relationship(
                 field,
                 field_call,
@@ -265,7 +454,7 @@ pub(crate) fn build_local_model_relationship<'s>(
     data_connector: &'s metadata_resolve::DataConnectorLink,
     type_mappings: &'s BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
     target_source: &'s metadata_resolve::ModelTargetSource,
-    filter_clause: ResolvedFilterExpression<'s>,
+    mut filter_clause: ResolvedFilterExpression<'s>,
     limit: Option<u32>,
     offset: Option<u32>,
     order_by: Option<ResolvedOrderBy<'s>>,
@@ -273,6 +462,29 @@ pub(crate) fn build_local_model_relationship<'s>(
     request_headers: &reqwest::header::HeaderMap,
     usage_counts: &mut UsagesCounts,
 ) -> Result<FieldSelection<'s>, error::Error> {
+    // the rows of a local relationship are nested in the rows of the source model, where the
+    // engine can't sort them
+    if let Some(relationship_name) = order_by
+        .as_ref()
+        .and_then(ResolvedOrderBy::first_remote_relationship)
+    {
+        return Err(error::Error::RemoteOrderByNotSupported {
+            relationship_name: relationship_name.clone(),
+            reason: "in the fields of local relationships",
+        });
+    }
+    let target_mappings = resolve_target_mappings(
+        &annotation.source_type,
+        &annotation.relationship_name,
+        type_mappings,
+        target_source,
+        &annotation.mappings,
+        session_variables,
+        usage_counts,
+    )?;
+    if let Some(predicate) = &target_mappings.predicate {
+        filter_clause.and(FilterExpression::Local(predicate.clone()));
+    }
     let relationships_ir = model_selection_ir(
         &field.selection_set,
         &annotation.target_type,
@@ -280,7 +492,7 @@ pub(crate) fn build_local_model_relationship<'s>(
         BTreeMap::new(),
         filter_clause,
         permissions::get_select_filter_predicate(field_call)?,
-        limit,
+        permissions::apply_select_limit(limit, field_call),
         offset,
         order_by,
         session_variables,
@@ -296,6 +508,7 @@ pub(crate) fn build_local_model_relationship<'s>(
         target_source,
         target_type: &annotation.target_type,
         mappings: &annotation.mappings,
+        target_mappings,
     };
 
     Ok(FieldSelection::ModelRelationshipLocal {
@@ -317,7 +530,7 @@ pub(crate) fn build_local_command_relationship<'s>(
     request_headers: &reqwest::header::HeaderMap,
     usage_counts: &mut UsagesCounts,
 ) -> Result<FieldSelection<'s>, error::Error> {
-    let relationships_ir = generate_function_based_command(
+    let mut relationships_ir = generate_function_based_command(
         &annotation.command_name,
         &target_source.function_name,
         field,
@@ -329,6 +542,13 @@ pub(crate) fn build_local_command_relationship<'s>(
         request_headers,
         usage_counts,
     )?;
+    add_command_argument_values(
+        &mut relationships_ir.command_info,
+        annotation,
+        target_source,
+        session_variables,
+        usage_counts,
+    )?;
 
     let rel_info = LocalCommandRelationshipInfo {
         annotation,
@@ -358,7 +578,7 @@ pub(crate) fn build_remote_relationship<'n, 's>(
     annotation: &'s ModelRelationshipAnnotation,
     type_mappings: &'s BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
     target_source: &'s metadata_resolve::ModelTargetSource,
-    filter_clause: ResolvedFilterExpression<'s>,
+    mut filter_clause: ResolvedFilterExpression<'s>,
     limit: Option<u32>,
     offset: Option<u32>,
     order_by: Option<ResolvedOrderBy<'s>>,
@@ -366,40 +586,32 @@ pub(crate) fn build_remote_relationship<'n, 's>(
     request_headers: &reqwest::header::HeaderMap,
     usage_counts: &mut UsagesCounts,
 ) -> Result<FieldSelection<'s>, error::Error> {
-    let mut join_mapping: Vec<(SourceField, TargetField)> = vec![];
-    for metadata_resolve::RelationshipModelMapping {
-        source_field: source_field_path,
-        target_field: target_field_path,
-        target_ndc_column,
-    } in &annotation.mappings
-    {
-        let source_column = get_field_mapping_of_field_name(
-            type_mappings,
-            &annotation.source_type,
-            &annotation.relationship_name,
-            &source_field_path.field_name,
-        )?;
-        let target_column = target_ndc_column.as_ref().ok_or_else(|| {
-            error::InternalEngineError::InternalGeneric {
-                description: format!(
-                    "No column mapping for relationship {} on {}",
-                    annotation.relationship_name, annotation.source_type
-                ),
-            }
-        })?;
-
-        let source_field = (source_field_path.field_name.clone(), source_column);
-        let target_field = (target_field_path.field_name.clone(), target_column.clone());
-        join_mapping.push((source_field, target_field));
+    let join_mapping = get_remote_join_mapping(
+        &annotation.source_type,
+        &annotation.relationship_name,
+        type_mappings,
+        &annotation.mappings,
+    )?;
+    let target_mappings = resolve_target_mappings(
+        &annotation.source_type,
+        &annotation.relationship_name,
+        type_mappings,
+        target_source,
+        &annotation.mappings,
+        session_variables,
+        usage_counts,
+    )?;
+    if let Some(predicate) = &target_mappings.predicate {
+        filter_clause.and(FilterExpression::Local(predicate.clone()));
     }
     let mut remote_relationships_ir = model_selection_ir(
         &field.selection_set,
         &annotation.target_type,
         &target_source.model,
-        BTreeMap::new(),
+        target_mappings.remote_arguments(),
         filter_clause,
         permissions::get_select_filter_predicate(field_call)?,
-        limit,
+        permissions::apply_select_limit(limit, field_call),
         offset,
         order_by,
         session_variables,
@@ -408,30 +620,16 @@ pub(crate) fn build_remote_relationship<'n, 's>(
     )?;
 
     // modify `ModelSelection` to include the join condition in `where` with a variable
-    for (_source, (_field_name, target_column)) in &join_mapping {
-        let target_value_variable = format!("${}", &target_column.column);
-        let comparison_exp = ndc_models::Expression::BinaryComparisonOperator {
-            column: ndc_models::ComparisonTarget::Column {
-                name: target_column.column.0.clone(),
-                path: vec![],
-                field_path: None,
-            },
-            operator: target_column.equal_operator.clone(),
-            value: ndc_models::ComparisonValue::Variable {
-                name: target_value_variable,
-            },
-        };
-        remote_relationships_ir.filter_clause.expression =
-            match remote_relationships_ir.filter_clause.expression {
-                Some(existing) => Some(ndc_models::Expression::And {
-                    expressions: vec![existing, comparison_exp],
-                }),
-                None => Some(comparison_exp),
-            };
+    for (_source, target_field) in &join_mapping {
+        remote_relationships_ir
+            .filter_clause
+            .and(FilterExpression::Local(target_field.join_condition()));
     }
     let rel_info = RemoteModelRelationshipInfo {
         annotation,
         join_mapping,
+        argument_join_mapping: target_mappings.argument_join_mapping(),
+        strategy: RemoteJoinStrategy::from_capabilities(&target_source.capabilities),
     };
     Ok(FieldSelection::ModelRelationshipRemote {
         ir: remote_relationships_ir,
@@ -452,18 +650,20 @@ pub(crate) fn build_remote_command_relationship<'n, 's>(
 ) -> Result<FieldSelection<'s>, error::Error> {
     let mut join_mapping: Vec<(SourceField, ArgumentName)> = vec![];
     for metadata_resolve::RelationshipCommandMapping {
-        source_field: source_field_path,
+        source,
         argument_name: target_argument_name,
     } in &annotation.mappings
     {
-        let source_column = get_field_mapping_of_field_name(
+        // values are passed to the command as they are, see `add_command_argument_values`
+        let metadata_resolve::RelationshipMappingSource::Field(source_field_path) = source else {
+            continue;
+        };
+        let source_field = get_source_field(
             type_mappings,
             &annotation.source_type,
             &annotation.relationship_name,
-            &source_field_path.field_name,
+            source_field_path,
         )?;
-
-        let source_field = (source_field_path.field_name.clone(), source_column);
         join_mapping.push((source_field, target_argument_name.clone()));
     }
     let mut remote_relationships_ir = generate_function_based_command(
@@ -478,6 +678,13 @@ pub(crate) fn build_remote_command_relationship<'n, 's>(
         request_headers,
         usage_counts,
     )?;
+    add_command_argument_values(
+        &mut remote_relationships_ir.command_info,
+        annotation,
+        target_source,
+        session_variables,
+        usage_counts,
+    )?;
 
     // Add the arguments on which the join is done to the command arguments
     let mut variable_arguments = BTreeMap::new();
@@ -501,6 +708,7 @@ pub(crate) fn build_remote_command_relationship<'n, 's>(
     let rel_info = RemoteCommandRelationshipInfo {
         annotation,
         join_mapping,
+        strategy: RemoteJoinStrategy::from_capabilities(&target_source.capabilities),
     };
     Ok(FieldSelection::CommandRelationshipRemote {
         ir: remote_relationships_ir,
@@ -508,6 +716,237 @@ pub(crate) fn build_remote_command_relationship<'n, 's>(
     })
 }
 
+/// Add the values passed by the mappings of a command relationship to the arguments of the
+/// command
+fn add_command_argument_values(
+    command_info: &mut CommandInfo<'_>,
+    annotation: &CommandRelationshipAnnotation,
+    target_source: &CommandTargetSource,
+    session_variables: &SessionVariables,
+    usage_counts: &mut UsagesCounts,
+) -> Result<(), error::Error> {
+    for metadata_resolve::RelationshipCommandMapping {
+        source,
+        argument_name,
+    } in &annotation.mappings
+    {
+        let metadata_resolve::RelationshipMappingSource::Value { value, value_type } = source
+        else {
+            continue;
+        };
+        let ndc_argument_name = target_source
+            .details
+            .argument_mappings
+            .get(argument_name)
+            .ok_or_else(|| {
+                error::InternalDeveloperError::ArgumentMappingNotFoundForRelationship {
+                    relationship_name: annotation.relationship_name.clone(),
+                    argument_name: argument_name.clone(),
+                }
+            })?;
+        let value = permissions::make_value_from_value_expression(
+            value,
+            value_type,
+            session_variables,
+            usage_counts,
+        )?;
+        command_info
+            .arguments
+            .insert(ndc_argument_name.0.clone(), value);
+    }
+    Ok(())
+}
+
+/// Pair the source and target columns of the mappings of a remote model relationship
+pub(crate) fn get_remote_join_mapping(
+    source_type: &Qualified<CustomTypeName>,
+    relationship_name: &RelationshipName,
+    type_mappings: &BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
+    mappings: &[metadata_resolve::RelationshipModelMapping],
+) -> Result<Vec<(SourceField, TargetField)>, error::Error> {
+    let mut join_mapping: Vec<(SourceField, TargetField)> = vec![];
+    for mapping in mappings {
+        // the other mappings are resolved by `resolve_target_mappings`
+        let (
+            metadata_resolve::RelationshipMappingSource::Field(source_field_path),
+            metadata_resolve::RelationshipModelMappingTarget::ModelField(
+                metadata_resolve::RelationshipModelMappingFieldTarget {
+                    target_field: target_field_path,
+                    target_ndc_column,
+                    target_ndc_field_path,
+                },
+            ),
+        ) = (&mapping.source, &mapping.target)
+        else {
+            continue;
+        };
+        let source_field = get_source_field(
+            type_mappings,
+            source_type,
+            relationship_name,
+            source_field_path,
+        )?;
+        let target_column = get_target_column(source_type, relationship_name, target_ndc_column)?;
+        let target_field = TargetField {
+            field_name: target_field_path.field.field_name.clone(),
+            ndc_column: target_column.clone(),
+            ndc_field_path: target_ndc_field_path.clone(),
+        };
+        join_mapping.push((source_field, target_field));
+    }
+    Ok(join_mapping)
+}
+
+/// Resolve the mappings of a model relationship which pass fields or values to arguments of the
+/// target model, or compare target fields with values
+pub(crate) fn resolve_target_mappings(
+    source_type: &Qualified<CustomTypeName>,
+    relationship_name: &RelationshipName,
+    type_mappings: &BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
+    target_source: &metadata_resolve::ModelTargetSource,
+    mappings: &[metadata_resolve::RelationshipModelMapping],
+    session_variables: &SessionVariables,
+    usage_counts: &mut UsagesCounts,
+) -> Result<RelationshipTargetMappings, error::Error> {
+    let mut arguments = BTreeMap::new();
+    let mut comparisons = Vec::new();
+    for mapping in mappings {
+        match (&mapping.source, &mapping.target) {
+            (
+                metadata_resolve::RelationshipMappingSource::Field(_),
+                metadata_resolve::RelationshipModelMappingTarget::ModelField(_),
+            ) => {}
+            (
+                metadata_resolve::RelationshipMappingSource::Field(source_field_path),
+                metadata_resolve::RelationshipModelMappingTarget::Argument(argument_name),
+            ) => {
+                arguments.insert(
+                    get_ndc_argument_name(target_source, relationship_name, argument_name)?,
+                    RelationshipArgument::Field {
+                        source_field: get_source_field(
+                            type_mappings,
+                            source_type,
+                            relationship_name,
+                            source_field_path,
+                        )?,
+                        argument_name: argument_name.clone(),
+                    },
+                );
+            }
+            (
+                metadata_resolve::RelationshipMappingSource::Value { value, value_type },
+                metadata_resolve::RelationshipModelMappingTarget::ModelField(field_target),
+            ) => {
+                let target_column = get_target_column(
+                    source_type,
+                    relationship_name,
+                    &field_target.target_ndc_column,
+                )?;
+                let value = permissions::make_value_from_value_expression(
+                    value,
+                    value_type,
+                    session_variables,
+                    usage_counts,
+                )?;
+                comparisons.push(ndc_models::Expression::BinaryComparisonOperator {
+                    column: comparison_target(
+                        &target_column.column,
+                        &field_target.target_ndc_field_path,
+                    ),
+                    operator: target_column.equal_operator.clone(),
+                    value: ndc_models::ComparisonValue::Scalar { value },
+                });
+            }
+            (
+                metadata_resolve::RelationshipMappingSource::Value { value, value_type },
+                metadata_resolve::RelationshipModelMappingTarget::Argument(argument_name),
+            ) => {
+                let value = permissions::make_value_from_value_expression(
+                    value,
+                    value_type,
+                    session_variables,
+                    usage_counts,
+                )?;
+                arguments.insert(
+                    get_ndc_argument_name(target_source, relationship_name, argument_name)?,
+                    RelationshipArgument::Value(value),
+                );
+            }
+        }
+    }
+    Ok(RelationshipTargetMappings {
+        arguments,
+        predicate: (!comparisons.is_empty()).then_some(ndc_models::Expression::And {
+            expressions: comparisons,
+        }),
+    })
+}
+
+fn get_target_column<'a>(
+    source_type: &Qualified<CustomTypeName>,
+    relationship_name: &RelationshipName,
+    target_ndc_column: &'a Option<metadata_resolve::NdcColumnForComparison>,
+) -> Result<&'a metadata_resolve::NdcColumnForComparison, error::Error> {
+    Ok(target_ndc_column
+        .as_ref()
+        .ok_or_else(|| error::InternalEngineError::InternalGeneric {
+            description: format!(
+                "No column mapping for relationship {relationship_name} on {source_type}"
+            ),
+        })?)
+}
+
+fn get_ndc_argument_name(
+    target_source: &metadata_resolve::ModelTargetSource,
+    relationship_name: &RelationshipName,
+    argument_name: &ArgumentName,
+) -> Result<ConnectorArgumentName, error::Error> {
+    Ok(target_source
+        .model
+        .argument_mappings
+        .get(argument_name)
+        .ok_or_else(
+            || error::InternalDeveloperError::ArgumentMappingNotFoundForRelationship {
+                relationship_name: relationship_name.clone(),
+                argument_name: argument_name.clone(),
+            },
+        )?
+        .clone())
+}
+
+/// Resolve the mappings of the fields along the path of a source field of a relationship
+pub(crate) fn get_source_field(
+    type_mappings: &BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
+    source_type: &Qualified<CustomTypeName>,
+    relationship_name: &RelationshipName,
+    field_path: &metadata_resolve::RelationshipFieldPath,
+) -> Result<SourceField, error::Error> {
+    let field_mapping = get_field_mapping_of_field_name(
+        type_mappings,
+        source_type,
+        relationship_name,
+        &field_path.field.field_name,
+    )?;
+    let nested_fields = field_path
+        .nested_fields
+        .iter()
+        .map(|(type_name, field_access)| {
+            let field_mapping = get_field_mapping_of_field_name(
+                type_mappings,
+                type_name,
+                relationship_name,
+                &field_access.field_name,
+            )?;
+            Ok((field_access.field_name.clone(), field_mapping))
+        })
+        .collect::<Result<_, error::Error>>()?;
+    Ok(SourceField {
+        field_name: field_path.field.field_name.clone(),
+        field_mapping,
+        nested_fields,
+    })
+}
+
 pub(crate) fn get_field_mapping_of_field_name(
     type_mappings: &BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
     type_name: &Qualified<CustomTypeName>,
@@ -5,7 +5,7 @@ use open_dds::permissions::Role;
 use serde::Serialize;
 
 use super::{
-    commands,
+    commands, model_mutation,
     query_root::{apollo_federation, node_field, select_aggregate, select_many, select_one},
 };
 use schema::GDS;
@@ -79,4 +79,9 @@ pub enum MutationRootField<'n, 's> {
         selection_set: &'n gql::normalized_ast::SelectionSet<'s, GDS>,
         ir: commands::ProcedureBasedCommand<'s>,
     },
+    // Operation that inserts, updates or deletes a single row of a model
+    ModelPointMutation {
+        selection_set: &'n gql::normalized_ast::SelectionSet<'s, GDS>,
+        ir: model_mutation::ModelPointMutation<'s>,
+    },
 }
@@ -4,9 +4,16 @@ mod global_id;
 mod ir;
 mod model_tracking;
 mod ndc;
+mod operation_cost;
 mod plan;
 mod process_response;
+mod query_cache;
 mod remote_joins;
+mod remote_predicates;
+mod subscription;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use thiserror::Error;
 
@@ -27,7 +34,11 @@ use tracing_util::{
 // we explicitly export things used by other crates
 pub use explain::execute_explain;
 pub use explain::types::{redact_ndc_explain, ExplainResponse};
+pub use ndc::client::{ConnectTimeoutClients, RetryPolicy};
 pub use plan::{execute_mutation_plan, execute_query_plan, generate_request_plan, RequestPlan};
+pub use query_cache::{QueryCache, DEFAULT_QUERY_CACHE_SIZE};
+pub use remote_joins::RemoteJoinOptions;
+pub use subscription::execute_subscription;
 
 /// Context for making HTTP requests
 pub struct HttpContext {
@@ -35,6 +46,14 @@ pub struct HttpContext {
     pub client: reqwest::Client,
     /// Response size limit for NDC requests
     pub ndc_response_size_limit: Option<usize>,
+    /// Timeout for a complete NDC request
+    pub ndc_request_timeout: Option<Duration>,
+    /// How idempotent NDC requests are retried
+    pub ndc_retry_policy: RetryPolicy,
+    /// Clients for data connectors which override the connect timeout of `client`
+    pub ndc_connect_timeout_clients: ConnectTimeoutClients,
+    /// Limits on the chunking and parallelism of remote join requests
+    pub remote_join_options: RemoteJoinOptions,
 }
 
 #[derive(Debug)]
@@ -82,6 +101,7 @@ pub async fn execute_query(
     request_headers: &reqwest::header::HeaderMap,
     request: RawRequest,
     project_id: Option<&ProjectId>,
+    query_cache: Option<&QueryCache>,
 ) -> GraphQLResponse {
     execute_query_internal(
         http_context,
@@ -90,6 +110,7 @@ pub async fn execute_query(
         request_headers,
         request,
         project_id,
+        query_cache,
     )
     .await
     .unwrap_or_else(|e| GraphQLResponse(Response::error(e.to_graphql_error())))
@@ -122,9 +143,13 @@ pub async fn execute_query_internal(
     request_headers: &reqwest::header::HeaderMap,
     raw_request: gql::http::RawRequest,
     project_id: Option<&ProjectId>,
+    query_cache: Option<&QueryCache>,
 ) -> Result<GraphQLResponse, error::RequestError> {
     let tracer = tracing_util::global_tracer();
-    tracer
+    let started = Instant::now();
+    // The operation type is only known once the request has been normalized
+    let mut operation_type = None;
+    let result = tracer
         .in_span_async(
             "execute_query",
             "Execute query request",
@@ -141,15 +166,24 @@ pub async fn execute_query_internal(
                     raw_request.query.clone(),
                 );
                 Box::pin(async {
-                    // parse the raw request into a GQL query
-                    let query = parse_query(&raw_request.query)?;
+                    // parse the raw request into a GQL query, reusing a recently parsed
+                    // document if possible
+                    let query = match query_cache {
+                        Some(query_cache) => query_cache.parse_query(&raw_request.query)?,
+                        None => Arc::new(parse_query(&raw_request.query)?),
+                    };
 
                     // normalize the parsed GQL query
                     let normalized_request =
-                        normalize_request(schema, session, query, raw_request)?;
+                        normalize_request(schema, session, &query, raw_request)?;
+                    operation_type = Some(normalized_request.ty);
 
                     // generate IR
-                    let ir = build_ir(schema, session, request_headers, &normalized_request)?;
+                    let mut ir = build_ir(schema, session, request_headers, &normalized_request)?;
+
+                    // evaluate the predicates across remote relationships
+                    remote_predicates::resolve_remote_predicates(http_context, &mut ir, project_id)
+                        .await?;
 
                     // construct a plan to execute the request
                     let request_plan = build_request_plan(&ir)?;
@@ -198,7 +232,18 @@ pub async fn execute_query_internal(
                 })
             },
         )
-        .await
+        .await;
+
+    metrics_util::global_metrics().observe_graphql_request(
+        operation_type.map_or("unknown", plan::operation_type_label),
+        metrics_util::RequestStatus::from_is_success(
+            result
+                .as_ref()
+                .is_ok_and(|response| !response.0.does_contains_error()),
+        ),
+        started.elapsed(),
+    );
+    result
 }
 
 /// Explains (query plan) a GraphQL query
@@ -232,10 +277,15 @@ pub async fn explain_query_internal(
 
                     // normalize the parsed GQL query
                     let normalized_request =
-                        normalize_request(schema, session, query, raw_request)?;
+                        normalize_request(schema, session, &query, raw_request)?;
 
                     // generate IR
-                    let ir = build_ir(schema, session, request_headers, &normalized_request)?;
+                    let mut ir = build_ir(schema, session, request_headers, &normalized_request)?;
+
+                    // plan the queries for the predicates across remote relationships, without
+                    // making them
+                    let remote_predicate_plans =
+                        remote_predicates::plan_remote_predicates(http_context, &mut ir)?;
 
                     // construct a plan to execute the request
                     let request_plan = build_request_plan(&ir)?;
@@ -266,7 +316,13 @@ pub async fn explain_query_internal(
                                     };
                                     // convert the query plan to explain step
                                     match request_result {
-                                        Ok(step) => step.make_explain_response(),
+                                        Ok(step) => crate::explain::explain_remote_predicates(
+                                            http_context,
+                                            remote_predicate_plans,
+                                            step,
+                                        )
+                                        .await
+                                        .make_explain_response(),
                                         Err(e) => explain::types::ExplainResponse::error(
                                             e.to_graphql_error(),
                                         ),
@@ -309,7 +365,7 @@ pub(crate) fn parse_query(
 pub(crate) fn normalize_request<'s>(
     schema: &'s gql::schema::Schema<GDS>,
     session: &Session,
-    query: gql::ast::executable::ExecutableDocument,
+    query: &gql::ast::executable::ExecutableDocument,
     raw_request: gql::http::RawRequest,
 ) -> Result<Operation<'s, GDS>, gql::validation::Error> {
     let tracer = tracing_util::global_tracer();
@@ -328,18 +384,25 @@ pub(crate) fn normalize_request<'s>(
                     );
                 }
 
-                let request = gql::http::Request {
-                    operation_name: raw_request.operation_name,
-                    query,
-                    variables: raw_request.variables.unwrap_or_default(),
-                };
-                gql::validation::normalize_request(
+                gql::validation::normalize_document(
                     &GDSRoleNamespaceGetter {
                         scope: session.role.clone(),
                     },
                     schema,
-                    &request,
+                    query,
+                    raw_request.operation_name.as_ref(),
+                    &raw_request.variables.unwrap_or_default(),
                 )
+                .and_then(|normalized_request| {
+                    // reject operations that are too large or expensive before doing any work
+                    // to execute them
+                    gql::validation::check_operation_limits(
+                        &normalized_request,
+                        schema.operation_limits.get(&session.role),
+                        &operation_cost::GDSCostEstimator,
+                    )?;
+                    Ok(normalized_request)
+                })
                 .map_err(GraphQlValidationError)
             },
         )
@@ -402,8 +465,16 @@ pub fn generate_ir<'n, 's>(
             )?;
             Ok(ir::IR::Mutation(mutation_ir))
         }
+        // Subscriptions are executed as live queries over the model root fields, so they share
+        // the IR of the query root.
         ast::OperationType::Subscription => {
-            Err(ir::error::InternalEngineError::SubscriptionsNotSupported)?
+            let subscription_ir = ir::query_root::generate_ir(
+                schema,
+                session,
+                request_headers,
+                &normalized_request.selection_set,
+            )?;
+            Ok(ir::IR::Query(subscription_ir))
         }
     }
 }
@@ -500,7 +571,8 @@ mod tests {
 
         let role = session_variables
             .get(&SESSION_VARIABLE_ROLE)
-            .map(|v| Role::new(&v.0));
+            .and_then(SessionVariableValue::as_str)
+            .map(Role::new);
         authorization
             .get_role_authorization(role.as_ref())
             .unwrap()
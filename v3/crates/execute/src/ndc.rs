@@ -1,6 +1,7 @@
 pub mod response;
 
 use std::borrow::Cow;
+use std::time::{Duration, Instant};
 
 use axum::http::HeaderMap;
 use serde_json as json;
@@ -12,7 +13,9 @@ use tracing_util::{set_attribute_on_active_span, AttributeVisibility, SpanVisibi
 
 use super::error;
 use super::plan::ProcessResponseAs;
-use super::process_response::process_command_mutation_response;
+use super::process_response::{process_command_mutation_response, FailedRemoteJoins};
+use super::remote_joins::types::{JoinId, JoinLocations, RemoteJoin};
+use super::remote_joins::{execute_mutation_join_locations, RemoteJoinFailure};
 use super::{HttpContext, ProjectId};
 use schema::GDS;
 
@@ -78,21 +81,73 @@ pub(crate) async fn fetch_from_data_connector<'s>(
                 Box::pin(async {
                     let headers =
                         append_project_id_to_headers(&data_connector.headers.0, project_id)?;
-                    let ndc_config = client::Configuration {
-                        base_path: data_connector.url.get_url(ast::OperationType::Query),
-                        // This is isn't expensive, reqwest::Client is behind an Arc
-                        client: http_context.client.clone(),
+                    let ndc_config = client_configuration(
+                        http_context,
+                        data_connector,
+                        ast::OperationType::Query,
                         headers,
-                        response_size_limit: http_context.ndc_response_size_limit,
-                    };
-                    client::query_post(ndc_config, query_request).await
-                    // .map_err(error::RequestError::from) // error::Error -> InternalError -> Error
+                    )?;
+                    let started = Instant::now();
+                    let response = client::query_post(ndc_config, query_request).await;
+                    observe_ndc_request(data_connector, "query", response.is_ok(), started);
+                    response
                 })
             },
         )
         .await
 }
 
+/// Builds the client configuration for a request to a data connector, applying the data
+/// connector's request options over the defaults in the `HttpContext`.
+pub(crate) fn client_configuration<'s>(
+    http_context: &HttpContext,
+    data_connector: &'s metadata_resolve::DataConnectorLink,
+    operation_type: ast::OperationType,
+    headers: Cow<'s, HeaderMap>,
+) -> Result<client::Configuration<'s>, client::Error> {
+    let request_options = data_connector.request_options.as_ref();
+    let client = match request_options.and_then(|options| options.connect_timeout_ms) {
+        // This is isn't expensive, reqwest::Client is behind an Arc
+        None => http_context.client.clone(),
+        Some(connect_timeout_ms) => http_context
+            .ndc_connect_timeout_clients
+            .get(Duration::from_millis(connect_timeout_ms))?,
+    };
+    Ok(client::Configuration {
+        base_path: data_connector.url.get_url(operation_type),
+        client,
+        headers,
+        response_size_limit: request_options
+            .and_then(|options| options.response_size_limit_bytes)
+            .or(http_context.ndc_response_size_limit),
+        request_timeout: request_options
+            .and_then(|options| options.request_timeout_ms)
+            .map(Duration::from_millis)
+            .or(http_context.ndc_request_timeout),
+        retry_policy: client::RetryPolicy {
+            max_retries: request_options
+                .and_then(|options| options.max_retries)
+                .unwrap_or(http_context.ndc_retry_policy.max_retries),
+            ..http_context.ndc_retry_policy
+        },
+    })
+}
+
+/// Records the latency and outcome of a request to a data connector.
+fn observe_ndc_request(
+    data_connector: &metadata_resolve::DataConnectorLink,
+    operation: &str,
+    is_success: bool,
+    started: Instant,
+) {
+    metrics_util::global_metrics().observe_ndc_request(
+        &data_connector.name.to_string(),
+        operation,
+        metrics_util::RequestStatus::from_is_success(is_success),
+        started.elapsed(),
+    );
+}
+
 // This function appends project-id (if present) to the HeaderMap defined by the data_connector object
 pub fn append_project_id_to_headers<'a>(
     headers: &'a HeaderMap,
@@ -111,17 +166,18 @@ pub fn append_project_id_to_headers<'a>(
     }
 }
 
-/// Executes a NDC mutation
+/// Executes a NDC mutation, along with any remote joins in the selection set of its result
 pub(crate) async fn execute_ndc_mutation<'n, 's, 'ir>(
     http_context: &HttpContext,
     query: &ndc_models::MutationRequest,
     data_connector: &metadata_resolve::DataConnectorLink,
     selection_set: &'n normalized_ast::SelectionSet<'s, GDS>,
-    execution_span_attribute: String,
+    execution_span_attribute: &'static str,
     field_span_attribute: String,
     process_response_as: ProcessResponseAs<'s, 'ir>,
+    join_locations: &JoinLocations<(RemoteJoin<'s, 'ir>, JoinId)>,
     project_id: Option<&ProjectId>,
-) -> Result<json::Value, error::FieldError> {
+) -> Result<(json::Value, Vec<RemoteJoinFailure>), error::FieldError> {
     let tracer = tracing_util::global_tracer();
     tracer
         .in_span_async(
@@ -150,45 +206,79 @@ pub(crate) async fn execute_ndc_mutation<'n, 's, 'ir>(
                         project_id,
                     )
                     .await?;
-                    // Post process the response to add the `__typename` fields
-                    tracer.in_span(
-                        "process_response",
-                        "Process NDC response",
-                        SpanVisibility::Internal,
-                        || {
-                            // NOTE: NDC returns a `Vec<RowSet>` (to account for
-                            // variables). We don't use variables in NDC queries yet,
-                            // hence we always pick the first `RowSet`.
-                            let mutation_results = connector_response
-                                .operation_results
-                                .into_iter()
-                                .next()
-                                .ok_or(error::NDCUnexpectedError::BadNDCResponse {
-                                    summary: "missing rowset".to_string(),
-                                })?;
-
-                            match process_response_as {
-                                ProcessResponseAs::CommandResponse {
-                                    command_name: _,
-                                    type_container,
-                                } => process_command_mutation_response(
-                                    mutation_results,
-                                    selection_set,
-                                    type_container,
-                                ),
-                                _ => Err(error::FieldInternalError::InternalGeneric {
-                                    description: "Only commands are supported for mutations"
-                                        .to_string(),
-                                })?,
-                            }
-                        },
+                    // NOTE: NDC returns a `Vec<MutationOperationResults>` (to account
+                    // for multiple operations). We only send one operation, hence we
+                    // always pick the first result.
+                    let mutation_results = connector_response
+                        .operation_results
+                        .into_iter()
+                        .next()
+                        .ok_or(error::NDCUnexpectedError::BadNDCResponse {
+                            summary: "missing rowset".to_string(),
+                        })?;
+                    process_mutation_results(
+                        http_context,
+                        mutation_results,
+                        selection_set,
+                        execution_span_attribute,
+                        process_response_as,
+                        join_locations,
+                        project_id,
                     )
+                    .await
                 })
             },
         )
         .await
 }
 
+/// Executes any remote joins in the selection set of the result of a NDC mutation operation, and
+/// processes the result into a response
+pub(crate) async fn process_mutation_results<'n, 's, 'ir>(
+    http_context: &HttpContext,
+    mutation_results: ndc_models::MutationOperationResults,
+    selection_set: &'n normalized_ast::SelectionSet<'s, GDS>,
+    execution_span_attribute: &'static str,
+    process_response_as: ProcessResponseAs<'s, 'ir>,
+    join_locations: &JoinLocations<(RemoteJoin<'s, 'ir>, JoinId)>,
+    project_id: Option<&ProjectId>,
+) -> Result<(json::Value, Vec<RemoteJoinFailure>), error::FieldError> {
+    let tracer = tracing_util::global_tracer();
+    let (mutation_results, remote_join_failures) = execute_mutation_join_locations(
+        http_context,
+        execution_span_attribute,
+        mutation_results,
+        &process_response_as,
+        join_locations,
+        project_id,
+    )
+    .await?;
+    // Post process the response to add the `__typename` fields
+    let value = tracer.in_span(
+        "process_response",
+        "Process NDC response",
+        SpanVisibility::Internal,
+        || match process_response_as {
+            ProcessResponseAs::CommandResponse {
+                command_name: _,
+                type_container,
+            }
+            | ProcessResponseAs::ModelMutationResponse { type_container } => {
+                process_command_mutation_response(
+                    mutation_results,
+                    selection_set,
+                    type_container,
+                    &FailedRemoteJoins::new(&remote_join_failures),
+                )
+            }
+            _ => Err(error::FieldInternalError::InternalGeneric {
+                description: "Only commands are supported for mutations".to_string(),
+            })?,
+        },
+    )?;
+    Ok((value, remote_join_failures))
+}
+
 pub(crate) async fn fetch_from_data_connector_mutation<'s>(
     http_context: &HttpContext,
     query_request: &ndc_models::MutationRequest,
@@ -205,14 +295,16 @@ pub(crate) async fn fetch_from_data_connector_mutation<'s>(
                 Box::pin(async {
                     let headers =
                         append_project_id_to_headers(&data_connector.headers.0, project_id)?;
-                    let ndc_config = client::Configuration {
-                        base_path: data_connector.url.get_url(ast::OperationType::Mutation),
-                        // This is isn't expensive, reqwest::Client is behind an Arc
-                        client: http_context.client.clone(),
+                    let ndc_config = client_configuration(
+                        http_context,
+                        data_connector,
+                        ast::OperationType::Mutation,
                         headers,
-                        response_size_limit: http_context.ndc_response_size_limit,
-                    };
-                    client::mutation_post(ndc_config, query_request).await
+                    )?;
+                    let started = Instant::now();
+                    let response = client::mutation_post(ndc_config, query_request).await;
+                    observe_ndc_request(data_connector, "mutation", response.is_ok(), started);
+                    response
                 })
             },
         )
@@ -1,4 +1,7 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{de::DeserializeOwned, Deserialize};
@@ -15,7 +18,10 @@ pub enum Error {
         "request to connector failed with status code {}: {0}",
         .0.status().map_or_else(|| "N/A".to_string(), |s| s.to_string())
     )]
-    Reqwest(#[from] reqwest::Error),
+    Reqwest(reqwest::Error),
+
+    #[error("request to connector timed out")]
+    Timeout,
 
     #[error("unable to decode JSON response from connector: {0}")]
     Serde(#[from] serde_json::Error),
@@ -39,6 +45,31 @@ pub enum Error {
     InvalidConnector(InvalidConnectorError),
 }
 
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Error::Timeout
+        } else {
+            Error::Reqwest(error)
+        }
+    }
+}
+
+impl Error {
+    /// Whether a request which failed with this error may succeed if it is retried, i.e. the
+    /// connector could not be reached or failed with a server error.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Error::Reqwest(error) => error.is_connect(),
+            Error::Connector(ConnectorError { status, .. })
+            | Error::InvalidConnector(InvalidConnectorError { status, .. }) => {
+                status.is_server_error()
+            }
+            _ => false,
+        }
+    }
+}
+
 impl tracing_util::TraceableError for Error {
     fn visibility(&self) -> tracing_util::ErrorVisibility {
         tracing_util::ErrorVisibility::Internal
@@ -67,6 +98,48 @@ pub struct Configuration<'s> {
     pub client: reqwest::Client,
     pub headers: Cow<'s, HeaderMap<HeaderValue>>,
     pub response_size_limit: Option<usize>,
+    pub request_timeout: Option<Duration>,
+    /// How to retry idempotent requests. Mutations are never retried.
+    pub retry_policy: RetryPolicy,
+}
+
+/// How failed requests to a connector are retried, backing off exponentially between attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of times a request is retried after its first attempt.
+    pub max_retries: u32,
+    /// The delay before the first retry, which doubles for every subsequent retry.
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+/// HTTP clients for connectors which override the default connect timeout. Connect timeouts can
+/// only be set on a client, so one client is shared by all connectors with the same timeout,
+/// which keeps their connections pooled.
+#[derive(Debug, Default)]
+pub struct ConnectTimeoutClients(Mutex<HashMap<Duration, reqwest::Client>>);
+
+impl ConnectTimeoutClients {
+    pub fn get(&self, connect_timeout: Duration) -> Result<reqwest::Client, Error> {
+        let mut clients = sync_ext::lock(&self.0);
+        if let Some(client) = clients.get(&connect_timeout) {
+            return Ok(client.clone());
+        }
+        let client = reqwest::Client::builder()
+            .connect_timeout(connect_timeout)
+            .build()
+            .map_err(Error::Reqwest)?;
+        clients.insert(connect_timeout, client.clone());
+        Ok(client)
+    }
 }
 
 /// POST on /query/explain endpoint
@@ -86,11 +159,12 @@ pub async fn explain_query_post(
                 Box::pin(async {
                     let url = append_path(configuration.base_path, &["query", "explain"])?;
                     let response_size_limit = configuration.response_size_limit;
+                    let retry_policy = configuration.retry_policy;
                     let request =
                         construct_request(configuration, reqwest::Method::POST, url, |r| {
                             r.json(query_request)
                         });
-                    execute_request(request, response_size_limit).await
+                    execute_request_with_retries(request, response_size_limit, retry_policy).await
                 })
             },
         )
@@ -170,11 +244,12 @@ pub async fn query_post(
                 Box::pin(async {
                     let url = append_path(configuration.base_path, &["query"])?;
                     let response_size_limit = configuration.response_size_limit;
+                    let retry_policy = configuration.retry_policy;
                     let request =
                         construct_request(configuration, reqwest::Method::POST, url, |r| {
                             r.json(query_request)
                         });
-                    execute_request(request, response_size_limit).await
+                    execute_request_with_retries(request, response_size_limit, retry_policy).await
                 })
             },
         )
@@ -211,6 +286,9 @@ fn construct_request(
                 request_builder = modify(request_builder);
                 // Set headers from configuration
                 request_builder = request_builder.headers(configuration.headers.into_owned());
+                if let Some(request_timeout) = configuration.request_timeout {
+                    request_builder = request_builder.timeout(request_timeout);
+                }
                 // Return the prepared request
                 Successful::new(request_builder)
             },
@@ -218,6 +296,33 @@ fn construct_request(
         .into_inner()
 }
 
+/// Execute an idempotent request, retrying it according to the retry policy if it fails with a
+/// connection error or a server error.
+async fn execute_request_with_retries<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    response_size_limit: Option<usize>,
+    retry_policy: RetryPolicy,
+) -> Result<T, Error> {
+    let mut backoff = retry_policy.initial_backoff;
+    for _ in 0..retry_policy.max_retries {
+        // Requests with a streaming body cannot be cloned, and so cannot be retried
+        let Some(attempt) = request.try_clone() else {
+            break;
+        };
+        match execute_request(attempt, response_size_limit).await {
+            Err(error) if error.is_retryable() => {
+                tracing_util::add_event_on_active_span(format!(
+                    "retrying request to connector in {backoff:?} after error: {error}"
+                ));
+                tokio::time::sleep(backoff).await;
+                backoff = backoff.saturating_mul(2);
+            }
+            result => return result,
+        }
+    }
+    execute_request(request, response_size_limit).await
+}
+
 /// Execute a request and deserialize the JSON response
 async fn execute_request<T: DeserializeOwned>(
     request: reqwest::RequestBuilder,
@@ -296,7 +401,9 @@ async fn construct_error(response: reqwest::Response) -> Error {
 
 #[cfg(test)]
 mod tests {
-    use super::append_path;
+    use std::time::Duration;
+
+    use super::{append_path, execute_request, execute_request_with_retries, Error, RetryPolicy};
 
     #[test]
     fn test_append_path() {
@@ -337,4 +444,58 @@ mod tests {
         let result = append_path(&url, &paths).unwrap();
         assert_eq!(result.as_str(), "http://hasura.io/ndc/query/explain");
     }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/query")
+            .with_status(500)
+            .with_body("{}")
+            .expect(3)
+            .create_async()
+            .await;
+        let request = reqwest::Client::new().post(server.url() + "/query");
+        let retry_policy = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+        };
+        let result =
+            execute_request_with_retries::<serde_json::Value>(request, None, retry_policy).await;
+        assert!(matches!(result, Err(Error::InvalidConnector(_))));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/query")
+            .with_status(400)
+            .with_body("{}")
+            .expect(1)
+            .create_async()
+            .await;
+        let request = reqwest::Client::new().post(server.url() + "/query");
+        let retry_policy = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+        };
+        let result =
+            execute_request_with_retries::<serde_json::Value>(request, None, retry_policy).await;
+        assert!(matches!(result, Err(Error::InvalidConnector(_))));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        // a listener which never accepts, so requests to it never receive a response
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/query", listener.local_addr().unwrap());
+        let request = reqwest::Client::new()
+            .post(url)
+            .timeout(Duration::from_millis(50));
+        let result = execute_request::<serde_json::Value>(request, None).await;
+        assert!(matches!(result, Err(Error::Timeout)));
+    }
 }
@@ -0,0 +1,199 @@
+//! Estimates the cost of executing a normalized operation, for enforcing the `maxCost` operation
+//! limit.
+//!
+//! Each model or command that has to be fetched costs 1, and a remote relationship, which needs
+//! its own request to a data connector, costs `REMOTE_JOIN_COST`. The cost of a field is
+//! multiplied by the number of rows its parent fields are expected to return: the `limit`
+//! argument when one is given, and `DEFAULT_LIST_LENGTH` otherwise.
+
+use lang_graphql::normalized_ast as normalized;
+use lang_graphql::validation::{CostEstimator, FieldCost};
+use open_dds::relationships::RelationshipType;
+use schema::{
+    Annotation, CommandRelationshipAnnotation, InputAnnotation, ModelInputAnnotation,
+    ModelRelationshipAnnotation, OutputAnnotation, RootFieldAnnotation, RootFieldKind, GDS,
+};
+
+/// The cost of fetching a model or command.
+const FETCH_COST: u64 = 1;
+
+/// The cost of a remote relationship, which is executed as a separate request.
+const REMOTE_JOIN_COST: u64 = 10;
+
+/// The number of rows a list field is assumed to return when it has no `limit` argument.
+const DEFAULT_LIST_LENGTH: u64 = 10;
+
+pub(crate) struct GDSCostEstimator;
+
+/// The data connector that the fields of a selection set are fetched from, if any.
+pub(crate) type SourceDataConnector<'s> = Option<&'s metadata_resolve::DataConnectorLink>;
+
+impl<'s> CostEstimator<'s, GDS> for GDSCostEstimator {
+    type Scope = SourceDataConnector<'s>;
+
+    fn root_scope(&self) -> Self::Scope {
+        None
+    }
+
+    fn estimate_field(
+        &self,
+        scope: &Self::Scope,
+        field: &normalized::Field<'s, GDS>,
+    ) -> FieldCost<Self::Scope> {
+        // Fields of interfaces and unions have a field call per possible type. Estimate the most
+        // expensive of them.
+        field
+            .field_calls
+            .values()
+            .map(|field_call| estimate_field_call(*scope, field, field_call))
+            .max_by_key(|field_cost| field_cost.cost.saturating_mul(field_cost.multiplier))
+            .unwrap_or(FieldCost {
+                cost: 0,
+                multiplier: 1,
+                scope: *scope,
+            })
+    }
+}
+
+fn estimate_field_call<'s>(
+    scope: SourceDataConnector<'s>,
+    field: &normalized::Field<'s, GDS>,
+    field_call: &normalized::FieldCall<'s, GDS>,
+) -> FieldCost<SourceDataConnector<'s>> {
+    match field_call.info.generic {
+        Annotation::Output(OutputAnnotation::RootField(RootFieldAnnotation::Model {
+            source,
+            kind,
+            ..
+        })) => FieldCost {
+            cost: FETCH_COST,
+            multiplier: match kind {
+                RootFieldKind::SelectMany => list_length(field_call),
+                RootFieldKind::SelectOne | RootFieldKind::SelectAggregate => 1,
+            },
+            scope: source.as_ref().map(|source| &source.data_connector),
+        },
+        // point mutations affect at most one row
+        Annotation::Output(OutputAnnotation::RootField(RootFieldAnnotation::ModelMutation {
+            source,
+            ..
+        })) => FieldCost {
+            cost: FETCH_COST,
+            multiplier: 1,
+            scope: source.as_ref().map(|source| &source.data_connector),
+        },
+        Annotation::Output(OutputAnnotation::RootField(
+            RootFieldAnnotation::FunctionCommand { source, .. }
+            | RootFieldAnnotation::ProcedureCommand { source, .. },
+        )) => FieldCost {
+            cost: FETCH_COST,
+            multiplier: type_length(field),
+            scope: source.as_ref().map(|source| &source.data_connector),
+        },
+        Annotation::Output(OutputAnnotation::RelationshipToModel(
+            ModelRelationshipAnnotation {
+                target_source,
+                relationship_type,
+                ..
+            },
+        )) => FieldCost {
+            cost: relationship_cost(
+                scope,
+                target_source.as_ref().map(|target_source| {
+                    (
+                        &target_source.model.data_connector,
+                        &target_source.capabilities,
+                    )
+                }),
+            ),
+            multiplier: match relationship_type {
+                RelationshipType::Array => list_length(field_call),
+                RelationshipType::Object => 1,
+            },
+            scope: target_source
+                .as_ref()
+                .map(|target_source| &target_source.model.data_connector),
+        },
+        Annotation::Output(OutputAnnotation::RelationshipToCommand(
+            CommandRelationshipAnnotation { target_source, .. },
+        )) => FieldCost {
+            cost: relationship_cost(
+                scope,
+                target_source.as_ref().map(|target_source| {
+                    (
+                        &target_source.details.data_connector,
+                        &target_source.capabilities,
+                    )
+                }),
+            ),
+            multiplier: type_length(field),
+            scope: target_source
+                .as_ref()
+                .map(|target_source| &target_source.details.data_connector),
+        },
+        Annotation::Output(OutputAnnotation::RootField(
+            RootFieldAnnotation::RelayNode { .. } | RootFieldAnnotation::ApolloFederation(_),
+        )) => FieldCost {
+            cost: FETCH_COST,
+            multiplier: type_length(field),
+            scope: None,
+        },
+        // Columns, nested objects, aggregates and typenames are fetched along with their parent.
+        _ => FieldCost {
+            cost: 0,
+            multiplier: type_length(field),
+            scope,
+        },
+    }
+}
+
+/// Relationships between different data connectors, or to data connectors which do not support
+/// relationships, are executed as remote joins.
+fn relationship_cost(
+    source_data_connector: SourceDataConnector<'_>,
+    target: Option<(
+        &metadata_resolve::DataConnectorLink,
+        &metadata_resolve::RelationshipCapabilities,
+    )>,
+) -> u64 {
+    match (source_data_connector, target) {
+        (Some(source_data_connector), Some((target_data_connector, target_capabilities))) => {
+            match metadata_resolve::relationship_execution_category(
+                source_data_connector,
+                target_data_connector,
+                target_capabilities,
+            ) {
+                metadata_resolve::RelationshipExecutionCategory::Local => FETCH_COST,
+                metadata_resolve::RelationshipExecutionCategory::RemoteForEach
+                | metadata_resolve::RelationshipExecutionCategory::RemoteNaive => REMOTE_JOIN_COST,
+            }
+        }
+        _ => FETCH_COST,
+    }
+}
+
+/// The number of rows a field with a `limit` argument is expected to return.
+fn list_length(field_call: &normalized::FieldCall<'_, GDS>) -> u64 {
+    field_call
+        .arguments
+        .values()
+        .find(|argument| {
+            matches!(
+                argument.info.generic,
+                Annotation::Input(InputAnnotation::Model(
+                    ModelInputAnnotation::ModelLimitArgument
+                ))
+            )
+        })
+        .and_then(|argument| argument.value.as_int_u32().ok())
+        .map_or(DEFAULT_LIST_LENGTH, u64::from)
+}
+
+/// The number of values a field is expected to return, judging by its type.
+fn type_length(field: &normalized::Field<'_, GDS>) -> u64 {
+    if field.type_container.is_list() {
+        DEFAULT_LIST_LENGTH
+    } else {
+        1
+    }
+}
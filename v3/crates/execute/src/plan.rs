@@ -1,6 +1,7 @@
 mod commands;
 pub(crate) mod error;
-mod model_selection;
+mod model_mutation;
+pub(crate) mod model_selection;
 mod relationships;
 pub(crate) mod selection_set;
 
@@ -11,20 +12,25 @@ use indexmap::IndexMap;
 use lang_graphql as gql;
 use lang_graphql::ast::common as ast;
 use serde_json as json;
-use tracing_util::{set_attribute_on_active_span, AttributeVisibility, Traceable};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::time::Instant;
+use tracing_util::{set_attribute_on_active_span, AttributeVisibility, Successful, Traceable};
 
 use super::ir;
 use super::ir::aggregates::AggregateFieldSelection;
 use super::ir::model_selection::ModelSelection;
 use super::ir::root_field;
 use super::ndc;
-use super::process_response::process_response;
-use super::remote_joins::execute_join_locations;
+use super::process_response::{process_response, FailedRemoteJoins};
+use super::remote_joins;
 use super::remote_joins::types::{
-    JoinId, JoinLocations, JoinNode, Location, LocationKind, MonotonicCounter, RemoteJoin,
+    EngineSort, JoinId, JoinLocations, JoinNode, Location, LocationKind, MonotonicCounter,
+    RemoteJoin,
 };
+use super::remote_joins::{execute_join_locations, RemoteJoinFailure};
 use super::{HttpContext, ProjectId};
-use crate::error::FieldError;
+use crate::error::{FieldError, NDCUnexpectedError, NestedFieldError};
 use schema::GDSRoleNamespaceGetter;
 use schema::GDS;
 
@@ -33,14 +39,13 @@ pub type QueryPlan<'n, 's, 'ir> = IndexMap<ast::Alias, NodeQueryPlan<'n, 's, 'ir
 /// Unlike a query, the root nodes of a mutation aren't necessarily independent. Specifically, the
 /// GraphQL specification says that each root mutation must be executed sequentially. Moreover, if
 /// we want to, say, insert a parent _and_ children in one query, we want the ability to make
-/// transactional requests. In a mutation plan, we group nodes by connector, allowing us to issue
-/// transactional commands to connectors whose capabilities allow for transactional mutations.
-/// Otherwise, we can just send them one-by-one (though still sequentially).
+/// transactional requests. In a mutation plan, we group adjacent nodes by connector, allowing us to
+/// issue transactional commands to connectors whose capabilities allow for transactional
+/// mutations. Otherwise, we can just send them one-by-one (though still sequentially).
 pub struct MutationPlan<'n, 's, 'ir> {
-    pub nodes: IndexMap<
-        metadata_resolve::DataConnectorLink,
-        IndexMap<ast::Alias, NDCMutationExecution<'n, 's, 'ir>>,
-    >,
+    /// Groups of adjacent root fields which mutate the same data connector, in the order in which
+    /// they appear in the request.
+    pub nodes: Vec<IndexMap<ast::Alias, NDCMutationExecution<'n, 's, 'ir>>>,
     pub type_names: IndexMap<ast::Alias, ast::TypeName>,
 }
 
@@ -77,6 +82,25 @@ pub enum NodeQueryPlan<'n, 's, 'ir> {
     ApolloFederationSelect(ApolloFederationSelect<'n, 's, 'ir>),
 }
 
+impl<'n, 's, 'ir> NodeQueryPlan<'n, 's, 'ir> {
+    /// The name of the root field this plan resolves, used to label metrics.
+    fn root_field_name(&self) -> &str {
+        match self {
+            NodeQueryPlan::TypeName { .. } => "__typename",
+            NodeQueryPlan::SchemaField { .. } => "__schema",
+            NodeQueryPlan::TypeField { .. } => "__type",
+            NodeQueryPlan::NDCQueryExecution(ndc_query) => &ndc_query.field_span_attribute,
+            NodeQueryPlan::RelayNodeSelect(_) => "node",
+            NodeQueryPlan::ApolloFederationSelect(ApolloFederationSelect::EntitiesSelect(_)) => {
+                "_entities"
+            }
+            NodeQueryPlan::ApolloFederationSelect(ApolloFederationSelect::ServiceField {
+                ..
+            }) => "_service",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct NDCQueryExecution<'s, 'ir> {
     pub execution_tree: ExecutionTree<'s, 'ir>,
@@ -104,7 +128,7 @@ pub struct NDCMutationExecution<'n, 's, 'ir> {
     pub query: ndc_models::MutationRequest,
     pub join_locations: JoinLocations<(RemoteJoin<'s, 'ir>, JoinId)>,
     pub data_connector: &'s metadata_resolve::DataConnectorLink,
-    pub execution_span_attribute: String,
+    pub execution_span_attribute: &'static str,
     pub field_span_attribute: String,
     pub process_response_as: ProcessResponseAs<'s, 'ir>,
     pub selection_set: &'n normalized_ast::SelectionSet<'s, GDS>,
@@ -113,6 +137,9 @@ pub struct NDCMutationExecution<'n, 's, 'ir> {
 #[derive(Debug)]
 pub struct ExecutionTree<'s, 'ir> {
     pub root_node: ExecutionNode<'s>,
+    /// The remote joins fetching the values the engine sorts the rows by, which are executed
+    /// before the rows are sorted. Empty unless the root node has an `engine_sort`.
+    pub sort_executions: JoinLocations<(RemoteJoin<'s, 'ir>, JoinId)>,
     pub remote_executions: JoinLocations<(RemoteJoin<'s, 'ir>, JoinId)>,
 }
 
@@ -120,6 +147,8 @@ pub struct ExecutionTree<'s, 'ir> {
 pub struct ExecutionNode<'s> {
     pub query: ndc_models::QueryRequest,
     pub data_connector: &'s metadata_resolve::DataConnectorLink,
+    /// Set when the rows are ordered across remote relationships, and so sorted by the engine
+    pub engine_sort: Option<EngineSort>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -134,6 +163,9 @@ pub enum ProcessResponseAs<'s, 'ir> {
         command_name: &'ir metadata_resolve::Qualified<open_dds::commands::CommandName>,
         type_container: &'ir ast::TypeContainer<ast::TypeName>,
     },
+    ModelMutationResponse {
+        type_container: &'ir ast::TypeContainer<ast::TypeName>,
+    },
     Aggregates {
         requested_fields: &'ir IndexMap<String, AggregateFieldSelection<'s>>,
     },
@@ -144,7 +176,10 @@ impl<'s, 'ir> ProcessResponseAs<'s, 'ir> {
         match self {
             ProcessResponseAs::Object { is_nullable }
             | ProcessResponseAs::Array { is_nullable } => *is_nullable,
-            ProcessResponseAs::CommandResponse { type_container, .. } => type_container.nullable,
+            ProcessResponseAs::CommandResponse { type_container, .. }
+            | ProcessResponseAs::ModelMutationResponse { type_container } => {
+                type_container.nullable
+            }
             ProcessResponseAs::Aggregates { .. } => false,
         }
     }
@@ -166,25 +201,37 @@ pub fn generate_request_plan<'n, 's, 'ir>(
         }
         ir::IR::Mutation(ir) => {
             let mut mutation_plan = MutationPlan {
-                nodes: IndexMap::new(),
+                nodes: Vec::new(),
                 type_names: IndexMap::new(),
             };
             for (alias, field) in ir {
-                match field {
+                let plan = match field {
                     root_field::MutationRootField::TypeName { type_name } => {
                         mutation_plan
                             .type_names
                             .insert(alias.clone(), type_name.clone());
+                        continue;
                     }
                     root_field::MutationRootField::ProcedureBasedCommand { selection_set, ir } => {
-                        let plan = plan_mutation(selection_set, ir)?;
-                        mutation_plan
-                            .nodes
-                            .entry(plan.data_connector.clone())
-                            .or_default()
-                            .insert(alias.clone(), plan);
+                        plan_mutation(selection_set, ir)?
+                    }
+                    root_field::MutationRootField::ModelPointMutation { selection_set, ir } => {
+                        plan_model_mutation(selection_set, ir)?
                     }
                 };
+                match mutation_plan.nodes.last_mut() {
+                    Some(mutation_group)
+                        if mutation_group
+                            .values()
+                            .next()
+                            .is_some_and(|node| node.data_connector == plan.data_connector) =>
+                    {
+                        mutation_group.insert(alias.clone(), plan);
+                    }
+                    _ => mutation_plan
+                        .nodes
+                        .push(IndexMap::from([(alias.clone(), plan)])),
+                }
             }
             Ok(RequestPlan::MutationPlan(mutation_plan))
         }
@@ -205,7 +252,7 @@ fn plan_mutation<'n, 's, 'ir>(
         join_locations: join_locations_ids,
         data_connector: ir.command_info.data_connector,
         selection_set,
-        execution_span_attribute: "execute_command".into(),
+        execution_span_attribute: "execute_command",
         field_span_attribute: ir.command_info.field_name.to_string(),
         process_response_as: ProcessResponseAs::CommandResponse {
             command_name: &ir.command_info.command_name,
@@ -214,6 +261,27 @@ fn plan_mutation<'n, 's, 'ir>(
     })
 }
 
+// Given a point mutation of a model, plan the execution of that root field.
+fn plan_model_mutation<'n, 's, 'ir>(
+    selection_set: &'n gql::normalized_ast::SelectionSet<'s, GDS>,
+    ir: &'ir super::ir::model_mutation::ModelPointMutation<'s>,
+) -> Result<NDCMutationExecution<'n, 's, 'ir>, error::Error> {
+    let mut join_id_counter = MonotonicCounter::new();
+    let (ndc_ir, join_locations) = model_mutation::ndc_mutation_ir(ir, &mut join_id_counter)?;
+    let join_locations_ids = assign_with_join_ids(join_locations)?;
+    Ok(NDCMutationExecution {
+        query: ndc_ir,
+        join_locations: join_locations_ids,
+        data_connector: ir.data_connector,
+        selection_set,
+        execution_span_attribute: "execute_model_mutation",
+        field_span_attribute: ir.field_name.to_string(),
+        process_response_as: ProcessResponseAs::ModelMutationResponse {
+            type_container: &ir.type_container,
+        },
+    })
+}
+
 // Given a singular root field of a query, plan the execution of that root field.
 fn plan_query<'n, 's, 'ir>(
     ir: &'ir root_field::QueryRootField<'n, 's>,
@@ -307,7 +375,9 @@ fn plan_query<'n, 's, 'ir>(
                 root_node: ExecutionNode {
                     query: ndc_ir,
                     data_connector: ir.command_info.data_connector,
+                    engine_sort: None,
                 },
+                sort_executions: JoinLocations::new(),
                 remote_executions: join_locations_ids,
             };
             NodeQueryPlan::NDCQueryExecution(NDCQueryExecution {
@@ -363,12 +433,30 @@ fn generate_execution_tree<'s, 'ir>(
 ) -> Result<ExecutionTree<'s, 'ir>, error::Error> {
     let mut counter = MonotonicCounter::new();
     let (ndc_ir, join_locations) = model_selection::ndc_ir(ir, &mut counter)?;
-    let join_locations_with_ids = assign_with_join_ids(join_locations)?;
+    let mut join_locations_with_ids = assign_with_join_ids(join_locations)?;
+    let engine_sort = model_selection::engine_sort(ir);
+    // only the joins for the values the rows are sorted by are needed before the sort; the others
+    // are executed for the rows that remain after the limit and offset
+    let sort_executions = match &engine_sort {
+        None => JoinLocations::new(),
+        Some(engine_sort) => {
+            let (sort_locations, locations) =
+                std::mem::take(&mut join_locations_with_ids.locations)
+                    .into_iter()
+                    .partition(|(alias, _)| engine_sort.sorts_by_field(alias));
+            join_locations_with_ids.locations = locations;
+            JoinLocations {
+                locations: sort_locations,
+            }
+        }
+    };
     Ok(ExecutionTree {
         root_node: ExecutionNode {
             query: ndc_ir,
             data_connector: ir.data_connector,
+            engine_sort,
         },
+        sort_executions,
         remote_executions: join_locations_with_ids,
     })
 }
@@ -493,6 +581,8 @@ impl<'s, 'ir> RemoteJoinCounter<'s, 'ir> {
 pub struct RootFieldResult {
     pub is_nullable: bool,
     pub result: Result<json::Value, FieldError>,
+    /// Errors of fields within the root field, which were replaced by `null`.
+    pub nested_errors: Vec<NestedFieldError>,
 }
 
 impl Traceable for RootFieldResult {
@@ -508,8 +598,29 @@ impl RootFieldResult {
         Self {
             is_nullable,
             result,
+            nested_errors: Vec::new(),
         }
     }
+
+    /// A result which may be partial, i.e. have some nested fields replaced by `null`.
+    pub fn from_partial(
+        is_nullable: bool,
+        result: Result<(json::Value, Vec<NestedFieldError>), FieldError>,
+    ) -> Self {
+        match result {
+            Ok((value, nested_errors)) => Self {
+                is_nullable,
+                result: Ok(value),
+                nested_errors,
+            },
+            Err(error) => Self::new(is_nullable, Err(error)),
+        }
+    }
+
+    /// Whether the root field resolved without any errors, including nested ones.
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok() && self.nested_errors.is_empty()
+    }
 }
 
 #[derive(Debug)]
@@ -525,7 +636,16 @@ impl ExecuteQueryResult {
         let mut errors = Vec::new();
         for (alias, field_result) in self.root_fields {
             let result = match field_result.result {
-                Ok(value) => value,
+                Ok(value) => {
+                    for nested_error in field_result.nested_errors {
+                        for nested_path in nested_error.paths {
+                            let mut path = vec![gql::http::PathSegment::field(alias.clone().0)];
+                            path.extend(nested_path);
+                            errors.push(nested_error.error.to_graphql_error(Some(path)));
+                        }
+                    }
+                    value
+                }
                 Err(e) => {
                     let path = vec![gql::http::PathSegment::field(alias.clone().0)];
                     // When error occur, check if the field is nullable
@@ -604,11 +724,11 @@ async fn execute_query_field_plan<'n, 's, 'ir>(
                                 resolve_schema_field(selection_set, schema, &GDSRoleNamespaceGetter{scope:namespace.clone()}),
                             )
                         }
-                        NodeQueryPlan::NDCQueryExecution(ndc_query) => RootFieldResult::new(
+                        NodeQueryPlan::NDCQueryExecution(ndc_query) => RootFieldResult::from_partial(
                             ndc_query.process_response_as.is_nullable(),
                             resolve_ndc_query_execution(http_context, &ndc_query, project_id).await,
                         ),
-                        NodeQueryPlan::RelayNodeSelect(optional_query) => RootFieldResult::new(
+                        NodeQueryPlan::RelayNodeSelect(optional_query) => RootFieldResult::from_partial(
                             optional_query.as_ref().map_or(true, |ndc_query| {
                                 ndc_query.process_response_as.is_nullable()
                             }),
@@ -637,16 +757,30 @@ async fn execute_query_field_plan<'n, 's, 'ir>(
 
                             let executed_entities = futures_util::future::join_all(tasks).await;
                             let mut entities_result = Vec::new();
-                            for result in executed_entities {
+                            let mut nested_errors = Vec::new();
+                            for (index, result) in executed_entities.into_iter().enumerate() {
                                 match result {
-                                    (Ok(value),) => entities_result.push(value),
+                                    (Ok((value, entity_nested_errors)),) => {
+                                        entities_result.push(value);
+                                        nested_errors.extend(entity_nested_errors.into_iter().map(
+                                            |mut nested_error| {
+                                                for path in &mut nested_error.paths {
+                                                    path.insert(0, gql::http::PathSegment::index(index));
+                                                }
+                                                nested_error
+                                            },
+                                        ));
+                                    }
                                     (Err(e),) => {
                                         return RootFieldResult::new(true, Err(e));
                                     }
                                 }
                             }
 
-                            RootFieldResult::new(true, Ok(json::Value::Array(entities_result)))
+                            RootFieldResult::from_partial(
+                                true,
+                                Ok((json::Value::Array(entities_result), nested_errors)),
+                            )
                         }
                         NodeQueryPlan::ApolloFederationSelect(
                             ApolloFederationSelect::ServiceField { sdl, selection_set },
@@ -709,7 +843,7 @@ async fn execute_mutation_field_plan<'n, 's, 'ir>(
             tracing_util::SpanVisibility::User,
             || {
                 Box::pin(async {
-                    RootFieldResult::new(
+                    RootFieldResult::from_partial(
                         mutation_plan.process_response_as.is_nullable(),
                         resolve_ndc_mutation_execution(http_context, mutation_plan, project_id)
                             .await,
@@ -720,9 +854,152 @@ async fn execute_mutation_field_plan<'n, 's, 'ir>(
         .await
 }
 
+/// The data connector of a group of root fields which should be executed as a single
+/// transactional request, if there is more than one field in the group and the data connector
+/// supports transactional mutations.
+pub(crate) fn transactional_data_connector<'s>(
+    mutation_group: &IndexMap<ast::Alias, NDCMutationExecution<'_, 's, '_>>,
+) -> Option<&'s metadata_resolve::DataConnectorLink> {
+    mutation_group
+        .values()
+        .next()
+        .map(|field_plan| field_plan.data_connector)
+        .filter(|data_connector| {
+            mutation_group.len() > 1 && data_connector.capabilities.supports_transactional_mutations
+        })
+}
+
+/// Combine the operations of a group of root fields into a single NDC mutation request, in the
+/// order in which the fields appear in the request.
+pub(crate) fn batch_mutation_requests(
+    mutation_group: &IndexMap<ast::Alias, NDCMutationExecution<'_, '_, '_>>,
+) -> ndc_models::MutationRequest {
+    let mut query = ndc_models::MutationRequest {
+        operations: Vec::new(),
+        collection_relationships: BTreeMap::new(),
+    };
+    for field_plan in mutation_group.values() {
+        query
+            .operations
+            .extend(field_plan.query.operations.iter().cloned());
+        query
+            .collection_relationships
+            .extend(field_plan.query.collection_relationships.clone());
+    }
+    query
+}
+
+/// Execute a group of root fields which mutate the same data connector as a single NDC request, so
+/// that a data connector which supports transactional mutations applies all of them or none of
+/// them.
+async fn execute_mutation_group_plan<'n, 's, 'ir>(
+    http_context: &HttpContext,
+    data_connector: &metadata_resolve::DataConnectorLink,
+    mutation_group: IndexMap<ast::Alias, NDCMutationExecution<'n, 's, 'ir>>,
+    project_id: Option<&ProjectId>,
+) -> Vec<(ast::Alias, RootFieldResult)> {
+    let tracer = tracing_util::global_tracer();
+    tracer
+        .in_span_async(
+            "execute_mutation_group_plan",
+            format!(
+                "Execute request plan for mutation fields on data connector {}",
+                data_connector.name
+            ),
+            tracing_util::SpanVisibility::User,
+            || {
+                Box::pin(async {
+                    Successful::new(
+                        execute_mutation_group(
+                            http_context,
+                            data_connector,
+                            mutation_group,
+                            project_id,
+                        )
+                        .await,
+                    )
+                })
+            },
+        )
+        .await
+        .into_inner()
+}
+
+async fn execute_mutation_group<'n, 's, 'ir>(
+    http_context: &HttpContext,
+    data_connector: &metadata_resolve::DataConnectorLink,
+    mutation_group: IndexMap<ast::Alias, NDCMutationExecution<'n, 's, 'ir>>,
+    project_id: Option<&ProjectId>,
+) -> Vec<(ast::Alias, RootFieldResult)> {
+    let query = batch_mutation_requests(&mutation_group);
+
+    let operation_results =
+        ndc::fetch_from_data_connector_mutation(http_context, &query, data_connector, project_id)
+            .await
+            .map_err(FieldError::from)
+            .and_then(|response| {
+                if response.operation_results.len() == mutation_group.len() {
+                    Ok(response.operation_results)
+                } else {
+                    Err(FieldError::from(NDCUnexpectedError::BadNDCResponse {
+                        summary: format!(
+                            "expected {} operation results, got {}",
+                            mutation_group.len(),
+                            response.operation_results.len()
+                        ),
+                    }))
+                }
+            });
+
+    let operation_results = match operation_results {
+        Ok(operation_results) => operation_results,
+        Err(e) => {
+            let reason = e.to_graphql_error(None).message;
+            return mutation_group
+                .into_iter()
+                .map(|(alias, field_plan)| {
+                    let result = RootFieldResult::new(
+                        field_plan.process_response_as.is_nullable(),
+                        Err(FieldError::MutationBatchRolledBack {
+                            data_connector: data_connector.name.clone(),
+                            reason: reason.clone(),
+                        }),
+                    );
+                    (alias, result)
+                })
+                .collect();
+        }
+    };
+
+    let mut executed_root_fields = Vec::new();
+    for ((alias, field_plan), mutation_results) in mutation_group.into_iter().zip(operation_results)
+    {
+        let result = ndc::process_mutation_results(
+            http_context,
+            mutation_results,
+            field_plan.selection_set,
+            field_plan.execution_span_attribute,
+            field_plan.process_response_as,
+            &field_plan.join_locations,
+            project_id,
+        )
+        .await
+        .map(|(value, remote_join_failures)| {
+            let nested_errors = nested_field_errors(&value, remote_join_failures);
+            (value, nested_errors)
+        });
+        executed_root_fields.push((
+            alias,
+            RootFieldResult::from_partial(field_plan.process_response_as.is_nullable(), result),
+        ));
+    }
+    executed_root_fields
+}
+
 /// Given an entire plan for a mutation, produce a result. We do this by executing the singular
 /// root fields of the mutation sequentially rather than concurrently, in the order defined by the
-/// `IndexMap`'s keys.
+/// `IndexMap`'s keys. Groups of adjacent root fields on a data connector which supports
+/// transactional mutations are executed as a single request.
 pub async fn execute_mutation_plan<'n, 's, 'ir>(
     http_context: &HttpContext,
     mutation_plan: MutationPlan<'n, 's, 'ir>,
@@ -743,12 +1020,47 @@ pub async fn execute_mutation_plan<'n, 's, 'ir>(
         ));
     }
 
-    for (_, mutation_group) in mutation_plan.nodes {
-        for (alias, field_plan) in mutation_group {
-            executed_root_fields.push((
-                alias,
-                execute_mutation_field_plan(http_context, field_plan, project_id).await,
-            ));
+    for mutation_group in mutation_plan.nodes {
+        match transactional_data_connector(&mutation_group) {
+            Some(data_connector) => {
+                // Every field in a group shares the latency of the single request
+                let field_names: Vec<String> = mutation_group
+                    .values()
+                    .map(|field_plan| field_plan.field_span_attribute.clone())
+                    .collect();
+                let started = Instant::now();
+                let group_results = execute_mutation_group_plan(
+                    http_context,
+                    data_connector,
+                    mutation_group,
+                    project_id,
+                )
+                .await;
+                for (field_name, (_, root_field)) in field_names.iter().zip(&group_results) {
+                    observe_root_field(
+                        ast::OperationType::Mutation,
+                        field_name,
+                        root_field.is_success(),
+                        started,
+                    );
+                }
+                executed_root_fields.extend(group_results);
+            }
+            None => {
+                for (alias, field_plan) in mutation_group {
+                    let field_name = field_plan.field_span_attribute.clone();
+                    let started = Instant::now();
+                    let root_field =
+                        execute_mutation_field_plan(http_context, field_plan, project_id).await;
+                    observe_root_field(
+                        ast::OperationType::Mutation,
+                        &field_name,
+                        root_field.is_success(),
+                        started,
+                    );
+                    executed_root_fields.push((alias, root_field));
+                }
+            }
         }
     }
 
@@ -773,8 +1085,16 @@ pub async fn execute_query_plan<'n, 's, 'ir>(
     // To run the field plans parallely, we will need to use tokio::spawn for each field plan.
     let executed_root_fields =
         futures_ext::execute_concurrently(query_plan.into_iter(), |(alias, field_plan)| async {
+            let field_name = field_plan.root_field_name().to_string();
+            let started = Instant::now();
             let plan_result =
                 execute_query_field_plan(&alias, http_context, field_plan, project_id).await;
+            observe_root_field(
+                ast::OperationType::Query,
+                &field_name,
+                plan_result.is_success(),
+                started,
+            );
             (alias, plan_result)
         })
         .await;
@@ -787,6 +1107,30 @@ pub async fn execute_query_plan<'n, 's, 'ir>(
     ExecuteQueryResult { root_fields }
 }
 
+/// The label used for an operation type in metrics.
+pub(crate) fn operation_type_label(operation_type: ast::OperationType) -> &'static str {
+    match operation_type {
+        ast::OperationType::Query => "query",
+        ast::OperationType::Mutation => "mutation",
+        ast::OperationType::Subscription => "subscription",
+    }
+}
+
+/// Records the latency and outcome of executing a single root field.
+fn observe_root_field(
+    operation_type: ast::OperationType,
+    root_field: &str,
+    is_success: bool,
+    started: Instant,
+) {
+    metrics_util::global_metrics().observe_root_field(
+        operation_type_label(operation_type),
+        root_field,
+        metrics_util::RequestStatus::from_is_success(is_success),
+        started.elapsed(),
+    );
+}
+
 fn resolve_type_name(type_name: ast::TypeName) -> Result<json::Value, FieldError> {
     Ok(json::to_value(type_name)?)
 }
@@ -824,7 +1168,7 @@ async fn resolve_ndc_query_execution(
     http_context: &HttpContext,
     ndc_query: &NDCQueryExecution<'_, '_>,
     project_id: Option<&ProjectId>,
-) -> Result<json::Value, FieldError> {
+) -> Result<(json::Value, Vec<NestedFieldError>), FieldError> {
     let NDCQueryExecution {
         execution_tree,
         selection_set,
@@ -832,34 +1176,122 @@ async fn resolve_ndc_query_execution(
         field_span_attribute,
         process_response_as,
     } = ndc_query;
+    let root_node = &execution_tree.root_node;
+    let max_sorted_rows = http_context.remote_join_options.max_sorted_rows;
+    let query = match &root_node.engine_sort {
+        None => Cow::Borrowed(&root_node.query),
+        Some(_) => {
+            let mut query = root_node.query.clone();
+            remote_joins::sort::limit_rows_to_sort(&mut query.query, max_sorted_rows);
+            Cow::Owned(query)
+        }
+    };
     let mut response = ndc::execute_ndc_query(
         http_context,
-        &execution_tree.root_node.query,
-        execution_tree.root_node.data_connector,
+        &query,
+        root_node.data_connector,
         execution_span_attribute,
         field_span_attribute.clone(),
         project_id,
     )
     .await?;
-    // TODO: Failures in remote joins should result in partial response
-    // https://github.com/hasura/v3-engine/issues/229
-    execute_join_locations(
-        http_context,
-        execution_span_attribute,
-        &mut response,
+    let mut remote_join_failures = Vec::new();
+    if let Some(engine_sort) = &root_node.engine_sort {
+        remote_join_failures = execute_join_locations(
+            http_context,
+            execution_span_attribute,
+            &mut response,
+            process_response_as,
+            &execution_tree.sort_executions,
+            project_id,
+        )
+        .await?;
+        remote_joins::sort::sort_row_sets(engine_sort, &mut response, max_sorted_rows)?;
+    }
+    remote_join_failures.extend(
+        execute_join_locations(
+            http_context,
+            execution_span_attribute,
+            &mut response,
+            process_response_as,
+            &execution_tree.remote_executions,
+            project_id,
+        )
+        .await?,
+    );
+    let value = process_response(
+        selection_set,
+        response,
         process_response_as,
-        &execution_tree.remote_executions,
-        project_id,
-    )
-    .await?;
-    process_response(selection_set, response, process_response_as)
+        &FailedRemoteJoins::new(&remote_join_failures),
+    )?;
+    let nested_errors = nested_field_errors(&value, remote_join_failures);
+    Ok((value, nested_errors))
+}
+
+/// Locates the relationship fields of failed remote joins in a processed response.
+fn nested_field_errors(
+    value: &json::Value,
+    remote_join_failures: Vec<RemoteJoinFailure>,
+) -> Vec<NestedFieldError> {
+    remote_join_failures
+        .into_iter()
+        .map(|failure| NestedFieldError {
+            paths: null_field_paths(value, &failure.field_path),
+            error: failure.error,
+        })
+        .collect()
+}
+
+/// The paths to every occurrence of a field in a processed response which is `null`, given the
+/// aliases of the fields leading to it. Lists along the way are expanded into an occurrence per
+/// element.
+fn null_field_paths(value: &json::Value, field_path: &[String]) -> Vec<gql::http::Path> {
+    fn go(
+        value: &json::Value,
+        field_path: &[String],
+        path: &mut gql::http::Path,
+        paths: &mut Vec<gql::http::Path>,
+    ) {
+        match value {
+            json::Value::Array(elements) => {
+                for (index, element) in elements.iter().enumerate() {
+                    path.push(gql::http::PathSegment::index(index));
+                    go(element, field_path, path, paths);
+                    path.pop();
+                }
+            }
+            json::Value::Object(object) => {
+                let Some((alias, rest)) = field_path.split_first() else {
+                    return;
+                };
+                let (Some(field_value), Ok(name)) = (object.get(alias), ast::Name::new(alias))
+                else {
+                    return;
+                };
+                path.push(gql::http::PathSegment::field(name));
+                if rest.is_empty() {
+                    if field_value.is_null() {
+                        paths.push(path.clone());
+                    }
+                } else {
+                    go(field_value, rest, path, paths);
+                }
+                path.pop();
+            }
+            _ => {}
+        }
+    }
+    let mut paths = Vec::new();
+    go(value, field_path, &mut Vec::new(), &mut paths);
+    paths
 }
 
 async fn resolve_ndc_mutation_execution(
     http_context: &HttpContext,
     ndc_query: NDCMutationExecution<'_, '_, '_>,
     project_id: Option<&ProjectId>,
-) -> Result<json::Value, FieldError> {
+) -> Result<(json::Value, Vec<NestedFieldError>), FieldError> {
     let NDCMutationExecution {
         query,
         data_connector,
@@ -867,10 +1299,9 @@ async fn resolve_ndc_mutation_execution(
         execution_span_attribute,
         field_span_attribute,
         process_response_as,
-        // TODO: remote joins are not handled for mutations
-        join_locations: _,
+        join_locations,
     } = ndc_query;
-    ndc::execute_ndc_mutation(
+    let (value, remote_join_failures) = ndc::execute_ndc_mutation(
         http_context,
         &query,
         data_connector,
@@ -878,18 +1309,21 @@ async fn resolve_ndc_mutation_execution(
         execution_span_attribute,
         field_span_attribute,
         process_response_as,
+        &join_locations,
         project_id,
     )
-    .await
+    .await?;
+    let nested_errors = nested_field_errors(&value, remote_join_failures);
+    Ok((value, nested_errors))
 }
 
 async fn resolve_optional_ndc_select(
     http_context: &HttpContext,
     optional_query: Option<NDCQueryExecution<'_, '_>>,
     project_id: Option<&ProjectId>,
-) -> Result<json::Value, FieldError> {
+) -> Result<(json::Value, Vec<NestedFieldError>), FieldError> {
     match optional_query {
-        None => Ok(json::Value::Null),
+        None => Ok((json::Value::Null, Vec::new())),
         Some(ndc_query) => resolve_ndc_query_execution(http_context, &ndc_query, project_id).await,
     }
 }
@@ -27,6 +27,9 @@ pub enum InternalError {
     #[error("remote relationships should have been handled separately")]
     RemoteRelationshipsAreNotSupported,
 
+    #[error("predicates across remote relationships should have been resolved before planning")]
+    RemotePredicateNotResolved,
+
     #[error("generic error: {description}")]
     InternalGeneric { description: String },
 }
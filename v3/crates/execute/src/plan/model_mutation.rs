@@ -0,0 +1,53 @@
+//! NDC mutation generation from 'ModelPointMutation' IR
+
+use std::collections::BTreeMap;
+
+use super::error;
+use super::relationships;
+use super::selection_set;
+use crate::ir::model_mutation::ModelPointMutation;
+use crate::remote_joins::types::{JoinLocations, MonotonicCounter, RemoteJoin};
+
+pub(crate) fn ndc_mutation_ir<'s, 'ir>(
+    ir: &'ir ModelPointMutation<'s>,
+    join_id_counter: &mut MonotonicCounter,
+) -> Result<
+    (
+        ndc_models::MutationRequest,
+        JoinLocations<RemoteJoin<'s, 'ir>>,
+    ),
+    error::Error,
+> {
+    let (ndc_nested_field, jl) = ir
+        .selection
+        .as_ref()
+        .map(|nested_selection| {
+            selection_set::process_nested_selection(nested_selection, join_id_counter)
+        })
+        .transpose()?
+        .unzip();
+    let mutation_operation = ndc_models::MutationOperation::Procedure {
+        name: ir.procedure_name.to_string(),
+        arguments: ir.arguments.clone(),
+        fields: ndc_nested_field,
+    };
+    let mut collection_relationships = BTreeMap::new();
+    if let Some(nested_selection) = &ir.selection {
+        selection_set::collect_relationships_from_nested_selection(
+            nested_selection,
+            &mut collection_relationships,
+        )?;
+    }
+    // the relationships used by the pre and post checks
+    for (name, relationship) in &ir.predicate_relationships {
+        collection_relationships.insert(
+            name.to_string(),
+            relationships::process_model_relationship_definition(relationship)?,
+        );
+    }
+    let mutation_request = ndc_models::MutationRequest {
+        operations: vec![mutation_operation],
+        collection_relationships,
+    };
+    Ok((mutation_request, jl.unwrap_or_default()))
+}
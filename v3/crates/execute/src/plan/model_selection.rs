@@ -1,22 +1,32 @@
 //! NDC query generation from 'ModelSelection' IR
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use indexmap::IndexMap;
 
 use super::error;
 use super::relationships;
 use super::selection_set;
+use super::ProcessResponseAs;
 use crate::ir::aggregates::{AggregateFieldSelection, AggregateSelectionSet};
+use crate::ir::filter::RemoteRelationshipPredicate;
 use crate::ir::model_selection::ModelSelection;
-use crate::remote_joins::types::{JoinLocations, MonotonicCounter, RemoteJoin};
+use crate::ir::order_by::{OrderByElement, RemoteOrderByElement, ResolvedOrderBy};
+use crate::ir::selection_set::ResultSelectionSet;
+use crate::remote_joins::types::{
+    EngineSort, JoinLocations, JoinNode, Location, MonotonicCounter, RemoteJoin,
+    RemoteJoinStrategy, RemoteJoinType, SortKey, TargetField,
+};
+
+/// The prefix of the aliases of the hidden fields holding the values the engine sorts rows by
+const SORT_FIELD_ALIAS_PREFIX: &str = "__hasura_order_by";
 
 /// Create an NDC `Query` based on the internal IR `ModelSelection` settings
 pub(crate) fn ndc_query<'s, 'ir>(
     ir: &'ir ModelSelection<'s>,
     join_id_counter: &mut MonotonicCounter,
 ) -> Result<(ndc_models::Query, JoinLocations<RemoteJoin<'s, 'ir>>), error::Error> {
-    let (ndc_fields, join_locations) = ir
+    let (mut ndc_fields, mut join_locations) = ir
         .selection
         .as_ref()
         .map(|selection| -> Result<_, error::Error> {
@@ -29,18 +39,246 @@ pub(crate) fn ndc_query<'s, 'ir>(
 
     let aggregates = ir.aggregate_selection.as_ref().map(ndc_aggregates);
 
+    let predicate = ir
+        .filter_clause
+        .expression
+        .as_ref()
+        .map(|expression| {
+            expression
+                .to_ndc_expression()
+                .ok_or(error::InternalError::RemotePredicateNotResolved)
+        })
+        .transpose()?;
+
+    // The data connector orders the rows, unless they are ordered across remote relationships.
+    // The engine sorts them then, and applies the limit and offset itself (see `engine_sort`).
+    // The data connector still orders them by the local elements, so that the ties of the sort
+    // are broken the same way for every page.
+    let (order_by, limit, offset) = match &ir.order_by {
+        None => (None, ir.limit, ir.offset),
+        Some(order_by) => match order_by.to_ndc_order_by() {
+            Some(ndc_order_by) => (Some(ndc_order_by), ir.limit, ir.offset),
+            None => {
+                let selection =
+                    ir.selection
+                        .as_ref()
+                        .ok_or_else(|| error::InternalError::InternalGeneric {
+                            description: "rows sorted by the engine should have a selection".into(),
+                        })?;
+                add_sort_fields(
+                    order_by,
+                    selection,
+                    ndc_fields.get_or_insert_with(IndexMap::new),
+                    &mut join_locations,
+                )?;
+                (order_by.to_local_ndc_order_by(), None, None)
+            }
+        },
+    };
+
     let ndc_query = ndc_models::Query {
         aggregates,
         fields: ndc_fields,
-        limit: ir.limit,
-        offset: ir.offset,
-        order_by: ir.order_by.as_ref().map(|x| x.order_by.clone()),
-        predicate: ir.filter_clause.expression.clone(),
+        limit,
+        offset,
+        order_by,
+        predicate,
     };
 
     Ok((ndc_query, join_locations))
 }
 
+/// How the engine sorts the rows of a model selection, if they are ordered across remote
+/// relationships. The values of every order_by element are in hidden fields of the rows (see
+/// `add_sort_fields`).
+pub(crate) fn engine_sort(ir: &ModelSelection<'_>) -> Option<EngineSort> {
+    let order_by = ir.order_by.as_ref()?;
+    order_by.first_remote_relationship()?;
+    let mut keys = Vec::new();
+    for (index, element) in order_by.elements.iter().enumerate() {
+        let alias = sort_field_alias(index);
+        match element {
+            OrderByElement::Local(element) => keys.push(sort_key(vec![alias], element)),
+            OrderByElement::RemoteRelationship(remote_element) => {
+                for (target_index, element) in remote_element.elements.iter().enumerate() {
+                    keys.push(sort_key(
+                        vec![alias.clone(), sort_field_alias(target_index)],
+                        element,
+                    ));
+                }
+            }
+        }
+    }
+    Some(EngineSort {
+        keys,
+        limit: ir.limit,
+        offset: ir.offset,
+    })
+}
+
+fn sort_field_alias(index: usize) -> String {
+    format!("{SORT_FIELD_ALIAS_PREFIX}_{index}")
+}
+
+/// The sort key of an order_by element, whose field is at `alias_path`. The value of a column
+/// across local relationships is nested in a row of each relationship.
+fn sort_key(mut alias_path: Vec<String>, element: &ndc_models::OrderByElement) -> SortKey {
+    if let ndc_models::OrderByTarget::Column { path, .. } = &element.target {
+        alias_path.extend(path.iter().map(|_| SORT_FIELD_ALIAS_PREFIX.to_string()));
+    }
+    SortKey {
+        alias_path,
+        descending: matches!(element.order_direction, ndc_models::OrderDirection::Desc),
+    }
+}
+
+/// Add hidden fields holding the values the rows are sorted by. The values across remote
+/// relationships are fetched by remote joins.
+fn add_sort_fields<'s, 'ir>(
+    order_by: &'ir ResolvedOrderBy<'s>,
+    selection: &ResultSelectionSet<'s>,
+    ndc_fields: &mut IndexMap<String, ndc_models::Field>,
+    join_locations: &mut JoinLocations<RemoteJoin<'s, 'ir>>,
+) -> Result<(), error::Error> {
+    for (index, element) in order_by.elements.iter().enumerate() {
+        let alias = sort_field_alias(index);
+        match element {
+            OrderByElement::Local(element) => {
+                ndc_fields.insert(alias, ndc_sort_field(element)?);
+            }
+            OrderByElement::RemoteRelationship(remote_element) => {
+                let mut join_mapping = HashMap::new();
+                for (src_field, target_field) in &remote_element.join_mapping {
+                    let ndc_field_alias = selection_set::process_remote_relationship_field_mapping(
+                        selection, src_field, ndc_fields,
+                    );
+                    join_mapping.insert(
+                        src_field.field_names(),
+                        (
+                            ndc_field_alias,
+                            TargetField::ModelField(target_field.clone()),
+                        ),
+                    );
+                }
+                for (src_field, argument_name) in
+                    remote_element.target_mappings.argument_join_mapping()
+                {
+                    let ndc_field_alias = selection_set::process_remote_relationship_field_mapping(
+                        selection, &src_field, ndc_fields,
+                    );
+                    join_mapping.insert(
+                        src_field.field_names(),
+                        (ndc_field_alias, TargetField::ModelArgument(argument_name)),
+                    );
+                }
+                let remote_join = RemoteJoin {
+                    target_data_connector: &remote_element.target_source.model.data_connector,
+                    target_ndc_ir: ndc_remote_sort_ir(remote_element)?,
+                    join_mapping,
+                    // the rows can't be sorted without the values, so failing to fetch them
+                    // fails the query
+                    process_response_as: ProcessResponseAs::Array { is_nullable: false },
+                    remote_join_type: RemoteJoinType::ToModel,
+                    strategy: RemoteJoinStrategy::from_capabilities(
+                        &remote_element.target_source.capabilities,
+                    ),
+                };
+                join_locations.locations.insert(
+                    alias,
+                    Location {
+                        join_node: JoinNode::Remote(remote_join),
+                        rest: JoinLocations::new(),
+                    },
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The field selecting the value of an order_by element, across the local relationships in its
+/// path
+fn ndc_sort_field(element: &ndc_models::OrderByElement) -> Result<ndc_models::Field, error::Error> {
+    match &element.target {
+        ndc_models::OrderByTarget::Column { name, path, .. } => {
+            let mut field = ndc_models::Field::Column {
+                column: name.clone(),
+                fields: None,
+                arguments: BTreeMap::new(),
+            };
+            for path_element in path.iter().rev() {
+                field = ndc_models::Field::Relationship {
+                    query: Box::new(ndc_models::Query {
+                        aggregates: None,
+                        fields: Some(IndexMap::from([(
+                            SORT_FIELD_ALIAS_PREFIX.to_string(),
+                            field,
+                        )])),
+                        limit: None,
+                        offset: None,
+                        order_by: None,
+                        predicate: None,
+                    }),
+                    relationship: path_element.relationship.clone(),
+                    arguments: path_element.arguments.clone(),
+                };
+            }
+            Ok(field)
+        }
+        _ => Err(error::InternalError::InternalGeneric {
+            description: "only columns can be sorted by the engine".into(),
+        })?,
+    }
+}
+
+/// Create the NDC `QueryRequest` which fetches the values to sort by from the target model of a
+/// remote relationship. It has a variable for every join column and argument.
+fn ndc_remote_sort_ir(
+    remote_element: &RemoteOrderByElement<'_>,
+) -> Result<ndc_models::QueryRequest, error::Error> {
+    let mut collection_relationships = BTreeMap::new();
+    for (name, relationship) in &remote_element.relationships {
+        let result = relationships::process_model_relationship_definition(relationship)?;
+        collection_relationships.insert(name.to_string(), result);
+    }
+
+    let fields = remote_element
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| Ok((sort_field_alias(index), ndc_sort_field(element)?)))
+        .collect::<Result<_, error::Error>>()?;
+
+    let mut join_condition: Vec<_> = remote_element
+        .join_mapping
+        .iter()
+        .map(|(_source_field, target_field)| target_field.join_condition())
+        .collect();
+    join_condition.extend(remote_element.target_mappings.predicate.clone());
+
+    Ok(ndc_models::QueryRequest {
+        query: ndc_models::Query {
+            aggregates: None,
+            fields: Some(fields),
+            limit: None,
+            offset: None,
+            order_by: None,
+            predicate: Some(ndc_models::Expression::And {
+                expressions: join_condition,
+            }),
+        },
+        collection: remote_element.target_source.model.collection.clone(),
+        arguments: remote_element
+            .target_mappings
+            .remote_arguments()
+            .into_iter()
+            .map(|(name, argument)| (name.to_string(), argument))
+            .collect(),
+        collection_relationships,
+        variables: None,
+    })
+}
+
 /// Translates the internal IR 'AggregateSelectionSet' into an NDC query aggregates selection
 fn ndc_aggregates(
     aggregate_selection_set: &AggregateSelectionSet,
@@ -130,3 +368,59 @@ pub(crate) fn ndc_ir<'s, 'ir>(
     };
     Ok((query_request, join_locations))
 }
+
+/// Create the NDC `QueryRequest` which evaluates a predicate across a remote relationship, by
+/// selecting the join keys of the target model rows that match the predicate
+pub(crate) fn ndc_remote_predicate_ir(
+    remote_predicate: &RemoteRelationshipPredicate<'_>,
+) -> Result<ndc_models::QueryRequest, error::Error> {
+    let mut collection_relationships = BTreeMap::new();
+    for (name, relationship) in &remote_predicate.predicate.relationships {
+        let result = relationships::process_model_relationship_definition(relationship)?;
+        collection_relationships.insert(name.to_string(), result);
+    }
+
+    let predicate = remote_predicate
+        .predicate
+        .expression
+        .as_ref()
+        .map(|expression| {
+            expression
+                .to_ndc_expression()
+                .ok_or(error::InternalError::RemotePredicateNotResolved)
+        })
+        .transpose()?;
+
+    let fields = remote_predicate
+        .join_columns
+        .iter()
+        .map(|join_column| {
+            (
+                join_column.target_alias(),
+                selection_set::ndc_nested_column_field(
+                    &join_column.target_column,
+                    &join_column.target_nested_field_path(),
+                ),
+            )
+        })
+        .collect();
+
+    Ok(ndc_models::QueryRequest {
+        query: ndc_models::Query {
+            aggregates: None,
+            fields: Some(fields),
+            limit: None,
+            offset: None,
+            order_by: None,
+            predicate,
+        },
+        collection: remote_predicate.target_source.model.collection.clone(),
+        arguments: remote_predicate
+            .arguments
+            .iter()
+            .map(|(name, argument)| (name.to_string(), argument.clone()))
+            .collect(),
+        collection_relationships,
+        variables: None,
+    })
+}
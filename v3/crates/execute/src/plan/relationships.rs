@@ -1,6 +1,8 @@
 //! NDC query generation from 'ModelSelection' IR for relationships.
 
-use open_dds::relationships::RelationshipType;
+use metadata_resolve::Qualified;
+use open_dds::relationships::{RelationshipName, RelationshipType};
+use open_dds::types::CustomTypeName;
 use std::collections::BTreeMap;
 
 use super::error;
@@ -85,15 +87,25 @@ pub(crate) fn process_model_relationship_definition(
         target_source,
         target_type: _,
         mappings,
+        ref target_mappings,
     } = relationship_info;
 
     let mut column_mapping = BTreeMap::new();
-    for metadata_resolve::RelationshipModelMapping {
-        source_field: source_field_path,
-        target_field: _,
-        target_ndc_column,
-    } in mappings
-    {
+    for mapping in mappings {
+        // mappings to arguments, or from values, are in the target mappings
+        let (
+            metadata_resolve::RelationshipMappingSource::Field(source_field_path),
+            metadata_resolve::RelationshipModelMappingTarget::ModelField(
+                metadata_resolve::RelationshipModelMappingFieldTarget {
+                    target_field: _,
+                    target_ndc_column,
+                    target_ndc_field_path: _,
+                },
+            ),
+        ) = (&mapping.source, &mapping.target)
+        else {
+            continue;
+        };
         if matches!(
             metadata_resolve::relationship_execution_category(
                 source_data_connector,
@@ -110,22 +122,27 @@ pub(crate) fn process_model_relationship_definition(
                 }
             })?;
 
-            let source_column = relationship::get_field_mapping_of_field_name(
+            // a source field nested in an object is a column of the object, which the
+            // relationship is selected from within
+            let source_field = relationship::get_source_field(
                 source_type_mappings,
                 source_type,
                 relationship_name,
-                &source_field_path.field_name,
+                source_field_path,
             )
             .map_err(|e| error::InternalError::InternalGeneric {
                 description: e.to_string(),
             })?;
 
             if column_mapping
-                .insert(source_column.column.0, target_column.column.0.clone())
+                .insert(
+                    source_field.joined_field_mapping().column.0.clone(),
+                    target_column.column.0.clone(),
+                )
                 .is_some()
             {
                 Err(error::InternalError::MappingExistsInRelationship {
-                    source_column: source_field_path.field_name.clone(),
+                    source_column: source_field_path.field.field_name.clone(),
                     relationship_name: relationship_name.clone(),
                 })?;
             }
@@ -142,7 +159,7 @@ pub(crate) fn process_model_relationship_definition(
             }
         },
         target_collection: target_source.model.collection.to_string(),
-        arguments: BTreeMap::new(),
+        arguments: target_mappings.local_arguments(),
     };
     Ok(ndc_relationship)
 }
@@ -159,10 +176,14 @@ pub(crate) fn process_command_relationship_definition(
 
     let mut arguments = BTreeMap::new();
     for metadata_resolve::RelationshipCommandMapping {
-        source_field: source_field_path,
+        source,
         argument_name: target_argument,
     } in &annotation.mappings
     {
+        // values are passed as arguments of the relationship field
+        let metadata_resolve::RelationshipMappingSource::Field(source_field_path) = source else {
+            continue;
+        };
         if matches!(
             metadata_resolve::relationship_execution_category(
                 source_data_connector,
@@ -171,18 +192,18 @@ pub(crate) fn process_command_relationship_definition(
             ),
             metadata_resolve::RelationshipExecutionCategory::Local
         ) {
-            let source_column = relationship::get_field_mapping_of_field_name(
+            let source_field = relationship::get_source_field(
                 source_type_mappings,
                 &annotation.source_type,
                 &annotation.relationship_name,
-                &source_field_path.field_name,
+                source_field_path,
             )
             .map_err(|e| error::InternalError::InternalGeneric {
                 description: e.to_string(),
             })?;
 
             let relationship_argument = ndc_models::RelationshipArgument::Column {
-                name: source_column.column.0,
+                name: source_field.joined_field_mapping().column.0.clone(),
             };
 
             if arguments
@@ -190,7 +211,7 @@ pub(crate) fn process_command_relationship_definition(
                 .is_some()
             {
                 Err(error::InternalError::MappingExistsInRelationship {
-                    source_column: source_field_path.field_name.clone(),
+                    source_column: source_field_path.field.field_name.clone(),
                     relationship_name: annotation.relationship_name.clone(),
                 })?;
             }
@@ -207,3 +228,63 @@ pub(crate) fn process_command_relationship_definition(
     };
     Ok(ndc_relationship)
 }
+
+/// The NDC names of the fields leading to the object, nested in the source type, whose fields a
+/// local model relationship joins on. NDC relationships join columns of the row they are selected
+/// in, so the relationship is selected from within that object. Empty when it joins on fields of
+/// the source type.
+pub(crate) fn model_relationship_source_object(
+    relationship_info: &LocalModelRelationshipInfo,
+) -> Result<Vec<String>, error::Error> {
+    let source_field_path = relationship_info
+        .mappings
+        .iter()
+        .find_map(|mapping| mapping.source.field());
+    get_source_object(
+        relationship_info.source_type_mappings,
+        relationship_info.source_type,
+        relationship_info.relationship_name,
+        source_field_path,
+    )
+}
+
+/// The NDC names of the fields leading to the object, nested in the source type, whose fields a
+/// local command relationship passes to the command. See `model_relationship_source_object`.
+pub(crate) fn command_relationship_source_object(
+    relationship_info: &LocalCommandRelationshipInfo,
+) -> Result<Vec<String>, error::Error> {
+    let annotation = relationship_info.annotation;
+    let source_field_path = annotation
+        .mappings
+        .iter()
+        .find_map(|mapping| mapping.source.field());
+    get_source_object(
+        relationship_info.source_type_mappings,
+        &annotation.source_type,
+        &annotation.relationship_name,
+        source_field_path,
+    )
+}
+
+/// The source fields of a local relationship are all fields of the same object, so the object of
+/// any of them will do
+fn get_source_object(
+    source_type_mappings: &BTreeMap<Qualified<CustomTypeName>, metadata_resolve::TypeMapping>,
+    source_type: &Qualified<CustomTypeName>,
+    relationship_name: &RelationshipName,
+    source_field_path: Option<&metadata_resolve::RelationshipFieldPath>,
+) -> Result<Vec<String>, error::Error> {
+    let Some(source_field_path) = source_field_path else {
+        return Ok(vec![]);
+    };
+    let source_field = relationship::get_source_field(
+        source_type_mappings,
+        source_type,
+        relationship_name,
+        source_field_path,
+    )
+    .map_err(|e| error::InternalError::InternalGeneric {
+        description: e.to_string(),
+    })?;
+    Ok(source_field.ndc_object_path())
+}
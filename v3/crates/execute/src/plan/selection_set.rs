@@ -3,6 +3,7 @@ use super::error;
 use super::model_selection;
 use super::relationships;
 use super::ProcessResponseAs;
+use crate::ir::relationship::SourceField;
 use crate::ir::selection_set::{FieldSelection, NestedSelection, ResultSelectionSet};
 use crate::remote_joins::types::SourceFieldAlias;
 use crate::remote_joins::types::{
@@ -10,7 +11,6 @@ use crate::remote_joins::types::{
 };
 use crate::remote_joins::types::{Location, RemoteJoin};
 use indexmap::IndexMap;
-use metadata_resolve::FieldMapping;
 use open_dds::data_connector::DataConnectorColumnName;
 use std::collections::{BTreeMap, HashMap};
 
@@ -93,7 +93,7 @@ pub(crate) fn process_selection_set_ir<'s, 'ir>(
             FieldSelection::ModelRelationshipLocal {
                 query,
                 name,
-                relationship_info: _,
+                relationship_info,
             } => {
                 let (relationship_query, jl) = model_selection::ndc_query(query, join_id_counter)?;
                 let ndc_field = ndc_models::Field::Relationship {
@@ -101,21 +101,27 @@ pub(crate) fn process_selection_set_ir<'s, 'ir>(
                     relationship: name.to_string(),
                     arguments: BTreeMap::new(),
                 };
-                if !jl.locations.is_empty() {
-                    join_locations.locations.insert(
-                        alias.clone(),
-                        Location {
-                            join_node: JoinNode::Local(LocationKind::LocalRelationship),
-                            rest: jl,
-                        },
-                    );
+                let location = (!jl.locations.is_empty()).then_some(Location {
+                    join_node: JoinNode::Local(LocationKind::LocalRelationship),
+                    rest: jl,
+                });
+                let (field_alias, ndc_field, location) = select_in_source_object(
+                    alias,
+                    &relationships::model_relationship_source_object(relationship_info)?,
+                    ndc_field,
+                    location,
+                );
+                if let Some(location) = location {
+                    join_locations
+                        .locations
+                        .insert(field_alias.clone(), location);
                 }
-                ndc_fields.insert(alias.to_string(), ndc_field);
+                ndc_fields.insert(field_alias, ndc_field);
             }
             FieldSelection::CommandRelationshipLocal {
                 ir,
                 name,
-                relationship_info: _,
+                relationship_info,
             } => {
                 let (relationship_query, jl) =
                     commands::ndc_query(&ir.command_info, join_id_counter)?;
@@ -140,37 +146,56 @@ pub(crate) fn process_selection_set_ir<'s, 'ir>(
                     arguments: relationship_arguments,
                 };
 
-                if !jl.locations.is_empty() {
-                    join_locations.locations.insert(
-                        alias.clone(),
-                        Location {
-                            join_node: JoinNode::Local(LocationKind::LocalRelationship),
-                            rest: jl,
-                        },
-                    );
+                let location = (!jl.locations.is_empty()).then_some(Location {
+                    join_node: JoinNode::Local(LocationKind::LocalRelationship),
+                    rest: jl,
+                });
+                let (field_alias, ndc_field, location) = select_in_source_object(
+                    alias,
+                    &relationships::command_relationship_source_object(relationship_info)?,
+                    ndc_field,
+                    location,
+                );
+                if let Some(location) = location {
+                    join_locations
+                        .locations
+                        .insert(field_alias.clone(), location);
                 }
-                ndc_fields.insert(alias.to_string(), ndc_field);
+                ndc_fields.insert(field_alias, ndc_field);
             }
             FieldSelection::ModelRelationshipRemote {
                 ir,
                 relationship_info,
             } => {
                 let mut join_mapping = HashMap::new();
-                for ((src_field_alias, src_field), target_field) in &relationship_info.join_mapping
-                {
+                for (src_field, target_field) in &relationship_info.join_mapping {
                     let ndc_field_alias = process_remote_relationship_field_mapping(
                         model_selection,
                         src_field,
                         &mut ndc_fields,
                     );
                     join_mapping.insert(
-                        src_field_alias.clone(),
+                        src_field.field_names(),
                         (
                             ndc_field_alias,
                             TargetField::ModelField(target_field.clone()),
                         ),
                     );
                 }
+                for (src_field, argument_name) in &relationship_info.argument_join_mapping {
+                    let ndc_field_alias = process_remote_relationship_field_mapping(
+                        model_selection,
+                        src_field,
+                        &mut ndc_fields,
+                    );
+                    join_mapping.insert(
+                        src_field.field_names(),
+                        (
+                            ndc_field_alias,
+                            TargetField::ModelArgument(argument_name.clone()),
+                        ),
+                    );
+                }
                 // Construct the `JoinLocations` tree
                 let (ndc_ir, sub_join_locations) = model_selection::ndc_ir(ir, join_id_counter)?;
                 let rj_info = RemoteJoin {
@@ -179,6 +204,7 @@ pub(crate) fn process_selection_set_ir<'s, 'ir>(
                     join_mapping,
                     process_response_as: ProcessResponseAs::Array { is_nullable: true },
                     remote_join_type: RemoteJoinType::ToModel,
+                    strategy: relationship_info.strategy,
                 };
                 join_locations.locations.insert(
                     alias.clone(),
@@ -194,15 +220,14 @@ pub(crate) fn process_selection_set_ir<'s, 'ir>(
             } => {
                 let mut join_mapping = HashMap::new();
 
-                for ((src_field_alias, src_field), target_field) in &relationship_info.join_mapping
-                {
+                for (src_field, target_field) in &relationship_info.join_mapping {
                     let ndc_field_alias = process_remote_relationship_field_mapping(
                         model_selection,
                         src_field,
                         &mut ndc_fields,
                     );
                     join_mapping.insert(
-                        src_field_alias.clone(),
+                        src_field.field_names(),
                         (
                             ndc_field_alias,
                             TargetField::CommandField(target_field.clone()),
@@ -220,6 +245,7 @@ pub(crate) fn process_selection_set_ir<'s, 'ir>(
                         type_container: &ir.command_info.type_container,
                     },
                     remote_join_type: RemoteJoinType::ToCommand,
+                    strategy: relationship_info.strategy,
                 };
                 join_locations.locations.insert(
                     alias.clone(),
@@ -234,6 +260,48 @@ pub(crate) fn process_selection_set_ir<'s, 'ir>(
     Ok((ndc_fields, join_locations))
 }
 
+/// The alias under which a local relationship, joining on the fields of an object nested in the
+/// source type, is selected. The relationship is selected from within the object, and each of
+/// the objects leading to it is selected under this alias as well.
+pub(crate) fn nested_relationship_alias(alias: &str) -> String {
+    format!("__hasura_nested_relationship__{alias}")
+}
+
+/// Select a local relationship field from within the object whose fields the relationship joins
+/// on, given the NDC names of the fields leading to the object. Returns the alias of the
+/// selection, along with the location of the remote joins in the relationship.
+fn select_in_source_object<T>(
+    alias: &str,
+    source_object: &[String],
+    ndc_field: ndc_models::Field,
+    location: Option<Location<T>>,
+) -> (String, ndc_models::Field, Option<Location<T>>) {
+    if source_object.is_empty() {
+        return (alias.to_string(), ndc_field, location);
+    }
+    let nested_alias = nested_relationship_alias(alias);
+    let (ndc_field, location) = source_object.iter().rev().fold(
+        (ndc_field, location),
+        |(ndc_field, location), field_name| {
+            let ndc_field = ndc_models::Field::Column {
+                column: field_name.clone(),
+                fields: Some(ndc_models::NestedField::Object(ndc_models::NestedObject {
+                    fields: IndexMap::from([(nested_alias.clone(), ndc_field)]),
+                })),
+                arguments: BTreeMap::new(),
+            };
+            let location = location.map(|location| Location {
+                join_node: JoinNode::Local(LocationKind::NestedData),
+                rest: JoinLocations {
+                    locations: IndexMap::from([(nested_alias.clone(), location)]),
+                },
+            });
+            (ndc_field, location)
+        },
+    );
+    (nested_alias, ndc_field, location)
+}
+
 /// Processes a remote relationship field mapping, and returns the alias used in
 /// the NDC IR for that field
 ///
@@ -241,30 +309,76 @@ pub(crate) fn process_selection_set_ir<'s, 'ir>(
 /// (with an internal alias), and return the alias
 /// - if the selection set already contains the field, do not insert the field
 /// in NDC IR, and return the existing alias
-fn process_remote_relationship_field_mapping(
+pub(crate) fn process_remote_relationship_field_mapping(
     selection: &ResultSelectionSet<'_>,
-    field: &FieldMapping,
+    source_field: &SourceField,
     ndc_fields: &mut IndexMap<String, ndc_models::Field>,
 ) -> SourceFieldAlias {
-    match selection.contains(field) {
+    let field = &source_field.field_mapping;
+    let nested_field_path = source_field.ndc_field_path();
+    // a nested field is picked from a phantom field selecting just the objects leading to it
+    let field_alias = if nested_field_path.is_empty() {
+        selection.contains(field)
+    } else {
+        None
+    };
+    match field_alias {
         None => {
-            let internal_alias = make_hasura_phantom_field(&field.column);
+            let internal_alias = make_hasura_phantom_field(&field.column, &nested_field_path);
             ndc_fields.insert(
                 internal_alias.clone(),
-                ndc_models::Field::Column {
-                    column: field.column.0.clone(),
-                    fields: None,
-                    arguments: BTreeMap::new(),
-                },
+                ndc_nested_column_field(&field.column, &nested_field_path),
             );
-            SourceFieldAlias(internal_alias)
+            SourceFieldAlias {
+                alias: internal_alias,
+                nested_field_path,
+            }
         }
-        Some(field_alias) => SourceFieldAlias(field_alias),
+        Some(field_alias) => SourceFieldAlias {
+            alias: field_alias,
+            nested_field_path,
+        },
     }
 }
 
-fn make_hasura_phantom_field(field_name: &DataConnectorColumnName) -> String {
-    format!("__hasura_phantom_field__{}", field_name.0)
+fn make_hasura_phantom_field(
+    field_name: &DataConnectorColumnName,
+    nested_field_path: &[String],
+) -> String {
+    let mut alias = format!("__hasura_phantom_field__{}", field_name.0);
+    for nested_field_name in nested_field_path {
+        alias.push_str("__");
+        alias.push_str(nested_field_name);
+    }
+    alias
+}
+
+/// The field selecting a column or, given the names of the fields of the objects nested in it,
+/// only the field at the end of them. Each nested field is aliased by its name.
+pub(crate) fn ndc_nested_column_field(
+    column: &DataConnectorColumnName,
+    nested_field_path: &[String],
+) -> ndc_models::Field {
+    let nested_field = nested_field_path
+        .iter()
+        .rev()
+        .fold(None, |nested_field, field_name| {
+            Some(ndc_models::NestedField::Object(ndc_models::NestedObject {
+                fields: IndexMap::from([(
+                    field_name.clone(),
+                    ndc_models::Field::Column {
+                        column: field_name.clone(),
+                        fields: nested_field,
+                        arguments: BTreeMap::new(),
+                    },
+                )]),
+            }))
+        });
+    ndc_models::Field::Column {
+        column: column.0.clone(),
+        fields: nested_field,
+        arguments: BTreeMap::new(),
+    }
 }
 
 pub(crate) fn collect_relationships_from_nested_selection(
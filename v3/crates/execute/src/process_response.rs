@@ -13,7 +13,9 @@ use open_dds::types::FieldName;
 
 use super::global_id::{global_id_col_format, GLOBAL_ID_VERSION};
 use super::ndc::FUNCTION_IR_VALUE_COLUMN_NAME;
+use super::plan::selection_set::nested_relationship_alias;
 use super::plan::ProcessResponseAs;
+use super::remote_joins::RemoteJoinFailure;
 use crate::error::{self, FieldInternalError};
 use metadata_resolve::Qualified;
 use schema::{Annotation, GlobalID, OutputAnnotation, GDS};
@@ -62,11 +64,80 @@ where
     Ok(json::Value::String(global_id_value))
 }
 
+/// The relationship fields of failed remote joins, as a tree of the aliases of the fields leading
+/// to them. Only these relationship fields may be `null` in a response.
+#[derive(Debug, Default)]
+pub(crate) struct FailedRemoteJoins {
+    failed: bool,
+    fields: BTreeMap<String, FailedRemoteJoins>,
+}
+
+static NO_FAILED_REMOTE_JOINS: FailedRemoteJoins = FailedRemoteJoins {
+    failed: false,
+    fields: BTreeMap::new(),
+};
+
+impl FailedRemoteJoins {
+    pub(crate) fn new(remote_join_failures: &[RemoteJoinFailure]) -> Self {
+        let mut failed_remote_joins = Self::default();
+        for failure in remote_join_failures {
+            let mut node = &mut failed_remote_joins;
+            for alias in &failure.field_path {
+                node = node.fields.entry(alias.clone()).or_default();
+            }
+            node.failed = true;
+        }
+        failed_remote_joins
+    }
+
+    /// The failed remote joins beneath a field of a row
+    fn field(&self, alias: &str) -> &Self {
+        self.fields.get(alias).unwrap_or(&NO_FAILED_REMOTE_JOINS)
+    }
+}
+
+/// Take the value of a relationship field from a row. A local relationship joining on the fields
+/// of an object nested in the source type is selected from within the object, under the alias
+/// made by `nested_relationship_alias`. There are no related rows when the object is `null`.
+fn remove_relationship_value<T>(
+    row: &mut T,
+    field_alias: &Alias,
+) -> Result<json::Value, error::FieldError>
+where
+    T: KeyValueResponse,
+{
+    if let Some(value) = row.remove(field_alias.0.as_str()) {
+        return Ok(value);
+    }
+    let nested_alias = nested_relationship_alias(field_alias.0.as_str());
+    let mut value = row.remove(nested_alias.as_str()).ok_or_else(|| {
+        error::NDCUnexpectedError::BadNDCResponse {
+            summary: format!("missing field: {}", field_alias.clone()),
+        }
+    })?;
+    loop {
+        let nested_value = match &mut value {
+            json::Value::Object(object) => object.remove(nested_alias.as_str()),
+            _ => None,
+        };
+        match nested_value {
+            Some(nested_value) => value = nested_value,
+            None => break,
+        }
+    }
+    if value.is_null() {
+        Ok(json::json!({ "rows": [] }))
+    } else {
+        Ok(value)
+    }
+}
+
 /// Processes a single NDC row and adds `__typename`
 /// wh needed.
 fn process_single_query_response_row<T>(
     mut row: T,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<IndexMap<ast::Alias, json::Value>, error::FieldError>
 where
     T: KeyValueResponse,
@@ -76,6 +147,7 @@ where
             if field_call.name.as_str() == "__typename" {
                 Ok(json::Value::String(type_name.to_string()))
             } else {
+                let failed_field = failed_remote_joins.field(field.alias.0.as_str());
                 match field_call.info.generic {
                     annotation @ Annotation::Output(field_annotation) => {
                         match field_annotation {
@@ -113,17 +185,26 @@ where
                                     })?;
 
                                 if field.type_container.is_list() {
-                                    process_field_selection_as_list(value, &field.selection_set)
+                                    process_field_selection_as_list(
+                                        value,
+                                        &field.selection_set,
+                                        failed_field,
+                                    )
                                 } else {
-                                    process_field_selection_as_object(value, &field.selection_set)
+                                    process_field_selection_as_object(
+                                        value,
+                                        &field.selection_set,
+                                        failed_field,
+                                    )
                                 }
                             }
                             OutputAnnotation::RelationshipToModel { .. } => {
-                                let field_json_value_result = row
-                                    .remove(field.alias.0.as_str())
-                                    .ok_or_else(|| error::NDCUnexpectedError::BadNDCResponse {
-                                        summary: format!("missing field: {}", field.alias.clone()),
-                                    })?;
+                                let field_json_value_result =
+                                    remove_relationship_value(&mut row, &field.alias)?;
+                                // A remote relationship is `null` when its remote join failed
+                                if field_json_value_result.is_null() && failed_field.failed {
+                                    return Ok(json::Value::Null);
+                                }
                                 let relationship_json_value_result =
                                     serde_json::from_value(field_json_value_result).ok();
                                 match relationship_json_value_result {
@@ -137,12 +218,14 @@ where
                                             process_selection_set_as_list(
                                                 rows_set,
                                                 &field.selection_set,
+                                                failed_field,
                                             )
                                             .and_then(|v| Ok(json::to_value(v)?))
                                         } else {
                                             process_selection_set_as_object(
                                                 rows_set,
                                                 &field.selection_set,
+                                                failed_field,
                                             )
                                             .and_then(|v| Ok(json::to_value(v)?))
                                         }
@@ -152,11 +235,12 @@ where
                             OutputAnnotation::RelationshipToCommand(
                                 command_relationship_annotation,
                             ) => {
-                                let field_json_value_result = row
-                                    .remove(field.alias.0.as_str())
-                                    .ok_or_else(|| error::NDCUnexpectedError::BadNDCResponse {
-                                        summary: format!("missing field: {}", field.alias.clone()),
-                                    })?;
+                                let field_json_value_result =
+                                    remove_relationship_value(&mut row, &field.alias)?;
+                                // A remote relationship is `null` when its remote join failed
+                                if field_json_value_result.is_null() && failed_field.failed {
+                                    return Ok(json::Value::Null);
+                                }
                                 let relationship_json_value_result: Option<ndc_models::RowSet> =
                                     serde_json::from_value(field_json_value_result).ok();
 
@@ -173,6 +257,7 @@ where
                                             rows_set.rows,
                                             &field.selection_set,
                                             &field.type_container,
+                                            failed_field,
                                         )
                                         .map(|v| match v {
                                             None => json::Value::Null,
@@ -198,12 +283,15 @@ where
 pub fn process_selection_set_as_list(
     row_set: ndc_models::RowSet,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<Option<Vec<IndexMap<ast::Alias, json::Value>>>, error::FieldError> {
     let processed_response = row_set
         .rows
         .map(|rows| {
             rows.into_iter()
-                .map(|row| process_single_query_response_row(row, selection_set))
+                .map(|row| {
+                    process_single_query_response_row(row, selection_set, failed_remote_joins)
+                })
                 .collect()
         })
         .transpose()?;
@@ -213,11 +301,12 @@ pub fn process_selection_set_as_list(
 pub fn process_selection_set_as_object(
     row_set: ndc_models::RowSet,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<Option<IndexMap<ast::Alias, json::Value>>, error::FieldError> {
     let processed_response = row_set
         .rows
         .and_then(|rows| rows.into_iter().next())
-        .map(|row| process_single_query_response_row(row, selection_set))
+        .map(|row| process_single_query_response_row(row, selection_set, failed_remote_joins))
         .transpose()?;
     Ok(processed_response)
 }
@@ -225,6 +314,7 @@ pub fn process_selection_set_as_object(
 pub fn process_field_selection_as_list(
     value: json::Value,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<json::Value, error::FieldError> {
     if selection_set.fields.is_empty() || value.is_null() {
         // If selection set is empty we return the whole value without further processing.
@@ -234,7 +324,7 @@ pub fn process_field_selection_as_list(
         let rows: Vec<IndexMap<String, ndc_models::RowFieldValue>> = json::from_value(value)?;
         let processed_rows: Vec<IndexMap<Alias, json::Value>> = rows
             .into_iter()
-            .map(|row| process_single_query_response_row(row, selection_set))
+            .map(|row| process_single_query_response_row(row, selection_set, failed_remote_joins))
             .collect::<Result<Vec<_>, _>>()?;
         Ok(json::to_value(processed_rows)?)
     }
@@ -243,6 +333,7 @@ pub fn process_field_selection_as_list(
 pub fn process_field_selection_as_object(
     value: json::Value,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<json::Value, error::FieldError> {
     if selection_set.fields.is_empty() || value.is_null() {
         // If selection set is empty we return the whole value without further processing.
@@ -250,7 +341,8 @@ pub fn process_field_selection_as_object(
         Ok(value)
     } else {
         let row: IndexMap<String, ndc_models::RowFieldValue> = json::from_value(value)?;
-        let processed_row = process_single_query_response_row(row, selection_set)?;
+        let processed_row =
+            process_single_query_response_row(row, selection_set, failed_remote_joins)?;
         Ok(json::to_value(processed_row)?)
     }
 }
@@ -260,6 +352,7 @@ pub fn process_command_rows(
     rows: Option<Vec<IndexMap<String, ndc_models::RowFieldValue, RandomState>>>,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
     type_container: &TypeContainer<TypeName>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<Option<json::Value>, error::FieldError> {
     match rows {
         None => Err(error::NDCUnexpectedError::BadNDCResponse {
@@ -279,7 +372,14 @@ pub fn process_command_rows(
             let processed_response = row_vector
                 .into_iter()
                 .next()
-                .map(|row| process_command_response_row(row, selection_set, type_container))
+                .map(|row| {
+                    process_command_response_row(
+                        row,
+                        selection_set,
+                        type_container,
+                        failed_remote_joins,
+                    )
+                })
                 .transpose()?;
             Ok(processed_response)
         }
@@ -290,6 +390,7 @@ fn process_command_response_row(
     mut row: IndexMap<String, ndc_models::RowFieldValue>,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
     type_container: &TypeContainer<TypeName>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<json::Value, error::FieldError> {
     let field_value_result = row
         .swap_remove(FUNCTION_IR_VALUE_COLUMN_NAME)
@@ -297,17 +398,23 @@ fn process_command_response_row(
             summary: format!("missing field: {FUNCTION_IR_VALUE_COLUMN_NAME}"),
         })?;
 
-    process_command_field_value(field_value_result.0, selection_set, type_container)
+    process_command_field_value(
+        field_value_result.0,
+        selection_set,
+        type_container,
+        failed_remote_joins,
+    )
 }
 
 pub fn process_command_mutation_response(
     mutation_result: ndc_models::MutationOperationResults,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
     type_container: &TypeContainer<TypeName>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<json::Value, error::FieldError> {
     match mutation_result {
         ndc_models::MutationOperationResults::Procedure { result } => {
-            process_command_field_value(result, selection_set, type_container)
+            process_command_field_value(result, selection_set, type_container, failed_remote_joins)
         }
     }
 }
@@ -316,6 +423,7 @@ fn process_command_field_value(
     field_value_result: serde_json::Value,
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
     type_container: &TypeContainer<TypeName>,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<json::Value, error::FieldError> {
     // When no selection set for commands, return back the value from the
     // connector without any processing.
@@ -344,7 +452,11 @@ fn process_command_field_value(
                 } else {
                     let index_map: IndexMap<String, json::Value> =
                         json::from_value(json::Value::Object(result_map))?;
-                    let value = process_single_query_response_row(index_map, selection_set)?;
+                    let value = process_single_query_response_row(
+                        index_map,
+                        selection_set,
+                        failed_remote_joins,
+                    )?;
                     Ok(json::to_value(value)?)
                 }
             }
@@ -355,7 +467,7 @@ fn process_command_field_value(
 
                     let r: Vec<IndexMap<Alias, json::Value>> = array_values
                         .into_iter()
-                        .map(|value| process_single_query_response_row(value, selection_set))
+                        .map(|value| process_single_query_response_row(value, selection_set, failed_remote_joins))
                         .collect::<Result<Vec<IndexMap<ast::Alias, json::Value>>, error::FieldError>>(
                         )?;
 
@@ -436,6 +548,7 @@ pub fn process_response(
     selection_set: &normalized_ast::SelectionSet<'_, GDS>,
     rows_sets: Vec<ndc_models::RowSet>,
     process_response_as: &ProcessResponseAs,
+    failed_remote_joins: &FailedRemoteJoins,
 ) -> Result<json::Value, error::FieldError> {
     let tracer = tracing_util::global_tracer();
     // Post process the response to add the `__typename` fields
@@ -447,11 +560,16 @@ pub fn process_response(
             let row_set = get_single_rowset(rows_sets)?;
             match process_response_as {
                 ProcessResponseAs::Array { .. } => {
-                    let result = process_selection_set_as_list(row_set, selection_set)?;
+                    let result =
+                        process_selection_set_as_list(row_set, selection_set, failed_remote_joins)?;
                     json::to_value(result).map_err(error::FieldError::from)
                 }
                 ProcessResponseAs::Object { .. } => {
-                    let result = process_selection_set_as_object(row_set, selection_set)?;
+                    let result = process_selection_set_as_object(
+                        row_set,
+                        selection_set,
+                        failed_remote_joins,
+                    )?;
                     json::to_value(result).map_err(error::FieldError::from)
                 }
                 ProcessResponseAs::CommandResponse {
@@ -463,6 +581,7 @@ pub fn process_response(
                         row_set.rows,
                         selection_set,
                         type_container,
+                        failed_remote_joins,
                     )?;
                     json::to_value(result).map_err(error::FieldError::from)
                 }
@@ -470,6 +589,11 @@ pub fn process_response(
                     let result = process_aggregate_requested_fields(row_set, requested_fields)?;
                     Ok(json::Value::Object(result))
                 }
+                ProcessResponseAs::ModelMutationResponse { .. } => {
+                    Err(error::FieldInternalError::InternalGeneric {
+                        description: "model mutations are not executed as queries".to_string(),
+                    })?
+                }
             }
         },
     )
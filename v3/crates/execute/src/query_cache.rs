@@ -0,0 +1,95 @@
+//! A bounded cache of parsed GraphQL queries.
+//!
+//! Clients tend to send the same few operations over and over, so the engine keeps the parsed
+//! documents of the most recently used queries and doesn't parse them again. Parsing depends on
+//! nothing but the query text, so entries are keyed by it alone and shared by every operation
+//! name and role. The cached documents are shared with the requests that use them rather than
+//! copied.
+//!
+//! The normalized operation, IR and plan are not cached. They borrow the schema, which is
+//! replaced when the metadata is reloaded, and the variables and session variables of the
+//! request are substituted into them while they are built.
+
+use std::sync::{Arc, Mutex};
+
+use lang_graphql as gql;
+use lru_cache::LruCache;
+
+/// The default number of parsed queries a `QueryCache` holds.
+pub const DEFAULT_QUERY_CACHE_SIZE: usize = 1_000;
+
+/// A least-recently-used cache of parsed queries, keyed by their text.
+pub struct QueryCache {
+    documents: Mutex<LruCache<Arc<str>, Arc<gql::ast::executable::ExecutableDocument>>>,
+}
+
+impl QueryCache {
+    /// A cache holding up to `capacity` parsed queries. A capacity of 0 disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            documents: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Parses a query, or reuses its parsed document if the same query was recently requested.
+    /// Queries which fail to parse are not cached.
+    pub(crate) fn parse_query(
+        &self,
+        query: &str,
+    ) -> Result<
+        Arc<gql::ast::executable::ExecutableDocument>,
+        gql::ast::spanning::Positioned<gql::parser::Error>,
+    > {
+        if sync_ext::lock(&self.documents).capacity() == 0 {
+            return crate::parse_query(query).map(Arc::new);
+        }
+        if let Some(document) = sync_ext::lock(&self.documents).get(query) {
+            metrics_util::global_metrics().inc_query_cache_lookup(true);
+            return Ok(document);
+        }
+        metrics_util::global_metrics().inc_query_cache_lookup(false);
+
+        // parse outside of the lock, so that other requests aren't held up
+        let document = Arc::new(crate::parse_query(query)?);
+        sync_ext::lock(&self.documents).insert(query.into(), document.clone());
+        Ok(document)
+    }
+
+    /// The number of cached queries.
+    pub fn len(&self) -> usize {
+        sync_ext::lock(&self.documents).len()
+    }
+
+    /// Whether no queries are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_document_is_shared() {
+        let cache = QueryCache::new(2);
+        let first = cache.parse_query("{ a }").unwrap();
+        let second = cache.parse_query("{ a }").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_query_is_not_cached() {
+        let cache = QueryCache::new(2);
+        assert!(cache.parse_query("{ a ").is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_caching() {
+        let cache = QueryCache::new(0);
+        cache.parse_query("{ a }").unwrap();
+        assert!(cache.is_empty());
+    }
+}
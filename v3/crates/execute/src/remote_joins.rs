@@ -64,7 +64,10 @@
 //! 3. Get the NDC query from the remote join node, and attach the values in the
 //! above step as variables in the NDC query. This NDC query already has a
 //! "where" filter clause with a variable on the join mapping field. Make the
-//! NDC query, and call the response as RHS response.
+//! NDC query, and call the response as RHS response. If the target data
+//! connector does not support variables, the values are substituted for the
+//! variables instead, and a separate NDC query is made for every set of values
+//! (see the [naive] module).
 //!
 //! 4. If there is a sub-tree from this remote join node, recursively perform
 //! this algorithm.
@@ -78,23 +81,66 @@
 
 use async_recursion::async_recursion;
 
+use indexmap::IndexMap;
 use serde_json as json;
 use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroUsize;
 use tracing_util::SpanVisibility;
 
-use super::ndc::execute_ndc_query;
+use super::ndc::{execute_ndc_query, FUNCTION_IR_VALUE_COLUMN_NAME};
 use super::plan::ProcessResponseAs;
 use super::{error, HttpContext, ProjectId};
 
 use self::collect::ExecutableJoinNode;
-use types::{Argument, JoinId, JoinLocations, RemoteJoin};
+use types::{Argument, JoinId, JoinLocations, RemoteJoin, RemoteJoinStrategy};
 
 pub(crate) mod collect;
 pub(crate) mod join;
+pub(crate) mod naive;
+pub(crate) mod sort;
 pub(crate) mod types;
 
+/// Limits on the NDC requests made to execute a single remote join, on the rows sorted by the
+/// engine when ordering across remote relationships, and on the rows matched by predicates across
+/// remote relationships.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteJoinOptions {
+    /// The maximum number of `foreach` variable sets sent in one NDC request.
+    pub chunk_size: NonZeroUsize,
+    /// The maximum number of NDC requests in flight at once for a remote join.
+    pub max_concurrent_chunks: NonZeroUsize,
+    /// The maximum number of rows the engine sorts in memory, per row set.
+    pub max_sorted_rows: NonZeroUsize,
+    /// The maximum number of target model rows matched by a predicate across a remote
+    /// relationship.
+    pub max_remote_predicate_rows: NonZeroUsize,
+}
+
+impl Default for RemoteJoinOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: NonZeroUsize::new(1000).unwrap(),
+            max_concurrent_chunks: NonZeroUsize::new(4).unwrap(),
+            max_sorted_rows: NonZeroUsize::new(10000).unwrap(),
+            max_remote_predicate_rows: NonZeroUsize::new(10000).unwrap(),
+        }
+    }
+}
+
+/// A remote join which failed. The relationship field it populates has been set
+/// to `null` in every row of the LHS response.
+#[derive(Debug)]
+pub(crate) struct RemoteJoinFailure {
+    /// The aliases of the fields from a LHS row to the relationship field.
+    pub(crate) field_path: Vec<String>,
+    pub(crate) error: error::FieldError,
+}
+
 /// Execute remote joins. As an entry-point it assumes the response is available
 /// for the top-level query, and executes further remote joins recursively.
+///
+/// Failures of remote joins to nullable relationship fields are returned rather
+/// than failing the whole response.
 #[async_recursion]
 pub(crate) async fn execute_join_locations<'ir>(
     http_context: &HttpContext,
@@ -103,7 +149,7 @@ pub(crate) async fn execute_join_locations<'ir>(
     lhs_response_type: &ProcessResponseAs,
     join_locations: &JoinLocations<(RemoteJoin<'async_recursion, 'ir>, JoinId)>,
     project_id: Option<&ProjectId>,
-) -> Result<(), error::FieldError>
+) -> Result<Vec<RemoteJoinFailure>, error::FieldError>
 where
     'ir: 'async_recursion,
 {
@@ -125,72 +171,75 @@ where
         },
     )?;
 
-    for executable_join_node in next_join_nodes {
+    // if we do not get any join arguments back, we have nothing on the RHS
+    // to execute. Skip execution.
+    let next_join_nodes = next_join_nodes
+        .into_iter()
+        .filter(|executable_join_node| !executable_join_node.arguments.is_empty());
+
+    // the RHS queries of sibling join nodes are independent, so they are
+    // executed concurrently; their responses are then joined into the LHS
+    // response one at a time, in order
+    let rhs_results =
+        futures_ext::execute_concurrently(next_join_nodes, |executable_join_node| async move {
+            let rhs_result = execute_join_node(
+                http_context,
+                execution_span_attribute,
+                &executable_join_node,
+                project_id,
+            )
+            .await;
+            (executable_join_node, rhs_result)
+        })
+        .await;
+
+    let mut failures = Vec::new();
+    for (executable_join_node, rhs_result) in rhs_results {
         let ExecutableJoinNode {
-            arguments,
             location_path,
-            mut join_node,
-            sub_tree,
+            join_node,
             remote_alias,
+            ..
         } = executable_join_node;
 
-        // if we do not get any join arguments back, we have nothing on the RHS
-        // to execute. Skip execution.
-        if arguments.is_empty() {
-            continue;
-        }
-        // patch the target/RHS IR with variable values
-        let foreach_variables: Vec<BTreeMap<String, json::Value>> = arguments
+        // the path of aliases from a LHS row to the relationship field
+        let mut field_path: Vec<String> = location_path
             .iter()
-            .map(|bmap| {
-                bmap.iter()
-                    .map(|(k, v)| (k.0.clone(), v.0.clone()))
-                    .collect()
-            })
+            .map(|location| location.alias.clone())
             .collect();
-        join_node.target_ndc_ir.variables = Some(foreach_variables);
-
-        // execute the remote query
-        let mut target_response = tracer
-            .in_span_async(
-                "execute_remote_join_query",
-                "Execute remote query for join",
-                SpanVisibility::Internal,
-                || {
-                    Box::pin(execute_ndc_query(
-                        http_context,
-                        &join_node.target_ndc_ir,
-                        join_node.target_data_connector,
-                        execution_span_attribute,
-                        remote_alias.clone(),
-                        project_id,
-                    ))
-                },
-            )
-            .await?;
+        field_path.push(remote_alias.clone());
+
+        let rhs_response: HashMap<Argument, ndc_models::RowSet> = match rhs_result {
+            Ok((rhs_response, sub_tree_failures)) => {
+                // failures down the tree are relative to the RHS rows, which
+                // are inserted at this relationship field
+                failures.extend(sub_tree_failures.into_iter().map(|failure| {
+                    RemoteJoinFailure {
+                        field_path: field_path
+                            .iter()
+                            .cloned()
+                            .chain(failure.field_path)
+                            .collect(),
+                        error: failure.error,
+                    }
+                }));
+                rhs_response
+            }
+            // a nullable relationship field is set to `null` in every LHS row
+            // when its remote join fails, so that the rest of the LHS response
+            // can still be returned
+            Err(error) if join_node.process_response_as.is_nullable() => {
+                failures.push(RemoteJoinFailure { field_path, error });
+                HashMap::new()
+            }
+            Err(error) => return Err(error),
+        };
 
-        // if the sub-tree is not empty, recursively process the sub-tree; which
-        // will modify the `target_response` with all joins down the tree
-        if !sub_tree.locations.is_empty() {
-            execute_join_locations(
-                http_context,
-                execution_span_attribute,
-                &mut target_response,
-                &join_node.process_response_as,
-                &sub_tree,
-                project_id,
-            )
-            .await?;
-        }
         tracer.in_span(
             "response_join",
             "Join responses for remote query",
             SpanVisibility::Internal,
             || {
-                // from `Vec<RowSet>` create `HashMap<Argument, RowSet>`
-                let rhs_response: HashMap<Argument, ndc_models::RowSet> =
-                    arguments.into_iter().zip(target_response).collect();
-
                 join::join_responses(
                     &location_path,
                     &join_node,
@@ -201,5 +250,163 @@ where
             },
         )?;
     }
-    Ok(())
+    Ok(failures)
+}
+
+/// Execute the RHS query of a join node, and then the remote joins beneath it.
+/// The join arguments are split into chunks of `foreach` variables, each of
+/// which is sent as a separate NDC request, with a bounded number of them in
+/// flight at once. Data connectors that don't support `foreach` get a separate
+/// request for every argument instead. Returns the RHS row set for every join
+/// argument, along with the failures of nested remote joins.
+async fn execute_join_node<'s, 'ir>(
+    http_context: &HttpContext,
+    execution_span_attribute: &'static str,
+    executable_join_node: &ExecutableJoinNode<'s, 'ir>,
+    project_id: Option<&ProjectId>,
+) -> Result<
+    (
+        HashMap<Argument, ndc_models::RowSet>,
+        Vec<RemoteJoinFailure>,
+    ),
+    error::FieldError,
+> {
+    let ExecutableJoinNode {
+        arguments,
+        join_node,
+        sub_tree,
+        remote_alias,
+        ..
+    } = executable_join_node;
+
+    metrics_util::global_metrics().observe_remote_join_fanout(
+        &join_node.target_data_connector.name.to_string(),
+        arguments.len(),
+    );
+
+    // without `foreach`, every argument is executed as a query of its own
+    let chunk_size = match join_node.strategy {
+        RemoteJoinStrategy::ForEach => http_context.remote_join_options.chunk_size.get(),
+        RemoteJoinStrategy::Naive => 1,
+    };
+    let arguments: Vec<&Argument> = arguments.iter().collect();
+    let chunk_responses = futures_ext::execute_concurrently_bounded(
+        arguments.chunks(chunk_size),
+        http_context.remote_join_options.max_concurrent_chunks,
+        |chunk| async move {
+            // patch the target/RHS IR with variable values
+            let mut target_ndc_ir = join_node.target_ndc_ir.clone();
+            let variables: Vec<BTreeMap<String, json::Value>> = chunk
+                .iter()
+                .map(|argument| {
+                    argument
+                        .iter()
+                        .map(|(k, v)| (k.0.clone(), v.0.clone()))
+                        .collect()
+                })
+                .collect();
+            match join_node.strategy {
+                RemoteJoinStrategy::ForEach => target_ndc_ir.variables = Some(variables),
+                RemoteJoinStrategy::Naive => {
+                    for variables in &variables {
+                        naive::substitute_variables(&mut target_ndc_ir, variables)?;
+                    }
+                }
+            }
+            // execute the remote query
+            tracing_util::global_tracer()
+                .in_span_async(
+                    "execute_remote_join_query",
+                    "Execute remote query for join",
+                    SpanVisibility::Internal,
+                    || {
+                        Box::pin(execute_ndc_query(
+                            http_context,
+                            &target_ndc_ir,
+                            join_node.target_data_connector,
+                            execution_span_attribute,
+                            remote_alias.clone(),
+                            project_id,
+                        ))
+                    },
+                )
+                .await
+        },
+    )
+    .await;
+
+    let mut target_response = Vec::with_capacity(arguments.len());
+    for chunk_response in chunk_responses {
+        target_response.extend(chunk_response?);
+    }
+
+    // if the sub-tree is not empty, recursively process the sub-tree; which
+    // will modify the `target_response` with all joins down the tree
+    let sub_tree_failures = if sub_tree.locations.is_empty() {
+        Vec::new()
+    } else {
+        execute_join_locations(
+            http_context,
+            execution_span_attribute,
+            &mut target_response,
+            &join_node.process_response_as,
+            sub_tree,
+            project_id,
+        )
+        .await?
+    };
+
+    // from `Vec<RowSet>` create `HashMap<Argument, RowSet>`
+    let rhs_response = arguments
+        .into_iter()
+        .cloned()
+        .zip(target_response)
+        .collect();
+    Ok((rhs_response, sub_tree_failures))
+}
+
+/// Execute remote joins on the result of a procedure. The result is not a
+/// `RowSet`, so it is wrapped in one the same way that the result of a function
+/// is, before executing remote joins as for a query response.
+pub(crate) async fn execute_mutation_join_locations<'s, 'ir>(
+    http_context: &HttpContext,
+    execution_span_attribute: &'static str,
+    mutation_results: ndc_models::MutationOperationResults,
+    process_response_as: &ProcessResponseAs<'s, 'ir>,
+    join_locations: &JoinLocations<(RemoteJoin<'s, 'ir>, JoinId)>,
+    project_id: Option<&ProjectId>,
+) -> Result<(ndc_models::MutationOperationResults, Vec<RemoteJoinFailure>), error::FieldError> {
+    if join_locations.locations.is_empty() {
+        return Ok((mutation_results, Vec::new()));
+    }
+    let ndc_models::MutationOperationResults::Procedure { result } = mutation_results;
+    let mut lhs_response = vec![ndc_models::RowSet {
+        aggregates: None,
+        rows: Some(vec![IndexMap::from([(
+            FUNCTION_IR_VALUE_COLUMN_NAME.to_string(),
+            ndc_models::RowFieldValue(result),
+        )])]),
+    }];
+    let failures = execute_join_locations(
+        http_context,
+        execution_span_attribute,
+        &mut lhs_response,
+        process_response_as,
+        join_locations,
+        project_id,
+    )
+    .await?;
+    let result = lhs_response
+        .into_iter()
+        .next()
+        .and_then(|row_set| row_set.rows)
+        .and_then(|rows| rows.into_iter().next())
+        .and_then(|mut row| row.swap_remove(FUNCTION_IR_VALUE_COLUMN_NAME))
+        .ok_or(error::FieldInternalError::InternalGeneric {
+            description: "unexpected: procedure result missing after remote joins".into(),
+        })?;
+    Ok((
+        ndc_models::MutationOperationResults::Procedure { result: result.0 },
+        failures,
+    ))
 }
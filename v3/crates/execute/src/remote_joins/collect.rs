@@ -15,7 +15,7 @@ use json_ext::ValueExt;
 use super::error;
 use super::types::{
     Argument, JoinId, JoinLocations, JoinNode, LocationKind, RemoteJoin, SourceFieldAlias,
-    TargetField, VariableName,
+    VariableName,
 };
 use crate::ndc::FUNCTION_IR_VALUE_COLUMN_NAME;
 use crate::plan::ProcessResponseAs;
@@ -117,7 +117,8 @@ fn collect_argument_from_rows(
                     ProcessResponseAs::CommandResponse {
                         command_name: _,
                         type_container,
-                    } => {
+                    }
+                    | ProcessResponseAs::ModelMutationResponse { type_container } => {
                         let mut command_rows = resolve_command_response_row(row, type_container)?;
                         for command_row in &mut command_rows {
                             collect_argument_from_row(
@@ -179,20 +180,7 @@ pub(crate) fn get_join_fields<'ir>(
 ) -> Vec<(&'ir SourceFieldAlias, VariableName)> {
     let mut join_fields = vec![];
     for (src_alias, target_field) in join_node.join_mapping.values() {
-        match target_field {
-            TargetField::ModelField((_, field_mapping)) => {
-                // use the target field name here to create the variable
-                // name to be used in RHS
-                let variable_name = VariableName(format!("${}", &field_mapping.column));
-                join_fields.push((src_alias, variable_name));
-            }
-            TargetField::CommandField(argument_name) => {
-                // use the target argument name here to create the variable
-                // name to be used in RHS
-                let variable_name = VariableName(format!("${}", &argument_name));
-                join_fields.push((src_alias, variable_name));
-            }
-        }
+        join_fields.push((src_alias, target_field.variable_name()));
     }
     join_fields
 }
@@ -215,12 +203,24 @@ pub(crate) fn get_value<'n>(
     pick_alias: &SourceFieldAlias,
     row: &'n IndexMap<String, ndc_models::RowFieldValue>,
 ) -> &'n json::Value {
-    match row.get(&pick_alias.0) {
-        Some(v) => &v.0,
+    match row.get(&pick_alias.alias) {
+        Some(v) => get_nested_value(&v.0, &pick_alias.nested_field_path),
         None => &json::Value::Null,
     }
 }
 
+/// Follow the fields of nested objects to a value. A missing value, such as that of a field in
+/// a `null` object, is `null`.
+pub(crate) fn get_nested_value<'n>(
+    value: &'n json::Value,
+    nested_field_path: &[String],
+) -> &'n json::Value {
+    nested_field_path
+        .iter()
+        .try_fold(value, |value, field_name| value.get(field_name))
+        .unwrap_or(&json::Value::Null)
+}
+
 fn rows_from_row_field_value(
     location_kind: LocationKind,
     nested_val: &ndc_models::RowFieldValue,
@@ -0,0 +1,326 @@
+//! Execution of remote joins on data connectors that don't support `foreach` queries.
+//!
+//! The NDC IR of a remote join refers to the values of the join fields through variables. When
+//! the target data connector doesn't support variables, the values of each join argument are
+//! substituted for the variables in a copy of the IR instead, which is executed as a query of its
+//! own.
+
+use std::collections::BTreeMap;
+
+use serde_json as json;
+
+use super::error;
+
+/// Replace the variables in a query request with their values.
+pub(crate) fn substitute_variables(
+    query_request: &mut ndc_models::QueryRequest,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    for argument in query_request.arguments.values_mut() {
+        substitute_argument(argument, variables)?;
+    }
+    for relationship in query_request.collection_relationships.values_mut() {
+        substitute_relationship_arguments(&mut relationship.arguments, variables)?;
+    }
+    substitute_query(&mut query_request.query, variables)
+}
+
+fn substitute_query(
+    query: &mut ndc_models::Query,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    if let Some(fields) = &mut query.fields {
+        for field in fields.values_mut() {
+            substitute_field(field, variables)?;
+        }
+    }
+    if let Some(order_by) = &mut query.order_by {
+        for element in &mut order_by.elements {
+            let path = match &mut element.target {
+                ndc_models::OrderByTarget::Column { path, .. }
+                | ndc_models::OrderByTarget::SingleColumnAggregate { path, .. }
+                | ndc_models::OrderByTarget::StarCountAggregate { path } => path,
+            };
+            substitute_path(path, variables)?;
+        }
+    }
+    if let Some(predicate) = &mut query.predicate {
+        substitute_expression(predicate, variables)?;
+    }
+    Ok(())
+}
+
+fn substitute_field(
+    field: &mut ndc_models::Field,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    match field {
+        ndc_models::Field::Column {
+            fields, arguments, ..
+        } => {
+            for argument in arguments.values_mut() {
+                substitute_argument(argument, variables)?;
+            }
+            if let Some(fields) = fields {
+                substitute_nested_field(fields, variables)?;
+            }
+        }
+        ndc_models::Field::Relationship {
+            query, arguments, ..
+        } => {
+            substitute_relationship_arguments(arguments, variables)?;
+            substitute_query(query, variables)?;
+        }
+    }
+    Ok(())
+}
+
+fn substitute_nested_field(
+    nested_field: &mut ndc_models::NestedField,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    match nested_field {
+        ndc_models::NestedField::Object(nested_object) => {
+            for field in nested_object.fields.values_mut() {
+                substitute_field(field, variables)?;
+            }
+            Ok(())
+        }
+        ndc_models::NestedField::Array(nested_array) => {
+            substitute_nested_field(&mut nested_array.fields, variables)
+        }
+    }
+}
+
+fn substitute_expression(
+    expression: &mut ndc_models::Expression,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    match expression {
+        ndc_models::Expression::And { expressions }
+        | ndc_models::Expression::Or { expressions } => {
+            for expression in expressions {
+                substitute_expression(expression, variables)?;
+            }
+        }
+        ndc_models::Expression::Not { expression } => {
+            substitute_expression(expression, variables)?;
+        }
+        ndc_models::Expression::UnaryComparisonOperator { column, .. } => {
+            substitute_comparison_target(column, variables)?;
+        }
+        ndc_models::Expression::BinaryComparisonOperator { column, value, .. } => {
+            substitute_comparison_target(column, variables)?;
+            match value {
+                ndc_models::ComparisonValue::Column { column } => {
+                    substitute_comparison_target(column, variables)?;
+                }
+                ndc_models::ComparisonValue::Scalar { .. } => {}
+                ndc_models::ComparisonValue::Variable { name } => {
+                    *value = ndc_models::ComparisonValue::Scalar {
+                        value: variable_value(name, variables)?,
+                    };
+                }
+            }
+        }
+        ndc_models::Expression::Exists {
+            in_collection,
+            predicate,
+        } => {
+            match in_collection {
+                ndc_models::ExistsInCollection::Related { arguments, .. }
+                | ndc_models::ExistsInCollection::Unrelated { arguments, .. } => {
+                    substitute_relationship_arguments(arguments, variables)?;
+                }
+            }
+            if let Some(predicate) = predicate {
+                substitute_expression(predicate, variables)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn substitute_comparison_target(
+    comparison_target: &mut ndc_models::ComparisonTarget,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    match comparison_target {
+        ndc_models::ComparisonTarget::Column { path, .. } => substitute_path(path, variables),
+        ndc_models::ComparisonTarget::RootCollectionColumn { .. } => Ok(()),
+    }
+}
+
+fn substitute_path(
+    path: &mut [ndc_models::PathElement],
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    for path_element in path {
+        substitute_relationship_arguments(&mut path_element.arguments, variables)?;
+        if let Some(predicate) = &mut path_element.predicate {
+            substitute_expression(predicate, variables)?;
+        }
+    }
+    Ok(())
+}
+
+fn substitute_argument(
+    argument: &mut ndc_models::Argument,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    if let ndc_models::Argument::Variable { name } = argument {
+        *argument = ndc_models::Argument::Literal {
+            value: variable_value(name, variables)?,
+        };
+    }
+    Ok(())
+}
+
+fn substitute_relationship_arguments(
+    arguments: &mut BTreeMap<String, ndc_models::RelationshipArgument>,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<(), error::FieldError> {
+    for argument in arguments.values_mut() {
+        if let ndc_models::RelationshipArgument::Variable { name } = argument {
+            *argument = ndc_models::RelationshipArgument::Literal {
+                value: variable_value(name, variables)?,
+            };
+        }
+    }
+    Ok(())
+}
+
+fn variable_value(
+    name: &str,
+    variables: &BTreeMap<String, json::Value>,
+) -> Result<json::Value, error::FieldError> {
+    variables.get(name).cloned().ok_or_else(|| {
+        error::FieldInternalError::InternalGeneric {
+            description: format!("no value for variable {name} in remote join"),
+        }
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use indexmap::IndexMap;
+    use serde_json as json;
+
+    use super::substitute_variables;
+
+    fn column(name: &str) -> ndc_models::ComparisonTarget {
+        ndc_models::ComparisonTarget::Column {
+            name: name.to_string(),
+            path: vec![],
+            field_path: None,
+        }
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let mut query_request = ndc_models::QueryRequest {
+            collection: "weather".to_string(),
+            query: ndc_models::Query {
+                aggregates: None,
+                fields: Some(IndexMap::from([(
+                    "temperature".to_string(),
+                    ndc_models::Field::Column {
+                        column: "temperature".to_string(),
+                        fields: None,
+                        arguments: BTreeMap::new(),
+                    },
+                )])),
+                limit: None,
+                offset: None,
+                order_by: None,
+                predicate: Some(ndc_models::Expression::And {
+                    expressions: vec![
+                        ndc_models::Expression::BinaryComparisonOperator {
+                            column: column("city_code"),
+                            operator: "_eq".to_string(),
+                            value: ndc_models::ComparisonValue::Variable {
+                                name: "$city_code".to_string(),
+                            },
+                        },
+                        ndc_models::Expression::BinaryComparisonOperator {
+                            column: column("humidity"),
+                            operator: "_gt".to_string(),
+                            value: ndc_models::ComparisonValue::Scalar {
+                                value: json::json!(50),
+                            },
+                        },
+                    ],
+                }),
+            },
+            arguments: BTreeMap::from([(
+                "date".to_string(),
+                ndc_models::Argument::Variable {
+                    name: "$argument:date".to_string(),
+                },
+            )]),
+            collection_relationships: BTreeMap::new(),
+            variables: None,
+        };
+        let variables = BTreeMap::from([
+            ("$city_code".to_string(), json::json!("LON")),
+            ("$argument:date".to_string(), json::json!("2024-06-01")),
+        ]);
+
+        substitute_variables(&mut query_request, &variables).unwrap();
+
+        assert_eq!(
+            query_request.arguments.get("date"),
+            Some(&ndc_models::Argument::Literal {
+                value: json::json!("2024-06-01")
+            })
+        );
+        assert_eq!(
+            query_request.query.predicate,
+            Some(ndc_models::Expression::And {
+                expressions: vec![
+                    ndc_models::Expression::BinaryComparisonOperator {
+                        column: column("city_code"),
+                        operator: "_eq".to_string(),
+                        value: ndc_models::ComparisonValue::Scalar {
+                            value: json::json!("LON"),
+                        },
+                    },
+                    ndc_models::Expression::BinaryComparisonOperator {
+                        column: column("humidity"),
+                        operator: "_gt".to_string(),
+                        value: ndc_models::ComparisonValue::Scalar {
+                            value: json::json!(50),
+                        },
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_variable() {
+        let mut query_request = ndc_models::QueryRequest {
+            collection: "weather".to_string(),
+            query: ndc_models::Query {
+                aggregates: None,
+                fields: None,
+                limit: None,
+                offset: None,
+                order_by: None,
+                predicate: None,
+            },
+            arguments: BTreeMap::from([(
+                "date".to_string(),
+                ndc_models::Argument::Variable {
+                    name: "$argument:date".to_string(),
+                },
+            )]),
+            collection_relationships: BTreeMap::new(),
+            variables: None,
+        };
+        assert!(substitute_variables(&mut query_request, &BTreeMap::new()).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Sorting of rows by the engine, for orderings across remote relationships.
+//!
+//! The values the rows are sorted by are fetched into hidden fields of the rows, by the query
+//! itself or by remote joins, so the rows are sorted once those remote joins have been executed.
+//! The other remote joins are executed after the sort, only for the rows within the limit and
+//! offset.
+//!
+//! The engine doesn't know the collations of the data connectors, so strings are sorted by their
+//! Unicode code points. This can differ from the order the data connector gives the same values,
+//! for example for case-insensitive or locale-aware collations.
+
+use std::cmp::Ordering;
+use std::num::NonZeroUsize;
+
+use indexmap::IndexMap;
+use serde_json as json;
+
+use super::error;
+use super::types::{EngineSort, SortKey};
+
+static NULL: json::Value = json::Value::Null;
+
+/// Limit a query to one row more than the engine sorts, so that exceeding the limit can be
+/// detected rather than sorting a partial set of rows.
+pub(crate) fn limit_rows_to_sort(query: &mut ndc_models::Query, max_sorted_rows: NonZeroUsize) {
+    query.limit = Some(
+        u32::try_from(max_sorted_rows.get()).map_or(u32::MAX, |max_sorted_rows| {
+            max_sorted_rows.saturating_add(1)
+        }),
+    );
+}
+
+/// Sort the rows of every row set, and then apply the limit and offset of the query to them.
+pub(crate) fn sort_row_sets(
+    engine_sort: &EngineSort,
+    row_sets: &mut [ndc_models::RowSet],
+    max_sorted_rows: NonZeroUsize,
+) -> Result<(), error::FieldError> {
+    let offset = engine_sort
+        .offset
+        .map_or(0, |offset| usize::try_from(offset).unwrap_or(usize::MAX));
+    let limit = engine_sort.limit.map_or(usize::MAX, |limit| {
+        usize::try_from(limit).unwrap_or(usize::MAX)
+    });
+    for row_set in row_sets {
+        if let Some(rows) = row_set.rows.as_mut() {
+            if rows.len() > max_sorted_rows.get() {
+                return Err(error::FieldError::TooManyRowsToSort {
+                    max_sorted_rows: max_sorted_rows.get(),
+                });
+            }
+            let mut order: Vec<usize> = (0..rows.len()).collect();
+            let sort_values: Vec<Vec<&json::Value>> = rows
+                .iter()
+                .map(|row| {
+                    engine_sort
+                        .keys
+                        .iter()
+                        .map(|key| sort_value(row, &key.alias_path))
+                        .collect()
+                })
+                .collect();
+            // a stable sort keeps the order of the data connector for equal values
+            order.sort_by(|left, right| {
+                compare_sort_values(&engine_sort.keys, &sort_values[*left], &sort_values[*right])
+            });
+
+            let mut unsorted_rows: Vec<Option<_>> =
+                std::mem::take(rows).into_iter().map(Some).collect();
+            *rows = order
+                .into_iter()
+                .skip(offset)
+                .take(limit)
+                .filter_map(|index| unsorted_rows[index].take())
+                .collect();
+        }
+    }
+    Ok(())
+}
+
+/// Follow the aliases from a row to a value. A missing value, such as that of a relationship
+/// without a related row, is `null`.
+fn sort_value<'a>(
+    row: &'a IndexMap<String, ndc_models::RowFieldValue>,
+    alias_path: &[String],
+) -> &'a json::Value {
+    let Some((first_alias, aliases)) = alias_path.split_first() else {
+        return &NULL;
+    };
+    let mut value = row.get(first_alias).map_or(&NULL, |value| &value.0);
+    for alias in aliases {
+        value = value
+            .get("rows")
+            .and_then(|rows| rows.get(0))
+            .and_then(|row| row.get(alias))
+            .unwrap_or(&NULL);
+    }
+    value
+}
+
+fn compare_sort_values(
+    keys: &[SortKey],
+    left: &[&json::Value],
+    right: &[&json::Value],
+) -> Ordering {
+    keys.iter()
+        .zip(left.iter().zip(right))
+        .map(|(key, (left, right))| {
+            let ordering = compare_values(left, right);
+            if key.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Compare two values. Values of different types are ordered by their type, and `null` sorts
+/// after any other value, so that nulls come last in ascending order. Strings are compared by
+/// their bytes, which for UTF-8 is the order of their code points, regardless of the collation of
+/// the data connector they come from.
+fn compare_values(left: &json::Value, right: &json::Value) -> Ordering {
+    match (left, right) {
+        (json::Value::Bool(left), json::Value::Bool(right)) => left.cmp(right),
+        (json::Value::Number(left), json::Value::Number(right)) => compare_numbers(left, right),
+        (json::Value::String(left), json::Value::String(right)) => left.cmp(right),
+        _ => type_rank(left).cmp(&type_rank(right)),
+    }
+}
+
+fn compare_numbers(left: &json::Number, right: &json::Number) -> Ordering {
+    if let (Some(left), Some(right)) = (left.as_i64(), right.as_i64()) {
+        return left.cmp(&right);
+    }
+    if let (Some(left), Some(right)) = (left.as_u64(), right.as_u64()) {
+        return left.cmp(&right);
+    }
+    match (left.as_f64(), right.as_f64()) {
+        (Some(left), Some(right)) => left.total_cmp(&right),
+        _ => Ordering::Equal,
+    }
+}
+
+fn type_rank(value: &json::Value) -> u8 {
+    match value {
+        json::Value::Bool(_) => 0,
+        json::Value::Number(_) => 1,
+        json::Value::String(_) => 2,
+        json::Value::Array(_) => 3,
+        json::Value::Object(_) => 4,
+        json::Value::Null => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use serde_json::json;
+
+    use super::sort_row_sets;
+    use crate::error::FieldError;
+    use crate::remote_joins::types::{EngineSort, SortKey};
+
+    fn row_set(rows: serde_json::Value) -> ndc_models::RowSet {
+        serde_json::from_value(json!({ "rows": rows })).unwrap()
+    }
+
+    fn sort_key(alias_path: &[&str], descending: bool) -> SortKey {
+        SortKey {
+            alias_path: alias_path.iter().map(ToString::to_string).collect(),
+            descending,
+        }
+    }
+
+    #[test]
+    fn test_sort_by_remote_relationship_field() {
+        let customer = |name: serde_json::Value| json!({ "rows": [{ "name": name }] });
+        let mut row_sets = vec![row_set(json!([
+            { "id": 1, "customer": customer(json!("Bob")) },
+            { "id": 2, "customer": { "rows": [] } },
+            { "id": 3, "customer": customer(json!("Alice")) },
+            { "id": 4, "customer": customer(json!("Bob")) },
+        ]))];
+        let engine_sort = EngineSort {
+            keys: vec![
+                sort_key(&["customer", "name"], false),
+                sort_key(&["id"], true),
+            ],
+            limit: Some(2),
+            offset: Some(1),
+        };
+        sort_row_sets(&engine_sort, &mut row_sets, NonZeroUsize::new(10).unwrap()).unwrap();
+        let ids: Vec<_> = row_sets[0]
+            .rows
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|row| row["id"].0.clone())
+            .collect();
+        // sorted: 3 (Alice), 4 (Bob), 1 (Bob), 2 (null)
+        assert_eq!(ids, vec![json!(4), json!(1)]);
+    }
+
+    #[test]
+    fn test_strings_sort_by_code_point() {
+        let mut row_sets = vec![row_set(json!([
+            { "name": "b" },
+            { "name": "B" },
+            { "name": "\u{e9}" },
+            { "name": "a" },
+        ]))];
+        let engine_sort = EngineSort {
+            keys: vec![sort_key(&["name"], false)],
+            limit: None,
+            offset: None,
+        };
+        sort_row_sets(&engine_sort, &mut row_sets, NonZeroUsize::new(10).unwrap()).unwrap();
+        let names: Vec<_> = row_sets[0]
+            .rows
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|row| row["name"].0.clone())
+            .collect();
+        assert_eq!(
+            names,
+            vec![json!("B"), json!("a"), json!("b"), json!("\u{e9}")]
+        );
+    }
+
+    #[test]
+    fn test_too_many_rows_to_sort() {
+        let mut row_sets = vec![row_set(json!([{ "id": 1 }, { "id": 2 }]))];
+        let engine_sort = EngineSort {
+            keys: vec![sort_key(&["id"], false)],
+            limit: None,
+            offset: None,
+        };
+        let result = sort_row_sets(&engine_sort, &mut row_sets, NonZeroUsize::new(1).unwrap());
+        assert!(matches!(
+            result,
+            Err(FieldError::TooManyRowsToSort { max_sorted_rows: 1 })
+        ));
+    }
+}
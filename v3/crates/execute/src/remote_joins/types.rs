@@ -6,7 +6,9 @@ use std::collections::{BTreeMap, HashMap};
 use json_ext::ValueExt;
 use open_dds::arguments::ArgumentName;
 use open_dds::types::FieldName;
+use serde::Serialize;
 
+use crate::ir::relationship;
 use crate::plan::ProcessResponseAs;
 
 /// This tree structure captures all the locations (in the selection set IR) where
@@ -111,42 +113,124 @@ pub struct RemoteJoin<'s, 'ir> {
     pub target_ndc_ir: ndc_models::QueryRequest,
     /// Mapping of the fields in source to fields in target.
     /// The HashMap has the following info -
-    ///   - key: is the field name in the source
+    ///   - key: is the field name in the source, followed by the names of the
+    ///   nested fields leading to the joined field, if it is nested in objects
     ///   - value->first item: is the alias we create for the
     ///   source field. If the user did not request the join field in the
     ///   selection set, we include the join mapping field and call it a phantom
     ///   field.
     ///   - value->second item: is the target NDC field. This could be a model
     ///   field or an argument name.
-    pub join_mapping: HashMap<SourceFieldName, (SourceFieldAlias, TargetField)>,
+    pub join_mapping: HashMap<SourceFieldPath, (SourceFieldAlias, TargetField)>,
     /// Represents how to process the join response.
     pub process_response_as: ProcessResponseAs<'s, 'ir>,
     /// Represents the type of the remote join
     pub remote_join_type: RemoteJoinType,
+    /// How the NDC IR is executed for the join arguments
+    pub strategy: RemoteJoinStrategy,
 }
 
-/// Name of the source field used in the join mapping
-pub type SourceFieldName = FieldName;
+/// Sorting of the rows of a query by the engine, for an order_by across remote relationships.
+/// The rows are fetched without a limit or offset, which are applied once they are sorted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineSort {
+    pub keys: Vec<SortKey>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl EngineSort {
+    /// Whether the rows are sorted by a value in the field with the alias
+    pub fn sorts_by_field(&self, alias: &str) -> bool {
+        self.keys
+            .iter()
+            .any(|key| key.alias_path.first().is_some_and(|first| first == alias))
+    }
+}
+
+/// A value the rows are sorted by
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey {
+    /// The aliases leading from a row to the value. Each alias after the first is of a field of
+    /// the first row in the relationship row set that the previous alias leads to.
+    pub alias_path: Vec<String>,
+    pub descending: bool,
+}
+
+/// Names of the source field used in the join mapping: the field of the source
+/// type, followed by the fields of the objects nested in it that lead to the
+/// joined field
+pub type SourceFieldPath = Vec<FieldName>;
 
 /// Alias of the source field used in the join mapping. This is basically a NDC
 /// field alias (which in the NDC IR is `String`). Change this when modifying
 /// the IR to have a newtype Alias.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct SourceFieldAlias(pub String);
+pub struct SourceFieldAlias {
+    pub alias: String,
+    /// The names of the fields of the nested objects in the field, which lead to
+    /// the joined value
+    pub nested_field_path: Vec<String>,
+}
 
 /// Target field used in the join mapping
 #[derive(Debug, Clone, PartialEq)]
 pub enum TargetField {
-    ModelField((FieldName, metadata_resolve::NdcColumnForComparison)),
+    ModelField(relationship::TargetField),
+    ModelArgument(ArgumentName),
     CommandField(ArgumentName),
 }
 
+impl TargetField {
+    /// The name of the variable holding the values of the source field in the target query
+    pub fn variable_name(&self) -> VariableName {
+        match self {
+            // use the target column or argument name to create the variable name. Model
+            // arguments are kept apart from the columns of the model, and nested fields
+            // from the column they are nested in.
+            TargetField::ModelField(target_field) => {
+                let mut variable_name = format!("${}", &target_field.ndc_column.column);
+                for field_name in &target_field.ndc_field_path {
+                    variable_name.push('.');
+                    variable_name.push_str(&field_name.0);
+                }
+                VariableName(variable_name)
+            }
+            TargetField::ModelArgument(argument_name) => {
+                VariableName(format!("$argument:{argument_name}"))
+            }
+            TargetField::CommandField(argument_name) => VariableName(format!("${argument_name}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum RemoteJoinType {
     ToModel,
     ToCommand,
 }
 
+/// How the NDC IR of a remote join is executed for the arguments collected from the LHS response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RemoteJoinStrategy {
+    /// The arguments are sent as `foreach` variable sets, so that many of them are executed in one
+    /// request
+    ForEach,
+    /// The values of each argument are substituted for the variables in the NDC IR, and it is
+    /// executed in a separate request, for data connectors that don't support variables
+    Naive,
+}
+
+impl RemoteJoinStrategy {
+    pub fn from_capabilities(capabilities: &metadata_resolve::RelationshipCapabilities) -> Self {
+        if capabilities.foreach {
+            Self::ForEach
+        } else {
+            Self::Naive
+        }
+    }
+}
+
 /// For assigning a unique number to each unique join
 #[derive(Debug, Clone, Copy)]
 pub struct JoinId(pub i16);
@@ -0,0 +1,615 @@
+//! Evaluation of predicates across remote relationships.
+//!
+//! The data connector of a model can't evaluate a predicate over a relationship to a model in
+//! another data connector. Before a request is planned, the target model of each such predicate
+//! is queried for the join keys of the rows that match it, and the predicate is replaced by a
+//! comparison of the source columns with those keys. A predicate may match at most
+//! `RemoteJoinOptions::max_remote_predicate_rows` rows of its target model.
+
+use futures_util::future::BoxFuture;
+use indexmap::IndexSet;
+use json_ext::ValueExt;
+use open_dds::relationships::RelationshipName;
+use serde_json as json;
+use tracing_util::SpanVisibility;
+
+use crate::error::{FieldError, FieldInternalError};
+use crate::ir;
+use crate::ir::filter::{
+    FilterExpression, RemotePredicateJoinColumn, RemoteRelationshipPredicate,
+    ResolvedFilterExpression,
+};
+use crate::ir::model_selection::ModelSelection;
+use crate::ir::root_field::{ApolloFederationRootFields, MutationRootField, QueryRootField};
+use crate::ir::selection_set::{FieldSelection, NestedSelection, ResultSelectionSet};
+use crate::ndc;
+use crate::plan;
+use crate::remote_joins;
+use crate::{HttpContext, ProjectId};
+
+/// Resolve all the predicates across remote relationships in the IR of a request, so that a
+/// plan can be built for it.
+pub(crate) async fn resolve_remote_predicates(
+    http_context: &HttpContext,
+    ir: &mut ir::IR<'_, '_>,
+    project_id: Option<&ProjectId>,
+) -> Result<(), FieldError> {
+    let remote_predicates = collect_from_ir(ir);
+
+    // most requests don't filter across remote relationships
+    if remote_predicates.is_empty() {
+        return Ok(());
+    }
+
+    let tracer = tracing_util::global_tracer();
+    tracer
+        .in_span_async(
+            "resolve_remote_predicates",
+            "Resolve predicates across remote relationships",
+            SpanVisibility::Internal,
+            || Box::pin(resolve_all(http_context, remote_predicates, project_id)),
+        )
+        .await
+}
+
+/// The query for the join keys of a predicate across a remote relationship, which is made before
+/// the request that depends on it.
+pub(crate) struct RemotePredicatePlan<'s> {
+    pub relationship_name: &'s RelationshipName,
+    pub data_connector: &'s metadata_resolve::DataConnectorLink,
+    pub query_request: ndc_models::QueryRequest,
+}
+
+/// Plan the queries for the join keys of the predicates across remote relationships in the IR of
+/// a request, without making them, so that the request can be explained. The remote predicates
+/// are replaced by comparisons of the source columns with variables standing for the join keys.
+///
+/// The plans are in the order in which the queries have to be made: the queries of the remote
+/// predicates nested in the predicate over a target model come before the query of that model.
+pub(crate) fn plan_remote_predicates<'s>(
+    http_context: &HttpContext,
+    ir: &mut ir::IR<'_, 's>,
+) -> Result<Vec<RemotePredicatePlan<'s>>, FieldError> {
+    let mut remote_predicate_plans = Vec::new();
+    for remote_predicate in collect_from_ir(ir) {
+        plan_remote_predicate(http_context, remote_predicate, &mut remote_predicate_plans)?;
+    }
+    Ok(remote_predicate_plans)
+}
+
+fn plan_remote_predicate<'s>(
+    http_context: &HttpContext,
+    remote_predicate: &mut RemoteRelationshipPredicate<'s>,
+    remote_predicate_plans: &mut Vec<RemotePredicatePlan<'s>>,
+) -> Result<(), FieldError> {
+    let mut nested_remote_predicates = Vec::new();
+    collect_from_filter_clause(
+        &mut remote_predicate.predicate,
+        &mut nested_remote_predicates,
+    );
+    for nested_remote_predicate in nested_remote_predicates {
+        plan_remote_predicate(
+            http_context,
+            nested_remote_predicate,
+            remote_predicate_plans,
+        )?;
+    }
+
+    let target_source = remote_predicate.target_source;
+    remote_predicate_plans.push(RemotePredicatePlan {
+        relationship_name: remote_predicate.relationship_name,
+        data_connector: &target_source.model.data_connector,
+        query_request: join_keys_query_request(http_context, remote_predicate)?,
+    });
+    remote_predicate.resolved = Some(build_join_key_variable_comparison(
+        &remote_predicate.join_columns,
+    ));
+    Ok(())
+}
+
+fn collect_from_ir<'a, 's>(
+    ir: &'a mut ir::IR<'_, 's>,
+) -> Vec<&'a mut RemoteRelationshipPredicate<'s>> {
+    let mut remote_predicates = Vec::new();
+    match ir {
+        ir::IR::Query(root_fields) => {
+            for root_field in root_fields.values_mut() {
+                collect_from_query_root_field(root_field, &mut remote_predicates);
+            }
+        }
+        ir::IR::Mutation(root_fields) => {
+            for root_field in root_fields.values_mut() {
+                match root_field {
+                    MutationRootField::TypeName { .. } => {}
+                    MutationRootField::ProcedureBasedCommand { ir, .. } => {
+                        if let Some(selection) = &mut ir.command_info.selection {
+                            collect_from_nested_selection(selection, &mut remote_predicates);
+                        }
+                    }
+                    MutationRootField::ModelPointMutation { ir, .. } => {
+                        if let Some(selection) = &mut ir.selection {
+                            collect_from_nested_selection(selection, &mut remote_predicates);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    remote_predicates
+}
+
+/// Resolve the remote predicates concurrently
+async fn resolve_all(
+    http_context: &HttpContext,
+    remote_predicates: Vec<&mut RemoteRelationshipPredicate<'_>>,
+    project_id: Option<&ProjectId>,
+) -> Result<(), FieldError> {
+    let results =
+        futures_ext::execute_concurrently(remote_predicates.into_iter(), |remote_predicate| {
+            resolve_remote_predicate(http_context, remote_predicate, project_id)
+        })
+        .await;
+    results.into_iter().collect()
+}
+
+fn resolve_remote_predicate<'a, 's>(
+    http_context: &'a HttpContext,
+    remote_predicate: &'a mut RemoteRelationshipPredicate<'s>,
+    project_id: Option<&'a ProjectId>,
+) -> BoxFuture<'a, Result<(), FieldError>> {
+    Box::pin(async move {
+        // the predicate over the target model may itself cross remote relationships, which have
+        // to be resolved before the target model can be queried
+        let mut nested_remote_predicates = Vec::new();
+        collect_from_filter_clause(
+            &mut remote_predicate.predicate,
+            &mut nested_remote_predicates,
+        );
+        resolve_all(http_context, nested_remote_predicates, project_id).await?;
+
+        let query_request = join_keys_query_request(http_context, remote_predicate)?;
+        let row_sets = ndc::execute_ndc_query(
+            http_context,
+            &query_request,
+            &remote_predicate.target_source.model.data_connector,
+            "execute_remote_predicate",
+            remote_predicate.relationship_name.to_string(),
+            project_id,
+        )
+        .await?;
+
+        let max_rows = http_context
+            .remote_join_options
+            .max_remote_predicate_rows
+            .get();
+        let row_count: usize = row_sets
+            .iter()
+            .filter_map(|row_set| row_set.rows.as_ref())
+            .map(Vec::len)
+            .sum();
+        if row_count > max_rows {
+            return Err(FieldError::TooManyRemotePredicateRows {
+                relationship_name: remote_predicate.relationship_name.clone(),
+                max_rows,
+            });
+        }
+
+        let join_keys = collect_join_keys(&remote_predicate.join_columns, row_sets)?;
+        remote_predicate.resolved = Some(build_join_key_comparison(
+            &remote_predicate.join_columns,
+            join_keys,
+        ));
+        Ok(())
+    })
+}
+
+/// The query for the join keys of the target model rows matching the predicate. It fetches one
+/// row more than the maximum number of rows, so that a predicate which matches too many rows can
+/// be told apart from one which matches exactly the maximum.
+fn join_keys_query_request(
+    http_context: &HttpContext,
+    remote_predicate: &RemoteRelationshipPredicate<'_>,
+) -> Result<ndc_models::QueryRequest, FieldError> {
+    let mut query_request = plan::model_selection::ndc_remote_predicate_ir(remote_predicate)
+        .map_err(|e| FieldInternalError::InternalGeneric {
+            description: e.to_string(),
+        })?;
+    let max_rows = http_context
+        .remote_join_options
+        .max_remote_predicate_rows
+        .get();
+    query_request.query.limit = Some(u32::try_from(max_rows + 1).unwrap_or(u32::MAX));
+    Ok(query_request)
+}
+
+/// Collect the distinct join keys of the target model rows. Rows with a `null` in any of the
+/// join columns can't match any source row, and are skipped.
+fn collect_join_keys(
+    join_columns: &[RemotePredicateJoinColumn],
+    row_sets: Vec<ndc_models::RowSet>,
+) -> Result<IndexSet<Vec<ValueExt>>, FieldError> {
+    let mut join_keys = IndexSet::new();
+    for rows in row_sets.into_iter().filter_map(|rows_set| rows_set.rows) {
+        for mut row in rows {
+            let mut join_key = Vec::with_capacity(join_columns.len());
+            for join_column in join_columns {
+                let alias = join_column.target_alias();
+                let value =
+                    row.swap_remove(&alias)
+                        .ok_or_else(|| FieldInternalError::InternalGeneric {
+                            description: format!(
+                            "join column {alias} not found in the response of the remote predicate"
+                        ),
+                        })?;
+                let value = remote_joins::collect::get_nested_value(
+                    &value.0,
+                    &join_column.target_nested_field_path(),
+                );
+                join_key.push(ValueExt::from(value.clone()));
+            }
+            if join_key.iter().all(|value| !value.0.is_null()) {
+                join_keys.insert(join_key);
+            }
+        }
+    }
+    Ok(join_keys)
+}
+
+/// Build the comparison of the source columns with the join keys. A single join column is
+/// compared using its `in` operator where there is one, otherwise each join key is compared
+/// using the equality operators.
+fn build_join_key_comparison(
+    join_columns: &[RemotePredicateJoinColumn],
+    join_keys: IndexSet<Vec<ValueExt>>,
+) -> ndc_models::Expression {
+    if let [join_column @ RemotePredicateJoinColumn {
+        in_operator: Some(in_operator),
+        ..
+    }] = join_columns
+    {
+        if !join_keys.is_empty() {
+            return ndc_models::Expression::BinaryComparisonOperator {
+                column: source_column_target(join_column),
+                operator: in_operator.clone(),
+                value: ndc_models::ComparisonValue::Scalar {
+                    value: json::Value::Array(
+                        join_keys
+                            .into_iter()
+                            .flat_map(|join_key| join_key.into_iter().map(|value| value.0))
+                            .collect(),
+                    ),
+                },
+            };
+        }
+    }
+
+    // an empty disjunction is false, which is what we want when no rows matched
+    ndc_models::Expression::Or {
+        expressions: join_keys
+            .into_iter()
+            .map(|join_key| ndc_models::Expression::And {
+                expressions: join_columns
+                    .iter()
+                    .zip(join_key)
+                    .map(
+                        |(join_column, value)| ndc_models::Expression::BinaryComparisonOperator {
+                            column: source_column_target(join_column),
+                            operator: join_column.equal_operator.clone(),
+                            value: ndc_models::ComparisonValue::Scalar { value: value.0 },
+                        },
+                    )
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+/// Build the comparison of the source columns with the join keys for a request which is only
+/// explained. The join keys are only known once the target model has been queried, so they are
+/// referred to by variables named after the join columns of the target model.
+fn build_join_key_variable_comparison(
+    join_columns: &[RemotePredicateJoinColumn],
+) -> ndc_models::Expression {
+    if let [join_column @ RemotePredicateJoinColumn {
+        in_operator: Some(in_operator),
+        ..
+    }] = join_columns
+    {
+        return ndc_models::Expression::BinaryComparisonOperator {
+            column: source_column_target(join_column),
+            operator: in_operator.clone(),
+            value: ndc_models::ComparisonValue::Variable {
+                name: join_column.target_alias(),
+            },
+        };
+    }
+
+    ndc_models::Expression::And {
+        expressions: join_columns
+            .iter()
+            .map(
+                |join_column| ndc_models::Expression::BinaryComparisonOperator {
+                    column: source_column_target(join_column),
+                    operator: join_column.equal_operator.clone(),
+                    value: ndc_models::ComparisonValue::Variable {
+                        name: join_column.target_alias(),
+                    },
+                },
+            )
+            .collect(),
+    }
+}
+
+fn source_column_target(join_column: &RemotePredicateJoinColumn) -> ndc_models::ComparisonTarget {
+    ir::relationship::comparison_target(&join_column.source_column, &join_column.source_field_path)
+}
+
+fn collect_from_query_root_field<'a, 's>(
+    root_field: &'a mut QueryRootField<'_, 's>,
+    remote_predicates: &mut Vec<&'a mut RemoteRelationshipPredicate<'s>>,
+) {
+    match root_field {
+        QueryRootField::TypeName { .. }
+        | QueryRootField::SchemaField { .. }
+        | QueryRootField::TypeField { .. }
+        | QueryRootField::NodeSelect(None)
+        | QueryRootField::ApolloFederation(ApolloFederationRootFields::ServiceField { .. }) => {}
+        QueryRootField::ModelSelectOne { ir, .. } => {
+            collect_from_model_selection(&mut ir.model_selection, remote_predicates);
+        }
+        QueryRootField::ModelSelectMany { ir, .. } => {
+            collect_from_model_selection(&mut ir.model_selection, remote_predicates);
+        }
+        QueryRootField::ModelSelectAggregate { ir, .. } => {
+            collect_from_model_selection(&mut ir.model_selection, remote_predicates);
+        }
+        QueryRootField::NodeSelect(Some(ir)) => {
+            collect_from_model_selection(&mut ir.model_selection, remote_predicates);
+        }
+        QueryRootField::ApolloFederation(ApolloFederationRootFields::EntitiesSelect(irs)) => {
+            for ir in irs {
+                collect_from_model_selection(&mut ir.model_selection, remote_predicates);
+            }
+        }
+        QueryRootField::FunctionBasedCommand { ir, .. } => {
+            if let Some(selection) = &mut ir.command_info.selection {
+                collect_from_nested_selection(selection, remote_predicates);
+            }
+        }
+    }
+}
+
+fn collect_from_model_selection<'a, 's>(
+    model_selection: &'a mut ModelSelection<'s>,
+    remote_predicates: &mut Vec<&'a mut RemoteRelationshipPredicate<'s>>,
+) {
+    collect_from_filter_clause(&mut model_selection.filter_clause, remote_predicates);
+    if let Some(selection) = &mut model_selection.selection {
+        collect_from_selection_set(selection, remote_predicates);
+    }
+}
+
+fn collect_from_filter_clause<'a, 's>(
+    filter_clause: &'a mut ResolvedFilterExpression<'s>,
+    remote_predicates: &mut Vec<&'a mut RemoteRelationshipPredicate<'s>>,
+) {
+    if let Some(expression) = &mut filter_clause.expression {
+        collect_from_filter_expression(expression, remote_predicates);
+    }
+}
+
+fn collect_from_filter_expression<'a, 's>(
+    expression: &'a mut FilterExpression<'s>,
+    remote_predicates: &mut Vec<&'a mut RemoteRelationshipPredicate<'s>>,
+) {
+    match expression {
+        FilterExpression::And { expressions } | FilterExpression::Or { expressions } => {
+            for expression in expressions {
+                collect_from_filter_expression(expression, remote_predicates);
+            }
+        }
+        FilterExpression::Not { expression } => {
+            collect_from_filter_expression(expression, remote_predicates);
+        }
+        FilterExpression::LocalRelationship { predicate, .. } => {
+            collect_from_filter_expression(predicate, remote_predicates);
+        }
+        // the predicate over the target model is resolved along with the remote predicate
+        FilterExpression::RemoteRelationship(remote_predicate) => {
+            remote_predicates.push(remote_predicate.as_mut());
+        }
+        FilterExpression::Local(_) => {}
+    }
+}
+
+fn collect_from_selection_set<'a, 's>(
+    selection_set: &'a mut ResultSelectionSet<'s>,
+    remote_predicates: &mut Vec<&'a mut RemoteRelationshipPredicate<'s>>,
+) {
+    for field in selection_set.fields.values_mut() {
+        match field {
+            FieldSelection::Column {
+                nested_selection, ..
+            } => {
+                if let Some(nested_selection) = nested_selection {
+                    collect_from_nested_selection(nested_selection, remote_predicates);
+                }
+            }
+            FieldSelection::ModelRelationshipLocal { query: ir, .. }
+            | FieldSelection::ModelRelationshipRemote { ir, .. } => {
+                collect_from_model_selection(ir, remote_predicates);
+            }
+            FieldSelection::CommandRelationshipLocal { ir, .. }
+            | FieldSelection::CommandRelationshipRemote { ir, .. } => {
+                if let Some(selection) = &mut ir.command_info.selection {
+                    collect_from_nested_selection(selection, remote_predicates);
+                }
+            }
+        }
+    }
+}
+
+fn collect_from_nested_selection<'a, 's>(
+    nested_selection: &'a mut NestedSelection<'s>,
+    remote_predicates: &mut Vec<&'a mut RemoteRelationshipPredicate<'s>>,
+) {
+    match nested_selection {
+        NestedSelection::Object(selection_set) => {
+            collect_from_selection_set(selection_set, remote_predicates);
+        }
+        NestedSelection::Array(nested_selection) => {
+            collect_from_nested_selection(nested_selection, remote_predicates);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+    use json_ext::ValueExt;
+    use open_dds::data_connector::DataConnectorColumnName;
+    use open_dds::relationships::RelationshipName;
+    use serde_json::json;
+
+    use super::{build_join_key_comparison, build_join_key_variable_comparison, collect_join_keys};
+    use crate::ir::filter::RemotePredicateJoinColumn;
+
+    fn join_column(
+        source_column: &str,
+        target_column: &str,
+        in_operator: Option<&str>,
+    ) -> RemotePredicateJoinColumn {
+        RemotePredicateJoinColumn {
+            source_column: DataConnectorColumnName(source_column.to_string()),
+            source_field_path: vec![],
+            equal_operator: "_eq".to_string(),
+            in_operator: in_operator.map(ToString::to_string),
+            target_column: DataConnectorColumnName(target_column.to_string()),
+            target_field_path: vec![],
+        }
+    }
+
+    fn field_path(field_names: &[&str]) -> Vec<DataConnectorColumnName> {
+        field_names
+            .iter()
+            .map(|field_name| DataConnectorColumnName((*field_name).to_string()))
+            .collect()
+    }
+
+    fn join_keys(keys: Vec<Vec<serde_json::Value>>) -> IndexSet<Vec<ValueExt>> {
+        keys.into_iter()
+            .map(|key| key.into_iter().map(ValueExt::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_single_join_column_uses_in_operator() {
+        let expression = build_join_key_comparison(
+            &[join_column("author_id", "id", Some("_in"))],
+            join_keys(vec![vec![json!(1)], vec![json!(2)], vec![json!(1)]]),
+        );
+        assert_eq!(
+            serde_json::to_value(expression).unwrap(),
+            json!({
+                "type": "binary_comparison_operator",
+                "column": {"type": "column", "name": "author_id", "path": []},
+                "operator": "_in",
+                "value": {"type": "scalar", "value": [1, 2]}
+            })
+        );
+    }
+
+    #[test]
+    fn test_multiple_join_columns_use_equality() {
+        let expression = build_join_key_comparison(
+            &[
+                join_column("first_name", "first", Some("_in")),
+                join_column("last_name", "last", None),
+            ],
+            join_keys(vec![vec![json!("Ada"), json!("Lovelace")]]),
+        );
+        assert_eq!(
+            serde_json::to_value(expression).unwrap(),
+            json!({
+                "type": "or",
+                "expressions": [{
+                    "type": "and",
+                    "expressions": [
+                        {
+                            "type": "binary_comparison_operator",
+                            "column": {"type": "column", "name": "first_name", "path": []},
+                            "operator": "_eq",
+                            "value": {"type": "scalar", "value": "Ada"}
+                        },
+                        {
+                            "type": "binary_comparison_operator",
+                            "column": {"type": "column", "name": "last_name", "path": []},
+                            "operator": "_eq",
+                            "value": {"type": "scalar", "value": "Lovelace"}
+                        }
+                    ]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_no_matching_rows_is_false() {
+        let expression = build_join_key_comparison(
+            &[join_column("author_id", "id", Some("_in"))],
+            join_keys(vec![]),
+        );
+        assert_eq!(
+            serde_json::to_value(expression).unwrap(),
+            json!({"type": "or", "expressions": []})
+        );
+    }
+
+    #[test]
+    fn test_explained_join_keys_are_variables() {
+        let expression =
+            build_join_key_variable_comparison(&[join_column("author_id", "id", Some("_in"))]);
+        assert_eq!(
+            serde_json::to_value(expression).unwrap(),
+            json!({
+                "type": "binary_comparison_operator",
+                "column": {"type": "column", "name": "author_id", "path": []},
+                "operator": "_in",
+                "value": {"type": "variable", "name": "id"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_nested_join_columns() {
+        let join_columns = [RemotePredicateJoinColumn {
+            source_field_path: field_path(&["address", "country_code"]),
+            target_field_path: field_path(&["iso"]),
+            ..join_column("shipping", "codes", Some("_in"))
+        }];
+        let row_sets = serde_json::from_value(json!([{
+            "rows": [
+                {"codes.iso": {"iso": "NZ"}},
+                {"codes.iso": null},
+                {"codes.iso": {"iso": "FR"}}
+            ]
+        }]))
+        .unwrap();
+        let join_keys = collect_join_keys(&join_columns, row_sets).unwrap();
+        let expression = build_join_key_comparison(&join_columns, join_keys);
+        assert_eq!(
+            serde_json::to_value(expression).unwrap(),
+            json!({
+                "type": "binary_comparison_operator",
+                "column": {
+                    "type": "column",
+                    "name": "shipping",
+                    "path": [],
+                    "field_path": ["address", "country_code"]
+                },
+                "operator": "_in",
+                "value": {"type": "scalar", "value": ["NZ", "FR"]}
+            })
+        );
+    }
+}
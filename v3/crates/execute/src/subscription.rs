@@ -0,0 +1,114 @@
+//! Execution of GraphQL subscriptions as polling-based live queries.
+//!
+//! A subscription is parsed, validated and lowered to IR once. A fresh query plan is then
+//! generated from that IR and executed on every tick of the polling interval, and the result is
+//! pushed to the subscriber only when it differs from the previously pushed one.
+
+use std::time::Duration;
+
+use hasura_authn_core::Session;
+use lang_graphql as gql;
+use lang_graphql::ast::common as ast;
+use schema::GDS;
+use tokio::sync::mpsc;
+
+use crate::error::RequestError;
+use crate::{
+    build_ir, build_request_plan, normalize_request, parse_query, plan, remote_predicates,
+    HttpContext,
+};
+
+/// Executes a GraphQL operation received over a subscription transport, sending every
+/// response to `sender`.
+///
+/// Subscription operations are re-executed every `poll_interval` until `sender` is closed, and
+/// a response is only sent when it differs from the previous one. Queries and mutations are
+/// executed exactly once. Request errors are sent as a single error response.
+pub async fn execute_subscription(
+    http_context: &HttpContext,
+    schema: &gql::schema::Schema<GDS>,
+    session: &Session,
+    request_headers: &reqwest::header::HeaderMap,
+    raw_request: gql::http::RawRequest,
+    poll_interval: Duration,
+    sender: mpsc::Sender<gql::http::Response>,
+) {
+    if let Err(e) = execute_subscription_internal(
+        http_context,
+        schema,
+        session,
+        request_headers,
+        raw_request,
+        poll_interval,
+        &sender,
+    )
+    .await
+    {
+        // the subscriber may have gone away already, in which case there is no one to tell
+        let _ = sender
+            .send(gql::http::Response::error(e.to_graphql_error()))
+            .await;
+    }
+}
+
+async fn execute_subscription_internal(
+    http_context: &HttpContext,
+    schema: &gql::schema::Schema<GDS>,
+    session: &Session,
+    request_headers: &reqwest::header::HeaderMap,
+    raw_request: gql::http::RawRequest,
+    poll_interval: Duration,
+    sender: &mpsc::Sender<gql::http::Response>,
+) -> Result<(), RequestError> {
+    // parse the raw request into a GQL query
+    let query = parse_query(&raw_request.query)?;
+
+    // normalize the parsed GQL query
+    let normalized_request = normalize_request(schema, session, &query, raw_request)?;
+
+    // generate IR, which is reused across polls
+    let mut ir = build_ir(schema, session, request_headers, &normalized_request)?;
+
+    // evaluate the predicates across remote relationships once, rather than on every poll: the
+    // join keys they select are those of the target model rows when the subscription starts
+    remote_predicates::resolve_remote_predicates(http_context, &mut ir, None).await?;
+
+    let is_live_query = normalized_request.ty == ast::OperationType::Subscription;
+
+    let mut interval = tokio::time::interval(poll_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut previous_response: Option<Vec<u8>> = None;
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            () = sender.closed() => return Ok(()),
+        }
+
+        // construct a plan to execute the request
+        let request_plan = build_request_plan(&ir)?;
+
+        let execute_query_result = match request_plan {
+            plan::RequestPlan::MutationPlan(mutation_plan) => {
+                plan::execute_mutation_plan(http_context, mutation_plan, None).await
+            }
+            plan::RequestPlan::QueryPlan(query_plan) => {
+                plan::execute_query_plan(http_context, query_plan, None).await
+            }
+        };
+        let response = execute_query_result.to_graphql_response();
+
+        // only push results that changed since the last poll
+        let serialized_response = serde_json::to_vec(&response).ok();
+        if serialized_response.is_none() || serialized_response != previous_response {
+            previous_response = serialized_response;
+            if sender.send(response).await.is_err() {
+                return Ok(());
+            }
+        }
+
+        if !is_live_query {
+            return Ok(());
+        }
+    }
+}
@@ -13,8 +13,29 @@ use crate::ast::executable;
 #[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
 pub struct RawRequest {
     pub operation_name: Option<ast::Name>,
+    /// Empty when the request only refers to a persisted query by its hash.
+    #[serde(default)]
     pub query: String,
     pub variables: Option<HashMap<ast::Name, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<RequestExtensions>,
+}
+
+/// Protocol extensions sent by the client along with the request.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct RequestExtensions {
+    /// Refers to a query by its hash, as in Apollo's automatic persisted queries.
+    /// <https://github.com/apollographql/apollo-link-persisted-queries#protocol>
+    pub persisted_query: Option<PersistedQuery>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct PersistedQuery {
+    pub version: u32,
+    /// The hex-encoded SHA-256 hash of the query.
+    pub sha256_hash: String,
 }
 
 pub struct Request {
@@ -32,7 +53,7 @@ pub type Path = Vec<PathSegment>;
 
 /// A path segment is either a field name or an index into a list.
 /// <https://spec.graphql.org/October2021/#sel-HAPHRPJABABEyoB>
-#[derive(Serialize, Debug, PartialEq)]
+#[derive(Serialize, Debug, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum PathSegment {
     /// Path segment that represent a field.
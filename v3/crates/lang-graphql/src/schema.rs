@@ -2,6 +2,7 @@ use crate::ast::common as ast;
 use crate::ast::common::TypeName;
 use crate::ast::value as gql;
 use crate::mk_name;
+use crate::validation::NamespacedOperationLimits;
 
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -107,6 +108,12 @@ pub trait SchemaContext: std::fmt::Debug + Clone + PartialEq + Serialize {
 
     fn get_schema_entry_point(&self) -> EntryPoint<Self>;
 
+    // Limits on the size and cost of operations, checked after validation. Operations are not
+    // limited by default.
+    fn get_operation_limits(&self) -> NamespacedOperationLimits<Self> {
+        NamespacedOperationLimits::default()
+    }
+
     // type ScalarValue: std::fmt::Debug;
 }
 
@@ -521,6 +528,7 @@ pub struct Schema<S: SchemaContext> {
     pub mutation_type: Option<ast::TypeName>,
     pub subscription_type: Option<ast::TypeName>,
     pub namespaces: HashSet<S::Namespace>,
+    pub operation_limits: NamespacedOperationLimits<S>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -107,6 +107,7 @@ where
         mutation_type: mutation_root_name.map(|v| v.0),
         subscription_type: subscription_root_name.map(|v| v.0),
         namespaces: builder.registered_namespaces,
+        operation_limits: s.get_operation_limits(),
     })
 }
 
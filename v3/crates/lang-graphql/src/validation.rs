@@ -8,22 +8,46 @@ use crate::normalized_ast as normalized;
 use crate::{http, schema};
 
 mod collect;
+mod directives;
 mod error;
 pub mod input;
+mod limits;
 pub mod selection_set;
 
 pub use error::*;
 use indexmap::IndexMap;
 use indexmap::IndexSet;
+pub use limits::{
+    check_operation_limits, CostEstimator, FieldCost, NamespacedOperationLimits, OperationLimits,
+};
 
 pub fn normalize_request<'s, S: schema::SchemaContext, NSGet: schema::NamespacedGetter<S>>(
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
     request: &http::Request,
+) -> Result<normalized::Operation<'s, S>> {
+    normalize_document(
+        namespaced_getter,
+        schema,
+        &request.query,
+        request.operation_name.as_ref(),
+        &request.variables,
+    )
+}
+
+/// Normalizes the operation called `operation_name` in a parsed document. Unlike
+/// `normalize_request`, the document is borrowed, so a document shared between requests
+/// can be normalized without copying it.
+pub fn normalize_document<'s, S: schema::SchemaContext, NSGet: schema::NamespacedGetter<S>>(
+    namespaced_getter: &NSGet,
+    schema: &'s schema::Schema<S>,
+    document: &executable::ExecutableDocument,
+    operation_name: Option<&ast::Name>,
+    variables: &VariableValues,
 ) -> Result<normalized::Operation<'s, S>> {
     let mut fragments = HashMap::new();
     let mut operations = HashMap::new();
-    for definition in &request.query.items {
+    for definition in &document.items {
         match &definition.item {
             executable::ExecutableDefinition::Operation(operation) => {
                 if operations
@@ -41,6 +65,10 @@ pub fn normalize_request<'s, S: schema::SchemaContext, NSGet: schema::Namespaced
                 }
             }
             executable::ExecutableDefinition::Fragment(fragment) => {
+                directives::check_no_directives_allowed(
+                    "fragment definitions",
+                    &fragment.directives,
+                )?;
                 if fragments.insert(&fragment.name.item, fragment).is_some() {
                     return Err(Error::DuplicateFragmentDefinitions {
                         fragment_name: fragment.name.item.clone(),
@@ -64,15 +92,8 @@ pub fn normalize_request<'s, S: schema::SchemaContext, NSGet: schema::Namespaced
         }
     }
     // TODO, lots of validation cases to be handled here
-    let operation_name = request.operation_name.as_ref();
     if let Some(&operation) = operations.get(&operation_name) {
-        normalize_operation(
-            namespaced_getter,
-            schema,
-            &fragments,
-            operation,
-            &request.variables,
-        )
+        normalize_operation(namespaced_getter, schema, &fragments, operation, variables)
     } else if let Some(operation_name) = operation_name {
         Err(Error::OperationNotFound {
             operation_name: operation_name.clone(),
@@ -83,7 +104,7 @@ pub fn normalize_request<'s, S: schema::SchemaContext, NSGet: schema::Namespaced
             schema,
             &fragments,
             operations.values().next().unwrap(),
-            &request.variables,
+            variables,
         )
     } else {
         Err(Error::AnonymousOperationNotFound)
@@ -146,6 +167,7 @@ pub fn normalize_operation<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
     operation: &'q executable::OperationDefinition,
     variable_values: &'q VariableValues,
 ) -> Result<normalized::Operation<'s, S>> {
+    directives::check_no_directives_allowed("operations", &operation.directives)?;
     let mut variables = HashMap::new();
     if let Some(variable_definitions) = &operation.variable_definitions {
         for definition in &variable_definitions.item {
@@ -182,7 +204,7 @@ pub fn normalize_operation<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
             .as_ref()
             .ok_or(Error::NoMutationsAreDefined)?,
         ast::OperationType::Subscription => schema
-            .mutation_type
+            .subscription_type
             .as_ref()
             .ok_or(Error::NoSubscriptionsAreDefined)?,
     };
@@ -203,6 +225,15 @@ pub fn normalize_operation<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
         &selection_set_type_info,
         &operation.selection_set.item,
     )?;
+
+    // A subscription operation must select exactly one root field
+    // <https://spec.graphql.org/October2021/#sec-Single-root-field>
+    if operation.ty == ast::OperationType::Subscription
+        && normalized_selection_set.fields.len() != 1
+    {
+        return Err(Error::SubscriptionMustSelectSingleRootField);
+    }
+
     Ok(normalized::Operation {
         ty: operation.ty,
         name: operation.name.as_ref().map(|name| name.item.clone()),
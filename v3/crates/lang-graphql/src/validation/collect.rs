@@ -3,7 +3,9 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use super::directives;
 use super::error::*;
+use super::input;
 use crate::ast::common as ast;
 use crate::ast::executable;
 use crate::ast::spanning;
@@ -134,6 +136,7 @@ fn collect_fields_from_fragment<
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
     fragments: &HashMap<&'q ast::Name, &'q executable::FragmentDefinition>,
+    variables: &input::value::Variables<'q, 's, S>,
 
     field_path: &Vec<&'s ast::TypeName>,
     selection_type: &SelectableType<'s, S>,
@@ -142,7 +145,10 @@ fn collect_fields_from_fragment<
     fragment_selection_type: &SelectableType<'s, S>,
     fragment_selection_set: &'q executable::SelectionSet,
     fields: &mut Vec<CollectedField<'q, 's, S>>,
-) -> Result<()> {
+) -> Result<()>
+where
+    's: 'q,
+{
     let common_types: HashSet<&ast::TypeName> = selection_type
         .possible_types
         .intersection(&fragment_selection_type.possible_types)
@@ -200,6 +206,7 @@ fn collect_fields_from_fragment<
         namespaced_getter,
         schema,
         fragments,
+        variables,
         &fragment_field_path,
         fragment_selection_type,
         &fragment_reachability,
@@ -211,20 +218,19 @@ fn collect_fields_from_fragment<
 }
 
 #[allow(clippy::too_many_arguments)]
-pub(super) fn collect_fields<
-    'q,
-    's,
-    S: schema::SchemaContext,
-    NSGet: schema::NamespacedGetter<S>,
->(
+pub(super) fn collect_fields<'q, 's, S: schema::SchemaContext, NSGet: schema::NamespacedGetter<S>>(
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
     fragments: &HashMap<&'q ast::Name, &'q executable::FragmentDefinition>,
+    variables: &input::value::Variables<'q, 's, S>,
     field_path: &Vec<&'s ast::TypeName>,
     selection_type: &SelectableType<'s, S>,
     selection_set: &'q [spanning::Spanning<executable::Selection>],
     fields: &mut Vec<CollectedField<'q, 's, S>>,
-) -> Result<()> {
+) -> Result<()>
+where
+    's: 'q,
+{
     // let selection_set_field_path = SelectionSetfield_path::Unconditional {
     //     root_type: selection_type,
     //     reachable_types: selection_type.possible_types.clone(),
@@ -233,6 +239,7 @@ pub(super) fn collect_fields<
         namespaced_getter,
         schema,
         fragments,
+        variables,
         field_path,
         selection_type,
         &selection_type.possible_types,
@@ -247,16 +254,24 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
     namespaced_getter: &NSGet,
     schema: &'s schema::Schema<S>,
     fragments: &HashMap<&'q ast::Name, &'q executable::FragmentDefinition>,
+    variables: &input::value::Variables<'q, 's, S>,
     field_path: &Vec<&'s ast::TypeName>,
     selection_type: &SelectableType<'s, S>,
     selection_set_reachability: &HashSet<&'s ast::TypeName>,
     selection_sub_type: Option<&SelectableType<'s, S>>,
     selection_set: &'q [spanning::Spanning<executable::Selection>],
     fields: &mut Vec<CollectedField<'q, 's, S>>,
-) -> Result<()> {
+) -> Result<()>
+where
+    's: 'q,
+{
     for selection in selection_set {
         match &selection.item {
             executable::Selection::Field(field) => {
+                // selections excluded by `@include` or `@skip` are not collected at all
+                if !directives::is_selection_included(variables, &field.directives)? {
+                    continue;
+                }
                 let field_info =
                     selection_type.lookup_field(namespaced_getter, &field.name.item)?;
                 let alias = &field
@@ -288,6 +303,9 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
                 });
             }
             executable::Selection::FragmentSpread(spread) => {
+                if !directives::is_selection_included(variables, &spread.directives)? {
+                    continue;
+                }
                 let fragment_name = &spread.fragment_name.item;
                 let fragment_definition = fragments
                     .get(&spread.fragment_name.item)
@@ -305,6 +323,7 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
                     namespaced_getter,
                     schema,
                     fragments,
+                    variables,
                     field_path,
                     selection_type,
                     selection_sub_type,
@@ -315,6 +334,9 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
                 )?;
             }
             executable::Selection::InlineFragment(spread) => {
+                if !directives::is_selection_included(variables, &spread.directives)? {
+                    continue;
+                }
                 let fragment_selection_type = match &spread.type_condition {
                     Some(type_condition) => {
                         let fragment_type_name = &type_condition.item.on.item;
@@ -332,6 +354,7 @@ fn collect_fields_internal<'q, 's, S: schema::SchemaContext, NSGet: schema::Name
                     namespaced_getter,
                     schema,
                     fragments,
+                    variables,
                     field_path,
                     selection_type,
                     selection_sub_type,
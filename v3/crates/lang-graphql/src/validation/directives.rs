@@ -0,0 +1,149 @@
+//! Validation and evaluation of executable directives.
+//!
+//! The only executable directives we support are the built-in `@include(if:)` and `@skip(if:)`,
+//! which are allowed on fields, fragment spreads and inline fragments. They are evaluated while
+//! collecting fields, so a skipped selection never makes it into the normalized AST.
+//! <https://spec.graphql.org/October2021/#sec--skip>
+
+use std::collections::HashSet;
+
+use lazy_static::lazy_static;
+
+use super::error::*;
+use super::input;
+use super::input::source::LocationType;
+use crate::ast::common as ast;
+use crate::ast::executable;
+use crate::ast::spanning;
+use crate::ast::value as gql;
+use crate::mk_name;
+use crate::schema;
+
+lazy_static! {
+    static ref INCLUDE: ast::Name = mk_name!("include");
+    static ref SKIP: ast::Name = mk_name!("skip");
+    static ref IF: ast::Name = mk_name!("if");
+    static ref BOOLEAN_NON_NULL: ast::Type =
+        ast::Type::named_non_null(ast::TypeName(mk_name!("Boolean")));
+}
+
+/// Decides whether a field, fragment spread or inline fragment with the given directives should
+/// be included in the response, by evaluating `@include` and `@skip` against the variables.
+pub(super) fn is_selection_included<'q, 's, S: schema::SchemaContext>(
+    variables: &input::value::Variables<'q, 's, S>,
+    directives: &'q [spanning::Spanning<executable::Directive>],
+) -> Result<bool>
+where
+    's: 'q,
+{
+    let mut seen_directives = HashSet::new();
+    let mut included = true;
+    for directive in directives {
+        let directive_name = &directive.item.name.item;
+        if !seen_directives.insert(directive_name) {
+            return Err(Error::DuplicateDirective {
+                directive_name: directive_name.clone(),
+            });
+        }
+        let condition = if *directive_name == *INCLUDE || *directive_name == *SKIP {
+            evaluate_if_argument(variables, &directive.item)?
+        } else {
+            return Err(Error::UnknownDirective {
+                directive_name: directive_name.clone(),
+            });
+        };
+        // a selection is included only if it isn't skipped and all of its `@include` conditions
+        // hold
+        if (*directive_name == *INCLUDE) != condition {
+            included = false;
+        }
+    }
+    Ok(included)
+}
+
+/// Checks that no directives are used at a location which doesn't allow any executable
+/// directives, such as operations and fragment definitions.
+pub(super) fn check_no_directives_allowed(
+    location: &'static str,
+    directives: &[spanning::Spanning<executable::Directive>],
+) -> Result<()> {
+    match directives.first() {
+        None => Ok(()),
+        Some(directive) => {
+            let directive_name = &directive.item.name.item;
+            if *directive_name == *INCLUDE || *directive_name == *SKIP {
+                Err(Error::DirectiveNotAllowed {
+                    directive_name: directive_name.clone(),
+                    location,
+                })
+            } else {
+                Err(Error::UnknownDirective {
+                    directive_name: directive_name.clone(),
+                })
+            }
+        }
+    }
+}
+
+/// Evaluates the required `if: Boolean!` argument of `@include` and `@skip`.
+fn evaluate_if_argument<'q, 's, S: schema::SchemaContext>(
+    variables: &input::value::Variables<'q, 's, S>,
+    directive: &'q executable::Directive,
+) -> Result<bool>
+where
+    's: 'q,
+{
+    let directive_name = &directive.name.item;
+    let mut condition = None;
+    for argument in directive
+        .arguments
+        .iter()
+        .flat_map(|arguments| &arguments.item)
+    {
+        let argument_name = &argument.item.key.item;
+        if *argument_name != *IF {
+            return Err(Error::UnknownDirectiveArgument {
+                directive_name: directive_name.clone(),
+                argument_name: argument_name.clone(),
+            });
+        }
+        if condition.is_some() {
+            return Err(Error::DuplicateDirectiveArgument {
+                directive_name: directive_name.clone(),
+                argument_name: argument_name.clone(),
+            });
+        }
+        condition = Some(&argument.item.value.item);
+    }
+    let condition = condition.ok_or_else(|| Error::RequiredDirectiveArgumentNotFound {
+        directive_name: directive_name.clone(),
+        argument_name: IF.clone(),
+    })?;
+
+    match condition {
+        gql::Value::SimpleValue(gql::SimpleValue::Boolean(value)) => Ok(*value),
+        gql::Value::Variable(variable) => {
+            let value = variables.get_json(
+                &LocationType::Argument {
+                    type_: &BOOLEAN_NON_NULL,
+                    default_value: None,
+                },
+                variable,
+            )?;
+            value
+                .as_bool()
+                .ok_or_else(|| Error::DirectiveArgumentNotBoolean {
+                    directive_name: directive_name.clone(),
+                    argument_name: IF.clone(),
+                    found: value.to_string(),
+                })
+        }
+        gql::Value::SimpleValue(_) | gql::Value::List(_) | gql::Value::Object(_) => {
+            Err(Error::DirectiveArgumentNotBoolean {
+                directive_name: directive_name.clone(),
+                argument_name: IF.clone(),
+                found: condition.kind().to_string(),
+            })
+        }
+    }
+}
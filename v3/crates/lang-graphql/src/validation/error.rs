@@ -127,6 +127,52 @@ pub enum Error {
     NoMutationsAreDefined,
     #[error("no subscriptions are defined in the schema")]
     NoSubscriptionsAreDefined,
+    #[error("subscription operations must select exactly one root field")]
+    SubscriptionMustSelectSingleRootField,
+    #[error("unknown directive: @{directive_name}")]
+    UnknownDirective { directive_name: ast::Name },
+    #[error("the directive @{directive_name} is not allowed on {location}")]
+    DirectiveNotAllowed {
+        directive_name: ast::Name,
+        location: &'static str,
+    },
+    #[error("the directive @{directive_name} can only be used once at this location")]
+    DuplicateDirective { directive_name: ast::Name },
+    #[error("unknown argument '{argument_name}' on directive @{directive_name}")]
+    UnknownDirectiveArgument {
+        directive_name: ast::Name,
+        argument_name: ast::Name,
+    },
+    #[error(
+        "the argument '{argument_name}' is provided more than once on directive @{directive_name}"
+    )]
+    DuplicateDirectiveArgument {
+        directive_name: ast::Name,
+        argument_name: ast::Name,
+    },
+    #[error(
+        "the required argument '{argument_name}' of directive @{directive_name} is not provided"
+    )]
+    RequiredDirectiveArgumentNotFound {
+        directive_name: ast::Name,
+        argument_name: ast::Name,
+    },
+    #[error("the argument '{argument_name}' of directive @{directive_name} expects a value of type Boolean!, but found: {found}")]
+    DirectiveArgumentNotBoolean {
+        directive_name: ast::Name,
+        argument_name: ast::Name,
+        found: String,
+    },
+    #[error(
+        "the operation has a depth of {depth}, which exceeds the maximum depth of {max_depth}"
+    )]
+    MaxDepthExceeded { depth: usize, max_depth: usize },
+    #[error("the operation selects {fields} fields, which exceeds the maximum of {max_fields}")]
+    MaxFieldsExceeded { fields: usize, max_fields: usize },
+    #[error("the operation uses {aliases} aliases, which exceeds the maximum of {max_aliases}")]
+    MaxAliasesExceeded { aliases: usize, max_aliases: usize },
+    #[error("the operation has an estimated cost of {cost}, which exceeds the maximum cost of {max_cost}")]
+    MaxCostExceeded { cost: u64, max_cost: u64 },
     #[error("internal error: selection root is not of object type")]
     InternalSelectionRootIsNotObject,
     #[error("operation not found: {operation_name}")]
@@ -88,6 +88,16 @@ impl<'q, 's, S: schema::SchemaContext> VariableValue<'q, 's, S> {
 }
 
 impl<'q, 's, S: schema::SchemaContext> Variables<'q, 's, S> {
+    /// Resolves the value of a variable used at the given location, falling back to the default
+    /// values of the variable and the location.
+    pub(in crate::validation) fn get_json(
+        &self,
+        location_type: &LocationType<'q, 's>,
+        variable: &ast::Name,
+    ) -> Result<json::Value> {
+        Ok(self.get(location_type, variable)?.into_json())
+    }
+
     fn get(
         &self,
         location_type: &LocationType<'q, 's>,
@@ -0,0 +1,315 @@
+//! Limits on the size and estimated cost of normalized operations.
+//!
+//! These are checked after an operation is normalized, so that fragments have been inlined and
+//! skipped selections removed, but before any work is done to execute it. Introspection root
+//! fields are exempt: the standard introspection query is deeply nested, and its cost does not
+//! depend on any data source.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::*;
+use crate::normalized_ast as normalized;
+use crate::schema::SchemaContext;
+
+/// Limits on the size and estimated cost of an operation. `None` means no limit.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct OperationLimits {
+    /// The maximum nesting depth of selection sets, where root fields are at depth 1.
+    pub max_depth: Option<usize>,
+    /// The maximum number of fields selected.
+    pub max_fields: Option<usize>,
+    /// The maximum number of fields selected under an alias.
+    pub max_aliases: Option<usize>,
+    /// The maximum cost, as estimated by a `CostEstimator`.
+    pub max_cost: Option<u64>,
+}
+
+impl OperationLimits {
+    fn is_unlimited(&self) -> bool {
+        self.max_depth.is_none()
+            && self.max_fields.is_none()
+            && self.max_aliases.is_none()
+            && self.max_cost.is_none()
+    }
+}
+
+/// The operation limits of a schema, which can be overridden per namespace.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct NamespacedOperationLimits<S: SchemaContext> {
+    pub default: OperationLimits,
+    pub namespaced: HashMap<S::Namespace, OperationLimits>,
+}
+
+impl<S: SchemaContext> Default for NamespacedOperationLimits<S> {
+    fn default() -> Self {
+        Self {
+            default: OperationLimits::default(),
+            namespaced: HashMap::new(),
+        }
+    }
+}
+
+impl<S: SchemaContext> NamespacedOperationLimits<S> {
+    pub fn get(&self, namespace: &S::Namespace) -> &OperationLimits {
+        self.namespaced.get(namespace).unwrap_or(&self.default)
+    }
+}
+
+/// The estimated cost of a field.
+pub struct FieldCost<Scope> {
+    /// The cost of evaluating the field once.
+    pub cost: u64,
+    /// How many times the field's selection set is expected to be evaluated each time the field
+    /// is, e.g. the number of rows returned by a field of a list type.
+    pub multiplier: u64,
+    /// The scope in which the field's selection set is estimated.
+    pub scope: Scope,
+}
+
+/// Estimates the cost of fields, for checking `OperationLimits::max_cost`.
+pub trait CostEstimator<'s, S: SchemaContext> {
+    /// What the estimator needs to know about the fields enclosing a selection set, e.g. which
+    /// data source they are fetched from.
+    type Scope;
+
+    /// The scope of the root selection set of an operation.
+    fn root_scope(&self) -> Self::Scope;
+
+    fn estimate_field(
+        &self,
+        scope: &Self::Scope,
+        field: &normalized::Field<'s, S>,
+    ) -> FieldCost<Self::Scope>;
+}
+
+#[derive(Default)]
+struct OperationSize {
+    depth: usize,
+    fields: usize,
+    aliases: usize,
+    cost: u64,
+}
+
+/// Checks that an operation does not exceed any of the given limits.
+pub fn check_operation_limits<'s, S: SchemaContext, C: CostEstimator<'s, S>>(
+    operation: &normalized::Operation<'s, S>,
+    limits: &OperationLimits,
+    cost_estimator: &C,
+) -> Result<()> {
+    if limits.is_unlimited() {
+        return Ok(());
+    }
+
+    let mut size = OperationSize::default();
+    let root_fields = operation
+        .selection_set
+        .fields
+        .values()
+        .filter(|field| !is_introspection_field(field));
+    measure_fields(
+        cost_estimator,
+        &cost_estimator.root_scope(),
+        root_fields,
+        1,
+        1,
+        &mut size,
+    );
+
+    if let Some(max_depth) = limits.max_depth {
+        if size.depth > max_depth {
+            return Err(Error::MaxDepthExceeded {
+                depth: size.depth,
+                max_depth,
+            });
+        }
+    }
+    if let Some(max_fields) = limits.max_fields {
+        if size.fields > max_fields {
+            return Err(Error::MaxFieldsExceeded {
+                fields: size.fields,
+                max_fields,
+            });
+        }
+    }
+    if let Some(max_aliases) = limits.max_aliases {
+        if size.aliases > max_aliases {
+            return Err(Error::MaxAliasesExceeded {
+                aliases: size.aliases,
+                max_aliases,
+            });
+        }
+    }
+    if let Some(max_cost) = limits.max_cost {
+        if size.cost > max_cost {
+            return Err(Error::MaxCostExceeded {
+                cost: size.cost,
+                max_cost,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn measure_fields<'a, 's: 'a, S: SchemaContext, C: CostEstimator<'s, S>>(
+    cost_estimator: &C,
+    scope: &C::Scope,
+    fields: impl Iterator<Item = &'a normalized::Field<'s, S>>,
+    depth: usize,
+    multiplier: u64,
+    size: &mut OperationSize,
+) {
+    for field in fields {
+        size.depth = size.depth.max(depth);
+        size.fields += 1;
+        if field
+            .field_calls
+            .values()
+            .any(|field_call| field_call.name != field.alias.0)
+        {
+            size.aliases += 1;
+        }
+
+        let field_cost = cost_estimator.estimate_field(scope, field);
+        size.cost = size
+            .cost
+            .saturating_add(field_cost.cost.saturating_mul(multiplier));
+        measure_fields(
+            cost_estimator,
+            &field_cost.scope,
+            field.selection_set.fields.values(),
+            depth + 1,
+            multiplier.saturating_mul(field_cost.multiplier),
+            size,
+        );
+    }
+}
+
+fn is_introspection_field<S: SchemaContext>(field: &normalized::Field<'_, S>) -> bool {
+    field
+        .field_calls
+        .values()
+        .all(|field_call| field_call.name.as_str().starts_with("__"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::http::Request;
+    use crate::parser::Parser;
+    use crate::schema::sdl::{SDLNamespacedGetter, SDL};
+    use crate::validation::normalize_request;
+
+    /// Every field with a selection set costs 1, and list fields are assumed to return 5 items.
+    struct TestCostEstimator;
+
+    impl<'s> CostEstimator<'s, SDL> for TestCostEstimator {
+        type Scope = ();
+
+        fn root_scope(&self) {}
+
+        fn estimate_field(&self, _scope: &(), field: &normalized::Field<'s, SDL>) -> FieldCost<()> {
+            FieldCost {
+                cost: u64::from(!field.selection_set.fields.is_empty()),
+                multiplier: if field.type_container.is_list() { 5 } else { 1 },
+                scope: (),
+            }
+        }
+    }
+
+    fn check(query: &str, limits: &OperationLimits) -> Result<()> {
+        let schema = SDL::new(
+            "type Query { author: Author, authors: [Author] }
+             type Author { name: String, articles: [Article] }
+             type Article { title: String, author: Author }",
+        )
+        .and_then(|sdl| sdl.build_schema())
+        .unwrap();
+        let request = Request {
+            operation_name: None,
+            query: Parser::new(query).parse_executable_document().unwrap(),
+            variables: HashMap::new(),
+        };
+        let operation = normalize_request(&SDLNamespacedGetter(), &schema, &request).unwrap();
+        check_operation_limits(&operation, limits, &TestCostEstimator)
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let query = "{ author { articles { author { name } } } }";
+        let limits = |max_depth| OperationLimits {
+            max_depth: Some(max_depth),
+            ..OperationLimits::default()
+        };
+        assert!(check(query, &limits(4)).is_ok());
+        assert!(matches!(
+            check(query, &limits(3)),
+            Err(Error::MaxDepthExceeded {
+                depth: 4,
+                max_depth: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_max_fields_and_aliases() {
+        let query = "{ a: author { name } b: author { n: name } authors { name } }";
+        let limits = OperationLimits {
+            max_fields: Some(6),
+            max_aliases: Some(3),
+            ..OperationLimits::default()
+        };
+        assert!(check(query, &limits).is_ok());
+        assert!(matches!(
+            check(
+                query,
+                &OperationLimits {
+                    max_aliases: Some(2),
+                    ..limits.clone()
+                }
+            ),
+            Err(Error::MaxAliasesExceeded { aliases: 3, .. })
+        ));
+        assert!(matches!(
+            check(
+                query,
+                &OperationLimits {
+                    max_fields: Some(5),
+                    ..limits
+                }
+            ),
+            Err(Error::MaxFieldsExceeded { fields: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn test_max_cost_is_multiplied_by_list_length() {
+        // 1 for `authors`, 5 for `articles` of each author, and 25 for `author` of each article
+        let query = "{ authors { articles { author { name } } } }";
+        let limits = |max_cost| OperationLimits {
+            max_cost: Some(max_cost),
+            ..OperationLimits::default()
+        };
+        assert!(check(query, &limits(31)).is_ok());
+        assert!(matches!(
+            check(query, &limits(30)),
+            Err(Error::MaxCostExceeded {
+                cost: 31,
+                max_cost: 30
+            })
+        ));
+    }
+
+    #[test]
+    fn test_introspection_is_not_limited() {
+        let query = "{ __schema { types { fields { type { ofType { ofType { name } } } } } } }";
+        let limits = OperationLimits {
+            max_depth: Some(1),
+            ..OperationLimits::default()
+        };
+        assert!(check(query, &limits).is_ok());
+    }
+}
@@ -60,12 +60,15 @@ where
     's: 'q,
 {
     let mut fields = Vec::new();
+    let mut has_selection_set = false;
     for (path, selection_sets) in selection_set_groups {
         for selection_set in selection_sets {
+            has_selection_set = true;
             collect::collect_fields(
                 namespaced_getter,
                 schema,
                 fragments,
+                variables,
                 path,
                 selection_type,
                 selection_set,
@@ -131,7 +134,9 @@ where
         None
     };
 
-    if normalized_fields.is_empty() {
+    // A selection set can legitimately end up empty when all of its selections are excluded
+    // by `@include` or `@skip`, so only complain when no selection set was given at all
+    if normalized_fields.is_empty() && !has_selection_set {
         Err(Error::FieldSelectionSetIsEmpty)?;
     }
 
@@ -179,7 +184,6 @@ where
             &cannonical_field.info.generic.arguments,
             &cannonical_field.field.arguments,
         )?;
-        let directives = normalize_directives(schema, &cannonical_field.field.directives)?;
 
         let cannonical_field_type = &cannonical_field.info.generic.field_type;
         if cannonical_field_type != alias_type {
@@ -201,8 +205,6 @@ where
                     field2: field.field.name.item.clone(),
                 });
             }
-            // TODO deal with directives
-            // let this_directives = normalize_directives( schema, &field.field.directives)?;
             let this_arguments = normalize_arguments(
                 namespaced_getter,
                 schema,
@@ -231,7 +233,9 @@ where
                 namespaced: cannonical_field.info.namespaced,
             },
             arguments,
-            directives,
+            // `@include` and `@skip` have already been evaluated when collecting the fields,
+            // and no other directives are allowed on fields
+            directives: IndexMap::new(),
         };
         if cannonical_field.reachable {
             field_calls.insert(reachability.iter().copied().cloned().collect(), field_call);
@@ -395,10 +399,3 @@ fn normalize_arguments<'q, 's, S: schema::SchemaContext, NSGet: schema::Namespac
         })
     }
 }
-
-fn normalize_directives<'s, S: schema::SchemaContext>(
-    _schema: &'s schema::Schema<S>,
-    _directives: &[spanning::Spanning<executable::Directive>],
-) -> Result<IndexMap<ast::Name, normalized::Directive<'s, S>>> {
-    Ok(IndexMap::new())
-}
@@ -264,16 +264,6 @@ pub(crate) fn resolve_value_expression_for_argument(
                     },
                 })?;
 
-            // Get available scalars defined for this data connector
-            let specific_data_connector_scalars = data_connector_scalars
-                .get(data_connector_name)
-                .ok_or(Error::TypePredicateError {
-                type_predicate_error: TypePredicateError::UnknownTypeDataConnector {
-                    type_name: base_type.clone(),
-                    data_connector: data_connector_name.clone(),
-                },
-            })?;
-
             let resolved_model_predicate = resolve_model_predicate_with_type(
                 bool_exp,
                 base_type,
@@ -282,7 +272,7 @@ pub(crate) fn resolve_value_expression_for_argument(
                 data_connector_name,
                 subgraph,
                 data_connectors,
-                specific_data_connector_scalars,
+                data_connector_scalars,
                 object_types,
                 scalar_types,
                 models,
@@ -308,7 +298,10 @@ pub(crate) fn resolve_model_predicate_with_type(
     data_connector_name: &Qualified<DataConnectorName>,
     subgraph: &str,
     data_connectors: &data_connectors::DataConnectors,
-    scalars: &data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    >,
     object_types: &BTreeMap<Qualified<CustomTypeName>, relationships::ObjectTypeWithRelationships>,
     scalar_types: &BTreeMap<Qualified<CustomTypeName>, scalar_types::ScalarTypeRepresentation>,
     models: &IndexMap<Qualified<ModelName>, models::Model>,
@@ -335,6 +328,16 @@ pub(crate) fn resolve_model_predicate_with_type(
             // Determine ndc type of the field
             let field_ndc_type = &field_mapping.column_type;
 
+            // Get available scalars defined for this data connector
+            let scalars = data_connector_scalars
+                .get(data_connector_name)
+                .ok_or_else(|| Error::TypePredicateError {
+                    type_predicate_error: TypePredicateError::UnknownTypeDataConnector {
+                        type_name: type_name.clone(),
+                        data_connector: data_connector_name.clone(),
+                    },
+                })?;
+
             // Get scalar type info from the data connector
             let scalar_type_info =
                 data_connector_scalar_types::get_simple_scalar(field_ndc_type.clone(), scalars)
@@ -437,82 +440,79 @@ pub(crate) fn resolve_model_predicate_with_type(
                             }}
                         })?;
 
-                        // predicates with relationships is currently only supported for local relationships
+                        // predicates over remote relationships are evaluated by the engine, by
+                        // querying the target model ahead of the source model
                         if let Some(target_model_source) = &target_model.source {
-                            if target_model_source.data_connector.name == *data_connector_name {
-                                let target_source =
-                                    model_permissions::ModelTargetSource::from_model_source(
-                                        target_model_source,
-                                        relationship,
-                                    )
-                                    .map_err(|_| {
-                                        Error::RelationshipError {
-                                    relationship_error:
-                                        RelationshipError::NoRelationshipCapabilitiesDefined {
-                                            relationship_name: relationship.name.clone(),
-                                            type_name: type_name.clone(),
-                                            data_connector_name: target_model_source
-                                                .data_connector
-                                                .name
-                                                .clone(),
-                                        },
-                                }
-                                    })?;
-
-                                let target_object_type_representation = object_types
-                                    .get(&target_model.data_type)
-                                    .ok_or(Error::UnknownType {
-                                        data_type: target_model.data_type.clone(),
-                                    })?;
-
-                                // validate data connector name
-                                let data_connector_context = data_connectors
-                                    .0
-                                    .get(data_connector_name)
-                                    .ok_or_else(|| {
-                                        Error::from(TypePredicateError::UnknownTypeDataConnector {
-                                            data_connector: data_connector_name.clone(),
-                                            type_name: type_name.clone(),
-                                        })
-                                    })?;
-
-                                let data_connector_link = data_connectors::DataConnectorLink::new(
-                                    data_connector_name.clone(),
-                                    &data_connector_context.inner,
-                                )?;
-
-                                // look up this type in the context of it's data connector
-                                // so that we use the correct column names for the data source
-                                let data_connector_field_mappings = target_object_type_representation.type_mappings.get(
-                                    &target_source.model.data_connector.name,
-                                    DataConnectorObjectType::ref_cast(&target_source.model.collection)
+                            let target_source =
+                                model_permissions::ModelTargetSource::from_model_source(
+                                    target_model_source,
+                                    relationship,
                                 )
-                                .map(|type_mapping| match type_mapping {
-                                    object_types::TypeMapping::Object {
-                                        field_mappings, ..
-                                    } => field_mappings,
-                                })
-                                .ok_or(Error::DataConnectorTypeMappingValidationError {
-                                    type_name: target_typename.clone(),
-                                    error: TypeMappingValidationError::DataConnectorTypeMappingNotFound {
-                                        object_type_name: target_typename.clone(),
-                                        data_connector_name: target_source.model.data_connector.name.clone(),
-                                        data_connector_object_type: DataConnectorObjectType(target_source.model.collection
-                                            .clone())
-                                    },
+                                .map_err(|_| {
+                                    Error::RelationshipError {
+                                        relationship_error:
+                                            RelationshipError::NoRelationshipCapabilitiesDefined {
+                                                relationship_name: relationship.name.clone(),
+                                                type_name: type_name.clone(),
+                                                data_connector_name: target_model_source
+                                                    .data_connector
+                                                    .name
+                                                    .clone(),
+                                            },
+                                    }
                                 })?;
 
-                                // Collect type mappings.
-                                let mut source_type_mappings = BTreeMap::new();
+                            let target_object_type_representation = object_types
+                                .get(&target_model.data_type)
+                                .ok_or(Error::UnknownType {
+                                    data_type: target_model.data_type.clone(),
+                                })?;
 
-                                // get names of collections we want to lookup
-                                let collection_types = object_type_representation
-                                    .type_mappings
-                                    .object_types_for_data_connector(data_connector_name);
+                            // validate data connector name
+                            let data_connector_context =
+                                data_connectors.0.get(data_connector_name).ok_or_else(|| {
+                                    Error::from(TypePredicateError::UnknownTypeDataConnector {
+                                        data_connector: data_connector_name.clone(),
+                                        type_name: type_name.clone(),
+                                    })
+                                })?;
+
+                            let data_connector_link = data_connectors::DataConnectorLink::new(
+                                data_connector_name.clone(),
+                                &data_connector_context.inner,
+                            )?;
+
+                            // look up this type in the context of it's data connector
+                            // so that we use the correct column names for the data source
+                            let data_connector_field_mappings = target_object_type_representation.type_mappings.get(
+                                &target_source.model.data_connector.name,
+                                DataConnectorObjectType::ref_cast(&target_source.model.collection)
+                            )
+                            .map(|type_mapping| match type_mapping {
+                                object_types::TypeMapping::Object {
+                                    field_mappings, ..
+                                } => field_mappings,
+                            })
+                            .ok_or(Error::DataConnectorTypeMappingValidationError {
+                                type_name: target_typename.clone(),
+                                error: TypeMappingValidationError::DataConnectorTypeMappingNotFound {
+                                    object_type_name: target_typename.clone(),
+                                    data_connector_name: target_source.model.data_connector.name.clone(),
+                                    data_connector_object_type: DataConnectorObjectType(target_source.model.collection
+                                        .clone())
+                                },
+                            })?;
 
-                                let type_mappings_to_collect: Vec<
-                                    type_mappings::TypeMappingToCollect,
-                                > = collection_types
+                            // Collect type mappings.
+                            let mut source_type_mappings = BTreeMap::new();
+
+                            // get names of collections we want to lookup
+                            let collection_types = object_type_representation
+                                .type_mappings
+                                .object_types_for_data_connector(data_connector_name);
+
+                            let type_mappings_to_collect: Vec<type_mappings::TypeMappingToCollect> =
+                                collection_types
                                     .iter()
                                     .map(|collection_type| type_mappings::TypeMappingToCollect {
                                         type_name,
@@ -520,68 +520,60 @@ pub(crate) fn resolve_model_predicate_with_type(
                                     })
                                     .collect();
 
-                                for type_mapping_to_collect in type_mappings_to_collect {
-                                    type_mappings::collect_type_mapping_for_source(
-                                        &type_mapping_to_collect,
-                                        data_connector_name,
-                                        &remove_object_relationships(object_types),
-                                        scalar_types,
-                                        &mut source_type_mappings,
-                                    )
-                                    .map_err(|error| {
-                                        Error::from(
-                                            TypePredicateError::TypeMappingCollectionError {
-                                                type_name: type_name.clone(),
-                                                error,
-                                            },
-                                        )
-                                    })?;
-                                }
-
-                                let annotation = model_permissions::PredicateRelationshipInfo {
-                                    source_type: relationship.source.clone(),
-                                    relationship_name: relationship.name.clone(),
-                                    target_model_name: model_name.clone(),
-                                    target_source: target_source.clone(),
-                                    target_type: target_typename.clone(),
-                                    relationship_type: relationship_type.clone(),
-                                    mappings: mappings.clone(),
-                                    source_data_connector: data_connector_link,
-                                    source_type_mappings,
-                                };
-
-                                let target_object_type =
-                                    object_types.get(&target_model.data_type).ok_or_else(|| {
-                                        Error::from(TypePredicateError::ObjectTypeNotFound {
-                                            type_name: target_model.data_type.clone(),
-                                        })
-                                    })?;
-
-                                let target_model_predicate = resolve_model_predicate_with_type(
-                                    nested_predicate,
-                                    &target_model.data_type,
-                                    target_object_type,
-                                    data_connector_field_mappings,
+                            for type_mapping_to_collect in type_mappings_to_collect {
+                                type_mappings::collect_type_mapping_for_source(
+                                    &type_mapping_to_collect,
                                     data_connector_name,
-                                    subgraph,
-                                    data_connectors,
-                                    scalars,
-                                    object_types,
+                                    &remove_object_relationships(object_types),
                                     scalar_types,
-                                    models,
-                                    &target_model.type_fields,
-                                )?;
-
-                                Ok(model_permissions::ModelPredicate::Relationship {
-                                    relationship_info: annotation,
-                                    predicate: Box::new(target_model_predicate),
-                                })
-                            } else {
-                                Err(Error::UnsupportedFeature {
-                                    message: "Predicate cannot be built using remote relationships"
-                                        .to_string(),
-                                })
+                                    &mut source_type_mappings,
+                                )
+                                .map_err(|error| {
+                                    Error::from(TypePredicateError::TypeMappingCollectionError {
+                                        type_name: type_name.clone(),
+                                        error,
+                                    })
+                                })?;
                             }
+
+                            let annotation = model_permissions::PredicateRelationshipInfo {
+                                source_type: relationship.source.clone(),
+                                relationship_name: relationship.name.clone(),
+                                target_model_name: model_name.clone(),
+                                target_source: target_source.clone(),
+                                target_type: target_typename.clone(),
+                                relationship_type: relationship_type.clone(),
+                                mappings: mappings.clone(),
+                                source_data_connector: data_connector_link,
+                                source_type_mappings,
+                            };
+
+                            let target_object_type =
+                                object_types.get(&target_model.data_type).ok_or_else(|| {
+                                    Error::from(TypePredicateError::ObjectTypeNotFound {
+                                        type_name: target_model.data_type.clone(),
+                                    })
+                                })?;
+
+                            let target_model_predicate = resolve_model_predicate_with_type(
+                                nested_predicate,
+                                &target_model.data_type,
+                                target_object_type,
+                                data_connector_field_mappings,
+                                &target_source.model.data_connector.name,
+                                subgraph,
+                                data_connectors,
+                                data_connector_scalars,
+                                object_types,
+                                scalar_types,
+                                models,
+                                &target_model.type_fields,
+                            )?;
+
+                            Ok(model_permissions::ModelPredicate::Relationship {
+                                relationship_info: annotation,
+                                predicate: Box::new(target_model_predicate),
+                            })
                         } else {
                             Err(Error::from(
                                 TypePredicateError::TargetSourceRequiredForRelationshipPredicate {
@@ -611,7 +603,7 @@ pub(crate) fn resolve_model_predicate_with_type(
                 data_connector_name,
                 subgraph,
                 data_connectors,
-                scalars,
+                data_connector_scalars,
                 object_types,
                 scalar_types,
                 models,
@@ -632,7 +624,7 @@ pub(crate) fn resolve_model_predicate_with_type(
                     data_connector_name,
                     subgraph,
                     data_connectors,
-                    scalars,
+                    data_connector_scalars,
                     object_types,
                     scalar_types,
                     models,
@@ -652,7 +644,7 @@ pub(crate) fn resolve_model_predicate_with_type(
                     data_connector_name,
                     subgraph,
                     data_connectors,
-                    scalars,
+                    data_connector_scalars,
                     object_types,
                     scalar_types,
                     models,
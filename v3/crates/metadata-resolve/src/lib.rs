@@ -28,11 +28,14 @@ pub use stages::command_permissions::CommandWithPermissions;
 pub use stages::commands::Command;
 pub use stages::data_connectors::DataConnectorLink;
 pub use stages::model_permissions::{
-    FilterPermission, ModelPredicate, ModelTargetSource, ModelWithPermissions,
+    DeletePermission, FilterPermission, InsertPermission, ModelPredicate, ModelTargetSource,
+    ModelWithPermissions, UpdatePermission,
 };
 pub use stages::models::{
-    ConnectorArgumentName, Model, ModelExpressionType, ModelOrderByExpression, ModelSource,
-    SelectAggregateGraphQlDefinition, SelectManyGraphQlDefinition, SelectUniqueGraphQlDefinition,
+    ConnectorArgumentName, DeleteMutationGraphQlDefinition, InsertMutationGraphQlDefinition, Model,
+    ModelExpressionType, ModelOrderByExpression, ModelSource, SelectAggregateGraphQlDefinition,
+    SelectManyGraphQlDefinition, SelectUniqueGraphQlDefinition, UniqueIdentifierField,
+    UpdateMutationGraphQlDefinition,
 };
 pub use stages::object_boolean_expressions::{
     ObjectBooleanExpressionDataConnector, ObjectBooleanExpressionType,
@@ -41,9 +44,11 @@ pub use stages::object_types::{
     FieldMapping, ObjectTypeRepresentation, ResolvedObjectApolloFederationConfig, TypeMapping,
 };
 pub use stages::relationships::{
-    relationship_execution_category, ObjectTypeWithRelationships, Relationship,
-    RelationshipCapabilities, RelationshipCommandMapping, RelationshipExecutionCategory,
-    RelationshipModelMapping, RelationshipTarget,
+    relationship_execution_category, relationship_predicate_execution_category,
+    ObjectTypeWithRelationships, Relationship, RelationshipCapabilities,
+    RelationshipCommandMapping, RelationshipExecutionCategory, RelationshipFieldPath,
+    RelationshipMappingSource, RelationshipModelMapping, RelationshipModelMappingFieldTarget,
+    RelationshipModelMappingTarget, RelationshipTarget,
 };
 pub use stages::scalar_types::ScalarTypeRepresentation;
 pub use stages::type_permissions::TypeInputPermission;
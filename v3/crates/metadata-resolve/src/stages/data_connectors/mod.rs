@@ -5,7 +5,7 @@ mod types;
 use std::collections::BTreeMap;
 pub use types::{
     ArgumentPreset, ComparisonOperators, DataConnectorCapabilities, DataConnectorContext,
-    DataConnectorLink, DataConnectorSchema, DataConnectors,
+    DataConnectorLink, DataConnectorSchema, DataConnectors, ScalarTypeInfo,
 };
 
 /// Resolve data connectors.
@@ -13,8 +13,8 @@ use open_dds::data_connector::SchemaAndCapabilitiesV01;
 use open_dds::{
     commands::{FunctionName, ProcedureName},
     data_connector::{
-        self, DataConnectorName, DataConnectorScalarType, DataConnectorUrl, ReadWriteUrls,
-        VersionedSchemaAndCapabilities,
+        self, DataConnectorName, DataConnectorRequestOptions, DataConnectorScalarType,
+        DataConnectorUrl, ReadWriteUrls, VersionedSchemaAndCapabilities,
     },
 };
 use serde::{Deserialize, Serialize};
@@ -65,6 +65,7 @@ pub struct DataConnectorCoreInfo<'a> {
     pub capabilities: &'a ndc_models::CapabilitiesResponse,
     pub argument_presets: Vec<ArgumentPreset>,
     pub response_headers: Option<ResponseHeaders>,
+    pub request_options: Option<&'a DataConnectorRequestOptions>,
 }
 
 impl<'a> DataConnectorCoreInfo<'a> {
@@ -108,6 +109,7 @@ impl<'a> DataConnectorCoreInfo<'a> {
             capabilities: &schema_and_capabilities.capabilities,
             argument_presets,
             response_headers,
+            request_options: data_connector.request_options.as_ref(),
         })
     }
 }
@@ -220,6 +222,10 @@ pub struct DataConnectorLink {
     /// function/procedure to the client.
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub response_headers: Option<ResponseHeaders>,
+    /// Timeouts, size limits and retries for requests to the data connector,
+    /// overriding the engine's defaults.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub request_options: Option<DataConnectorRequestOptions>,
     pub capabilities: DataConnectorCapabilities,
 }
 
@@ -279,6 +285,12 @@ impl DataConnectorLink {
                 .mutation
                 .explain
                 .is_some(),
+            supports_transactional_mutations: info
+                .capabilities
+                .capabilities
+                .mutation
+                .transactional
+                .is_some(),
             supports_nested_object_aggregations: info
                 .capabilities
                 .capabilities
@@ -294,6 +306,7 @@ impl DataConnectorLink {
             capabilities,
             argument_presets: info.argument_presets.clone(),
             response_headers: info.response_headers.clone(),
+            request_options: info.request_options.cloned(),
         })
     }
 }
@@ -439,6 +452,7 @@ impl ResponseHeaders {
 pub struct DataConnectorCapabilities {
     pub supports_explaining_queries: bool,
     pub supports_explaining_mutations: bool,
+    pub supports_transactional_mutations: bool,
     pub supports_nested_object_aggregations: bool,
 }
 
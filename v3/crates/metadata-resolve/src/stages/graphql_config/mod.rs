@@ -1,12 +1,14 @@
 //! This is where we will resolve graphql configuration
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use crate::helpers::types::mk_name;
 use crate::types::error::{Error, GraphqlConfigError};
 use lang_graphql::ast::common as ast;
+use lang_graphql::validation::OperationLimits;
 use open_dds::accessor::QualifiedObject;
 use open_dds::graphql_config::{self, OrderByDirection};
+use open_dds::permissions::Role;
 use open_dds::types::GraphQlFieldName;
 use serde::{Deserialize, Serialize};
 
@@ -51,7 +53,9 @@ lazy_static::lazy_static! {
         mutation: graphql_config::MutationGraphqlConfig{
             root_operation_type_name: "Mutation".to_string(),
         },
+        subscription: None,
         apollo_federation: None,
+        operation_limits: None,
     });
 }
 
@@ -106,8 +110,17 @@ pub fn resolve(
 pub struct GlobalGraphqlConfig {
     pub query_root_type_name: ast::TypeName,
     pub mutation_root_type_name: ast::TypeName,
+    pub subscription_root_type_name: Option<ast::TypeName>,
     pub order_by_input: Option<OrderByInputGraphqlConfig>,
     pub enable_apollo_federation_fields: bool,
+    pub operation_limits: OperationLimitsConfig,
+}
+
+/// Limits on the size and estimated cost of operations, which are unlimited by default.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct OperationLimitsConfig {
+    pub default: OperationLimits,
+    pub role_overrides: BTreeMap<Role, OperationLimits>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -209,6 +222,13 @@ pub fn resolve_graphql_config(
                     .root_operation_type_name
                     .as_str(),
             )?);
+            let subscription_root_type_name = graphql_config_metadata
+                .subscription
+                .as_ref()
+                .map(|subscription| {
+                    mk_name(subscription.root_operation_type_name.as_str()).map(ast::TypeName)
+                })
+                .transpose()?;
 
             let order_by_input = match &graphql_config_metadata.query.order_by_input {
                 None => None,
@@ -286,6 +306,13 @@ pub fn resolve_graphql_config(
                 .as_ref()
                 .is_some_and(|federation_config| federation_config.enable_root_fields);
 
+            let operation_limits = graphql_config_metadata
+                .operation_limits
+                .as_ref()
+                .map(resolve_operation_limits_config)
+                .transpose()?
+                .unwrap_or_default();
+
             Ok(GraphqlConfig {
                 query: QueryGraphqlConfig {
                     arguments_field_name,
@@ -298,10 +325,47 @@ pub fn resolve_graphql_config(
                 global: GlobalGraphqlConfig {
                     query_root_type_name,
                     mutation_root_type_name,
+                    subscription_root_type_name,
                     order_by_input,
                     enable_apollo_federation_fields,
+                    operation_limits,
                 },
             })
         }
     }
 }
+
+fn resolve_operation_limits_config(
+    operation_limits: &graphql_config::OperationLimitsGraphqlConfig,
+) -> Result<OperationLimitsConfig, Error> {
+    let mut role_overrides = BTreeMap::new();
+    for role_operation_limits in &operation_limits.role_overrides {
+        if role_overrides
+            .insert(
+                role_operation_limits.role.clone(),
+                resolve_operation_limits(&role_operation_limits.limits),
+            )
+            .is_some()
+        {
+            return Err(Error::GraphqlConfigError {
+                graphql_config_error: GraphqlConfigError::DuplicateOperationLimitsForRole {
+                    role: role_operation_limits.role.clone(),
+                },
+            });
+        }
+    }
+    Ok(OperationLimitsConfig {
+        default: resolve_operation_limits(&operation_limits.default),
+        role_overrides,
+    })
+}
+
+fn resolve_operation_limits(operation_limits: &graphql_config::OperationLimits) -> OperationLimits {
+    let to_usize = |limit: u32| usize::try_from(limit).unwrap_or(usize::MAX);
+    OperationLimits {
+        max_depth: operation_limits.max_depth.map(to_usize),
+        max_fields: operation_limits.max_fields.map(to_usize),
+        max_aliases: operation_limits.max_aliases.map(to_usize),
+        max_cost: operation_limits.max_cost.map(u64::from),
+    }
+}
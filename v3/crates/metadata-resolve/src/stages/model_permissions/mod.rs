@@ -8,19 +8,20 @@ use indexmap::IndexMap;
 use open_dds::{data_connector::DataConnectorName, models::ModelName, types::CustomTypeName};
 use std::collections::BTreeMap;
 pub use types::{
-    FilterPermission, ModelPredicate, ModelTargetSource, ModelWithPermissions,
-    PredicateRelationshipInfo, SelectPermission,
+    DeletePermission, FilterPermission, InsertPermission, ModelPredicate, ModelTargetSource,
+    ModelWithPermissions, PredicateRelationshipInfo, SelectPermission, UpdatePermission,
 };
 
 use crate::helpers::argument::{
     resolve_model_predicate_with_type, resolve_value_expression_for_argument,
 };
-use crate::types::error::{Error, TypePredicateError};
+use crate::types::error::Error;
+use crate::types::permission::ValueExpression;
 
-use crate::types::subgraph::Qualified;
+use crate::types::subgraph::{Qualified, QualifiedTypeReference};
 
 use ndc_models;
-use open_dds::permissions::NullableModelPredicate;
+use open_dds::permissions::{FieldPreset, NullableModelPredicate};
 use open_dds::{
     arguments::ArgumentName,
     permissions::{ModelPermissionsV1, Role},
@@ -53,6 +54,9 @@ pub fn resolve(
                 ModelWithPermissions {
                     model: model.clone(),
                     select_permissions: BTreeMap::new(),
+                    insert_permissions: BTreeMap::new(),
+                    update_permissions: BTreeMap::new(),
+                    delete_permissions: BTreeMap::new(),
                 },
             )
         })
@@ -73,7 +77,11 @@ pub fn resolve(
                 model_name: model_name.clone(),
             })?;
 
-        if model.select_permissions.is_empty() {
+        if model.select_permissions.is_empty()
+            && model.insert_permissions.is_empty()
+            && model.update_permissions.is_empty()
+            && model.delete_permissions.is_empty()
+        {
             let select_permissions = resolve_model_select_permissions(
                 &model.model,
                 subgraph,
@@ -87,8 +95,38 @@ pub fn resolve(
             )?;
 
             model.select_permissions = select_permissions;
+            model.insert_permissions = resolve_model_insert_permissions(
+                &model.model,
+                subgraph,
+                permissions,
+                data_connectors,
+                data_connector_scalars,
+                object_types,
+                scalar_types,
+                models,
+            )?;
+            model.update_permissions = resolve_model_update_permissions(
+                &model.model,
+                subgraph,
+                permissions,
+                data_connectors,
+                data_connector_scalars,
+                object_types,
+                scalar_types,
+                models,
+            )?;
+            model.delete_permissions = resolve_model_delete_permissions(
+                &model.model,
+                subgraph,
+                permissions,
+                data_connectors,
+                data_connector_scalars,
+                object_types,
+                scalar_types,
+                models,
+            )?;
         } else {
-            return Err(Error::DuplicateModelSelectPermission {
+            return Err(Error::DuplicateModelPermissions {
                 model_name: model_name.clone(),
             });
         }
@@ -119,17 +157,6 @@ fn resolve_model_predicate_with_model(
 
     let data_connector_name = &model_source.data_connector.name;
 
-    // get available scalars defined for this data connector
-    let scalars =
-        data_connector_scalars
-            .get(data_connector_name)
-            .ok_or(Error::TypePredicateError {
-                type_predicate_error: TypePredicateError::UnknownTypeDataConnector {
-                    type_name: model.data_type.clone(),
-                    data_connector: data_connector_name.clone(),
-                },
-            })?;
-
     // get the type that the expression is based on
     let object_type_representation =
         object_types
@@ -156,7 +183,7 @@ fn resolve_model_predicate_with_model(
         data_connector_name,
         subgraph,
         data_connectors,
-        scalars,
+        data_connector_scalars,
         object_types,
         scalar_types,
         models,
@@ -164,6 +191,207 @@ fn resolve_model_predicate_with_model(
     )
 }
 
+fn resolve_filter_permission(
+    nullable_model_predicate: &NullableModelPredicate,
+    model: &models::Model,
+    subgraph: &str,
+    data_connectors: &data_connectors::DataConnectors,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    >,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, relationships::ObjectTypeWithRelationships>,
+    scalar_types: &BTreeMap<Qualified<CustomTypeName>, scalar_types::ScalarTypeRepresentation>,
+    models: &IndexMap<Qualified<ModelName>, models::Model>,
+) -> Result<FilterPermission, Error> {
+    match nullable_model_predicate {
+        NullableModelPredicate::NotNull(model_predicate) => resolve_model_predicate_with_model(
+            model_predicate,
+            model,
+            subgraph,
+            data_connectors,
+            data_connector_scalars,
+            &model.type_fields,
+            object_types,
+            scalar_types,
+            models,
+        )
+        .map(FilterPermission::Filter),
+        NullableModelPredicate::Null(()) => Ok(FilterPermission::AllowAll),
+    }
+}
+
+/// Resolve the values preset for fields of the rows written to a model. Boolean expressions are
+/// only meaningful as arguments, so only literals and session variables can be preset.
+fn resolve_model_field_presets(
+    model: &models::Model,
+    field_presets: &[FieldPreset],
+) -> Result<BTreeMap<FieldName, (QualifiedTypeReference, ValueExpression)>, Error> {
+    let mut resolved_field_presets = BTreeMap::new();
+    for FieldPreset {
+        field: field_name,
+        value,
+    } in field_presets
+    {
+        if resolved_field_presets.contains_key(field_name) {
+            return Err(Error::DuplicateModelFieldPreset {
+                model_name: model.name.clone(),
+                field_name: field_name.clone(),
+            });
+        }
+        let field_definition =
+            model
+                .type_fields
+                .get(field_name)
+                .ok_or_else(|| Error::UnknownFieldInModelFieldPreset {
+                    model_name: model.name.clone(),
+                    field_name: field_name.clone(),
+                })?;
+        typecheck::typecheck_value_expression(&field_definition.field_type, value).map_err(
+            |type_error| Error::ModelFieldPresetTypeError {
+                model_name: model.name.clone(),
+                field_name: field_name.clone(),
+                type_error,
+            },
+        )?;
+        let value_expression = match value {
+            open_dds::permissions::ValueExpression::Literal(value) => {
+                ValueExpression::Literal(value.clone())
+            }
+            open_dds::permissions::ValueExpression::SessionVariable(session_variable) => {
+                ValueExpression::SessionVariable(session_variable.clone())
+            }
+            open_dds::permissions::ValueExpression::BooleanExpression(_) => {
+                return Err(Error::BooleanExpressionInModelFieldPreset {
+                    model_name: model.name.clone(),
+                    field_name: field_name.clone(),
+                });
+            }
+        };
+        resolved_field_presets.insert(
+            field_name.clone(),
+            (field_definition.field_type.clone(), value_expression),
+        );
+    }
+    Ok(resolved_field_presets)
+}
+
+pub fn resolve_model_insert_permissions(
+    model: &models::Model,
+    subgraph: &str,
+    model_permissions: &ModelPermissionsV1,
+    data_connectors: &data_connectors::DataConnectors,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    >,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, relationships::ObjectTypeWithRelationships>,
+    scalar_types: &BTreeMap<Qualified<CustomTypeName>, scalar_types::ScalarTypeRepresentation>,
+    models: &IndexMap<Qualified<ModelName>, models::Model>,
+) -> Result<BTreeMap<Role, InsertPermission>, Error> {
+    let mut validated_permissions = BTreeMap::new();
+    for model_permission in &model_permissions.permissions {
+        if let Some(insert) = &model_permission.insert {
+            let check = resolve_filter_permission(
+                &insert.check,
+                model,
+                subgraph,
+                data_connectors,
+                data_connector_scalars,
+                object_types,
+                scalar_types,
+                models,
+            )?;
+            let resolved_permission = InsertPermission {
+                check,
+                field_presets: resolve_model_field_presets(model, &insert.field_presets)?,
+            };
+            validated_permissions.insert(model_permission.role.clone(), resolved_permission);
+        }
+    }
+    Ok(validated_permissions)
+}
+
+pub fn resolve_model_update_permissions(
+    model: &models::Model,
+    subgraph: &str,
+    model_permissions: &ModelPermissionsV1,
+    data_connectors: &data_connectors::DataConnectors,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    >,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, relationships::ObjectTypeWithRelationships>,
+    scalar_types: &BTreeMap<Qualified<CustomTypeName>, scalar_types::ScalarTypeRepresentation>,
+    models: &IndexMap<Qualified<ModelName>, models::Model>,
+) -> Result<BTreeMap<Role, UpdatePermission>, Error> {
+    let mut validated_permissions = BTreeMap::new();
+    for model_permission in &model_permissions.permissions {
+        if let Some(update) = &model_permission.update {
+            let filter = resolve_filter_permission(
+                &update.filter,
+                model,
+                subgraph,
+                data_connectors,
+                data_connector_scalars,
+                object_types,
+                scalar_types,
+                models,
+            )?;
+            let check = resolve_filter_permission(
+                &update.check,
+                model,
+                subgraph,
+                data_connectors,
+                data_connector_scalars,
+                object_types,
+                scalar_types,
+                models,
+            )?;
+            let resolved_permission = UpdatePermission {
+                filter,
+                check,
+                field_presets: resolve_model_field_presets(model, &update.field_presets)?,
+            };
+            validated_permissions.insert(model_permission.role.clone(), resolved_permission);
+        }
+    }
+    Ok(validated_permissions)
+}
+
+pub fn resolve_model_delete_permissions(
+    model: &models::Model,
+    subgraph: &str,
+    model_permissions: &ModelPermissionsV1,
+    data_connectors: &data_connectors::DataConnectors,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    >,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, relationships::ObjectTypeWithRelationships>,
+    scalar_types: &BTreeMap<Qualified<CustomTypeName>, scalar_types::ScalarTypeRepresentation>,
+    models: &IndexMap<Qualified<ModelName>, models::Model>,
+) -> Result<BTreeMap<Role, DeletePermission>, Error> {
+    let mut validated_permissions = BTreeMap::new();
+    for model_permission in &model_permissions.permissions {
+        if let Some(delete) = &model_permission.delete {
+            let filter = resolve_filter_permission(
+                &delete.filter,
+                model,
+                subgraph,
+                data_connectors,
+                data_connector_scalars,
+                object_types,
+                scalar_types,
+                models,
+            )?;
+            validated_permissions
+                .insert(model_permission.role.clone(), DeletePermission { filter });
+        }
+    }
+    Ok(validated_permissions)
+}
+
 // get the ndc_models::Type for an argument if it is available
 fn get_model_source_argument<'a>(
     argument_name: &'a ArgumentName,
@@ -201,23 +429,16 @@ pub fn resolve_model_select_permissions(
     let mut validated_permissions = BTreeMap::new();
     for model_permission in &model_permissions.permissions {
         if let Some(select) = &model_permission.select {
-            let resolved_predicate = match &select.filter {
-                NullableModelPredicate::NotNull(model_predicate) => {
-                    resolve_model_predicate_with_model(
-                        model_predicate,
-                        model,
-                        subgraph,
-                        data_connectors,
-                        data_connector_scalars,
-                        &model.type_fields,
-                        object_types,
-                        scalar_types,
-                        models,
-                    )
-                    .map(FilterPermission::Filter)?
-                }
-                NullableModelPredicate::Null(()) => FilterPermission::AllowAll,
-            };
+            let resolved_predicate = resolve_filter_permission(
+                &select.filter,
+                model,
+                subgraph,
+                data_connectors,
+                data_connector_scalars,
+                object_types,
+                scalar_types,
+                models,
+            )?;
 
             let mut argument_presets = BTreeMap::new();
 
@@ -289,6 +510,8 @@ pub fn resolve_model_select_permissions(
             let resolved_permission = SelectPermission {
                 filter: resolved_predicate.clone(),
                 argument_presets,
+                limit: select.limit,
+                allow_aggregations: select.allow_aggregations.unwrap_or(true),
             };
             validated_permissions.insert(model_permission.role.clone(), resolved_permission);
         }
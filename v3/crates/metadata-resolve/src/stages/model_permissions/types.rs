@@ -22,6 +22,9 @@ use serde::{Deserialize, Serialize};
 pub struct ModelWithPermissions {
     pub model: models::Model,
     pub select_permissions: BTreeMap<Role, SelectPermission>,
+    pub insert_permissions: BTreeMap<Role, InsertPermission>,
+    pub update_permissions: BTreeMap<Role, UpdatePermission>,
+    pub delete_permissions: BTreeMap<Role, DeletePermission>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -33,8 +36,33 @@ pub enum FilterPermission {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct SelectPermission {
     pub filter: FilterPermission,
-    // pub allow_aggregations: bool,
     pub argument_presets: BTreeMap<ArgumentName, (QualifiedTypeReference, ValueExpression)>,
+    /// The maximum number of rows that can be selected
+    pub limit: Option<u32>,
+    /// Whether aggregates of the model can be selected
+    pub allow_aggregations: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InsertPermission {
+    /// The predicate every inserted row must satisfy
+    pub check: FilterPermission,
+    pub field_presets: BTreeMap<FieldName, (QualifiedTypeReference, ValueExpression)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UpdatePermission {
+    /// The predicate selecting the rows that can be updated
+    pub filter: FilterPermission,
+    /// The predicate every row must satisfy once it is updated
+    pub check: FilterPermission,
+    pub field_presets: BTreeMap<FieldName, (QualifiedTypeReference, ValueExpression)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DeletePermission {
+    /// The predicate selecting the rows that can be deleted
+    pub filter: FilterPermission,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -3,11 +3,12 @@ use open_dds::data_connector::{
     DataConnectorName, DataConnectorObjectType, DataConnectorScalarType,
 };
 pub use types::{
-    ConnectorArgumentName, LimitFieldGraphqlConfig, Model, ModelExpressionType, ModelGraphQlApi,
+    ConnectorArgumentName, DeleteMutationGraphQlDefinition, InsertMutationGraphQlDefinition,
+    LimitFieldGraphqlConfig, Model, ModelExpressionType, ModelGraphQlApi,
     ModelGraphqlApiArgumentsConfig, ModelOrderByExpression, ModelSource, ModelsOutput,
     NDCFieldSourceMapping, OffsetFieldGraphqlConfig, OrderByExpressionInfo,
     SelectAggregateGraphQlDefinition, SelectManyGraphQlDefinition, SelectUniqueGraphQlDefinition,
-    UniqueIdentifierField,
+    UniqueIdentifierField, UpdateMutationGraphQlDefinition,
 };
 mod types;
 
@@ -30,8 +31,10 @@ use indexmap::IndexMap;
 use lang_graphql::ast::common::{self as ast};
 
 use open_dds::{
+    commands::ProcedureName,
     models::{
-        self, EnableAllOrSpecific, ModelGraphQlDefinition, ModelName, ModelV1, OrderableField,
+        self, EnableAllOrSpecific, ModelGraphQlDefinition, ModelName,
+        ModelPointMutationsGraphQlDefinition, ModelV1, OrderableField,
     },
     types::{CustomTypeName, FieldName},
 };
@@ -137,6 +140,7 @@ pub fn resolve(
                 model_graphql_definition,
                 &mut resolved_model,
                 &mut graphql_types,
+                data_connectors,
                 data_connector_scalars,
                 &model.description,
                 &qualified_aggregate_expression_name,
@@ -637,6 +641,7 @@ fn resolve_model_graphql_api(
     model_graphql_definition: &ModelGraphQlDefinition,
     model: &mut Model,
     existing_graphql_types: &mut BTreeSet<ast::TypeName>,
+    data_connectors: &data_connectors::DataConnectors,
     data_connector_scalars: &BTreeMap<
         Qualified<DataConnectorName>,
         data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
@@ -648,44 +653,12 @@ fn resolve_model_graphql_api(
 ) -> Result<(), Error> {
     let model_name = &model.name;
     for select_unique in &model_graphql_definition.select_uniques {
-        let mut unique_identifier_fields = IndexMap::new();
-        for field_name in &select_unique.unique_identifier {
-            let field_type = &model
-                .type_fields
-                .get(field_name)
-                .ok_or_else(|| Error::UnknownFieldInUniqueIdentifier {
-                    model_name: model_name.clone(),
-                    field_name: field_name.clone(),
-                })?
-                .field_type;
-            let ndc_column = model
-                .source
-                .as_ref()
-                .map(|model_source| {
-                    get_ndc_column_for_comparison(
-                        &model.name,
-                        &model.data_type,
-                        model_source,
-                        field_name,
-                        data_connector_scalars,
-                        || "the unique identifier for select unique".to_string(),
-                    )
-                })
-                .transpose()?;
-            let unique_identifier_field = UniqueIdentifierField {
-                field_type: field_type.clone(),
-                ndc_column,
-            };
-            if unique_identifier_fields
-                .insert(field_name.clone(), unique_identifier_field)
-                .is_some()
-            {
-                return Err(Error::DuplicateFieldInUniqueIdentifier {
-                    model_name: model_name.clone(),
-                    field_name: field_name.clone(),
-                });
-            }
-        }
+        let unique_identifier_fields = resolve_unique_identifier(
+            model,
+            &select_unique.unique_identifier,
+            data_connector_scalars,
+            "the unique identifier for select unique",
+        )?;
         let select_unique_field_name = mk_name(&select_unique.query_root_field.0)?;
         let select_unique_description = if select_unique.description.is_some() {
             select_unique.description.clone()
@@ -882,6 +855,236 @@ fn resolve_model_graphql_api(
         }
     }
 
+    if let Some(point_mutations) = &model_graphql_definition.point_mutations {
+        resolve_model_point_mutations(
+            point_mutations,
+            model,
+            existing_graphql_types,
+            data_connectors,
+            data_connector_scalars,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Resolve the fields of a unique identifier of a model, along with the columns they are compared
+/// with in the data connector.
+fn resolve_unique_identifier(
+    model: &Model,
+    unique_identifier: &[FieldName],
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    >,
+    comparison_location: &str,
+) -> Result<IndexMap<FieldName, UniqueIdentifierField>, Error> {
+    let mut unique_identifier_fields = IndexMap::new();
+    for field_name in unique_identifier {
+        let field_type = &model
+            .type_fields
+            .get(field_name)
+            .ok_or_else(|| Error::UnknownFieldInUniqueIdentifier {
+                model_name: model.name.clone(),
+                field_name: field_name.clone(),
+            })?
+            .field_type;
+        let ndc_column = model
+            .source
+            .as_ref()
+            .map(|model_source| {
+                get_ndc_column_for_comparison(
+                    &model.name,
+                    &model.data_type,
+                    model_source,
+                    field_name,
+                    data_connector_scalars,
+                    || comparison_location.to_string(),
+                )
+            })
+            .transpose()?;
+        let unique_identifier_field = UniqueIdentifierField {
+            field_type: field_type.clone(),
+            ndc_column,
+        };
+        if unique_identifier_fields
+            .insert(field_name.clone(), unique_identifier_field)
+            .is_some()
+        {
+            return Err(Error::DuplicateFieldInUniqueIdentifier {
+                model_name: model.name.clone(),
+                field_name: field_name.clone(),
+            });
+        }
+    }
+    Ok(unique_identifier_fields)
+}
+
+/// Resolve the insert, update and delete mutations of a model, checking that the procedures
+/// executing them exist in its data connector and take the expected arguments.
+fn resolve_model_point_mutations(
+    point_mutations: &ModelPointMutationsGraphQlDefinition,
+    model: &mut Model,
+    existing_graphql_types: &mut BTreeSet<ast::TypeName>,
+    data_connectors: &data_connectors::DataConnectors,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    >,
+) -> Result<(), Error> {
+    let model_source =
+        model
+            .source
+            .as_ref()
+            .ok_or_else(|| Error::PointMutationsWithoutModelSource {
+                model_name: model.name.clone(),
+            })?;
+
+    // check that a procedure exists in the model's data connector, with all the given arguments
+    let validate_procedure =
+        |procedure: &ProcedureName, argument_names: &[String]| -> Result<(), Error> {
+            let procedure_info = data_connectors
+                .0
+                .get(&model_source.data_connector.name)
+                .and_then(|data_connector_context| {
+                    data_connector_context
+                        .inner
+                        .schema
+                        .procedures
+                        .get(procedure)
+                })
+                .ok_or_else(|| Error::UnknownPointMutationProcedure {
+                    model_name: model.name.clone(),
+                    data_connector: model_source.data_connector.name.clone(),
+                    procedure: procedure.clone(),
+                })?;
+            for argument_name in argument_names {
+                if !procedure_info.arguments.contains_key(argument_name) {
+                    return Err(Error::MissingPointMutationProcedureArgument {
+                        model_name: model.name.clone(),
+                        procedure: procedure.clone(),
+                        argument_name: argument_name.clone(),
+                    });
+                }
+            }
+            Ok(())
+        };
+
+    // the arguments holding the keys of the object to update or delete
+    let key_argument_names = |unique_identifier: &IndexMap<FieldName, UniqueIdentifierField>| {
+        unique_identifier
+            .values()
+            .filter_map(|field| field.ndc_column.as_ref())
+            .map(|ndc_column| format!("key_{}", ndc_column.column))
+            .collect::<Vec<_>>()
+    };
+
+    // the suffix of the procedures updating or deleting an object by its unique identifier
+    let by_columns = |unique_identifier: &IndexMap<FieldName, UniqueIdentifierField>| {
+        unique_identifier
+            .values()
+            .filter_map(|field| field.ndc_column.as_ref())
+            .map(|ndc_column| ndc_column.column.0.as_str())
+            .collect::<Vec<_>>()
+            .join("_and_")
+    };
+
+    let insert_mutation = point_mutations
+        .insert
+        .as_ref()
+        .map(|insert| -> Result<_, Error> {
+            let procedure = insert
+                .procedure
+                .clone()
+                .unwrap_or_else(|| ProcedureName(format!("insert_{}", model_source.collection)));
+            validate_procedure(
+                &procedure,
+                &["object".to_string(), "post_check".to_string()],
+            )?;
+            let insert_input_type_name =
+                mk_name(insert.insert_input_type.0.as_str()).map(ast::TypeName)?;
+            store_new_graphql_type(existing_graphql_types, Some(&insert_input_type_name))?;
+            Ok(InsertMutationGraphQlDefinition {
+                mutation_root_field: mk_name(&insert.mutation_root_field.0)?,
+                insert_input_type_name,
+                procedure,
+                description: insert.description.clone(),
+                deprecated: insert.deprecated.clone(),
+            })
+        })
+        .transpose()?;
+
+    let update_mutation = point_mutations
+        .update
+        .as_ref()
+        .map(|update| -> Result<_, Error> {
+            let unique_identifier = resolve_unique_identifier(
+                &*model,
+                &update.unique_identifier,
+                data_connector_scalars,
+                "the unique identifier for the update mutation",
+            )?;
+            let procedure = update.procedure.clone().unwrap_or_else(|| {
+                ProcedureName(format!(
+                    "update_{}_by_{}",
+                    model_source.collection,
+                    by_columns(&unique_identifier)
+                ))
+            });
+            let mut argument_names = key_argument_names(&unique_identifier);
+            argument_names.extend([
+                "update_columns".to_string(),
+                "pre_check".to_string(),
+                "post_check".to_string(),
+            ]);
+            validate_procedure(&procedure, &argument_names)?;
+            let update_input_type_name =
+                mk_name(update.update_input_type.0.as_str()).map(ast::TypeName)?;
+            store_new_graphql_type(existing_graphql_types, Some(&update_input_type_name))?;
+            Ok(UpdateMutationGraphQlDefinition {
+                mutation_root_field: mk_name(&update.mutation_root_field.0)?,
+                unique_identifier,
+                update_input_type_name,
+                procedure,
+                description: update.description.clone(),
+                deprecated: update.deprecated.clone(),
+            })
+        })
+        .transpose()?;
+
+    let delete_mutation = point_mutations
+        .delete
+        .as_ref()
+        .map(|delete| -> Result<_, Error> {
+            let unique_identifier = resolve_unique_identifier(
+                &*model,
+                &delete.unique_identifier,
+                data_connector_scalars,
+                "the unique identifier for the delete mutation",
+            )?;
+            let procedure = delete.procedure.clone().unwrap_or_else(|| {
+                ProcedureName(format!(
+                    "delete_{}_by_{}",
+                    model_source.collection,
+                    by_columns(&unique_identifier)
+                ))
+            });
+            let mut argument_names = key_argument_names(&unique_identifier);
+            argument_names.push("pre_check".to_string());
+            validate_procedure(&procedure, &argument_names)?;
+            Ok(DeleteMutationGraphQlDefinition {
+                mutation_root_field: mk_name(&delete.mutation_root_field.0)?,
+                unique_identifier,
+                procedure,
+                description: delete.description.clone(),
+                deprecated: delete.deprecated.clone(),
+            })
+        })
+        .transpose()?;
+
+    model.graphql_api.insert_mutation = insert_mutation;
+    model.graphql_api.update_mutation = update_mutation;
+    model.graphql_api.delete_mutation = delete_mutation;
     Ok(())
 }
 
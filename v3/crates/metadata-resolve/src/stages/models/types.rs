@@ -11,6 +11,7 @@ use indexmap::IndexMap;
 use lang_graphql::ast::common::{self as ast, Name};
 
 use open_dds::aggregates::AggregateExpressionName;
+use open_dds::commands::ProcedureName;
 use open_dds::data_connector::DataConnectorObjectType;
 use open_dds::types::Deprecated;
 use open_dds::{
@@ -77,6 +78,34 @@ pub struct SelectAggregateGraphQlDefinition {
     pub filter_input_field_name: ast::Name,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InsertMutationGraphQlDefinition {
+    pub mutation_root_field: ast::Name,
+    pub insert_input_type_name: ast::TypeName,
+    pub procedure: ProcedureName,
+    pub description: Option<String>,
+    pub deprecated: Option<Deprecated>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UpdateMutationGraphQlDefinition {
+    pub mutation_root_field: ast::Name,
+    pub unique_identifier: IndexMap<FieldName, UniqueIdentifierField>,
+    pub update_input_type_name: ast::TypeName,
+    pub procedure: ProcedureName,
+    pub description: Option<String>,
+    pub deprecated: Option<Deprecated>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DeleteMutationGraphQlDefinition {
+    pub mutation_root_field: ast::Name,
+    pub unique_identifier: IndexMap<FieldName, UniqueIdentifierField>,
+    pub procedure: ProcedureName,
+    pub description: Option<String>,
+    pub deprecated: Option<Deprecated>,
+}
+
 // TODO: add support for aggregates
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct OrderByExpressionInfo {
@@ -116,6 +145,9 @@ pub struct ModelGraphQlApi {
     pub limit_field: Option<LimitFieldGraphqlConfig>,
     pub offset_field: Option<OffsetFieldGraphqlConfig>,
     pub filter_input_type_name: Option<ast::TypeName>,
+    pub insert_mutation: Option<InsertMutationGraphQlDefinition>,
+    pub update_mutation: Option<UpdateMutationGraphQlDefinition>,
+    pub delete_mutation: Option<DeleteMutationGraphQlDefinition>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
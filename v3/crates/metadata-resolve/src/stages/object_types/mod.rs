@@ -1,7 +1,10 @@
 use std::collections::{BTreeMap, BTreeSet};
 pub mod types;
 use open_dds::commands::ArgumentMapping;
-use open_dds::{data_connector::DataConnectorColumnName, types::CustomTypeName};
+use open_dds::{
+    data_connector::{DataConnectorColumnName, DataConnectorScalarType},
+    types::CustomTypeName,
+};
 use ref_cast::RefCast;
 pub use types::{
     DataConnectorTypeMappingsForObject, DataConnectorTypeMappingsOutput, FieldDefinition,
@@ -331,6 +334,10 @@ pub fn resolve_data_connector_type_mapping(
         let resolved_field_mapping = FieldMapping {
             column: resolved_field_mapping_column.clone(),
             column_type: source_column.r#type.clone(),
+            comparison_operators: get_column_comparison_operators(
+                &source_column.r#type,
+                &data_connector_context.scalars,
+            ),
             argument_mappings: resolved_argument_mappings.0,
         };
 
@@ -366,6 +373,23 @@ pub fn resolve_data_connector_type_mapping(
     Ok(resolved_type_mapping)
 }
 
+/// The comparison operators defined on a column's type, unless it isn't a (possibly nullable)
+/// scalar type.
+fn get_column_comparison_operators(
+    column_type: &ndc_models::Type,
+    scalars: &BTreeMap<DataConnectorScalarType, data_connectors::ScalarTypeInfo>,
+) -> Option<data_connectors::ComparisonOperators> {
+    match column_type {
+        ndc_models::Type::Named { name } => scalars
+            .get(DataConnectorScalarType::ref_cast(name))
+            .map(|scalar_type_info| scalar_type_info.comparison_operators.clone()),
+        ndc_models::Type::Nullable { underlying_type } => {
+            get_column_comparison_operators(underlying_type, scalars)
+        }
+        ndc_models::Type::Array { .. } | ndc_models::Type::Predicate { .. } => None,
+    }
+}
+
 fn get_column<'a>(
     ndc_type: &'a ndc_models::ObjectType,
     field_name: &open_dds::types::FieldName,
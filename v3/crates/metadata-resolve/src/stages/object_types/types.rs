@@ -9,6 +9,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use open_dds::models::ModelName;
 
+use crate::stages::data_connectors;
 use crate::types::subgraph::Qualified;
 
 use lang_graphql::ast::common as ast;
@@ -129,6 +130,8 @@ pub struct ResolvedApolloFederationObjectKey {
 pub struct FieldMapping {
     pub column: DataConnectorColumnName,
     pub column_type: ndc_models::Type,
+    /// The comparison operators the data connector defines for the column, if it is a scalar
+    pub comparison_operators: Option<data_connectors::ComparisonOperators>,
     pub argument_mappings: BTreeMap<ArgumentName, DataConnectorArgumentName>,
 }
 
@@ -5,23 +5,29 @@ use std::collections::BTreeSet;
 
 use indexmap::IndexMap;
 
-use open_dds::relationships::{self, FieldAccess, RelationshipName, RelationshipV1};
+use open_dds::relationships::{self, RelationshipName, RelationshipV1};
 use open_dds::{
-    commands::CommandName, data_connector::DataConnectorName, models::ModelName,
-    types::CustomTypeName,
+    commands::CommandName,
+    data_connector::{DataConnectorColumnName, DataConnectorName},
+    models::ModelName,
+    types::{CustomTypeName, FieldName},
 };
 
-use crate::helpers::types::mk_name;
+use crate::helpers::types::{mk_name, NdcColumnForComparison};
 use crate::stages::{
     commands, data_connector_scalar_types, data_connectors, models, object_types, type_permissions,
 };
 use crate::types::error::{Error, RelationshipError};
-use crate::types::subgraph::Qualified;
+use crate::types::permission::ValueExpression;
+use crate::types::subgraph::{
+    Qualified, QualifiedBaseType, QualifiedTypeName, QualifiedTypeReference,
+};
 
 pub use types::{
     ObjectTypeWithRelationships, Relationship, RelationshipCapabilities,
-    RelationshipCommandMapping, RelationshipExecutionCategory, RelationshipModelMapping,
-    RelationshipTarget, RelationshipTargetName,
+    RelationshipCommandMapping, RelationshipExecutionCategory, RelationshipFieldPath,
+    RelationshipMappingSource, RelationshipModelMapping, RelationshipModelMappingFieldTarget,
+    RelationshipModelMappingTarget, RelationshipTarget, RelationshipTargetName,
 };
 
 /// resolve relationships
@@ -84,6 +90,7 @@ pub fn resolve(
             data_connectors,
             data_connector_scalars,
             &object_representation.object_type,
+            object_types_with_permissions,
         )?;
 
         if object_representation
@@ -104,7 +111,6 @@ pub fn resolve(
     Ok(object_types_with_relationships)
 }
 
-#[allow(clippy::match_single_binding)]
 pub fn relationship_execution_category(
     source_connector: &data_connectors::DataConnectorLink,
     target_connector: &data_connectors::DataConnectorLink,
@@ -116,50 +122,182 @@ pub fn relationship_execution_category(
         && target_source_relationship_capabilities.relationships
     {
         RelationshipExecutionCategory::Local
+    } else if target_source_relationship_capabilities.foreach {
+        RelationshipExecutionCategory::RemoteForEach
     } else {
-        match target_source_relationship_capabilities.foreach {
-            // TODO: When we support naive relationships for connectors not implementing foreach,
-            // add another match arm / return enum variant
-            () => RelationshipExecutionCategory::RemoteForEach,
+        RelationshipExecutionCategory::RemoteNaive
+    }
+}
+
+/// The execution category of a model relationship used in a predicate or in an ordering. NDC
+/// paths across relationships start at the rows of the source collection, so a relationship
+/// joining on source fields nested in an object, which NDC only selects from within the object,
+/// is evaluated by the engine.
+pub fn relationship_predicate_execution_category(
+    source_connector: &data_connectors::DataConnectorLink,
+    target_connector: &data_connectors::DataConnectorLink,
+    target_source_relationship_capabilities: &RelationshipCapabilities,
+    mappings: &[RelationshipModelMapping],
+) -> RelationshipExecutionCategory {
+    match relationship_execution_category(
+        source_connector,
+        target_connector,
+        target_source_relationship_capabilities,
+    ) {
+        RelationshipExecutionCategory::Local if joins_nested_source_fields(mappings) => {
+            if target_source_relationship_capabilities.foreach {
+                RelationshipExecutionCategory::RemoteForEach
+            } else {
+                RelationshipExecutionCategory::RemoteNaive
+            }
         }
+        category => category,
     }
 }
 
-fn resolve_relationship_source_mapping<'a>(
-    relationship_name: &'a RelationshipName,
-    source_type_name: &'a Qualified<CustomTypeName>,
+fn resolve_relationship_source_mapping(
+    relationship_name: &RelationshipName,
+    source_type_name: &Qualified<CustomTypeName>,
     source_type: &object_types::ObjectTypeRepresentation,
-    relationship_mapping: &'a open_dds::relationships::RelationshipMapping,
-) -> Result<&'a FieldAccess, Error> {
+    relationship_mapping: &open_dds::relationships::RelationshipMapping,
+    target_type: &QualifiedTypeReference,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, type_permissions::ObjectTypeWithPermissions>,
+) -> Result<RelationshipMappingSource, Error> {
     match &relationship_mapping.source {
-        relationships::RelationshipMappingSource::Value(_v) => Err(Error::NotSupported {
-            reason: "Relationship mappings from value expressions are not supported yet."
-                .to_string(),
-        }),
-        relationships::RelationshipMappingSource::FieldPath(field_path) => match &field_path[..] {
-            [] => Err(Error::EmptyFieldPath {
-                location: "source".to_string(),
-                type_name: source_type_name.clone(),
-                relationship_name: relationship_name.clone(),
-            }),
-            [field_access] => {
-                if !source_type.fields.contains_key(&field_access.field_name) {
+        relationships::RelationshipMappingSource::Value(value_expression) => {
+            let value = match value_expression {
+                open_dds::permissions::ValueExpression::Literal(json_value) => {
+                    ValueExpression::Literal(json_value.clone())
+                }
+                open_dds::permissions::ValueExpression::SessionVariable(session_variable) => {
+                    ValueExpression::SessionVariable(session_variable.clone())
+                }
+                open_dds::permissions::ValueExpression::BooleanExpression(_) => {
                     return Err(Error::RelationshipError {
                         relationship_error:
-                            RelationshipError::UnknownSourceFieldInRelationshipMapping {
+                            RelationshipError::BooleanExpressionInRelationshipMappingNotSupported {
                                 relationship_name: relationship_name.clone(),
-                                source_type: source_type_name.clone(),
-                                field_name: field_access.field_name.clone(),
+                                type_name: source_type_name.clone(),
                             },
-                    });
-                };
-                Ok(field_access)
+                    })
+                }
+            };
+            Ok(RelationshipMappingSource::Value {
+                value,
+                value_type: target_type.clone(),
+            })
+        }
+        relationships::RelationshipMappingSource::FieldPath(field_path) => {
+            let (source_field, _) = resolve_field_path(
+                "source",
+                relationship_name,
+                source_type_name,
+                source_type_name,
+                &source_type.fields,
+                field_path,
+                object_types,
+                |nested_type_name, field_name| Error::RelationshipError {
+                    relationship_error:
+                        RelationshipError::UnknownSourceFieldInRelationshipMapping {
+                            relationship_name: relationship_name.clone(),
+                            source_type: nested_type_name.unwrap_or(source_type_name).clone(),
+                            field_name: field_name.clone(),
+                        },
+                },
+            )?;
+            Ok(RelationshipMappingSource::Field(source_field))
+        }
+    }
+}
+
+/// Resolve the field path of a relationship mapping, from the fields of the object type
+/// `fields_type_name`. Every field in the path but the last has to be an object, which the next
+/// field is a field of. An unknown field is reported by `unknown_field_error`, along with the
+/// type of the object it is nested in, if any. Returns the path along with the definition of the
+/// mapped field.
+#[allow(clippy::too_many_arguments)]
+fn resolve_field_path<'a>(
+    location: &str,
+    relationship_name: &RelationshipName,
+    source_type_name: &Qualified<CustomTypeName>,
+    fields_type_name: &Qualified<CustomTypeName>,
+    fields: &'a IndexMap<FieldName, object_types::FieldDefinition>,
+    field_path: &[relationships::FieldAccess],
+    object_types: &'a BTreeMap<
+        Qualified<CustomTypeName>,
+        type_permissions::ObjectTypeWithPermissions,
+    >,
+    unknown_field_error: impl Fn(Option<&Qualified<CustomTypeName>>, &FieldName) -> Error,
+) -> Result<(RelationshipFieldPath, &'a object_types::FieldDefinition), Error> {
+    let Some((field_access, nested_field_path)) = field_path.split_first() else {
+        return Err(Error::EmptyFieldPath {
+            location: location.to_string(),
+            type_name: source_type_name.clone(),
+            relationship_name: relationship_name.clone(),
+        });
+    };
+    let mut field_definition = fields
+        .get(&field_access.field_name)
+        .ok_or_else(|| unknown_field_error(None, &field_access.field_name))?;
+    let mut parent = (fields_type_name, field_access);
+    let mut nested_fields = Vec::new();
+    for nested_field_access in nested_field_path {
+        // arrays of objects have no single value to map
+        let nested_type = match &field_definition.field_type.underlying_type {
+            QualifiedBaseType::Named(QualifiedTypeName::Custom(nested_type_name)) => object_types
+                .get(nested_type_name)
+                .map(|nested_type| (nested_type_name, nested_type)),
+            _ => None,
+        };
+        let (nested_type_name, nested_type) = nested_type.ok_or_else(|| {
+            let (parent_type_name, parent_field_access) = parent;
+            Error::RelationshipError {
+                relationship_error: RelationshipError::NonObjectFieldInRelationshipFieldPath {
+                    location: location.to_string(),
+                    relationship_name: relationship_name.clone(),
+                    type_name: parent_type_name.clone(),
+                    field_name: parent_field_access.field_name.clone(),
+                },
             }
-            _ => Err(Error::NotSupported {
-                reason: "Relationships with nested field paths are not supported yet.".to_string(),
-            }),
+        })?;
+        field_definition = nested_type
+            .object_type
+            .fields
+            .get(&nested_field_access.field_name)
+            .ok_or_else(|| {
+                unknown_field_error(Some(nested_type_name), &nested_field_access.field_name)
+            })?;
+        nested_fields.push((nested_type_name.clone(), nested_field_access.clone()));
+        parent = (nested_type_name, nested_field_access);
+    }
+    Ok((
+        RelationshipFieldPath {
+            field: field_access.clone(),
+            nested_fields,
         },
+        field_definition,
+    ))
+}
+
+/// Check that a source field is mapped to a single target
+fn validate_unique_source_field(
+    source: &RelationshipMappingSource,
+    mapped_source_fields: &mut BTreeSet<Vec<FieldName>>,
+    source_type_name: &Qualified<CustomTypeName>,
+    relationship_name: &RelationshipName,
+) -> Result<(), Error> {
+    if let Some(source_field) = source.field() {
+        if !mapped_source_fields.insert(source_field.field_names()) {
+            return Err(Error::RelationshipError {
+                relationship_error: RelationshipError::MappingExistsInRelationship {
+                    type_name: source_type_name.clone(),
+                    field_name: source_field.mapped_field().field_name.clone(),
+                    relationship_name: relationship_name.clone(),
+                },
+            });
+        }
     }
+    Ok(())
 }
 
 fn resolve_relationship_mappings_model(
@@ -171,121 +309,214 @@ fn resolve_relationship_mappings_model(
         Qualified<DataConnectorName>,
         data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
     >,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, type_permissions::ObjectTypeWithPermissions>,
 ) -> Result<Vec<RelationshipModelMapping>, Error> {
     let mut resolved_relationship_mappings = Vec::new();
-    let mut field_mapping_btree_set_for_validation: BTreeSet<&String> = BTreeSet::new();
+    let mut mapped_source_fields = BTreeSet::new();
+    let mut mapped_target_arguments = BTreeSet::new();
     for relationship_mapping in &relationship.mapping {
+        let (resolved_relationship_target_mapping, target_type) = match &relationship_mapping.target
+        {
+            relationships::RelationshipMappingTarget::Argument(argument_mapping_target) => {
+                let argument_name = &argument_mapping_target.argument_name;
+                // Check if the target argument exists in the target model.
+                let argument = target_model.arguments.get(argument_name).ok_or_else(|| {
+                    Error::RelationshipError {
+                        relationship_error:
+                            RelationshipError::UnknownTargetModelArgumentInRelationshipMapping {
+                                relationship_name: relationship.name.clone(),
+                                source_type: source_type_name.clone(),
+                                model_name: target_model.name.clone(),
+                                argument_name: argument_name.clone(),
+                            },
+                    }
+                })?;
+                // Check if the target argument is already mapped.
+                if !mapped_target_arguments.insert(argument_name) {
+                    return Err(Error::RelationshipError {
+                        relationship_error:
+                            RelationshipError::ModelArgumentMappingExistsInRelationship {
+                                argument_name: argument_name.clone(),
+                                model_name: target_model.name.clone(),
+                                relationship_name: relationship.name.clone(),
+                                type_name: source_type_name.clone(),
+                            },
+                    });
+                }
+                (
+                    RelationshipModelMappingTarget::Argument(argument_name.clone()),
+                    &argument.argument_type,
+                )
+            }
+            relationships::RelationshipMappingTarget::ModelField(field_path) => {
+                // Check if the target field exists in the target model.
+                let (target_field, target_field_definition) = resolve_field_path(
+                    "target",
+                    &relationship.name,
+                    source_type_name,
+                    &target_model.data_type,
+                    &target_model.type_fields,
+                    field_path,
+                    object_types,
+                    |nested_type_name, field_name| Error::RelationshipError {
+                        relationship_error: match nested_type_name {
+                            None => RelationshipError::UnknownTargetFieldInRelationshipMapping {
+                                relationship_name: relationship.name.clone(),
+                                source_type: source_type_name.clone(),
+                                model_name: target_model.name.clone(),
+                                field_name: field_name.clone(),
+                            },
+                            Some(nested_type_name) => {
+                                RelationshipError::UnknownNestedTargetFieldInRelationshipMapping {
+                                    relationship_name: relationship.name.clone(),
+                                    source_type: source_type_name.clone(),
+                                    model_name: target_model.name.clone(),
+                                    type_name: nested_type_name.clone(),
+                                    field_name: field_name.clone(),
+                                }
+                            }
+                        },
+                    },
+                )?;
+
+                let (target_ndc_column, target_ndc_field_path) = match &target_model.source {
+                    Some(target_model_source) => {
+                        let (target_ndc_column, target_ndc_field_path) = get_target_ndc_column(
+                            relationship,
+                            source_type_name,
+                            target_model,
+                            target_model_source,
+                            &target_field,
+                            data_connector_scalars,
+                        )?;
+                        (Some(target_ndc_column), target_ndc_field_path)
+                    }
+                    None => (None, vec![]),
+                };
+                (
+                    RelationshipModelMappingTarget::ModelField(
+                        RelationshipModelMappingFieldTarget {
+                            target_field,
+                            target_ndc_column,
+                            target_ndc_field_path,
+                        },
+                    ),
+                    &target_field_definition.field_type,
+                )
+            }
+        };
+
         let resolved_relationship_source_mapping = resolve_relationship_source_mapping(
             &relationship.name,
             source_type_name,
             source_type,
             relationship_mapping,
+            target_type,
+            object_types,
         )?;
 
-        let resolved_relationship_target_mapping = match &relationship_mapping.target {
-            relationships::RelationshipMappingTarget::Argument(_argument_mapping_target) => {
-                return Err(Error::NotSupported {
-                    reason: "Relationship mappings to model arguments expressions are not supported yet."
-                        .to_string(),
-                })
-            }
-            relationships::RelationshipMappingTarget::ModelField(field_path) => {
-                match &field_path[..] {
-                    [] => {
-                        return Err(Error::EmptyFieldPath {
-                            location: "target".to_string(),
-                            type_name: source_type_name.clone(),
-                            relationship_name: relationship.name.clone(),
-                        })
-                    }
-                    [t] => t,
-                    _ => {
-                        return Err(Error::NotSupported {
-                            reason: "Relationships with nested field paths are not supported yet."
-                                .to_string(),
-                        })
-                    }
-                }
-            }
-        };
-
-        // Check if the target field exists in the target model.
-        if !target_model
-            .type_fields
-            .contains_key(&resolved_relationship_target_mapping.field_name)
-        {
-            return Err(Error::RelationshipError {
-                relationship_error: RelationshipError::UnknownTargetFieldInRelationshipMapping {
-                    relationship_name: relationship.name.clone(),
-                    source_type: source_type_name.clone(),
-                    model_name: target_model.name.clone(),
-                    field_name: resolved_relationship_source_mapping.field_name.clone(),
-                },
-            });
-        }
+        // Check if the source field is already mapped to a target
+        validate_unique_source_field(
+            &resolved_relationship_source_mapping,
+            &mut mapped_source_fields,
+            source_type_name,
+            &relationship.name,
+        )?;
 
-        // Check if the source field is already mapped to a target field
-        let resolved_relationship_mapping = {
-            if field_mapping_btree_set_for_validation
-                .insert(&resolved_relationship_source_mapping.field_name.0)
-            {
-                let target_ndc_column = target_model
-                    .source
-                    .as_ref()
-                    .map(|target_model_source| {
-                        models::get_ndc_column_for_comparison(
-                            &target_model.name,
-                            &target_model.data_type,
-                            target_model_source,
-                            &resolved_relationship_target_mapping.field_name,
-                            data_connector_scalars,
-                            || {
-                                format!(
-                                    "the mapping for relationship {} on type {}",
-                                    relationship.name, source_type_name
-                                )
-                            },
-                        )
-                    })
-                    .transpose()?;
-                Ok(RelationshipModelMapping {
-                    source_field: resolved_relationship_source_mapping.clone(),
-                    target_field: resolved_relationship_target_mapping.clone(),
-                    target_ndc_column,
-                })
-            } else {
-                Err(Error::RelationshipError {
-                    relationship_error: RelationshipError::MappingExistsInRelationship {
-                        type_name: source_type_name.clone(),
-                        field_name: resolved_relationship_source_mapping.field_name.clone(),
-                        relationship_name: relationship.name.clone(),
-                    },
-                })
-            }
-        }?;
-        resolved_relationship_mappings.push(resolved_relationship_mapping);
+        resolved_relationship_mappings.push(RelationshipModelMapping {
+            source: resolved_relationship_source_mapping,
+            target: resolved_relationship_target_mapping,
+        });
     }
 
     Ok(resolved_relationship_mappings)
 }
 
+/// The column a target field of a relationship is compared with, and the NDC field path from
+/// the column to the target field if it is nested. The nested field is compared using its own
+/// equality operator.
+fn get_target_ndc_column(
+    relationship: &RelationshipV1,
+    source_type_name: &Qualified<CustomTypeName>,
+    target_model: &models::Model,
+    target_model_source: &models::ModelSource,
+    target_field: &RelationshipFieldPath,
+    data_connector_scalars: &BTreeMap<
+        Qualified<DataConnectorName>,
+        data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
+    >,
+) -> Result<(NdcColumnForComparison, Vec<DataConnectorColumnName>), Error> {
+    let comparison_location = || {
+        format!(
+            "the mapping for relationship {} on type {}",
+            relationship.name, source_type_name
+        )
+    };
+    let (mapped_field_type, mapped_field) = target_field.nested_fields.last().map_or(
+        (&target_model.data_type, &target_field.field),
+        |(type_name, field_access)| (type_name, field_access),
+    );
+    let mapped_ndc_column = models::get_ndc_column_for_comparison(
+        &target_model.name,
+        mapped_field_type,
+        target_model_source,
+        &mapped_field.field_name,
+        data_connector_scalars,
+        comparison_location,
+    )?;
+    let Some((_, object_fields)) = target_field.nested_fields.split_last() else {
+        return Ok((mapped_ndc_column, vec![]));
+    };
+
+    // the columns of the fields the target field is nested in
+    let mut ndc_columns = std::iter::once((&target_model.data_type, &target_field.field))
+        .chain(
+            object_fields
+                .iter()
+                .map(|(type_name, field_access)| (type_name, field_access)),
+        )
+        .map(|(type_name, field_access)| {
+            let object_types::TypeMapping::Object { field_mappings, .. } = target_model_source
+                .type_mappings
+                .get(type_name)
+                .ok_or_else(|| Error::TypeMappingRequired {
+                    model_name: target_model.name.clone(),
+                    type_name: type_name.clone(),
+                    data_connector: target_model_source.data_connector.name.clone(),
+                })?;
+            field_mappings
+                .get(&field_access.field_name)
+                .map(|field_mapping| field_mapping.column.clone())
+                .ok_or_else(|| Error::NoFieldMappingForComparedField {
+                    comparison_location: comparison_location(),
+                    field_name: field_access.field_name.clone(),
+                    model_name: target_model.name.clone(),
+                })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let column = ndc_columns.remove(0);
+    ndc_columns.push(mapped_ndc_column.column);
+    Ok((
+        NdcColumnForComparison {
+            column,
+            equal_operator: mapped_ndc_column.equal_operator,
+        },
+        ndc_columns,
+    ))
+}
+
 fn resolve_relationship_mappings_command(
     relationship: &RelationshipV1,
     source_type_name: &Qualified<CustomTypeName>,
     source_type: &object_types::ObjectTypeRepresentation,
     target_command: &commands::Command,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, type_permissions::ObjectTypeWithPermissions>,
 ) -> Result<Vec<RelationshipCommandMapping>, Error> {
     let mut resolved_relationship_mappings = Vec::new();
-    let mut field_mapping_btree_set_for_validation: BTreeSet<&String> = BTreeSet::new();
+    let mut mapped_source_fields = BTreeSet::new();
     let mut target_command_arguments_btree_set_for_validation: BTreeSet<&String> = BTreeSet::new();
 
     for relationship_mapping in &relationship.mapping {
-        let resolved_relationship_source_mapping = resolve_relationship_source_mapping(
-            &relationship.name,
-            source_type_name,
-            source_type,
-            relationship_mapping,
-        )?;
         let target_argument_name = match &relationship_mapping.target {
             relationships::RelationshipMappingTarget::Argument(argument_mapping_target) => {
                 &argument_mapping_target.argument_name
@@ -299,16 +530,17 @@ fn resolve_relationship_mappings_command(
         };
 
         // Check if the target argument exists in the target command.
-        if !target_command.arguments.contains_key(target_argument_name) {
-            return Err(Error::RelationshipError {
+        let target_argument = target_command
+            .arguments
+            .get(target_argument_name)
+            .ok_or_else(|| Error::RelationshipError {
                 relationship_error: RelationshipError::UnknownTargetArgumentInRelationshipMapping {
                     relationship_name: relationship.name.clone(),
                     source_type: source_type_name.clone(),
                     command_name: target_command.name.clone(),
                     argument_name: target_argument_name.clone(),
                 },
-            });
-        }
+            })?;
 
         // Check if the target argument is already mapped to a field in the source type.
         if !target_command_arguments_btree_set_for_validation.insert(&target_argument_name.0) {
@@ -322,34 +554,79 @@ fn resolve_relationship_mappings_command(
             });
         };
 
+        let resolved_relationship_source_mapping = resolve_relationship_source_mapping(
+            &relationship.name,
+            source_type_name,
+            source_type,
+            relationship_mapping,
+            &target_argument.argument_type,
+            object_types,
+        )?;
+
         // Check if the source field is already mapped to a target argument
-        let resolved_relationship_mapping = {
-            if field_mapping_btree_set_for_validation
-                .insert(&resolved_relationship_source_mapping.field_name.0)
-            {
-                Ok(RelationshipCommandMapping {
-                    source_field: resolved_relationship_source_mapping.clone(),
-                    argument_name: target_argument_name.clone(),
-                })
-            } else {
-                Err(Error::RelationshipError {
-                    relationship_error: RelationshipError::MappingExistsInRelationship {
-                        type_name: source_type_name.clone(),
-                        field_name: resolved_relationship_source_mapping.field_name.clone(),
-                        relationship_name: relationship.name.clone(),
-                    },
-                })
-            }
-        }?;
-        resolved_relationship_mappings.push(resolved_relationship_mapping);
+        validate_unique_source_field(
+            &resolved_relationship_source_mapping,
+            &mut mapped_source_fields,
+            source_type_name,
+            &relationship.name,
+        )?;
+
+        resolved_relationship_mappings.push(RelationshipCommandMapping {
+            source: resolved_relationship_source_mapping,
+            argument_name: target_argument_name.clone(),
+        });
     }
 
     Ok(resolved_relationship_mappings)
 }
 
+/// Whether an NDC relationship can join the source and target of a relationship. NDC
+/// relationships join columns of the source row with columns of the target collection, so they
+/// can't join on target fields nested in objects. A relationship joining on source fields nested
+/// in an object is selected from within that object, so they all have to be fields of it.
+fn is_ndc_relationship(relationship_target: &RelationshipTarget) -> bool {
+    let (mut source_fields, joins_nested_target_fields): (Vec<&RelationshipFieldPath>, bool) =
+        match relationship_target {
+            RelationshipTarget::Model { mappings, .. } => (
+                mappings
+                    .iter()
+                    .filter_map(|mapping| mapping.source.field())
+                    .collect(),
+                mappings.iter().any(|mapping| match &mapping.target {
+                    RelationshipModelMappingTarget::ModelField(field_target) => {
+                        mapping.source.field().is_some() && field_target.target_field.is_nested()
+                    }
+                    RelationshipModelMappingTarget::Argument(_) => false,
+                }),
+            ),
+            RelationshipTarget::Command { mappings, .. } => (
+                mappings
+                    .iter()
+                    .filter_map(|mapping| mapping.source.field())
+                    .collect(),
+                false,
+            ),
+        };
+    let source_object = source_fields
+        .pop()
+        .map(RelationshipFieldPath::object_field_names);
+    let joins_fields_of_one_object = source_fields
+        .iter()
+        .all(|source_field| Some(source_field.object_field_names()) == source_object);
+    joins_fields_of_one_object && !joins_nested_target_fields
+}
+
+/// Whether a model relationship joins on source fields nested in an object
+fn joins_nested_source_fields(mappings: &[RelationshipModelMapping]) -> bool {
+    mappings.iter().any(|mapping| {
+        mapping
+            .source
+            .field()
+            .map_or(false, RelationshipFieldPath::is_nested)
+    })
+}
+
 fn get_relationship_capabilities(
-    type_name: &Qualified<CustomTypeName>,
-    relationship_name: &RelationshipName,
     source_data_connector: Option<&data_connectors::DataConnectorLink>,
     target_name: &RelationshipTargetName,
     data_connectors: &data_connectors::DataConnectors,
@@ -377,20 +654,11 @@ fn get_relationship_capabilities(
 
     let capabilities = &resolved_data_connector.inner.capabilities.capabilities;
 
-    if capabilities.query.variables.is_none() {
-        return Err(Error::RelationshipError {
-            relationship_error: RelationshipError::RelationshipTargetDoesNotSupportForEach {
-                type_name: type_name.clone(),
-                relationship_name: relationship_name.clone(),
-                data_connector_name: data_connector.name.clone(),
-            },
-        });
-    };
-
+    let foreach = capabilities.query.variables.is_some();
     let relationships = capabilities.relationships.is_some();
 
     Ok(Some(RelationshipCapabilities {
-        foreach: (),
+        foreach,
         relationships,
     }))
 }
@@ -406,6 +674,7 @@ pub fn resolve_relationship(
         data_connector_scalar_types::ScalarTypeWithRepresentationInfoMap,
     >,
     source_type: &object_types::ObjectTypeRepresentation,
+    object_types: &BTreeMap<Qualified<CustomTypeName>, type_permissions::ObjectTypeWithPermissions>,
 ) -> Result<Relationship, Error> {
     let source_type_name = Qualified::new(subgraph.to_string(), relationship.source_type.clone());
     let (relationship_target, source_data_connector, target_name) = match &relationship.target {
@@ -437,6 +706,7 @@ pub fn resolve_relationship(
                         source_type,
                         resolved_target_model,
                         data_connector_scalars,
+                        object_types,
                     )?,
                 },
                 source_data_connector,
@@ -474,6 +744,7 @@ pub fn resolve_relationship(
                         &source_type_name,
                         source_type,
                         resolved_target_command,
+                        object_types,
                     )?,
                 },
                 source_data_connector,
@@ -482,13 +753,16 @@ pub fn resolve_relationship(
         }
     };
 
-    let target_capabilities = get_relationship_capabilities(
-        &source_type_name,
-        &relationship.name,
-        source_data_connector,
-        &target_name,
-        data_connectors,
-    )?;
+    let mut target_capabilities =
+        get_relationship_capabilities(source_data_connector, &target_name, data_connectors)?;
+
+    // the engine joins the relationships NDC can't express itself, as it does across data
+    // connectors
+    if !is_ndc_relationship(&relationship_target) {
+        if let Some(capabilities) = &mut target_capabilities {
+            capabilities.relationships = false;
+        }
+    }
 
     let field_name = mk_name(&relationship.name.0)?;
     Ok(Relationship {
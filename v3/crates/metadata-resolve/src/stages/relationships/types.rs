@@ -1,8 +1,14 @@
 use crate::stages::{object_types, type_permissions};
+use crate::types::permission::ValueExpression;
 use crate::types::subgraph::{Qualified, QualifiedTypeReference};
 use indexmap::IndexMap;
 use open_dds::permissions::Role;
-use open_dds::{commands::CommandName, models::ModelName, types::CustomTypeName};
+use open_dds::{
+    commands::CommandName,
+    data_connector::DataConnectorColumnName,
+    models::ModelName,
+    types::{CustomTypeName, FieldName},
+};
 use serde::{Deserialize, Serialize};
 
 use crate::helpers::types::NdcColumnForComparison;
@@ -52,15 +58,98 @@ pub enum RelationshipTargetName {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RelationshipModelMapping {
-    pub source_field: FieldAccess,
-    pub target_field: FieldAccess,
-    // Optional because we allow building schema without specifying a data source
+    pub source: RelationshipMappingSource,
+    pub target: RelationshipModelMappingTarget,
+}
+
+/// A field mapped by a relationship: a field of an object type, or a field of an object nested in
+/// it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelationshipFieldPath {
+    /// The field of the object type
+    pub field: FieldAccess,
+    /// The fields leading from `field` to the mapped field, each with the object type it is a
+    /// field of. Empty when `field` itself is mapped.
+    pub nested_fields: Vec<(Qualified<CustomTypeName>, FieldAccess)>,
+}
+
+impl RelationshipFieldPath {
+    /// Whether the mapped field is nested in an object
+    pub fn is_nested(&self) -> bool {
+        !self.nested_fields.is_empty()
+    }
+
+    /// The mapped field, at the end of the path
+    pub fn mapped_field(&self) -> &FieldAccess {
+        self.nested_fields
+            .last()
+            .map_or(&self.field, |(_, field_access)| field_access)
+    }
+
+    /// The names of the fields along the path
+    pub fn field_names(&self) -> Vec<FieldName> {
+        std::iter::once(&self.field)
+            .chain(
+                self.nested_fields
+                    .iter()
+                    .map(|(_, field_access)| field_access),
+            )
+            .map(|field_access| field_access.field_name.clone())
+            .collect()
+    }
+
+    /// The names of the fields leading to the object the mapped field is a field of. Empty when
+    /// it is a field of the object type.
+    pub fn object_field_names(&self) -> Vec<FieldName> {
+        let mut field_names = self.field_names();
+        field_names.pop();
+        field_names
+    }
+}
+
+/// What a relationship mapping passes to its target
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum RelationshipMappingSource {
+    /// A field of the source type, or a field nested in it
+    Field(RelationshipFieldPath),
+    /// A literal or a session variable. `value_type` is the type of the target the value is
+    /// passed to, which session variables are cast to.
+    Value {
+        value: ValueExpression,
+        value_type: QualifiedTypeReference,
+    },
+}
+
+impl RelationshipMappingSource {
+    /// The source field path, if the mapping is from a field rather than a value
+    pub fn field(&self) -> Option<&RelationshipFieldPath> {
+        match self {
+            RelationshipMappingSource::Field(field_path) => Some(field_path),
+            RelationshipMappingSource::Value { .. } => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum RelationshipModelMappingTarget {
+    ModelField(RelationshipModelMappingFieldTarget),
+    Argument(ArgumentName),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelationshipModelMappingFieldTarget {
+    pub target_field: RelationshipFieldPath,
+    // Optional because we allow building schema without specifying a data source. The column of
+    // a nested target field is that of the field of the model, compared with the equality
+    // operator of the nested field.
     pub target_ndc_column: Option<NdcColumnForComparison>,
+    // The NDC names of the fields leading from `target_ndc_column` to a nested target field
+    pub target_ndc_field_path: Vec<DataConnectorColumnName>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RelationshipCommandMapping {
-    pub source_field: FieldAccess,
+    pub source: RelationshipMappingSource,
     pub argument_name: ArgumentName,
 }
 
@@ -80,9 +169,7 @@ pub struct Relationship {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RelationshipCapabilities {
-    // TODO: We don't handle relationships without foreach.
-    // Change this to a bool, when we support that
-    pub foreach: (),
+    pub foreach: bool,
     pub relationships: bool,
 }
 
@@ -91,4 +178,7 @@ pub enum RelationshipExecutionCategory {
     Local,
     // Use foreach in the data connector to fetch related rows for multiple objects in a single request
     RemoteForEach,
+    // Fetch the related rows for each distinct set of join values in a separate request, for data
+    // connectors that don't support foreach
+    RemoteNaive,
 }
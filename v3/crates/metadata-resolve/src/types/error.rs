@@ -11,6 +11,7 @@ use open_dds::{
     commands::{CommandName, FunctionName, ProcedureName},
     data_connector::{DataConnectorName, DataConnectorScalarType},
     models::ModelName,
+    permissions::Role,
     relationships::RelationshipName,
     types::{CustomTypeName, FieldName, OperatorName, TypeReference},
 };
@@ -150,6 +151,21 @@ pub enum Error {
         model_name: Qualified<ModelName>,
         argument_name: ArgumentName,
     },
+    #[error("a preset field {field_name:} has been set for the model {model_name:} but no such field exists for this model")]
+    UnknownFieldInModelFieldPreset {
+        model_name: Qualified<ModelName>,
+        field_name: FieldName,
+    },
+    #[error("duplicate preset field {field_name:} for model {model_name:}")]
+    DuplicateModelFieldPreset {
+        model_name: Qualified<ModelName>,
+        field_name: FieldName,
+    },
+    #[error("the preset field {field_name:} for model {model_name:} is a boolean expression, but only literals and session variables can be preset for fields")]
+    BooleanExpressionInModelFieldPreset {
+        model_name: Qualified<ModelName>,
+        field_name: FieldName,
+    },
 
     #[error("the procedure {procedure:} in the data connector {data_connector:} for command {command_name:} has not been defined")]
     UnknownCommandProcedure {
@@ -284,6 +300,20 @@ pub enum Error {
         model_name: Qualified<ModelName>,
         field_name: FieldName,
     },
+    #[error("point mutations have been defined for model {model_name:}, which has no source")]
+    PointMutationsWithoutModelSource { model_name: Qualified<ModelName> },
+    #[error("the procedure {procedure:} used by the point mutations of model {model_name:} has not been defined in the data connector {data_connector:}")]
+    UnknownPointMutationProcedure {
+        model_name: Qualified<ModelName>,
+        data_connector: Qualified<DataConnectorName>,
+        procedure: ProcedureName,
+    },
+    #[error("the procedure {procedure:} used by the point mutations of model {model_name:} has no argument named {argument_name:}")]
+    MissingPointMutationProcedureArgument {
+        model_name: Qualified<ModelName>,
+        procedure: ProcedureName,
+        argument_name: String,
+    },
     #[error("no equality operator has been defined in the data connector for field {field_name:} of model {model_name:} used in {comparison_location}")]
     NoEqualOperatorForComparedField {
         comparison_location: String,
@@ -371,8 +401,8 @@ pub enum Error {
     },
     #[error("unknown model used in model select permissions definition: {model_name:}")]
     UnknownModelInModelSelectPermissions { model_name: Qualified<ModelName> },
-    #[error("multiple select permissions defined for model: {model_name:}")]
-    DuplicateModelSelectPermission { model_name: Qualified<ModelName> },
+    #[error("multiple model permissions defined for model: {model_name:}")]
+    DuplicateModelPermissions { model_name: Qualified<ModelName> },
     #[error("model source is required for model '{model_name:}' to resolve predicate")]
     ModelSourceRequiredForPredicate { model_name: Qualified<ModelName> },
     #[error(
@@ -610,6 +640,12 @@ pub enum Error {
         argument_name: ArgumentName,
         type_error: typecheck::TypecheckError,
     },
+    #[error("Type error in preset field {field_name:} for model {model_name:}: {type_error:}")]
+    ModelFieldPresetTypeError {
+        model_name: Qualified<ModelName>,
+        field_name: FieldName,
+        type_error: typecheck::TypecheckError,
+    },
     #[error("{graphql_config_error:}")]
     GraphqlConfigError {
         graphql_config_error: GraphqlConfigError,
@@ -735,6 +771,14 @@ pub enum RelationshipError {
         model_name: Qualified<ModelName>,
         field_name: FieldName,
     },
+    #[error("target field {field_name} of type {type_name} in field mapping for relationship {relationship_name} on type {source_type} to model {model_name} is unknown.")]
+    UnknownNestedTargetFieldInRelationshipMapping {
+        source_type: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+        model_name: Qualified<ModelName>,
+        type_name: Qualified<CustomTypeName>,
+        field_name: FieldName,
+    },
     #[error("target argument {argument_name} in argument mapping for relationship {relationship_name} on type {source_type} to command {command_name} is unknown.")]
     UnknownTargetArgumentInRelationshipMapping {
         source_type: Qualified<CustomTypeName>,
@@ -742,6 +786,20 @@ pub enum RelationshipError {
         command_name: Qualified<CommandName>,
         argument_name: ArgumentName,
     },
+    #[error("target argument {argument_name} in argument mapping for relationship {relationship_name} on type {source_type} to model {model_name} is unknown.")]
+    UnknownTargetModelArgumentInRelationshipMapping {
+        source_type: Qualified<CustomTypeName>,
+        relationship_name: RelationshipName,
+        model_name: Qualified<ModelName>,
+        argument_name: ArgumentName,
+    },
+    #[error("field {field_name} of type {type_name} in the {location} field path of relationship {relationship_name} is not an object, so no fields nested in it can be mapped.")]
+    NonObjectFieldInRelationshipFieldPath {
+        location: String,
+        relationship_name: RelationshipName,
+        type_name: Qualified<CustomTypeName>,
+        field_name: FieldName,
+    },
     #[error("Mapping for source field {field_name} already exists in the relationship {relationship_name} on type {type_name}")]
     MappingExistsInRelationship {
         type_name: Qualified<CustomTypeName>,
@@ -755,17 +813,23 @@ pub enum RelationshipError {
         relationship_name: RelationshipName,
         type_name: Qualified<CustomTypeName>,
     },
-    #[error("No mapping for target command argument {argument_name} in the relationship {relationship_name} on type {type_name}")]
-    MissingArgumentMappingInRelationship {
-        type_name: Qualified<CustomTypeName>,
+    #[error("Mapping for target argument {argument_name} of model {model_name} already exists in the relationship {relationship_name} on type {type_name}")]
+    ModelArgumentMappingExistsInRelationship {
         argument_name: ArgumentName,
+        model_name: Qualified<ModelName>,
+        relationship_name: RelationshipName,
+        type_name: Qualified<CustomTypeName>,
+    },
+    #[error("Boolean expressions are not supported as the source of a mapping in the relationship {relationship_name} on type {type_name}")]
+    BooleanExpressionInRelationshipMappingNotSupported {
         relationship_name: RelationshipName,
+        type_name: Qualified<CustomTypeName>,
     },
-    #[error("The target data connector {data_connector_name} for relationship {relationship_name} on type {type_name} does not support the variables capability")]
-    RelationshipTargetDoesNotSupportForEach {
+    #[error("No mapping for target command argument {argument_name} in the relationship {relationship_name} on type {type_name}")]
+    MissingArgumentMappingInRelationship {
         type_name: Qualified<CustomTypeName>,
+        argument_name: ArgumentName,
         relationship_name: RelationshipName,
-        data_connector_name: Qualified<DataConnectorName>,
     },
     #[error("The target data connector {data_connector_name} for relationship {relationship_name} on type {type_name} has not defined any capabilities")]
     NoRelationshipCapabilitiesDefined {
@@ -873,6 +937,8 @@ pub enum GraphqlConfigError {
     MissingArgumentsInputFieldInGraphqlConfig,
     #[error("the filterInputFieldName for aggregate needs to be defined in GraphqlConfig, when models have a selectAggregate graphql API")]
     MissingAggregateFilterInputFieldNameInGraphqlConfig,
+    #[error("operation limits are defined more than once for role {role:} in GraphqlConfig")]
+    DuplicateOperationLimitsForRole { role: Role },
 }
 
 #[derive(Error, Debug)]
@@ -7,7 +7,7 @@ use crate::{
     arguments::{ArgumentDefinition, ArgumentName},
     data_connector::DataConnectorName,
     identifier::Identifier,
-    impl_JsonSchema_with_OpenDd_for,
+    impl_JsonSchema_with_OpenDd_for, impl_OpenDd_default_for,
     types::{DataConnectorArgumentName, Deprecated, GraphQlFieldName, TypeReference},
 };
 
@@ -59,6 +59,8 @@ pub struct FunctionName(pub String);
 )]
 pub struct ProcedureName(pub String);
 
+impl_OpenDd_default_for!(ProcedureName);
+
 #[derive(
     Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, JsonSchema, opendds_derive::OpenDd,
 )]
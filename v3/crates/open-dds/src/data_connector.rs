@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 mod v1;
 
 pub use v1::{
-    ArgumentPreset, ArgumentPresetValue, DataConnectorLinkV1,
+    ArgumentPreset, ArgumentPresetValue, DataConnectorLinkV1, DataConnectorRequestOptions,
     DataConnectorUrlV1 as DataConnectorUrl, HttpHeadersPreset, ReadWriteUrls, ResponseHeaders,
 };
 
@@ -65,6 +65,9 @@ pub struct DataConnectorLinkV1 {
     /// HTTP response headers configuration that is forwarded from a data
     /// connector to the client.
     pub response_headers: Option<ResponseHeaders>,
+    /// Timeouts, size limits and retries for requests to the data connector,
+    /// overriding the defaults configured for the engine.
+    pub request_options: Option<DataConnectorRequestOptions>,
 }
 
 #[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
@@ -122,3 +125,22 @@ pub struct ResponseHeaders {
     /// response headers
     pub forward_headers: Vec<String>,
 }
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, opendds_derive::OpenDd, Eq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+/// Options for the HTTP requests made to a data connector. An omitted option
+/// falls back to the default configured for the engine.
+pub struct DataConnectorRequestOptions {
+    /// Timeout, in milliseconds, for establishing a connection to the data
+    /// connector.
+    pub connect_timeout_ms: Option<u64>,
+    /// Timeout, in milliseconds, for a complete request to the data connector,
+    /// including reading the response.
+    pub request_timeout_ms: Option<u64>,
+    /// The maximum size, in bytes, of a response from the data connector.
+    pub response_size_limit_bytes: Option<usize>,
+    /// The maximum number of times a failed query is retried. Mutations are
+    /// never retried.
+    pub max_retries: Option<u32>,
+}
@@ -5,6 +5,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::permissions::Role;
 use crate::types::GraphQlFieldName;
 
 #[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
@@ -30,7 +31,9 @@ pub enum GraphqlConfig {
 pub struct GraphqlConfigV1 {
     pub query: QueryGraphqlConfig,
     pub mutation: MutationGraphqlConfig,
+    pub subscription: Option<SubscriptionGraphqlConfig>,
     pub apollo_federation: Option<GraphqlApolloFederationConfig>,
+    pub operation_limits: Option<OperationLimitsGraphqlConfig>,
 }
 
 /// Configuration for the GraphQL schema of Hasura features for queries.
@@ -171,6 +174,60 @@ pub struct MutationGraphqlConfig {
     pub root_operation_type_name: String,
 }
 
+/// Configuration for the GraphQL schema of Hasura features for subscriptions.
+/// `None` means subscriptions are disabled.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "SubscriptionGraphqlConfig"))]
+pub struct SubscriptionGraphqlConfig {
+    /// The name of the root operation type name for subscriptions. Usually `subscription`.
+    pub root_operation_type_name: String,
+}
+
+/// Limits on the size and estimated cost of GraphQL operations. Operations exceeding any of the
+/// limits are rejected before they are executed.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "OperationLimitsGraphqlConfig"))]
+pub struct OperationLimitsGraphqlConfig {
+    /// The limits that apply to all roles without an override.
+    pub default: OperationLimits,
+    /// Limits that replace the default limits for particular roles.
+    #[opendd(default, json_schema(default_exp = "serde_json::json!([])"))]
+    pub role_overrides: Vec<RoleOperationLimits>,
+}
+
+/// Limits on the size and estimated cost of a GraphQL operation. An omitted limit is not enforced.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "OperationLimits"))]
+pub struct OperationLimits {
+    /// The maximum nesting depth of the selection sets in an operation.
+    pub max_depth: Option<u32>,
+    /// The maximum number of fields selected in an operation.
+    pub max_fields: Option<u32>,
+    /// The maximum number of aliased fields in an operation.
+    pub max_aliases: Option<u32>,
+    /// The maximum estimated cost of an operation, which weighs relationships and remote joins
+    /// by the number of rows they are expected to be evaluated for.
+    pub max_cost: Option<u32>,
+}
+
+/// Operation limits for a particular role.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "RoleOperationLimits"))]
+pub struct RoleOperationLimits {
+    /// The role the limits apply to.
+    pub role: Role,
+    /// The limits for the role.
+    pub limits: OperationLimits,
+}
+
 /// Configuration for the GraphQL schema of Hasura features for Apollo Federation.
 #[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
 #[serde(rename_all = "camelCase")]
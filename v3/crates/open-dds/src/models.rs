@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     aggregates::AggregateExpressionName,
     arguments::ArgumentDefinition,
-    commands::ArgumentMapping,
+    commands::{ArgumentMapping, ProcedureName},
     data_connector::DataConnectorName,
     identifier::Identifier,
     impl_JsonSchema_with_OpenDd_for,
@@ -192,6 +192,9 @@ pub struct ModelGraphQlDefinition {
     /// Configures the query root field added to the GraphQL API that can be used to
     /// aggregate over the model
     pub aggregate: Option<ModelAggregateGraphQlDefinition>,
+    /// Configures the mutation root fields added to the GraphQL API that can be used to
+    /// insert, update and delete single objects of the model
+    pub point_mutations: Option<ModelPointMutationsGraphQlDefinition>,
 }
 
 impl ModelGraphQlDefinition {
@@ -236,6 +239,128 @@ pub struct SelectUniqueGraphQlDefinition {
     pub deprecated: Option<Deprecated>,
 }
 
+/// The definition of the GraphQL API for inserting, updating and deleting single objects of a
+/// model.
+///
+/// Each mutation is executed by a procedure of the model's data connector. Unless a procedure is
+/// given explicitly, the procedure is found by the following naming convention, where
+/// `<collection>` is the collection backing the model and `<columns>` are the columns of the
+/// unique identifier joined by `_and_`:
+///
+/// - `insert_<collection>`, with the arguments `object` and `post_check`
+/// - `update_<collection>_by_<columns>`, with the arguments `key_<column>` for each column of the
+///   unique identifier, `update_columns`, `pre_check` and `post_check`
+/// - `delete_<collection>_by_<columns>`, with the arguments `key_<column>` for each column of the
+///   unique identifier and `pre_check`
+///
+/// The `pre_check` and `post_check` arguments are predicates over the collection, which the rows
+/// have to satisfy before and after the mutation respectively. Each procedure returns the
+/// affected row, or null if there is none.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(
+    title = "ModelPointMutationsGraphQlDefinition",
+    example = "ModelPointMutationsGraphQlDefinition::example"
+))]
+pub struct ModelPointMutationsGraphQlDefinition {
+    /// Adds a mutation root field that inserts an object into the model.
+    pub insert: Option<InsertMutationGraphQlDefinition>,
+    /// Adds a mutation root field that updates the object of the model with the given unique
+    /// identifier.
+    pub update: Option<UpdateMutationGraphQlDefinition>,
+    /// Adds a mutation root field that deletes the object of the model with the given unique
+    /// identifier.
+    pub delete: Option<DeleteMutationGraphQlDefinition>,
+}
+
+impl ModelPointMutationsGraphQlDefinition {
+    fn example() -> serde_json::Value {
+        serde_json::json!({
+            "insert": {
+                "mutationRootField": "insertArticle",
+                "insertInputType": "ArticleInsertInput"
+            },
+            "update": {
+                "mutationRootField": "updateArticleByID",
+                "uniqueIdentifier": [
+                    "article_id"
+                ],
+                "updateInputType": "ArticleUpdateInput"
+            },
+            "delete": {
+                "mutationRootField": "deleteArticleByID",
+                "uniqueIdentifier": [
+                    "article_id"
+                ],
+                "procedure": "remove_article"
+            }
+        })
+    }
+}
+
+/// The definition of the GraphQL API for inserting an object into a model.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "InsertMutationGraphQlDefinition"))]
+pub struct InsertMutationGraphQlDefinition {
+    /// The name of the mutation root field for this API.
+    pub mutation_root_field: GraphQlFieldName,
+    /// The type name of the input type holding the object to insert.
+    pub insert_input_type: GraphQlTypeName,
+    /// The procedure of the data connector that inserts the object. Defaults to
+    /// `insert_<collection>`.
+    pub procedure: Option<ProcedureName>,
+    /// The description of the insert mutation field.
+    pub description: Option<String>,
+    /// Whether this insert mutation field is deprecated.
+    /// If set, the deprecation status is added to the insert mutation field's graphql schema.
+    pub deprecated: Option<Deprecated>,
+}
+
+/// The definition of the GraphQL API for updating the object of a model with a unique identifier.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "UpdateMutationGraphQlDefinition"))]
+pub struct UpdateMutationGraphQlDefinition {
+    /// The name of the mutation root field for this API.
+    pub mutation_root_field: GraphQlFieldName,
+    /// A set of fields which can uniquely identify a row/object in the model.
+    pub unique_identifier: Vec<FieldName>,
+    /// The type name of the input type holding the new values of the updated fields.
+    pub update_input_type: GraphQlTypeName,
+    /// The procedure of the data connector that updates the object. Defaults to
+    /// `update_<collection>_by_<columns>`.
+    pub procedure: Option<ProcedureName>,
+    /// The description of the update mutation field.
+    pub description: Option<String>,
+    /// Whether this update mutation field is deprecated.
+    /// If set, the deprecation status is added to the update mutation field's graphql schema.
+    pub deprecated: Option<Deprecated>,
+}
+
+/// The definition of the GraphQL API for deleting the object of a model with a unique identifier.
+#[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+#[opendd(json_schema(title = "DeleteMutationGraphQlDefinition"))]
+pub struct DeleteMutationGraphQlDefinition {
+    /// The name of the mutation root field for this API.
+    pub mutation_root_field: GraphQlFieldName,
+    /// A set of fields which can uniquely identify a row/object in the model.
+    pub unique_identifier: Vec<FieldName>,
+    /// The procedure of the data connector that deletes the object. Defaults to
+    /// `delete_<collection>_by_<columns>`.
+    pub procedure: Option<ProcedureName>,
+    /// The description of the delete mutation field.
+    pub description: Option<String>,
+    /// Whether this delete mutation field is deprecated.
+    /// If set, the deprecation status is added to the delete mutation field's graphql schema.
+    pub deprecated: Option<Deprecated>,
+}
+
 /// The definition of the GraphQL API for selecting rows from a model.
 #[derive(Serialize, Clone, Debug, PartialEq, opendds_derive::OpenDd)]
 #[serde(rename_all = "camelCase")]
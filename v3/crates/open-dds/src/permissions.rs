@@ -258,6 +258,15 @@ pub struct ModelPermission {
     /// The permissions for selecting from this model for this role.
     /// If this is null, the role is not allowed to query the model.
     pub select: Option<SelectPermission>,
+    /// The permissions for inserting into this model for this role.
+    /// If this is null, the role is not allowed to insert into the model.
+    pub insert: Option<InsertPermission>,
+    /// The permissions for updating this model for this role.
+    /// If this is null, the role is not allowed to update the model.
+    pub update: Option<UpdatePermission>,
+    /// The permissions for deleting from this model for this role.
+    /// If this is null, the role is not allowed to delete from the model.
+    pub delete: Option<DeletePermission>,
 }
 
 impl ModelPermission {
@@ -299,6 +308,53 @@ pub struct SelectPermission {
     /// Preset values for arguments for this role
     #[opendd(default, json_schema(default_exp = "serde_json::json!([])"))]
     pub argument_presets: Vec<ArgumentPreset>,
+    /// The maximum number of rows this role can select from the model in a
+    /// single query. A smaller limit requested in the query takes precedence.
+    pub limit: Option<u32>,
+    /// Whether this role can select aggregates of the model. Aggregates are
+    /// allowed if this is not set.
+    pub allow_aggregations: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Debug, Eq, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "InsertPermission"))]
+/// Defines the permissions for inserting into a model for a role.
+pub struct InsertPermission {
+    /// Check expression that every inserted row must satisfy.
+    /// Null check implies any row can be inserted.
+    pub check: NullableModelPredicate,
+    /// Preset values for fields of the inserted rows for this role.
+    /// Preset fields can't be set by the role.
+    #[opendd(default, json_schema(default_exp = "serde_json::json!([])"))]
+    pub field_presets: Vec<FieldPreset>,
+}
+
+#[derive(Serialize, Clone, Debug, Eq, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "UpdatePermission"))]
+/// Defines the permissions for updating a model for a role.
+pub struct UpdatePermission {
+    /// Filter expression selecting the rows that can be updated.
+    /// Null filter implies all rows can be updated.
+    pub filter: NullableModelPredicate,
+    /// Check expression that every row must satisfy once it is updated.
+    /// Null check implies any update is allowed.
+    pub check: NullableModelPredicate,
+    /// Preset values for fields of the updated rows for this role.
+    /// Preset fields can't be set by the role.
+    #[opendd(default, json_schema(default_exp = "serde_json::json!([])"))]
+    pub field_presets: Vec<FieldPreset>,
+}
+
+#[derive(Serialize, Clone, Debug, Eq, PartialEq, opendds_derive::OpenDd)]
+#[serde(rename_all = "camelCase")]
+#[opendd(json_schema(title = "DeletePermission"))]
+/// Defines the permissions for deleting from a model for a role.
+pub struct DeletePermission {
+    /// Filter expression selecting the rows that can be deleted.
+    /// Null filter implies all rows can be deleted.
+    pub filter: NullableModelPredicate,
 }
 
 // We use this instead of an Option, so that we can make the filter field in
@@ -3,6 +3,7 @@ use lang_graphql::{ast::common as ast, mk_name};
 use open_dds::aggregates::AggregateExpressionName;
 use open_dds::types::FieldName;
 use open_dds::{
+    arguments::ArgumentName,
     commands::CommandName,
     models::ModelName,
     permissions::Role,
@@ -27,11 +28,13 @@ mod commands;
 mod model_arguments;
 mod model_filter;
 mod model_filter_input;
+mod model_mutations;
 mod model_order_by;
 mod mutation_root;
 mod permissions;
 mod query_root;
 mod relay;
+mod subscription_root;
 mod types;
 
 pub use aggregates::{AggregateOutputAnnotation, AggregationFunctionAnnotation};
@@ -42,9 +45,9 @@ pub use types::output_type::relationship::{
 pub use types::{
     Annotation, ApolloFederationRootFields, ArgumentNameAndPath, ArgumentPresets,
     BooleanExpressionAnnotation, CommandSourceDetail, EntityFieldTypeNameMapping, GlobalID,
-    InputAnnotation, ModelFilterArgument, ModelInputAnnotation, ModelOrderByDirection,
-    NamespaceAnnotation, NodeFieldTypeNameMapping, OutputAnnotation, RootFieldAnnotation,
-    RootFieldKind, TypeKind,
+    InputAnnotation, ModelFilterArgument, ModelInputAnnotation, ModelMutationKind,
+    ModelOrderByDirection, NamespaceAnnotation, NodeFieldTypeNameMapping, OutputAnnotation,
+    RootFieldAnnotation, RootFieldKind, TypeKind,
 };
 
 /// This 'NamespacedGetter' looks up 'NamespacedNodeInfo's according to actual roles.
@@ -142,6 +145,11 @@ impl gql_schema::SchemaContext for GDS {
             types::TypeId::MutationRoot { graphql_type_name } => Ok(gql_schema::TypeInfo::Object(
                 mutation_root::mutation_root_schema(builder, self, graphql_type_name)?,
             )),
+            types::TypeId::SubscriptionRoot { graphql_type_name } => {
+                Ok(gql_schema::TypeInfo::Object(
+                    subscription_root::subscription_root_schema(builder, self, graphql_type_name)?,
+                ))
+            }
             types::TypeId::OutputType {
                 gds_type_name,
                 graphql_type_name,
@@ -199,11 +207,13 @@ impl gql_schema::SchemaContext for GDS {
             types::TypeId::ModelOrderByExpression {
                 model_name,
                 graphql_type_name,
+                include_remote_relationships,
             } => model_order_by::build_model_order_by_input_schema(
                 self,
                 builder,
                 graphql_type_name,
                 model_name,
+                *include_remote_relationships,
             ),
             types::TypeId::OrderByEnumType { graphql_type_name } => {
                 model_order_by::build_order_by_enum_type_schema(self, builder, graphql_type_name)
@@ -243,6 +253,24 @@ impl gql_schema::SchemaContext for GDS {
                 model_name,
                 graphql_type_name,
             ),
+            types::TypeId::ModelInsertInputType {
+                model_name,
+                graphql_type_name,
+            } => model_mutations::build_model_insert_input_type(
+                self,
+                builder,
+                model_name,
+                graphql_type_name,
+            ),
+            types::TypeId::ModelUpdateInputType {
+                model_name,
+                graphql_type_name,
+            } => model_mutations::build_model_update_input_type(
+                self,
+                builder,
+                model_name,
+                graphql_type_name,
+            ),
         }
     }
 
@@ -254,7 +282,26 @@ impl gql_schema::SchemaContext for GDS {
             mutation: Some(types::TypeId::MutationRoot {
                 graphql_type_name: self.metadata.graphql_config.mutation_root_type_name.clone(),
             }),
-            subscription: None,
+            subscription: self
+                .metadata
+                .graphql_config
+                .subscription_root_type_name
+                .as_ref()
+                .map(|graphql_type_name| types::TypeId::SubscriptionRoot {
+                    graphql_type_name: graphql_type_name.clone(),
+                }),
+        }
+    }
+
+    fn get_operation_limits(&self) -> lang_graphql::validation::NamespacedOperationLimits<Self> {
+        let operation_limits = &self.metadata.graphql_config.operation_limits;
+        lang_graphql::validation::NamespacedOperationLimits {
+            default: operation_limits.default.clone(),
+            namespaced: operation_limits
+                .role_overrides
+                .iter()
+                .map(|(role, limits)| (role.clone(), limits.clone()))
+                .collect(),
         }
     }
 }
@@ -289,6 +336,13 @@ pub enum Error {
         field_name: ast::Name,
         type_name: Qualified<CustomTypeName>,
     },
+    #[error("the relationship {relationship_name} of type {type_name} does not map the argument {argument_name} of its target model {model_name}")]
+    UnmappedModelArgumentInRelationship {
+        relationship_name: RelationshipName,
+        type_name: Qualified<CustomTypeName>,
+        model_name: Qualified<ModelName>,
+        argument_name: ArgumentName,
+    },
     #[error("field name for relationship {relationship_name} of type {type_name} conflicts with the existing field {field_name}")]
     RelationshipFieldNameConflict {
         relationship_name: RelationshipName,
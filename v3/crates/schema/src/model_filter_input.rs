@@ -83,10 +83,13 @@ pub fn build_model_filter_input_type(
         builder,
         model_with_permissions,
     )?;
+    // aggregates are computed by the data connector, which can't order by remote relationships
     add_order_by_input_field(
         &mut filter_input_type_fields,
+        gds,
         builder,
         model_with_permissions,
+        false,
     )?;
     add_where_input_field(
         &mut filter_input_type_fields,
@@ -147,19 +150,23 @@ pub fn add_offset_input_field(
     Ok(())
 }
 
+/// Adds the order_by input field. The fields of remote relationships are only included in it when
+/// the engine sorts the rows, which is only done for root fields.
 pub fn add_order_by_input_field(
     fields: &mut BTreeMap<ast::Name, gql_schema::Namespaced<GDS, gql_schema::InputField<GDS>>>,
+    gds: &GDS,
     builder: &mut gql_schema::Builder<GDS>,
     model: &metadata_resolve::ModelWithPermissions,
+    include_remote_relationships: bool,
 ) -> Result<(), Error> {
     if let Some(order_by_expression_info) = &model.model.graphql_api.order_by_expression {
-        let order_by_argument = {
-            get_order_by_expression_input_field(
-                builder,
-                model.model.name.clone(),
-                order_by_expression_info,
-            )
-        };
+        let order_by_argument = get_order_by_expression_input_field(
+            gds,
+            builder,
+            model,
+            order_by_expression_info,
+            include_remote_relationships,
+        )?;
 
         fields.insert(
             order_by_argument.name.clone(),
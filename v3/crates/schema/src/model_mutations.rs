@@ -0,0 +1,360 @@
+//! Schema for the point mutations of a model
+//!
+//! Point mutations insert, update or delete a single row of a model, and are
+//! executed using the procedures of the model's data connector.
+
+use lang_graphql::{ast::common as ast, schema as gql_schema};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::types::output_type::{get_custom_output_type, get_object_type_representation};
+use crate::{mk_deprecation_status, permissions, GDS};
+use crate::{
+    types::{self, input_type::get_input_type, Annotation, ModelInputAnnotation, TypeId},
+    Error,
+};
+use metadata_resolve::{mk_name, Qualified, QualifiedTypeReference};
+use open_dds::models::ModelName;
+use open_dds::permissions::Role;
+use open_dds::types::FieldName;
+
+/// The name of the argument holding the object to insert
+const INSERT_OBJECT_ARGUMENT_NAME: &str = "object";
+
+/// The name of the argument holding the columns to update
+const UPDATE_COLUMNS_ARGUMENT_NAME: &str = "set";
+
+/// Generates schema for an 'insert' point mutation
+pub(crate) fn insert_mutation_field(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model: &metadata_resolve::ModelWithPermissions,
+    insert_mutation: &metadata_resolve::InsertMutationGraphQlDefinition,
+) -> Result<
+    (
+        ast::Name,
+        gql_schema::Namespaced<GDS, gql_schema::Field<GDS>>,
+    ),
+    crate::Error,
+> {
+    let mutation_root_field = insert_mutation.mutation_root_field.clone();
+
+    let object_argument_name = mk_name(INSERT_OBJECT_ARGUMENT_NAME)?;
+    let insert_input_type = builder.register_type(TypeId::ModelInsertInputType {
+        model_name: model.model.name.clone(),
+        graphql_type_name: insert_mutation.insert_input_type_name.clone(),
+    });
+    let object_argument = gql_schema::InputField::new(
+        object_argument_name.clone(),
+        None,
+        Annotation::Input(types::InputAnnotation::Model(
+            ModelInputAnnotation::ModelInsertObjectArgument,
+        )),
+        ast::TypeContainer::named_non_null(insert_input_type),
+        None,
+        gql_schema::DeprecationStatus::NotDeprecated,
+    );
+    let arguments = BTreeMap::from([(
+        object_argument_name,
+        builder.allow_all_namespaced(object_argument),
+    )]);
+
+    let output_typename = get_custom_output_type(gds, builder, &model.model.data_type)?;
+
+    let field_annotations =
+        permissions::get_insert_mutation_namespace_annotations(model, &gds.metadata.object_types)?;
+
+    let field = builder.conditional_namespaced(
+        gql_schema::Field::new(
+            mutation_root_field.clone(),
+            insert_mutation.description.clone(),
+            Annotation::Output(types::OutputAnnotation::RootField(
+                types::RootFieldAnnotation::ModelMutation {
+                    data_type: model.model.data_type.clone(),
+                    source: model.model.source.clone(),
+                    procedure_name: insert_mutation.procedure.clone(),
+                    kind: types::ModelMutationKind::Insert,
+                    name: model.model.name.clone(),
+                },
+            )),
+            ast::TypeContainer::named_null(output_typename),
+            arguments,
+            mk_deprecation_status(&insert_mutation.deprecated),
+        ),
+        field_annotations,
+    );
+    Ok((mutation_root_field, field))
+}
+
+/// Generates schema for an 'update' point mutation
+pub(crate) fn update_mutation_field(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model: &metadata_resolve::ModelWithPermissions,
+    update_mutation: &metadata_resolve::UpdateMutationGraphQlDefinition,
+    parent_type: &ast::TypeName,
+) -> Result<
+    (
+        ast::Name,
+        gql_schema::Namespaced<GDS, gql_schema::Field<GDS>>,
+    ),
+    crate::Error,
+> {
+    let mutation_root_field = update_mutation.mutation_root_field.clone();
+
+    let mut arguments =
+        unique_identifier_arguments(gds, builder, update_mutation.unique_identifier.iter())?;
+
+    let update_columns_argument_name = mk_name(UPDATE_COLUMNS_ARGUMENT_NAME)?;
+    let update_input_type = builder.register_type(TypeId::ModelUpdateInputType {
+        model_name: model.model.name.clone(),
+        graphql_type_name: update_mutation.update_input_type_name.clone(),
+    });
+    let update_columns_argument = gql_schema::InputField::new(
+        update_columns_argument_name.clone(),
+        None,
+        Annotation::Input(types::InputAnnotation::Model(
+            ModelInputAnnotation::ModelUpdateColumnsArgument,
+        )),
+        ast::TypeContainer::named_non_null(update_input_type),
+        None,
+        gql_schema::DeprecationStatus::NotDeprecated,
+    );
+    if arguments
+        .insert(
+            update_columns_argument_name.clone(),
+            builder.allow_all_namespaced(update_columns_argument),
+        )
+        .is_some()
+    {
+        return Err(crate::Error::GraphQlArgumentConflict {
+            argument_name: update_columns_argument_name,
+            field_name: mutation_root_field,
+            type_name: parent_type.clone(),
+        });
+    }
+
+    let object_type_representation = get_object_type_representation(gds, &model.model.data_type)?;
+    let output_typename = get_custom_output_type(gds, builder, &model.model.data_type)?;
+
+    let field_annotations = permissions::get_update_mutation_namespace_annotations(
+        model,
+        object_type_representation,
+        update_mutation,
+    )?;
+
+    let field = builder.conditional_namespaced(
+        gql_schema::Field::new(
+            mutation_root_field.clone(),
+            update_mutation.description.clone(),
+            Annotation::Output(types::OutputAnnotation::RootField(
+                types::RootFieldAnnotation::ModelMutation {
+                    data_type: model.model.data_type.clone(),
+                    source: model.model.source.clone(),
+                    procedure_name: update_mutation.procedure.clone(),
+                    kind: types::ModelMutationKind::Update,
+                    name: model.model.name.clone(),
+                },
+            )),
+            ast::TypeContainer::named_null(output_typename),
+            arguments,
+            mk_deprecation_status(&update_mutation.deprecated),
+        ),
+        field_annotations,
+    );
+    Ok((mutation_root_field, field))
+}
+
+/// Generates schema for a 'delete' point mutation
+pub(crate) fn delete_mutation_field(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model: &metadata_resolve::ModelWithPermissions,
+    delete_mutation: &metadata_resolve::DeleteMutationGraphQlDefinition,
+) -> Result<
+    (
+        ast::Name,
+        gql_schema::Namespaced<GDS, gql_schema::Field<GDS>>,
+    ),
+    crate::Error,
+> {
+    let mutation_root_field = delete_mutation.mutation_root_field.clone();
+
+    let arguments =
+        unique_identifier_arguments(gds, builder, delete_mutation.unique_identifier.iter())?;
+
+    let object_type_representation = get_object_type_representation(gds, &model.model.data_type)?;
+    let output_typename = get_custom_output_type(gds, builder, &model.model.data_type)?;
+
+    let field_annotations = permissions::get_delete_mutation_namespace_annotations(
+        model,
+        object_type_representation,
+        delete_mutation,
+    );
+
+    let field = builder.conditional_namespaced(
+        gql_schema::Field::new(
+            mutation_root_field.clone(),
+            delete_mutation.description.clone(),
+            Annotation::Output(types::OutputAnnotation::RootField(
+                types::RootFieldAnnotation::ModelMutation {
+                    data_type: model.model.data_type.clone(),
+                    source: model.model.source.clone(),
+                    procedure_name: delete_mutation.procedure.clone(),
+                    kind: types::ModelMutationKind::Delete,
+                    name: model.model.name.clone(),
+                },
+            )),
+            ast::TypeContainer::named_null(output_typename),
+            arguments,
+            mk_deprecation_status(&delete_mutation.deprecated),
+        ),
+        field_annotations,
+    );
+    Ok((mutation_root_field, field))
+}
+
+/// The arguments identifying the row to update or delete
+fn unique_identifier_arguments<'a>(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    unique_identifier: impl Iterator<
+        Item = (
+            &'a open_dds::types::FieldName,
+            &'a metadata_resolve::UniqueIdentifierField,
+        ),
+    >,
+) -> Result<BTreeMap<ast::Name, gql_schema::Namespaced<GDS, gql_schema::InputField<GDS>>>, Error> {
+    let mut arguments = BTreeMap::new();
+    for (field_name, field) in unique_identifier {
+        let graphql_field_name = mk_name(field_name.0.as_str())?;
+        let argument = gql_schema::InputField::new(
+            graphql_field_name,
+            None,
+            Annotation::Input(types::InputAnnotation::Model(
+                ModelInputAnnotation::ModelUniqueIdentifierArgument {
+                    ndc_column: field.ndc_column.clone(),
+                },
+            )),
+            get_input_type(gds, builder, &field.field_type)?,
+            None,
+            gql_schema::DeprecationStatus::NotDeprecated,
+        );
+
+        arguments.insert(
+            argument.name.clone(),
+            builder.allow_all_namespaced(argument),
+        );
+    }
+    Ok(arguments)
+}
+
+/// Builds the input type holding the object to insert in an 'insert' point
+/// mutation. Its fields are those of the model's object type, except the ones
+/// preset for the role.
+pub fn build_model_insert_input_type(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model_name: &Qualified<ModelName>,
+    graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::TypeInfo<GDS>, Error> {
+    let model = get_model(gds, model_name)?;
+    let role_field_presets: BTreeMap<_, _> = model
+        .insert_permissions
+        .iter()
+        .map(|(role, insert_permission)| (role, &insert_permission.field_presets))
+        .collect();
+    let input_fields = model_input_fields(gds, builder, model, &role_field_presets, false)?;
+    Ok(gql_schema::TypeInfo::InputObject(
+        gql_schema::InputObject::new(graphql_type_name.clone(), None, input_fields, Vec::new()),
+    ))
+}
+
+/// Builds the input type holding the columns to set in an 'update' point
+/// mutation. Every field of the model's object type is optional, and only the
+/// given fields are updated.
+pub fn build_model_update_input_type(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model_name: &Qualified<ModelName>,
+    graphql_type_name: &ast::TypeName,
+) -> Result<gql_schema::TypeInfo<GDS>, Error> {
+    let model = get_model(gds, model_name)?;
+    let role_field_presets: BTreeMap<_, _> = model
+        .update_permissions
+        .iter()
+        .map(|(role, update_permission)| (role, &update_permission.field_presets))
+        .collect();
+    let input_fields = model_input_fields(gds, builder, model, &role_field_presets, true)?;
+    Ok(gql_schema::TypeInfo::InputObject(
+        gql_schema::InputObject::new(graphql_type_name.clone(), None, input_fields, Vec::new()),
+    ))
+}
+
+fn get_model<'a>(
+    gds: &'a GDS,
+    model_name: &Qualified<ModelName>,
+) -> Result<&'a metadata_resolve::ModelWithPermissions, Error> {
+    gds.metadata
+        .models
+        .get(model_name)
+        .ok_or_else(|| Error::InternalModelNotFound {
+            model_name: model_name.clone(),
+        })
+}
+
+/// The fields of the input type of an 'insert' or 'update' point mutation, one
+/// for each field of the model's object type. A role can set a field only if it
+/// may perform the mutation and has no preset for the field, either in the input
+/// permissions of the object type or in the mutation permission of the model.
+fn model_input_fields(
+    gds: &GDS,
+    builder: &mut gql_schema::Builder<GDS>,
+    model: &metadata_resolve::ModelWithPermissions,
+    role_field_presets: &BTreeMap<
+        &Role,
+        &BTreeMap<FieldName, (QualifiedTypeReference, metadata_resolve::ValueExpression)>,
+    >,
+    make_nullable: bool,
+) -> Result<BTreeMap<ast::Name, gql_schema::Namespaced<GDS, gql_schema::InputField<GDS>>>, Error> {
+    let object_type_representation = get_object_type_representation(gds, &model.model.data_type)?;
+
+    let mut input_fields = BTreeMap::new();
+    for (field_name, field_definition) in &object_type_representation.object_type.fields {
+        let graphql_field_name = mk_name(field_name.0.as_str())?;
+        let input_field_type = QualifiedTypeReference {
+            underlying_type: field_definition.field_type.underlying_type.clone(),
+            nullable: make_nullable || field_definition.field_type.nullable,
+        };
+
+        let input_field = gql_schema::InputField::new(
+            graphql_field_name.clone(),
+            field_definition.description.clone(),
+            Annotation::Input(types::InputAnnotation::InputObjectField {
+                field_name: field_name.clone(),
+                field_type: field_definition.field_type.clone(),
+            }),
+            get_input_type(gds, builder, &input_field_type)?,
+            None,
+            gql_schema::DeprecationStatus::NotDeprecated,
+        );
+
+        let mut role_map = HashMap::new();
+        for (role, field_presets) in role_field_presets {
+            let has_type_input_preset = object_type_representation
+                .type_input_permissions
+                .get(role)
+                .map_or(false, |permission| {
+                    permission.field_presets.contains_key(field_name)
+                });
+            if !has_type_input_preset && !field_presets.contains_key(field_name) {
+                role_map.insert((*role).clone(), None);
+            }
+        }
+
+        input_fields.insert(
+            graphql_field_name,
+            builder.conditional_namespaced(input_field, role_map),
+        );
+    }
+    Ok(input_fields)
+}
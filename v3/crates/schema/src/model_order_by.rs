@@ -7,6 +7,7 @@ use std::collections::{BTreeMap, HashMap};
 
 use super::types::output_type::relationship::OrderByRelationshipAnnotation;
 use super::types::{output_type::get_object_type_representation, Annotation, TypeId};
+use crate::mk_typename;
 use crate::permissions;
 use crate::types;
 use crate::GDS;
@@ -70,25 +71,112 @@ pub fn build_order_by_enum_type_schema(
 }
 
 pub fn get_order_by_expression_input_field(
+    gds: &GDS,
     builder: &mut gql_schema::Builder<GDS>,
-    model_name: Qualified<ModelName>,
+    model: &metadata_resolve::ModelWithPermissions,
     order_by_expression_info: &metadata_resolve::ModelOrderByExpression,
-) -> gql_schema::InputField<GDS> {
-    gql_schema::InputField::new(
+    include_remote_relationships: bool,
+) -> Result<gql_schema::InputField<GDS>, Error> {
+    Ok(gql_schema::InputField::new(
         order_by_expression_info.order_by_field_name.clone(),
         None,
         types::Annotation::Input(types::InputAnnotation::Model(
             types::ModelInputAnnotation::ModelOrderByExpression,
         )),
         ast::TypeContainer::list_null(ast::TypeContainer::named_non_null(builder.register_type(
-            types::TypeId::ModelOrderByExpression {
-                model_name,
-                graphql_type_name: order_by_expression_info.order_by_type_name.clone(),
-            },
+            get_order_by_expression_type_id(
+                gds,
+                model,
+                order_by_expression_info,
+                include_remote_relationships,
+            )?,
         ))),
         None,
         gql_schema::DeprecationStatus::NotDeprecated,
-    )
+    ))
+}
+
+/// The type of the order_by input of a model. Only the engine can order by the fields of remote
+/// relationships, which it does for root fields, so the order_by inputs elsewhere leave them out.
+/// Those get a separate type, suffixed with `Local`, when the model has remote relationships to
+/// order by.
+fn get_order_by_expression_type_id(
+    gds: &GDS,
+    model: &metadata_resolve::ModelWithPermissions,
+    order_by_expression_info: &metadata_resolve::ModelOrderByExpression,
+    include_remote_relationships: bool,
+) -> Result<TypeId, Error> {
+    if include_remote_relationships || !has_remote_order_by_relationships(gds, model)? {
+        Ok(TypeId::ModelOrderByExpression {
+            model_name: model.model.name.clone(),
+            graphql_type_name: order_by_expression_info.order_by_type_name.clone(),
+            include_remote_relationships: true,
+        })
+    } else {
+        Ok(TypeId::ModelOrderByExpression {
+            model_name: model.model.name.clone(),
+            graphql_type_name: mk_typename(&format!(
+                "{}Local",
+                order_by_expression_info.order_by_type_name
+            ))?,
+            include_remote_relationships: false,
+        })
+    }
+}
+
+fn has_remote_order_by_relationships(
+    gds: &GDS,
+    model: &metadata_resolve::ModelWithPermissions,
+) -> Result<bool, Error> {
+    let Some(model_source) = &model.model.source else {
+        return Ok(false);
+    };
+    let object_type_representation = get_object_type_representation(gds, &model.model.data_type)?;
+    for relationship in object_type_representation.relationships.values() {
+        if let metadata_resolve::RelationshipTarget::Model {
+            model_name,
+            relationship_type: RelationshipType::Object,
+            mappings,
+            ..
+        } = &relationship.target
+        {
+            let target_model = gds.metadata.models.get(model_name).ok_or_else(|| {
+                crate::Error::InternalModelNotFound {
+                    model_name: model_name.clone(),
+                }
+            })?;
+            if let (Some(target_source), Some(_)) = (
+                &target_model.model.source,
+                &target_model.model.graphql_api.order_by_expression,
+            ) {
+                let target_model_source = metadata_resolve::ModelTargetSource::from_model_source(
+                    target_source,
+                    relationship,
+                )?;
+                if is_remote_relationship(model_source, &target_model_source, mappings) {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn is_remote_relationship(
+    model_source: &metadata_resolve::ModelSource,
+    target_model_source: &metadata_resolve::ModelTargetSource,
+    mappings: &[metadata_resolve::RelationshipModelMapping],
+) -> bool {
+    match metadata_resolve::relationship_predicate_execution_category(
+        &model_source.data_connector,
+        &target_model_source.model.data_connector,
+        &target_model_source.capabilities,
+        mappings,
+    ) {
+        metadata_resolve::RelationshipExecutionCategory::Local => false,
+        metadata_resolve::RelationshipExecutionCategory::RemoteForEach
+        | metadata_resolve::RelationshipExecutionCategory::RemoteNaive => true,
+    }
 }
 
 pub fn build_model_order_by_input_schema(
@@ -96,6 +184,7 @@ pub fn build_model_order_by_input_schema(
     builder: &mut gql_schema::Builder<GDS>,
     type_name: &ast::TypeName,
     model_name: &Qualified<ModelName>,
+    include_remote_relationships: bool,
 ) -> Result<gql_schema::TypeInfo<GDS>, Error> {
     let model =
         gds.metadata
@@ -176,37 +265,43 @@ pub fn build_model_order_by_input_schema(
                             target_source,
                             relationship,
                         )?;
-                    // order_by expression with relationships is currently only supported for local relationships
-                    if let metadata_resolve::RelationshipExecutionCategory::Local =
-                        metadata_resolve::relationship_execution_category(
-                            &model_source.data_connector,
-                            &target_source.data_connector,
-                            &target_model_source.capabilities,
-                        )
+                    // Ordering by fields of remote relationships is done by the engine rather than by the
+                    // data connector.
+                    if !include_remote_relationships
+                        && is_remote_relationship(model_source, &target_model_source, mappings)
                     {
-                        // TODO(naveen): Support Array relationships in order_by when the support for aggregates is implemented
-                        if let RelationshipType::Object = relationship_type {
-                            // If the relationship target model does not have orderByExpressionType do not include
-                            // it in the source model order_by input type.
-                            if let Some(target_model_order_by_expression) =
-                                target_model.model.graphql_api.order_by_expression.as_ref()
-                            {
-                                let target_model_order_by_expression_type_name =
-                                    &target_model_order_by_expression.order_by_type_name;
-
-                                let annotation = OrderByRelationshipAnnotation {
-                                    source_type: relationship.source.clone(),
-                                    relationship_name: relationship.name.clone(),
-                                    target_model_name: model_name.clone(),
-                                    target_source: target_model_source.clone(),
-                                    target_type: target_typename.clone(),
-                                    relationship_type: relationship_type.clone(),
-                                    mappings: mappings.clone(),
-                                    source_data_connector: model_source.data_connector.clone(),
-                                    source_type_mappings: model_source.type_mappings.clone(),
-                                };
-
-                                fields.insert(
+                        continue;
+                    }
+                    // TODO(naveen): Support Array relationships in order_by when the support for aggregates is implemented
+                    if let RelationshipType::Object = relationship_type {
+                        // If the relationship target model does not have orderByExpressionType do not include
+                        // it in the source model order_by input type.
+                        if let Some(target_model_order_by_expression) =
+                            target_model.model.graphql_api.order_by_expression.as_ref()
+                        {
+                            // the engine doesn't order by remote relationships reached through
+                            // other relationships
+                            let target_model_order_by_expression_type =
+                                builder.register_type(get_order_by_expression_type_id(
+                                    gds,
+                                    target_model,
+                                    target_model_order_by_expression,
+                                    false,
+                                )?);
+
+                            let annotation = OrderByRelationshipAnnotation {
+                                source_type: relationship.source.clone(),
+                                relationship_name: relationship.name.clone(),
+                                target_model_name: model_name.clone(),
+                                target_source: target_model_source.clone(),
+                                target_type: target_typename.clone(),
+                                relationship_type: relationship_type.clone(),
+                                mappings: mappings.clone(),
+                                source_data_connector: model_source.data_connector.clone(),
+                                source_type_mappings: model_source.type_mappings.clone(),
+                            };
+
+                            fields.insert(
                                     rel_name.clone(),
                                     builder.conditional_namespaced(
                                         gql_schema::InputField::new(
@@ -216,9 +311,7 @@ pub fn build_model_order_by_input_schema(
                                                 types::ModelInputAnnotation::ModelOrderByRelationshipArgument(annotation),
                                             )),
                                             ast::TypeContainer::named_null(
-                                                gql_schema::RegisteredTypeName::new(
-                                                    target_model_order_by_expression_type_name.0.clone(),
-                                                ),
+                                                target_model_order_by_expression_type,
                                             ),
                                             None,
                                             gql_schema::DeprecationStatus::NotDeprecated,
@@ -232,7 +325,6 @@ pub fn build_model_order_by_input_schema(
                                         )?,
                                     ),
                                 );
-                            }
                         }
                     }
                 }
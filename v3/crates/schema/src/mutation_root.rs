@@ -4,7 +4,7 @@ use lang_graphql::ast::common::{self as ast, TypeName};
 use lang_graphql::schema as gql_schema;
 use std::collections::BTreeMap;
 
-use crate::{commands, GDS};
+use crate::{commands, model_mutations, GDS};
 
 /// Generates schema for the query root type
 pub fn mutation_root_schema(
@@ -37,6 +37,30 @@ pub fn mutation_root_schema(
         }
     }
 
+    // Add the point mutations of the models which have them defined
+    for model in gds.metadata.models.values() {
+        if let Some(insert_mutation) = &model.model.graphql_api.insert_mutation {
+            let (field_name, field) =
+                model_mutations::insert_mutation_field(gds, builder, model, insert_mutation)?;
+            fields.insert(field_name, field);
+        }
+        if let Some(update_mutation) = &model.model.graphql_api.update_mutation {
+            let (field_name, field) = model_mutations::update_mutation_field(
+                gds,
+                builder,
+                model,
+                update_mutation,
+                mutation_root_type_name,
+            )?;
+            fields.insert(field_name, field);
+        }
+        if let Some(delete_mutation) = &model.model.graphql_api.delete_mutation {
+            let (field_name, field) =
+                model_mutations::delete_mutation_field(gds, builder, model, delete_mutation)?;
+            fields.insert(field_name, field);
+        }
+    }
+
     Ok(gql_schema::Object::new(
         builder,
         mutation_root_type_name.clone(),
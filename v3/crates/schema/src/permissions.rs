@@ -51,6 +51,7 @@ pub(crate) fn get_select_permissions_namespace_annotations(
                             })
                             .collect(),
                     },
+                    limit: select_permission.limit,
                 }),
             )
         })
@@ -86,6 +87,7 @@ pub(crate) fn get_select_permissions_namespace_annotations(
             if let Some(Some(types::NamespaceAnnotation::Model {
                 filter: _,
                 argument_presets,
+                limit: _,
             })) = permissions.get_mut(&role)
             {
                 *argument_presets = preset_map;
@@ -122,6 +124,31 @@ pub(crate) fn get_select_one_namespace_annotations(
     Ok(permissions)
 }
 
+/// Build namespace annotation for select aggregate permissions.
+/// Only the roles allowed to aggregate the model can access the field.
+pub(crate) fn get_select_aggregate_namespace_annotations(
+    model: &metadata_resolve::ModelWithPermissions,
+    object_types: &BTreeMap<
+        Qualified<CustomTypeName>,
+        metadata_resolve::ObjectTypeWithRelationships,
+    >,
+) -> Result<HashMap<Role, Option<types::NamespaceAnnotation>>, crate::Error> {
+    let select_permissions = get_select_permissions_namespace_annotations(model, object_types)?;
+
+    let permissions = select_permissions
+        .into_iter()
+        .filter(|(role, _)| {
+            model
+                .select_permissions
+                .get(role)
+                .map_or(false, |select_permission| {
+                    select_permission.allow_aggregations
+                })
+        })
+        .collect();
+    Ok(permissions)
+}
+
 /// Build namespace annotation for model relationship permissions.
 /// We need to check the permissions of the source and target fields
 /// in the relationship mappings.
@@ -141,19 +168,234 @@ pub(crate) fn get_model_relationship_namespace_annotations(
         .into_iter()
         .filter(|(role, _)| {
             mappings.iter().all(|mapping| {
-                let source_name = mapping.source_field.field_name.clone();
-                let target_name = mapping.target_field.field_name.clone();
-
-                get_allowed_roles_for_field(source_object_type_representation, &source_name)
-                    .any(|allowed_role| role == allowed_role)
-                    && get_allowed_roles_for_field(target_object_type_representation, &target_name)
-                        .any(|allowed_role| role == allowed_role)
+                // values and model arguments are not subject to field permissions
+                let source_allowed = mapping.source.field().map_or(true, |source_field| {
+                    is_field_path_allowed(
+                        role,
+                        source_object_type_representation,
+                        source_field,
+                        object_types,
+                    )
+                });
+                let target_allowed = match &mapping.target {
+                    metadata_resolve::RelationshipModelMappingTarget::ModelField(field_target) => {
+                        is_field_path_allowed(
+                            role,
+                            target_object_type_representation,
+                            &field_target.target_field,
+                            object_types,
+                        )
+                    }
+                    metadata_resolve::RelationshipModelMappingTarget::Argument(_) => true,
+                };
+                source_allowed && target_allowed
             })
         })
         .collect();
     Ok(permissions)
 }
 
+/// Build namespace annotation for insert point mutations.
+/// Any field presets, whether defined on the input permissions of the object
+/// type or on the insert permission of the model, are added to the `object`
+/// argument of the procedure.
+pub(crate) fn get_insert_mutation_namespace_annotations(
+    model: &metadata_resolve::ModelWithPermissions,
+    object_types: &BTreeMap<
+        Qualified<CustomTypeName>,
+        metadata_resolve::ObjectTypeWithRelationships,
+    >,
+) -> Result<HashMap<Role, Option<types::NamespaceAnnotation>>, crate::Error> {
+    let ndc_argument_name = Some(ConnectorArgumentName("object".to_string()));
+
+    // collect the presets defined on the input permissions of the object type
+    // (and any object types nested in it)
+    let mut role_presets_map = HashMap::new();
+    if let Some(model_source) = model.model.source.as_ref() {
+        let object_type_reference = QualifiedTypeReference {
+            underlying_type: metadata_resolve::QualifiedBaseType::Named(
+                metadata_resolve::QualifiedTypeName::Custom(model.model.data_type.clone()),
+            ),
+            nullable: false,
+        };
+        let mut processed_input_types = Vec::new();
+        let mut field_path = Vec::new();
+        build_annotations_from_input_object_type_permissions(
+            &mut field_path,
+            &object_type_reference,
+            &ndc_argument_name,
+            object_types,
+            &model_source.type_mappings,
+            &mut role_presets_map,
+            &mut processed_input_types,
+        )?;
+    }
+
+    let mut permissions = HashMap::new();
+    for (role, insert_permission) in &model.insert_permissions {
+        let mut argument_presets = role_presets_map
+            .get(role)
+            .cloned()
+            .unwrap_or_default()
+            .argument_presets;
+        argument_presets.extend(build_model_field_presets(
+            model,
+            &insert_permission.field_presets,
+            &ndc_argument_name,
+        )?);
+        permissions.insert(
+            role.clone(),
+            Some(types::NamespaceAnnotation::ModelMutation {
+                pre_check: metadata_resolve::FilterPermission::AllowAll,
+                post_check: insert_permission.check.clone(),
+                argument_presets: types::ArgumentPresets { argument_presets },
+            }),
+        );
+    }
+    Ok(permissions)
+}
+
+/// Build namespace annotation for update point mutations.
+/// Like select one, we need to check the permissions of the fields of the
+/// unique identifier. Field presets are added to the `update_columns`
+/// argument of the procedure.
+pub(crate) fn get_update_mutation_namespace_annotations(
+    model: &metadata_resolve::ModelWithPermissions,
+    object_type_representation: &metadata_resolve::ObjectTypeWithRelationships,
+    update_mutation: &metadata_resolve::UpdateMutationGraphQlDefinition,
+) -> Result<HashMap<Role, Option<types::NamespaceAnnotation>>, crate::Error> {
+    let ndc_argument_name = Some(ConnectorArgumentName("update_columns".to_string()));
+
+    let field_mappings = model
+        .model
+        .source
+        .as_ref()
+        .and_then(|model_source| model_source.type_mappings.get(&model.model.data_type))
+        .map(|type_mapping| match type_mapping {
+            metadata_resolve::TypeMapping::Object {
+                ndc_object_type_name: _,
+                field_mappings,
+            } => field_mappings,
+        });
+    let object_type_reference = QualifiedTypeReference {
+        underlying_type: metadata_resolve::QualifiedBaseType::Named(
+            metadata_resolve::QualifiedTypeName::Custom(model.model.data_type.clone()),
+        ),
+        nullable: false,
+    };
+
+    let mut permissions = HashMap::new();
+    for (role, update_permission) in &model.update_permissions {
+        let is_unique_identifier_allowed =
+            update_mutation.unique_identifier.keys().all(|field_name| {
+                get_allowed_roles_for_field(object_type_representation, field_name)
+                    .any(|allowed_role| role == allowed_role)
+            });
+        if !is_unique_identifier_allowed {
+            continue;
+        }
+
+        // only the presets on the fields of the object type itself apply, as
+        // the fields of an update are set individually
+        let mut argument_presets = match object_type_representation.type_input_permissions.get(role)
+        {
+            Some(type_input_permission) => build_preset_map_from_input_object_type_permission(
+                type_input_permission,
+                field_mappings,
+                &object_type_reference,
+                &[],
+                &ndc_argument_name,
+                &model.model.data_type,
+            )?,
+            None => BTreeMap::new(),
+        };
+        argument_presets.extend(build_model_field_presets(
+            model,
+            &update_permission.field_presets,
+            &ndc_argument_name,
+        )?);
+        permissions.insert(
+            role.clone(),
+            Some(types::NamespaceAnnotation::ModelMutation {
+                pre_check: update_permission.filter.clone(),
+                post_check: update_permission.check.clone(),
+                argument_presets: types::ArgumentPresets { argument_presets },
+            }),
+        );
+    }
+    Ok(permissions)
+}
+
+/// Build namespace annotation for delete point mutations.
+/// Like select one, we need to check the permissions of the fields of the
+/// unique identifier.
+pub(crate) fn get_delete_mutation_namespace_annotations(
+    model: &metadata_resolve::ModelWithPermissions,
+    object_type_representation: &metadata_resolve::ObjectTypeWithRelationships,
+    delete_mutation: &metadata_resolve::DeleteMutationGraphQlDefinition,
+) -> HashMap<Role, Option<types::NamespaceAnnotation>> {
+    model
+        .delete_permissions
+        .iter()
+        .filter(|(role, _)| {
+            delete_mutation.unique_identifier.keys().all(|field_name| {
+                get_allowed_roles_for_field(object_type_representation, field_name)
+                    .any(|allowed_role| *role == allowed_role)
+            })
+        })
+        .map(|(role, delete_permission)| {
+            (
+                role.clone(),
+                Some(types::NamespaceAnnotation::ModelMutation {
+                    pre_check: delete_permission.filter.clone(),
+                    post_check: metadata_resolve::FilterPermission::AllowAll,
+                    argument_presets: types::ArgumentPresets::default(),
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Build the argument presets for the field presets of an insert or update
+/// permission, each of which sets a column of the mutated object
+fn build_model_field_presets(
+    model: &metadata_resolve::ModelWithPermissions,
+    field_presets: &BTreeMap<FieldName, (QualifiedTypeReference, ValueExpression)>,
+    ndc_argument_name: &Option<ConnectorArgumentName>,
+) -> Result<
+    BTreeMap<ArgumentNameAndPath, (QualifiedTypeReference, metadata_resolve::ValueExpression)>,
+    crate::Error,
+> {
+    let field_mappings = model
+        .model
+        .source
+        .as_ref()
+        .and_then(|model_source| model_source.type_mappings.get(&model.model.data_type))
+        .map(|type_mapping| match type_mapping {
+            metadata_resolve::TypeMapping::Object {
+                ndc_object_type_name: _,
+                field_mappings,
+            } => field_mappings,
+        });
+    field_presets
+        .iter()
+        .map(|(field_name, preset)| {
+            let ndc_field = field_mappings
+                .and_then(|mappings| mappings.get(field_name))
+                .map(|field_mapping| field_mapping.column.clone())
+                .ok_or_else(|| crate::Error::InternalMappingNotFound {
+                    type_name: model.model.data_type.clone(),
+                    field_name: field_name.clone(),
+                })?;
+            let key = ArgumentNameAndPath {
+                ndc_argument_name: ndc_argument_name.clone(),
+                field_path: vec![ndc_field],
+            };
+            Ok((key, preset.clone()))
+        })
+        .collect()
+}
+
 /// Build namespace annotation for commands
 pub(crate) fn get_command_namespace_annotations(
     command: &metadata_resolve::CommandWithPermissions,
@@ -403,11 +645,14 @@ pub(crate) fn get_command_relationship_namespace_annotations(
         .into_iter()
         .filter(|(role, _)| {
             mappings.iter().all(|mapping| {
-                get_allowed_roles_for_field(
-                    source_object_type_representation,
-                    &mapping.source_field.field_name,
-                )
-                .any(|allowed_role| role == allowed_role)
+                mapping.source.field().map_or(true, |source_field| {
+                    is_field_path_allowed(
+                        role,
+                        source_object_type_representation,
+                        source_field,
+                        object_types,
+                    )
+                })
             })
         })
         .collect())
@@ -484,6 +729,35 @@ pub(crate) fn get_allowed_roles_for_field<'a>(
         })
 }
 
+/// Whether a role can access every field along the field path of a relationship mapping
+fn is_field_path_allowed(
+    role: &Role,
+    object_type_representation: &metadata_resolve::ObjectTypeWithRelationships,
+    field_path: &metadata_resolve::RelationshipFieldPath,
+    object_types: &BTreeMap<
+        Qualified<CustomTypeName>,
+        metadata_resolve::ObjectTypeWithRelationships,
+    >,
+) -> bool {
+    let is_field_allowed =
+        |object_type_representation: &metadata_resolve::ObjectTypeWithRelationships,
+         field_name: &FieldName| {
+            get_allowed_roles_for_field(object_type_representation, field_name)
+                .any(|allowed_role| role == allowed_role)
+        };
+    is_field_allowed(object_type_representation, &field_path.field.field_name)
+        && field_path
+            .nested_fields
+            .iter()
+            .all(|(type_name, field_access)| {
+                object_types
+                    .get(type_name)
+                    .map_or(false, |object_type_representation| {
+                        is_field_allowed(object_type_representation, &field_access.field_name)
+                    })
+            })
+}
+
 /// Builds namespace annotations for the `node` field.
 pub(crate) fn get_node_field_namespace_permissions(
     object_type_representation: &metadata_resolve::ObjectTypeWithRelationships,
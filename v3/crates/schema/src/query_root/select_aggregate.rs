@@ -55,10 +55,8 @@ pub(crate) fn select_aggregate_field(
         model,
     )?;
 
-    let field_permissions = permissions::get_select_permissions_namespace_annotations(
-        model,
-        &gds.metadata.object_types,
-    )?;
+    let field_permissions =
+        permissions::get_select_aggregate_namespace_annotations(model, &gds.metadata.object_types)?;
 
     let output_typename = get_aggregate_select_output_type(builder, aggregate_expression)?;
 
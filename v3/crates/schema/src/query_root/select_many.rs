@@ -19,17 +19,26 @@ use crate::{
 use metadata_resolve;
 
 /// Generates the schema for the arguments of a model selection, which includes
-/// limit, offset, order_by and where.
+/// limit, offset, order_by and where. The engine only orders by the fields of remote
+/// relationships where `include_remote_relationships` is set.
 pub(crate) fn generate_select_many_arguments(
+    gds: &GDS,
     builder: &mut gql_schema::Builder<GDS>,
     model: &metadata_resolve::ModelWithPermissions,
+    include_remote_relationships: bool,
 ) -> Result<BTreeMap<Name, gql_schema::Namespaced<GDS, gql_schema::InputField<GDS>>>, crate::Error>
 {
     let mut arguments = BTreeMap::new();
 
     add_limit_input_field(&mut arguments, builder, model)?;
     add_offset_input_field(&mut arguments, builder, model)?;
-    add_order_by_input_field(&mut arguments, builder, model)?;
+    add_order_by_input_field(
+        &mut arguments,
+        gds,
+        builder,
+        model,
+        include_remote_relationships,
+    )?;
     add_where_input_field(&mut arguments, builder, model)?;
 
     Ok(arguments)
@@ -50,7 +59,7 @@ pub(crate) fn select_many_field(
     crate::Error,
 > {
     let query_root_field = select_many.query_root_field.clone();
-    let mut arguments = generate_select_many_arguments(builder, model)?;
+    let mut arguments = generate_select_many_arguments(gds, builder, model, true)?;
 
     // Generate the `args` input object and add the model
     // arguments within it.
@@ -0,0 +1,62 @@
+//! Schema of the subscription root type
+//!
+//! The subscription root mirrors the model root fields of the query root. Subscriptions are
+//! served as live queries: the engine re-runs the query plan of the selected root field and
+//! pushes the result to the client whenever it changes.
+
+use lang_graphql::ast::common::TypeName;
+use lang_graphql::schema as gql_schema;
+use std::collections::BTreeMap;
+
+use crate::query_root::{select_aggregate, select_many, select_one};
+use crate::GDS;
+
+/// Generates schema for the subscription root type
+pub fn subscription_root_schema(
+    builder: &mut gql_schema::Builder<GDS>,
+    gds: &GDS,
+    subscription_root_type_name: &TypeName,
+) -> Result<gql_schema::Object<GDS>, crate::Error> {
+    let mut fields = BTreeMap::new();
+    for model in gds.metadata.models.values() {
+        for select_unique in &model.model.graphql_api.select_uniques {
+            let (field_name, field) = select_one::select_one_field(
+                gds,
+                builder,
+                model,
+                select_unique,
+                subscription_root_type_name,
+            )?;
+            fields.insert(field_name, field);
+        }
+        for select_many in &model.model.graphql_api.select_many {
+            let (field_name, field) = select_many::select_many_field(
+                gds,
+                builder,
+                model,
+                select_many,
+                subscription_root_type_name,
+            )?;
+            fields.insert(field_name, field);
+        }
+        if let Some(select_aggregate) = &model.model.graphql_api.select_aggregate {
+            let (field_name, field) = select_aggregate::select_aggregate_field(
+                gds,
+                builder,
+                model,
+                select_aggregate,
+                subscription_root_type_name,
+            )?;
+            fields.insert(field_name, field);
+        }
+    }
+
+    Ok(gql_schema::Object::new(
+        builder,
+        subscription_root_type_name.clone(),
+        None,
+        fields,
+        BTreeMap::new(),
+        Vec::new(),
+    ))
+}
@@ -78,6 +78,13 @@ pub enum RootFieldKind {
     SelectAggregate,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ModelMutationKind {
+    Insert,
+    Update,
+    Delete,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum ModelFilterArgument {
     AndOp,
@@ -129,6 +136,13 @@ pub enum RootFieldAnnotation {
         kind: RootFieldKind,
         name: Qualified<models::ModelName>,
     },
+    ModelMutation {
+        data_type: Qualified<types::CustomTypeName>,
+        source: Option<metadata_resolve::ModelSource>,
+        procedure_name: commands::ProcedureName,
+        kind: ModelMutationKind,
+        name: Qualified<models::ModelName>,
+    },
     FunctionCommand {
         name: Qualified<commands::CommandName>,
         result_type: QualifiedTypeReference,
@@ -223,6 +237,8 @@ pub enum ModelInputAnnotation {
         ndc_column: Option<NdcColumnForComparison>,
     },
     ModelFilterInputArgument,
+    ModelInsertObjectArgument,
+    ModelUpdateColumnsArgument,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Display)]
@@ -278,7 +294,7 @@ pub enum Annotation {
     Input(InputAnnotation),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 /// Preset arguments for models or commands
 pub struct ArgumentPresets {
     #[serde(
@@ -309,10 +325,18 @@ pub struct ArgumentNameAndPath {
 pub enum NamespaceAnnotation {
     /// any arguments that we should prefill for a command or type
     Command(ArgumentPresets),
-    /// any filter and arguments for selecting from a model
+    /// any filter, arguments and row limit for selecting from a model
     Model {
         filter: metadata_resolve::FilterPermission,
         argument_presets: ArgumentPresets,
+        limit: Option<u32>,
+    },
+    /// the predicates the rows affected by a model mutation have to satisfy before and after
+    /// it, and any fields of the mutated object that we should prefill
+    ModelMutation {
+        pre_check: metadata_resolve::FilterPermission,
+        post_check: metadata_resolve::FilterPermission,
+        argument_presets: ArgumentPresets,
     },
     /// The `NodeFieldTypeMappings` contains a Hashmap of typename to the filter permission.
     /// While executing the `node` field, the `id` field is supposed to be decoded and after
@@ -337,6 +361,9 @@ pub enum TypeId {
     MutationRoot {
         graphql_type_name: ast::TypeName,
     },
+    SubscriptionRoot {
+        graphql_type_name: ast::TypeName,
+    },
     OutputType {
         gds_type_name: Qualified<types::CustomTypeName>,
         graphql_type_name: ast::TypeName,
@@ -361,6 +388,9 @@ pub enum TypeId {
     ModelOrderByExpression {
         model_name: Qualified<models::ModelName>,
         graphql_type_name: ast::TypeName,
+        /// Whether the fields of remote object relationships are included. The engine only
+        /// sorts the rows of root fields, so other order_by inputs leave them out.
+        include_remote_relationships: bool,
     },
     ScalarTypeComparisonExpression {
         graphql_type_name: ast::TypeName,
@@ -383,6 +413,14 @@ pub enum TypeId {
         model_name: Qualified<models::ModelName>,
         graphql_type_name: ast::TypeName,
     },
+    ModelInsertInputType {
+        model_name: Qualified<models::ModelName>,
+        graphql_type_name: ast::TypeName,
+    },
+    ModelUpdateInputType {
+        model_name: Qualified<models::ModelName>,
+        graphql_type_name: ast::TypeName,
+    },
 }
 
 #[derive(Serialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -404,6 +442,7 @@ impl TypeId {
         match self {
             TypeId::QueryRoot { graphql_type_name }
             | TypeId::MutationRoot { graphql_type_name }
+            | TypeId::SubscriptionRoot { graphql_type_name }
             | TypeId::OutputType {
                 graphql_type_name, ..
             }
@@ -430,6 +469,12 @@ impl TypeId {
             }
             | TypeId::ModelFilterInputType {
                 graphql_type_name, ..
+            }
+            | TypeId::ModelInsertInputType {
+                graphql_type_name, ..
+            }
+            | TypeId::ModelUpdateInputType {
+                graphql_type_name, ..
             } => graphql_type_name.clone(),
             TypeId::NodeRoot => ast::TypeName(mk_name!("Node")),
             TypeId::ModelArgumentsInput { type_name, .. } => type_name.clone(),
@@ -308,15 +308,30 @@ fn object_type_fields(
                     }
                 })?;
 
-                if !model.model.arguments.is_empty() {
-                    return Err(Error::InternalUnsupported {
-                        summary: "Relationships to models with arguments aren't supported".into(),
+                // the arguments of the target model are passed by the relationship, rather
+                // than by the field
+                if let Some(argument_name) = model.model.arguments.keys().find(|argument_name| {
+                    !mappings.iter().any(|mapping| {
+                        matches!(
+                            &mapping.target,
+                            metadata_resolve::RelationshipModelMappingTarget::Argument(mapped_argument_name)
+                                if mapped_argument_name == *argument_name
+                        )
+                    })
+                }) {
+                    return Err(Error::UnmappedModelArgumentInRelationship {
+                        relationship_name: relationship.name.clone(),
+                        type_name: type_name.clone(),
+                        model_name: model_name.clone(),
+                        argument_name: argument_name.clone(),
                     });
                 }
 
                 let arguments = match relationship_type {
+                    // whether a relationship is remote depends on the model the object type
+                    // comes from, so ordering by remote relationships is left out here
                     relationships::RelationshipType::Array => {
-                        generate_select_many_arguments(builder, model)?
+                        generate_select_many_arguments(gds, builder, model, false)?
                     }
                     relationships::RelationshipType::Object => BTreeMap::new(),
                 };
@@ -1,5 +1,7 @@
 //! Various utilities and extensions to futures/futures_utils
-use futures_util::Future;
+use std::num::NonZeroUsize;
+
+use futures_util::{Future, StreamExt};
 
 /// Execute an async function on all items in an iterator, in a concurrent
 /// fashion
@@ -16,6 +18,34 @@ where
     futures_util::future::join_all(iterator.map(f)).await
 }
 
+/// Execute an async function on all items in an iterator, running at most
+/// `limit` of them at a time. The results are returned in the order of the
+/// items.
+#[inline]
+pub async fn execute_concurrently_bounded<T, U, F, Fut>(
+    iterator: impl Iterator<Item = T>,
+    limit: NonZeroUsize,
+    // function to execute for each item
+    f: F,
+) -> Vec<U>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = U>,
+{
+    // `buffered` would hold back new items while an earlier one is still
+    // running, so results are run unordered and put back in order afterwards
+    let mut results: Vec<(usize, U)> = futures_util::stream::iter(iterator.enumerate())
+        .map(|(index, item)| {
+            let result = f(item);
+            async move { (index, result.await) }
+        })
+        .buffer_unordered(limit.get())
+        .collect()
+        .await;
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
 #[cfg(test)]
 mod execute_utils_tests {
     use std::time::SystemTime;
@@ -37,4 +67,24 @@ mod execute_utils_tests {
         let time_taken = end.duration_since(start).unwrap();
         assert_eq!(time_taken.as_secs(), 2);
     }
+
+    #[tokio::test]
+    async fn test_execute_concurrently_bounded() {
+        let input = vec![2, 1, 1];
+        let func = |item: u64| async move {
+            sleep(Duration::from_secs(item)).await;
+            item
+        };
+        // with two tasks running at a time, the first task runs alongside the
+        // other two, so the whole job should take 2 secs and keep the order of
+        // the input.
+        let start = SystemTime::now();
+        let output =
+            execute_concurrently_bounded(input.into_iter(), NonZeroUsize::new(2).unwrap(), func)
+                .await;
+        let end = SystemTime::now();
+        let time_taken = end.duration_since(start).unwrap();
+        assert_eq!(time_taken.as_secs(), 2);
+        assert_eq!(output, vec![2, 1, 1]);
+    }
 }
@@ -0,0 +1,118 @@
+//! A bounded cache which evicts its least recently used entries
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A cache holding up to a fixed number of entries. Once it is full, the least recently used
+/// entry is evicted to make room for a new one. It isn't synchronised, so a cache shared between
+/// requests lives behind a lock.
+pub struct LruCache<K, V> {
+    /// The values, with the tick at which they were last used.
+    entries: HashMap<K, (V, u64)>,
+    /// The keys in the order they were last used, oldest first.
+    recency: BTreeMap<u64, K>,
+    /// Incremented on every use of an entry.
+    clock: u64,
+    capacity: usize,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> LruCache<K, V> {
+    /// A cache holding up to `capacity` entries. A capacity of 0 disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            capacity,
+        }
+    }
+
+    /// The value cached for a key, which becomes the most recently used entry.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (value, last_used) = self.entries.get_mut(key)?;
+        let key = self.recency.remove(last_used)?;
+        self.clock += 1;
+        *last_used = self.clock;
+        self.recency.insert(self.clock, key);
+        Some(value.clone())
+    }
+
+    /// Caches a value as the most recently used entry, replacing any value cached for the same
+    /// key, and evicting the least recently used entries if the cache is full.
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some((_, last_used)) = self.entries.remove(&key) {
+            self.recency.remove(&last_used);
+        }
+        while self.entries.len() >= self.capacity {
+            match self.recency.pop_first() {
+                Some((_, oldest)) => {
+                    self.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+        self.clock += 1;
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(key, (value, self.clock));
+    }
+
+    /// The keys of the cached entries, from the least to the most recently used.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.recency.values()
+    }
+
+    /// The maximum number of cached entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of cached entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no entries are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LruCache;
+
+    #[test]
+    fn test_least_recently_used_entry_is_evicted() {
+        let mut cache = LruCache::new(2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        // using `a` again makes `b` the least recently used entry
+        assert_eq!(cache.get("a"), Some(1));
+        cache.insert("c", 3);
+        assert_eq!(cache.keys().copied().collect::<Vec<_>>(), vec!["a", "c"]);
+        assert_eq!(cache.get("b"), None);
+    }
+
+    #[test]
+    fn test_insert_replaces_value() {
+        let mut cache = LruCache::new(2);
+        cache.insert("a", 1);
+        cache.insert("a", 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("a"), Some(2));
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_caching() {
+        let mut cache = LruCache::new(0);
+        cache.insert("a", 1);
+        assert!(cache.is_empty());
+    }
+}
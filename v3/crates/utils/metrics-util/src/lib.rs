@@ -0,0 +1,292 @@
+//! Prometheus metrics collected by the engine and served over `/metrics`.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use prometheus::{
+    exponential_buckets, Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry,
+    TextEncoder,
+};
+
+const NAMESPACE: &str = "hasura";
+
+/// The outcome of a request, used as the `status` label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestStatus {
+    Success,
+    Failed,
+}
+
+impl RequestStatus {
+    pub fn from_is_success(is_success: bool) -> Self {
+        if is_success {
+            Self::Success
+        } else {
+            Self::Failed
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// All metrics collected by the engine, registered in their own registry.
+pub struct Metrics {
+    registry: Registry,
+    graphql_requests_total: IntCounterVec,
+    graphql_request_duration_seconds: HistogramVec,
+    root_field_requests_total: IntCounterVec,
+    root_field_duration_seconds: HistogramVec,
+    ndc_requests_total: IntCounterVec,
+    ndc_request_errors_total: IntCounterVec,
+    ndc_request_duration_seconds: HistogramVec,
+    remote_join_fanout: HistogramVec,
+    auth_failures_total: IntCounterVec,
+    response_size_bytes: HistogramVec,
+    query_cache_lookups_total: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new();
+
+        let graphql_requests_total = IntCounterVec::new(
+            Opts::new("graphql_requests_total", "Number of GraphQL requests").namespace(NAMESPACE),
+            &["operation_type", "status"],
+        )?;
+        let graphql_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "graphql_request_duration_seconds",
+                "Time taken to execute a GraphQL request",
+            )
+            .namespace(NAMESPACE),
+            &["operation_type"],
+        )?;
+        let root_field_requests_total = IntCounterVec::new(
+            Opts::new(
+                "graphql_root_field_requests_total",
+                "Number of executions of each root field",
+            )
+            .namespace(NAMESPACE),
+            &["operation_type", "root_field", "status"],
+        )?;
+        let root_field_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "graphql_root_field_duration_seconds",
+                "Time taken to execute each root field",
+            )
+            .namespace(NAMESPACE),
+            &["operation_type", "root_field"],
+        )?;
+        let ndc_requests_total = IntCounterVec::new(
+            Opts::new(
+                "ndc_requests_total",
+                "Number of requests made to data connectors",
+            )
+            .namespace(NAMESPACE),
+            &["data_connector", "operation"],
+        )?;
+        let ndc_request_errors_total = IntCounterVec::new(
+            Opts::new(
+                "ndc_request_errors_total",
+                "Number of failed requests made to data connectors",
+            )
+            .namespace(NAMESPACE),
+            &["data_connector", "operation"],
+        )?;
+        let ndc_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "ndc_request_duration_seconds",
+                "Time taken by requests made to data connectors",
+            )
+            .namespace(NAMESPACE),
+            &["data_connector", "operation"],
+        )?;
+        let remote_join_fanout = HistogramVec::new(
+            HistogramOpts::new(
+                "remote_join_fanout",
+                "Number of distinct join arguments sent to a data connector for a remote join",
+            )
+            .namespace(NAMESPACE)
+            .buckets(exponential_buckets(1.0, 4.0, 10)?),
+            &["data_connector"],
+        )?;
+        let auth_failures_total = IntCounterVec::new(
+            Opts::new("auth_failures_total", "Number of failed authentications")
+                .namespace(NAMESPACE),
+            &["reason"],
+        )?;
+        let response_size_bytes = HistogramVec::new(
+            HistogramOpts::new("response_size_bytes", "Size of response bodies in bytes")
+                .namespace(NAMESPACE)
+                .buckets(exponential_buckets(64.0, 4.0, 10)?),
+            &["endpoint"],
+        )?;
+        let query_cache_lookups_total = IntCounterVec::new(
+            Opts::new(
+                "query_cache_lookups_total",
+                "Number of lookups in the cache of parsed queries",
+            )
+            .namespace(NAMESPACE),
+            &["result"],
+        )?;
+
+        registry.register(Box::new(graphql_requests_total.clone()))?;
+        registry.register(Box::new(graphql_request_duration_seconds.clone()))?;
+        registry.register(Box::new(root_field_requests_total.clone()))?;
+        registry.register(Box::new(root_field_duration_seconds.clone()))?;
+        registry.register(Box::new(ndc_requests_total.clone()))?;
+        registry.register(Box::new(ndc_request_errors_total.clone()))?;
+        registry.register(Box::new(ndc_request_duration_seconds.clone()))?;
+        registry.register(Box::new(remote_join_fanout.clone()))?;
+        registry.register(Box::new(auth_failures_total.clone()))?;
+        registry.register(Box::new(response_size_bytes.clone()))?;
+        registry.register(Box::new(query_cache_lookups_total.clone()))?;
+
+        Ok(Self {
+            registry,
+            graphql_requests_total,
+            graphql_request_duration_seconds,
+            root_field_requests_total,
+            root_field_duration_seconds,
+            ndc_requests_total,
+            ndc_request_errors_total,
+            ndc_request_duration_seconds,
+            remote_join_fanout,
+            auth_failures_total,
+            response_size_bytes,
+            query_cache_lookups_total,
+        })
+    }
+
+    /// Records a GraphQL request of the given operation type (`query`, `mutation`,
+    /// `subscription`, or `unknown` if the request could not be normalized).
+    pub fn observe_graphql_request(
+        &self,
+        operation_type: &str,
+        status: RequestStatus,
+        duration: Duration,
+    ) {
+        self.graphql_requests_total
+            .with_label_values(&[operation_type, status.as_str()])
+            .inc();
+        self.graphql_request_duration_seconds
+            .with_label_values(&[operation_type])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Records the execution of a single root field of a GraphQL request.
+    pub fn observe_root_field(
+        &self,
+        operation_type: &str,
+        root_field: &str,
+        status: RequestStatus,
+        duration: Duration,
+    ) {
+        self.root_field_requests_total
+            .with_label_values(&[operation_type, root_field, status.as_str()])
+            .inc();
+        self.root_field_duration_seconds
+            .with_label_values(&[operation_type, root_field])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Records a request made to a data connector. `operation` is one of `query`,
+    /// `mutation`.
+    pub fn observe_ndc_request(
+        &self,
+        data_connector: &str,
+        operation: &str,
+        status: RequestStatus,
+        duration: Duration,
+    ) {
+        let labels = [data_connector, operation];
+        self.ndc_requests_total.with_label_values(&labels).inc();
+        if status == RequestStatus::Failed {
+            self.ndc_request_errors_total
+                .with_label_values(&labels)
+                .inc();
+        }
+        self.ndc_request_duration_seconds
+            .with_label_values(&labels)
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Records the number of join arguments sent to a data connector for a remote join.
+    pub fn observe_remote_join_fanout(&self, data_connector: &str, fanout: usize) {
+        #[allow(clippy::cast_precision_loss)]
+        self.remote_join_fanout
+            .with_label_values(&[data_connector])
+            .observe(fanout as f64);
+    }
+
+    /// Records a failed authentication, labelled with the kind of authentication that failed.
+    pub fn inc_auth_failure(&self, reason: &str) {
+        self.auth_failures_total.with_label_values(&[reason]).inc();
+    }
+
+    /// Records the size of a response body sent from the given endpoint.
+    pub fn observe_response_size(&self, endpoint: &str, size_bytes: u64) {
+        #[allow(clippy::cast_precision_loss)]
+        self.response_size_bytes
+            .with_label_values(&[endpoint])
+            .observe(size_bytes as f64);
+    }
+
+    /// Records a lookup in the cache of parsed queries, labelled `hit` or `miss`.
+    pub fn inc_query_cache_lookup(&self, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.query_cache_lookups_total
+            .with_label_values(&[result])
+            .inc();
+    }
+
+    /// Encodes all metrics in the Prometheus text exposition format.
+    pub fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+}
+
+/// The content type of the text exposition format returned by [`Metrics::encode`].
+pub const CONTENT_TYPE: &str = prometheus::TEXT_FORMAT;
+
+/// Util for accessing the global metrics, which are registered on first use.
+pub fn global_metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(|| Metrics::new().expect("metrics must be valid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_includes_recorded_metrics() {
+        let metrics = Metrics::new().unwrap();
+        metrics.observe_graphql_request("query", RequestStatus::Success, Duration::from_millis(5));
+        metrics.observe_ndc_request(
+            "db",
+            "query",
+            RequestStatus::Failed,
+            Duration::from_millis(1),
+        );
+        metrics.inc_auth_failure("jwt");
+        metrics.inc_query_cache_lookup(false);
+
+        let encoded = metrics.encode().unwrap();
+        assert!(encoded.contains(
+            r#"hasura_graphql_requests_total{operation_type="query",status="success"} 1"#
+        ));
+        assert!(encoded.contains(
+            r#"hasura_ndc_request_errors_total{data_connector="db",operation="query"} 1"#
+        ));
+        assert!(encoded.contains(r#"hasura_auth_failures_total{reason="jwt"} 1"#));
+        assert!(encoded.contains(r#"hasura_query_cache_lookups_total{result="miss"} 1"#));
+    }
+}
@@ -0,0 +1,40 @@
+//! Various utilities and extensions to std::sync
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+// A lock is poisoned when a thread panics while holding it. The engine only keeps caches and
+// snapshots behind locks, and only ever changes them by inserting, removing or replacing whole
+// values, so the data behind a poisoned lock is still consistent. These helpers use it anyway
+// rather than propagating the panic to every later request.
+
+/// Lock a mutex, ignoring poisoning
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Lock a read-write lock for reading, ignoring poisoning
+pub fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Lock a read-write lock for writing, ignoring poisoning
+pub fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_lock_poisoned_mutex() {
+        let mutex = Arc::new(Mutex::new(1));
+        let poisoner = mutex.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(mutex.is_poisoned());
+        assert_eq!(*super::lock(&mutex), 1);
+    }
+}